@@ -14,11 +14,55 @@ pub type Result<I, E = Error> = core::result::Result<I, E>;
 ///
 /// Used as error value for e.g. [`Template::render()`][crate::Template::render()]
 /// and custom filters.
+///
+/// The variants let an application tell the causes of a failed rendering apart, e.g. to map
+/// them to an HTTP status. A failure of the output writer is reported as [`Error::Fmt`], a
+/// failure of an [`Escaper`][crate::filters::Escaper] as [`Error::Escape`]. A failed lookup in
+/// the runtime [`Values`][crate::Values] is never reported as [`Error::Fmt`], but as
+/// [`Error::ValueMissing`] or [`Error::ValueType`], even if it happens in a sub-template or
+/// inside of a filter.
+///
+/// The distinction is only available if the template is rendered with one of the `render*()`
+/// methods of [`Template`][crate::Template]. If it is rendered through its [`fmt::Display`]
+/// implementation, e.g. with `to_string()` or `format!()`, then every error is a [`fmt::Error`].
+///
+/// ```
+/// # #[cfg(feature = "derive")] {
+/// # use std::any::Any;
+/// # use askama::Template;
+/// #[derive(Template)]
+/// #[template(source = r#"Hello, {{ ("user" | value::<&str>)? }}!"#, ext = "txt")]
+/// struct Greeting;
+///
+/// fn status(err: &askama::Error) -> u16 {
+///     match err {
+///         askama::Error::ValueMissing | askama::Error::ValueType => 400,
+///         _ => 500,
+///     }
+/// }
+///
+/// let values: (&str, &dyn Any) = ("user", &"Alice");
+/// assert_eq!(Greeting.render_with_values(&values).unwrap(), "Hello, Alice!");
+///
+/// let err = Greeting.render().unwrap_err();
+/// assert!(matches!(err, askama::Error::ValueMissing));
+/// assert_eq!(status(&err), 400);
+/// # }
+/// ```
 #[non_exhaustive]
 #[derive(Debug)]
 pub enum Error {
     /// Generic, unspecified formatting error
+    ///
+    /// Returned if the output writer or a [`fmt::Display`] implementation fails, or if a
+    /// built-in filter is called with an invalid argument, e.g. a negative length.
     Fmt,
+    /// An [`Escaper`][crate::filters::Escaper] failed, while the output writer did not
+    ///
+    /// Escaping a value that only implements [`fmt::Display`], but not
+    /// [`FastWritable`][crate::FastWritable], goes through a [`fmt::Formatter`], so a failed
+    /// escaper is reported as [`Error::Fmt`] for such a value.
+    Escape,
     /// Key not present in [`Values`][crate::Values]
     ValueMissing,
     /// Incompatible value type for key in [`Values`][crate::Values]
@@ -45,6 +89,7 @@ impl Error {
     pub fn into_box(self) -> Box<dyn StdError + Send + Sync> {
         match self {
             Error::Fmt => fmt::Error.into(),
+            Error::Escape => Box::new(Error::Escape),
             Error::ValueMissing => Box::new(Error::ValueMissing),
             Error::ValueType => Box::new(Error::ValueType),
             Error::Custom(err) => err,
@@ -72,6 +117,7 @@ impl StdError for Error {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         match self {
             Error::Fmt => Some(&fmt::Error),
+            Error::Escape => None,
             Error::ValueMissing => None,
             Error::ValueType => None,
             #[cfg(feature = "alloc")]
@@ -86,6 +132,7 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Error::Fmt => fmt::Error.fmt(f),
+            Error::Escape => f.write_str("escaper failed"),
             Error::ValueMissing => f.write_str("key missing in values"),
             Error::ValueType => f.write_str("value has wrong type"),
             #[cfg(feature = "alloc")]
@@ -252,8 +299,8 @@ impl From<Infallible> for Error {
 
 #[cfg(test)]
 const _: () = {
-    trait AssertSendSyncStatic: Send + Sync + 'static {}
-    impl AssertSendSyncStatic for Error {}
+    const fn assert_send_sync_static<T: Send + Sync + 'static>() {}
+    assert_send_sync_static::<Error>();
 };
 
 /// Helper trait to convert a custom `?` call into a [`crate::Result`]
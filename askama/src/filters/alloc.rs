@@ -1,5 +1,6 @@
 use alloc::str;
 use alloc::string::String;
+use alloc::vec::Vec;
 use core::cell::Cell;
 use core::convert::Infallible;
 use core::fmt::{self, Write};
@@ -265,6 +266,614 @@ fn flush_paragraphbreaks(dest: &mut (impl fmt::Write + ?Sized), s: &str) -> fmt:
     write!(dest, "<p>{linebroken}</p>")
 }
 
+/// Splits an iterable into consecutive chunks of up to `size` elements
+///
+/// Unlike a "batch" filter, the last chunk is never padded: it simply contains the remaining
+/// elements. If `size` is `0`, then no chunks are produced.
+///
+/// In templates, chunking a [`Vec`] or a slice yields sub-slices that borrow from the input.
+/// Any other iterable is buffered, and each chunk is a [`Vec`] of its elements.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {% for row in cells|chunks(3) -%}
+/// /// <tr>{% for cell in row %}<td>{{ cell }}</td>{% endfor %}</tr>
+/// /// {% endfor %}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example {
+///     cells: Vec<u32>,
+/// }
+///
+/// assert_eq!(
+///     Example { cells: vec![1, 2, 3, 4, 5] }.to_string(),
+///     "<tr><td>1</td><td>2</td><td>3</td></tr>\n<tr><td>4</td><td>5</td></tr>\n"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn chunks<I: IntoIterator>(
+    iterable: I,
+    size: usize,
+) -> Result<Chunks<I::IntoIter>, Infallible> {
+    Ok(Chunks {
+        iter: iterable.into_iter(),
+        size,
+    })
+}
+
+/// Result of the filter [`chunks()`] for an iterable that is not a slice.
+#[derive(Debug, Clone)]
+pub struct Chunks<I> {
+    iter: I,
+    size: usize,
+}
+
+impl<I: Iterator> Iterator for Chunks<I> {
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.size == 0 {
+            return None;
+        }
+        let chunk: Vec<_> = self.iter.by_ref().take(self.size).collect();
+        (!chunk.is_empty()).then_some(chunk)
+    }
+}
+
+/// Splits an iterable into `count` columns of balanced sizes
+///
+/// The elements are distributed column-major: the first column gets the first elements, the
+/// second column the following ones, and so on. The sizes of the columns differ by at most one,
+/// and the first columns are the longer ones, e.g. 7 elements in 3 columns have the sizes 3, 2
+/// and 2. Exactly `count` columns are produced, so if there are fewer elements than columns, then
+/// the last columns are empty. If `count` is `0`, then no columns are produced.
+///
+/// Unlike [`chunks()`], which fixes the size of each chunk, this filter fixes the number of
+/// columns. Each column is a [`Vec`] of its elements.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {% for column in links|columns(3) -%}
+/// /// <ul>{% for link in column %}<li>{{ link }}</li>{% endfor %}</ul>
+/// /// {% endfor %}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     links: Vec<&'a str>,
+/// }
+///
+/// assert_eq!(
+///     Example { links: vec!["a", "b", "c", "d", "e"] }.to_string(),
+///     "<ul><li>a</li><li>b</li></ul>\n<ul><li>c</li><li>d</li></ul>\n<ul><li>e</li></ul>\n"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn columns<I: IntoIterator>(iterable: I, count: usize) -> Result<Columns<I::Item>, Infallible> {
+    Ok(Columns {
+        items: iterable.into_iter().collect::<Vec<_>>().into_iter(),
+        count,
+    })
+}
+
+/// Result of the filter [`columns()`].
+#[derive(Debug, Clone)]
+pub struct Columns<T> {
+    items: alloc::vec::IntoIter<T>,
+    count: usize,
+}
+
+impl<T> Iterator for Columns<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.count == 0 {
+            return None;
+        }
+        let size = self.items.len().div_ceil(self.count);
+        self.count -= 1;
+        Some(self.items.by_ref().take(size).collect())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.count, Some(self.count))
+    }
+}
+
+impl<T> ExactSizeIterator for Columns<T> {}
+
+/// Groups runs of adjacent elements with equal keys
+///
+/// The filter yields `(key, run)` pairs, where `run` is a [`Vec`] of the adjacent elements that
+/// have the same `key`. Unlike a "groupby" filter, the input is not sorted or grouped globally:
+/// equal keys that are not adjacent produce separate runs. This makes the filter useful for data
+/// that is already sorted, e.g. log entries grouped by date. The input is consumed lazily, and
+/// only the current run is buffered. An empty input yields no runs.
+///
+/// In a template, an optional argument selects a field of the elements that is used as key,
+/// e.g. `{{ entries|group_consecutive("date") }}`. Without the argument, the elements themselves
+/// are compared.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {% for (level, run) in levels|group_consecutive -%}
+/// /// {{ level }}: {{ run.len() }}
+/// /// {% endfor %}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example<'a> {
+///     levels: Vec<&'a str>,
+/// }
+///
+/// assert_eq!(
+///     Example { levels: vec!["info", "info", "warn", "info"] }.to_string(),
+///     "info: 2\nwarn: 1\ninfo: 1\n"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn group_consecutive<I, K, F>(
+    iterable: I,
+    key: F,
+) -> Result<GroupConsecutive<I::IntoIter, K, F>, Infallible>
+where
+    I: IntoIterator,
+    K: PartialEq,
+    F: FnMut(&I::Item) -> K,
+{
+    Ok(GroupConsecutive {
+        iter: iterable.into_iter(),
+        key,
+        next: None,
+    })
+}
+
+/// Result of the filter [`group_consecutive()`].
+#[derive(Debug, Clone)]
+pub struct GroupConsecutive<I: Iterator, K, F> {
+    iter: I,
+    key: F,
+    /// The first element of the next run, and its key.
+    next: Option<(K, I::Item)>,
+}
+
+impl<I, K, F> Iterator for GroupConsecutive<I, K, F>
+where
+    I: Iterator,
+    K: PartialEq,
+    F: FnMut(&I::Item) -> K,
+{
+    type Item = (K, Vec<I::Item>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, first) = match self.next.take() {
+            Some(next) => next,
+            None => {
+                let first = self.iter.next()?;
+                ((self.key)(&first), first)
+            }
+        };
+        let mut run = alloc::vec![first];
+        for item in self.iter.by_ref() {
+            let item_key = (self.key)(&item);
+            if item_key != key {
+                self.next = Some((item_key, item));
+                break;
+            }
+            run.push(item);
+        }
+        Some((key, run))
+    }
+}
+
+/// Renders rows of cells as a plain text table with aligned columns
+///
+/// Every row is an iterable of cells. Each column is padded to its widest cell, and the columns
+/// are separated by two spaces. Trailing spaces are omitted, and the lines are separated by `\n`
+/// without a final newline. Rows may have different lengths. If `header` is `true`, then the
+/// first row is underlined with dashes.
+///
+/// The width of a cell is the number of its `char`s. The result is escaped like any other
+/// string, so use this filter in a template that does not escape its output, e.g. a `.txt`
+/// template, or inside of a `<pre>` element.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {{ rows|table(true) }}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example<'a> {
+///     rows: Vec<Vec<&'a str>>,
+/// }
+///
+/// assert_eq!(
+///     Example {
+///         rows: vec![vec!["name", "count"], vec!["apples", "3"], vec!["kiwis", "12"]],
+///     }
+///     .to_string(),
+///     "name    count\n------  -----\napples  3\nkiwis   12"
+/// );
+/// # }
+/// ```
+pub fn table<R>(rows: R, header: bool) -> Result<String, fmt::Error>
+where
+    R: IntoIterator,
+    R::Item: IntoIterator,
+    <R::Item as IntoIterator>::Item: fmt::Display,
+{
+    let mut widths = Vec::<usize>::new();
+    let mut cells = Vec::new();
+    for row in rows {
+        let row = row
+            .into_iter()
+            .map(|cell| {
+                let mut buffer = String::new();
+                write!(buffer, "{cell}")?;
+                Ok(buffer)
+            })
+            .collect::<Result<Vec<_>, fmt::Error>>()?;
+        for (idx, cell) in row.iter().enumerate() {
+            let width = cell.chars().count();
+            match widths.get_mut(idx) {
+                Some(w) => *w = (*w).max(width),
+                None => widths.push(width),
+            }
+        }
+        cells.push(row);
+    }
+
+    let mut dest = String::new();
+    for (idx, row) in cells.iter().enumerate() {
+        if idx > 0 {
+            dest.push('\n');
+        }
+        let start = dest.len();
+        for (idx, (cell, &width)) in row.iter().zip(&widths).enumerate() {
+            if idx > 0 {
+                dest.push_str("  ");
+            }
+            dest.push_str(cell);
+            dest.extend(core::iter::repeat_n(' ', width - cell.chars().count()));
+        }
+        dest.truncate(start + dest[start..].trim_end_matches(' ').len());
+
+        if header && idx == 0 {
+            dest.push('\n');
+            for (idx, &width) in widths.iter().enumerate() {
+                if idx > 0 {
+                    dest.push_str("  ");
+                }
+                dest.extend(core::iter::repeat_n('-', width));
+            }
+        }
+    }
+    Ok(dest)
+}
+
+/// Draws a box around the lines of a text, e.g. for a banner in a CLI output
+///
+/// Every line is padded with spaces to the width of the widest line. If `title` is not empty,
+/// then it is placed in the top border. The lines are separated by `\n` without a final newline.
+///
+/// The width of a line is the number of terminal columns it takes up, so a CJK character counts
+/// as two columns. The result is escaped like any other string, so use this filter in a template
+/// that does not escape its output, e.g. a `.txt` template, or inside of a `<pre>` element.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {{ text|box(title = "askama") }}
+/// /// {{ text|box("ascii") }}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example<'a> {
+///     text: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { text: "Hello,\nworld!" }.to_string(),
+///     "\
+///     ┌─ askama ─┐\n\
+///     │ Hello,   │\n\
+///     │ world!   │\n\
+///     └──────────┘\n\
+///     +--------+\n\
+///     | Hello, |\n\
+///     | world! |\n\
+///     +--------+"
+/// );
+/// # }
+/// ```
+pub fn text_box<S: fmt::Display, T: fmt::Display>(
+    source: S,
+    style: BoxStyle,
+    title: T,
+) -> Result<String, fmt::Error> {
+    let mut text = String::new();
+    write!(text, "{source}")?;
+    let mut title_text = String::new();
+    write!(title_text, "{title}")?;
+
+    let mut lines = text
+        .lines()
+        .map(|line| (line, text_width(line)))
+        .collect::<Vec<_>>();
+    if lines.is_empty() {
+        lines.push(("", 0));
+    }
+    let mut width = lines
+        .iter()
+        .map(|&(_, width)| width)
+        .max()
+        .unwrap_or_default();
+    let title_width = text_width(&title_text);
+    if !title_text.is_empty() {
+        // At least one horizontal line on each side of the title.
+        width = width.max(title_width + 2);
+    }
+
+    let [
+        top_left,
+        top_right,
+        bottom_left,
+        bottom_right,
+        horizontal,
+        vertical,
+    ] = match style {
+        BoxStyle::Unicode => ['┌', '┐', '└', '┘', '─', '│'],
+        BoxStyle::Ascii => ['+', '+', '+', '+', '-', '|'],
+    };
+    let mut dest = String::new();
+    dest.push(top_left);
+    if title_text.is_empty() {
+        dest.extend(core::iter::repeat_n(horizontal, width + 2));
+    } else {
+        dest.push(horizontal);
+        dest.push(' ');
+        dest.push_str(&title_text);
+        dest.push(' ');
+        dest.extend(core::iter::repeat_n(horizontal, width - title_width - 1));
+    }
+    dest.push(top_right);
+    for (line, line_width) in lines {
+        dest.push('\n');
+        dest.push(vertical);
+        dest.push(' ');
+        dest.push_str(line);
+        dest.extend(core::iter::repeat_n(' ', width - line_width + 1));
+        dest.push(vertical);
+    }
+    dest.push('\n');
+    dest.push(bottom_left);
+    dest.extend(core::iter::repeat_n(horizontal, width + 2));
+    dest.push(bottom_right);
+    Ok(dest)
+}
+
+/// The characters to draw the border of a [`text_box()`] with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoxStyle {
+    /// Unicode box-drawing characters, e.g. `┌─┐`
+    Unicode,
+    /// ASCII characters, e.g. `+-+`
+    Ascii,
+}
+
+fn text_width(s: &str) -> usize {
+    s.chars().map(super::builtin::char_width).sum()
+}
+
+/// Escapes HTML characters, but leaves existing character references untouched
+///
+/// The characters `"`, `&`, `'`, `<` and `>` are escaped like the [`escape`](super::escape)
+/// filter does, except that an ampersand `&` which starts a character reference like `&amp;`,
+/// `&#38;` or `&#x26;` is copied verbatim. Use this filter for text that may or may not be escaped
+/// already, to prevent escaping it twice.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ text|escape_once }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     text: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { text: "Fish &amp; Chips & <Peas>" }.to_string(),
+///     "<div>Fish &amp; Chips &#38; &#60;Peas&#62;</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn escape_once<S: fmt::Display>(
+    source: S,
+) -> Result<HtmlSafeOutput<EscapeOnce<S>>, Infallible> {
+    Ok(HtmlSafeOutput(EscapeOnce(source)))
+}
+
+pub struct EscapeOnce<S>(S);
+
+impl<S: fmt::Display> fmt::Display for EscapeOnce<S> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer;
+        flush_escape_once(dest, try_to_str!(self.0 => buffer))
+    }
+}
+
+impl<S: FastWritable> FastWritable for EscapeOnce<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn crate::Values,
+    ) -> crate::Result<()> {
+        let mut buffer = String::new();
+        self.0.write_into(&mut buffer, values)?;
+        Ok(flush_escape_once(dest, &buffer)?)
+    }
+}
+
+fn flush_escape_once(dest: &mut (impl fmt::Write + ?Sized), s: &str) -> fmt::Result {
+    let mut parts = s.split('&');
+    if let Some(first) = parts.next() {
+        crate::html::write_escaped_str(&mut *dest, first)?;
+    }
+    for part in parts {
+        let reference_len = character_reference_len(part);
+        dest.write_str(if reference_len > 0 { "&" } else { "&#38;" })?;
+        dest.write_str(&part[..reference_len])?;
+        crate::html::write_escaped_str(&mut *dest, &part[reference_len..])?;
+    }
+    Ok(())
+}
+
+/// Returns the length of the character reference at the start of `s`, including the trailing
+/// semicolon, if `s` starts with `name;`, `#digits;` or `#xhexdigits;`. Otherwise returns `0`.
+fn character_reference_len(s: &str) -> usize {
+    let s = s.as_bytes();
+    let (prefix_len, is_valid): (usize, fn(u8) -> bool) = match s {
+        [b'#', b'x' | b'X', ..] => (2, |c| c.is_ascii_hexdigit()),
+        [b'#', ..] => (1, |c| c.is_ascii_digit()),
+        [c, ..] if c.is_ascii_alphabetic() => (0, |c| c.is_ascii_alphanumeric()),
+        _ => return 0,
+    };
+    let body_len = s[prefix_len..].iter().take_while(|&&c| is_valid(c)).count();
+    match s.get(prefix_len + body_len) {
+        Some(b';') if body_len > 0 => prefix_len + body_len + 1,
+        _ => 0,
+    }
+}
+
+/// HTML escapes the value, and wraps all occurrences of `query` in `<mark>` elements
+///
+/// The search is case-insensitive, and the matches do not overlap, i.e. the text is searched from
+/// left to right. The query is searched in the unescaped text, so neither the query nor the value
+/// can inject markup into the output. An empty query only escapes the value.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ text|highlight(query) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     text: &'a str,
+///     query: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { text: "Rust & rusty <tools>", query: "rust" }.to_string(),
+///     "<div><mark>Rust</mark> &#38; <mark>rust</mark>y &#60;tools&#62;</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn highlight<S: fmt::Display, Q: fmt::Display>(
+    source: S,
+    query: Q,
+) -> Result<HtmlSafeOutput<Highlight<S, Q>>, Infallible> {
+    Ok(HtmlSafeOutput(Highlight { source, query }))
+}
+
+pub struct Highlight<S, Q> {
+    source: S,
+    query: Q,
+}
+
+impl<S: fmt::Display, Q: fmt::Display> fmt::Display for Highlight<S, Q> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut source_buffer;
+        let mut query_buffer;
+        flush_highlight(
+            dest,
+            try_to_str!(self.source => source_buffer),
+            try_to_str!(self.query => query_buffer),
+        )
+    }
+}
+
+impl<S: FastWritable, Q: fmt::Display> FastWritable for Highlight<S, Q> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn crate::Values,
+    ) -> crate::Result<()> {
+        let mut buffer = String::new();
+        self.source.write_into(&mut buffer, values)?;
+        let mut query_buffer;
+        Ok(flush_highlight(
+            dest,
+            &buffer,
+            try_to_str!(self.query => query_buffer),
+        )?)
+    }
+}
+
+fn flush_highlight(dest: &mut (impl fmt::Write + ?Sized), s: &str, query: &str) -> fmt::Result {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return crate::html::write_escaped_str(dest, s);
+    }
+
+    let mut last = 0;
+    let mut start = 0;
+    while start < s.len() {
+        if let Some(len) = case_insensitive_prefix_len(&s[start..], &query) {
+            crate::html::write_escaped_str(&mut *dest, &s[last..start])?;
+            dest.write_str("<mark>")?;
+            crate::html::write_escaped_str(&mut *dest, &s[start..start + len])?;
+            dest.write_str("</mark>")?;
+            start += len;
+            last = start;
+        } else {
+            start += s[start..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    crate::html::write_escaped_str(dest, &s[last..])
+}
+
+/// Returns the length in bytes of the prefix of `s` that equals the lowercase `query`, ignoring
+/// the case of `s`.
+fn case_insensitive_prefix_len(s: &str, query: &[char]) -> Option<usize> {
+    let mut query = query.iter();
+    for (idx, c) in s.char_indices() {
+        for c in c.to_lowercase() {
+            if query.next() != Some(&c) {
+                return None;
+            }
+        }
+        if query.len() == 0 {
+            return Some(idx + c.len_utf8());
+        }
+    }
+    None
+}
+
 /// Converts to lowercase
 ///
 /// ```
@@ -503,13 +1112,17 @@ fn flush_trim(dest: &mut (impl fmt::Write + ?Sized), collector: TrimCollector) -
     dest.write_str(collector.0.trim_end())
 }
 
-/// Capitalize a value. The first character will be uppercase, all others lowercase.
+/// Strip leading and trailing whitespace from every line
+///
+/// Line breaks are kept, so empty lines remain empty. If the optional [`bool`] argument
+/// `trailing_only` is `true`, only trailing whitespace is removed and indentation is kept.
 ///
 /// ```
 /// # #[cfg(feature = "code-in-doc")] {
 /// # use askama::Template;
 /// /// ```jinja
-/// /// <div>{{ example|capitalize }}</div>
+/// /// <pre>{{ example|trim_lines }}</pre>
+/// /// <pre>{{ example|trim_lines(trailing_only = true) }}</pre>
 /// /// ```
 /// #[derive(Template)]
 /// #[template(ext = "html", in_doc = true)]
@@ -518,13 +1131,262 @@ fn flush_trim(dest: &mut (impl fmt::Write + ?Sized), collector: TrimCollector) -
 /// }
 ///
 /// assert_eq!(
-///     Example { example: "hello" }.to_string(),
-///     "<div>Hello</div>"
-/// );
-///
-/// assert_eq!(
-///     Example { example: "hElLO" }.to_string(),
-///     "<div>Hello</div>"
+///     Example { example: "  a  \n\n    b\t" }.to_string(),
+///     "<pre>a\n\nb</pre>\n<pre>  a\n\n    b</pre>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn trim_lines<S: fmt::Display>(
+    source: S,
+    trailing_only: bool,
+) -> Result<TrimLines<S>, Infallible> {
+    Ok(TrimLines {
+        source,
+        trailing_only,
+    })
+}
+
+pub struct TrimLines<S> {
+    source: S,
+    trailing_only: bool,
+}
+
+impl<S: fmt::Display> fmt::Display for TrimLines<S> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer = String::new();
+        write!(buffer, "{}", self.source)?;
+        flush_trim_lines(dest, &buffer, self.trailing_only)
+    }
+}
+
+impl<S: FastWritable> FastWritable for TrimLines<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn crate::Values,
+    ) -> crate::Result<()> {
+        let mut buffer = String::new();
+        self.source.write_into(&mut buffer, values)?;
+        Ok(flush_trim_lines(dest, &buffer, self.trailing_only)?)
+    }
+}
+
+fn flush_trim_lines(
+    dest: &mut (impl fmt::Write + ?Sized),
+    s: &str,
+    trailing_only: bool,
+) -> fmt::Result {
+    for line in s.split_inclusive('\n') {
+        let (content, line_break) = match line.strip_suffix('\n') {
+            Some(content) => match content.strip_suffix('\r') {
+                Some(content) => (content, "\r\n"),
+                None => (content, "\n"),
+            },
+            None => (line, ""),
+        };
+        let content = match trailing_only {
+            true => content.trim_end(),
+            false => content.trim(),
+        };
+        dest.write_str(content)?;
+        dest.write_str(line_break)?;
+    }
+    Ok(())
+}
+
+/// Normalize the whitespace of a plain text, keeping its paragraphs
+///
+/// Runs of spaces, tabs and other whitespace within a line are collapsed into a single space,
+/// and leading and trailing whitespace is removed from every line. Runs of blank lines are
+/// collapsed into a single blank line, and blank lines at the start and the end of the text are
+/// removed. Lines are separated by `\n`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <pre>{{ example|normalize_ws }}</pre>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "\n  Hello,   \t world!\n\n\n\nBye.  \n\n" }.to_string(),
+///     "<pre>Hello, world!\n\nBye.</pre>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn normalize_ws<S: fmt::Display>(source: S) -> Result<NormalizeWs<S>, Infallible> {
+    Ok(NormalizeWs(source))
+}
+
+pub struct NormalizeWs<S>(S);
+
+impl<S: fmt::Display> fmt::Display for NormalizeWs<S> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer;
+        flush_normalize_ws(dest, try_to_str!(self.0 => buffer))
+    }
+}
+
+impl<S: FastWritable> FastWritable for NormalizeWs<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn crate::Values,
+    ) -> crate::Result<()> {
+        let mut buffer = String::new();
+        self.0.write_into(&mut buffer, values)?;
+        Ok(flush_normalize_ws(dest, &buffer)?)
+    }
+}
+
+fn flush_normalize_ws(dest: &mut (impl fmt::Write + ?Sized), s: &str) -> fmt::Result {
+    // `None` before the first paragraph, `Some(true)` after a blank line.
+    let mut blank_line = None;
+    for line in s.lines() {
+        let mut words = line.split_whitespace();
+        let Some(first) = words.next() else {
+            blank_line = blank_line.map(|_| true);
+            continue;
+        };
+        match blank_line {
+            Some(true) => dest.write_str("\n\n")?,
+            Some(false) => dest.write_char('\n')?,
+            None => {}
+        }
+        blank_line = Some(false);
+        dest.write_str(first)?;
+        for word in words {
+            dest.write_char(' ')?;
+            dest.write_str(word)?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove the common leading whitespace from all lines
+///
+/// Like Python's [`textwrap.dedent()`](https://docs.python.org/3/library/textwrap.html#textwrap.dedent),
+/// this allows indenting a block of text in the template for readability, but rendering it
+/// flush-left. Only spaces and tabs count as indentation, and they have to match exactly, so
+/// `"  "` and `"\t"` have no common prefix. Lines that consist only of whitespace are ignored
+/// when computing the common indentation, and they are rendered as empty lines.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <pre>
+/// ///     {%- filter dedent %}
+/// ///         fn main() {
+/// ///             println!("Hello, world!");
+/// ///         }
+/// ///     {%- endfilter %}
+/// /// </pre>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example;
+///
+/// assert_eq!(
+///     Example.to_string(),
+///     "<pre>\nfn main() {\n    println!(&#34;Hello, world!&#34;);\n}\n</pre>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn dedent<S: fmt::Display>(source: S) -> Result<Dedent<S>, Infallible> {
+    Ok(Dedent(source))
+}
+
+pub struct Dedent<S>(S);
+
+impl<S: fmt::Display> fmt::Display for Dedent<S> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer;
+        flush_dedent(dest, try_to_str!(self.0 => buffer))
+    }
+}
+
+impl<S: FastWritable> FastWritable for Dedent<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn crate::Values,
+    ) -> crate::Result<()> {
+        let mut buffer = String::new();
+        self.0.write_into(&mut buffer, values)?;
+        Ok(flush_dedent(dest, &buffer)?)
+    }
+}
+
+fn flush_dedent(dest: &mut (impl fmt::Write + ?Sized), s: &str) -> fmt::Result {
+    fn indentation(line: &str) -> &str {
+        &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+    }
+
+    let mut common: Option<&str> = None;
+    for line in s.lines().filter(|line| !line.trim().is_empty()) {
+        let indent = indentation(line);
+        common = Some(match common {
+            None => indent,
+            Some(common) => {
+                let len = common
+                    .bytes()
+                    .zip(indent.bytes())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                &common[..len]
+            }
+        });
+    }
+    let common = common.unwrap_or_default();
+
+    for line in s.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\r', '\n']);
+        let line_break = &line[content.len()..];
+        if !content.trim().is_empty() {
+            dest.write_str(&content[common.len()..])?;
+        }
+        dest.write_str(line_break)?;
+    }
+    Ok(())
+}
+
+/// Capitalize a value. The first character will be uppercase, all others lowercase.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|capitalize }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "hello" }.to_string(),
+///     "<div>Hello</div>"
+/// );
+///
+/// assert_eq!(
+///     Example { example: "hElLO" }.to_string(),
+///     "<div>Hello</div>"
 /// );
 /// # }
 /// ```
@@ -570,6 +1432,187 @@ fn flush_capitalize(dest: &mut (impl fmt::Write + ?Sized), s: &str) -> fmt::Resu
     }
 }
 
+/// Shortens a string to `length` characters by replacing its middle with "…"
+///
+/// This is useful for long file paths or hashes, where the start and the end are more telling
+/// than the middle. Strings that have at most `length` characters are not changed.
+///
+/// Otherwise, the output has exactly `length` characters: "…" takes one character, the start
+/// keeps `(length - 1) / 2` characters, and the end keeps the remaining ones. So if the
+/// characters cannot be split evenly, then the end (e.g. the file name) keeps one more.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ path|ellipsize_middle(16) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     path: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { path: "src/filters/alloc.rs" }.to_string(),
+///     "<div>src/fil…alloc.rs</div>"
+/// );
+///
+/// assert_eq!(
+///     Example { path: "src/lib.rs" }.to_string(),
+///     "<div>src/lib.rs</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn ellipsize_middle<S: fmt::Display>(
+    source: S,
+    length: usize,
+) -> Result<EllipsizeMiddle<S>, Infallible> {
+    Ok(EllipsizeMiddle { source, length })
+}
+
+pub struct EllipsizeMiddle<S> {
+    source: S,
+    length: usize,
+}
+
+impl<S: fmt::Display> fmt::Display for EllipsizeMiddle<S> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer;
+        flush_ellipsize_middle(dest, try_to_str!(self.source => buffer), self.length)
+    }
+}
+
+impl<S: FastWritable> FastWritable for EllipsizeMiddle<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn crate::Values,
+    ) -> crate::Result<()> {
+        let mut buffer = String::new();
+        self.source.write_into(&mut buffer, values)?;
+        Ok(flush_ellipsize_middle(dest, &buffer, self.length)?)
+    }
+}
+
+fn flush_ellipsize_middle(
+    dest: &mut (impl fmt::Write + ?Sized),
+    s: &str,
+    length: usize,
+) -> fmt::Result {
+    let count = s.chars().count();
+    if count <= length {
+        return dest.write_str(s);
+    } else if length == 0 {
+        return Ok(());
+    }
+
+    let start = (length - 1) / 2;
+    let end = length - 1 - start;
+    let start = s.char_indices().nth(start).map_or(s.len(), |(idx, _)| idx);
+    let end = s
+        .char_indices()
+        .nth(count - end)
+        .map_or(s.len(), |(idx, _)| idx);
+    dest.write_str(&s[..start])?;
+    dest.write_char('…')?;
+    dest.write_str(&s[end..])
+}
+
+/// Masks a sensitive value, e.g. a credit card number, revealing only its last `last` characters
+///
+/// All other characters are replaced with `mask`, which is `'*'` by default. The optional argument
+/// `first` reveals the first characters, too. The characters are counted as `char`s.
+///
+/// If the value has no more than `first + last` characters, it is masked completely, so that the
+/// filter never reveals a whole value.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ card|redact(4) }}</div>
+/// /// <div>{{ token|redact(2, first = 3, mask = '#') }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     card: &'a str,
+///     token: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { card: "4111111111111234", token: "sk_live_a1b2c3" }.to_string(),
+///     "<div>************1234</div>\n<div>sk_#########c3</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn redact<S: fmt::Display>(
+    source: S,
+    last: usize,
+    first: usize,
+    mask: char,
+) -> Result<Redact<S>, Infallible> {
+    Ok(Redact {
+        source,
+        last,
+        first,
+        mask,
+    })
+}
+
+pub struct Redact<S> {
+    source: S,
+    last: usize,
+    first: usize,
+    mask: char,
+}
+
+impl<S: fmt::Display> fmt::Display for Redact<S> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer;
+        flush_redact(dest, try_to_str!(self.source => buffer), self)
+    }
+}
+
+impl<S: FastWritable> FastWritable for Redact<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn crate::Values,
+    ) -> crate::Result<()> {
+        let mut buffer = String::new();
+        self.source.write_into(&mut buffer, values)?;
+        Ok(flush_redact(dest, &buffer, self)?)
+    }
+}
+
+fn flush_redact<S>(
+    dest: &mut (impl fmt::Write + ?Sized),
+    s: &str,
+    redact: &Redact<S>,
+) -> fmt::Result {
+    let count = s.chars().count();
+    let (first, last) = match redact.first.checked_add(redact.last) {
+        Some(revealed) if revealed < count => (redact.first, redact.last),
+        _ => (0, 0),
+    };
+    for (idx, c) in s.chars().enumerate() {
+        if idx < first || idx >= count - last {
+            dest.write_char(c)?;
+        } else {
+            dest.write_char(redact.mask)?;
+        }
+    }
+    Ok(())
+}
+
 /// Count the words in that string.
 ///
 /// ```
@@ -594,137 +1637,871 @@ fn flush_capitalize(dest: &mut (impl fmt::Write + ?Sized), s: &str) -> fmt::Resu
 pub fn wordcount<S>(source: S) -> Wordcount<S> {
     Wordcount(Cell::new(Some(WordcountInner {
         source,
-        buffer: String::new(),
-    })))
+        buffer: String::new(),
+    })))
+}
+
+pub struct Wordcount<S>(Cell<Option<WordcountInner<S>>>);
+
+struct WordcountInner<S> {
+    source: S,
+    buffer: String,
+}
+
+impl<S: fmt::Display> fmt::Display for Wordcount<S> {
+    #[inline]
+    fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(mut inner) = self.0.take() {
+            write!(inner.buffer, "{}", inner.source)?;
+            self.0.set(Some(inner));
+        }
+        Ok(())
+    }
+}
+
+impl<S: FastWritable> FastWritable for Wordcount<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        _: &mut W,
+        values: &dyn crate::Values,
+    ) -> crate::Result<()> {
+        if let Some(mut inner) = self.0.take() {
+            inner.source.write_into(&mut inner.buffer, values)?;
+            self.0.set(Some(inner));
+        }
+        Ok(())
+    }
+}
+
+impl<S> Wordcount<S> {
+    pub fn into_count(self) -> usize {
+        if let Some(inner) = self.0.into_inner() {
+            inner.buffer.split_whitespace().count()
+        } else {
+            0
+        }
+    }
+}
+
+/// Check if the value contains the substring `needle`
+///
+/// The comparison is case-sensitive.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {% if path|contains("/admin/") %}<b>admin</b>{% else %}user{% endif %}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     path: &'a str,
+/// }
+///
+/// assert_eq!(Example { path: "/admin/users" }.to_string(), "<b>admin</b>");
+/// assert_eq!(Example { path: "/Admin/users" }.to_string(), "user");
+/// # }
+/// ```
+#[inline]
+pub fn contains<S: fmt::Display, N: AsRef<str>>(source: S, needle: N) -> Result<bool, fmt::Error> {
+    let mut buffer;
+    Ok(try_to_str!(source => buffer).contains(needle.as_ref()))
+}
+
+/// Count the non-overlapping occurrences of the substring `needle` in the value
+///
+/// The comparison is case-sensitive. An empty `needle` never matches, so the result is `0`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ text|count_matches("aa") }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     text: &'a str,
+/// }
+///
+/// assert_eq!(Example { text: "aaaaa Aa" }.to_string(), "<div>2</div>");
+/// # }
+/// ```
+#[inline]
+pub fn count_matches<S: fmt::Display, N: AsRef<str>>(
+    source: S,
+    needle: N,
+) -> Result<usize, fmt::Error> {
+    let needle = needle.as_ref();
+    if needle.is_empty() {
+        return Ok(0);
+    }
+    let mut buffer;
+    Ok(try_to_str!(source => buffer).matches(needle).count())
+}
+
+/// Return a title cased version of the value. Words will start with uppercase letters, all
+/// remaining characters are lowercase.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|title }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "hello WORLD" }.to_string(),
+///     "<div>Hello World</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn title<S: fmt::Display>(source: S) -> Result<Title<S>, Infallible> {
+    Ok(Title(source))
+}
+
+pub struct Title<S>(S);
+
+impl<S: fmt::Display> fmt::Display for Title<S> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer;
+        flush_title(dest, try_to_str!(self.0 => buffer))
+    }
+}
+
+impl<S: FastWritable> FastWritable for Title<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn crate::Values,
+    ) -> crate::Result<()> {
+        let mut buffer = String::new();
+        self.0.write_into(&mut buffer, values)?;
+        Ok(flush_title(dest, &buffer)?)
+    }
+}
+
+fn flush_title(dest: &mut (impl fmt::Write + ?Sized), s: &str) -> fmt::Result {
+    for word in s.split_inclusive(char::is_whitespace) {
+        flush_capitalize(dest, word)?;
+    }
+    Ok(())
+}
+
+/// Return a title cased version of the value. Alias for the [`|title`](title) filter.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|titlecase }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "hello WORLD" }.to_string(),
+///     "<div>Hello World</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn titlecase<S: fmt::Display>(source: S) -> Result<Title<S>, Infallible> {
+    title(source)
+}
+
+/// Convert an identifier to `snake_case`
+///
+/// The value is split into words at underscores, hyphens, whitespace and any other character that
+/// is not alphanumeric, and at case transitions, i.e. before an uppercase letter that follows a
+/// lowercase letter or a digit. A run of uppercase letters is treated as an acronym: it is one
+/// word, but its last letter starts a new word if a lowercase letter follows, so `"HTTPServer"`
+/// consists of the words `HTTP` and `Server`. The words are lowercased and joined with `_`.
+///
+/// Acronyms are not kept in uppercase by any of the case conversion filters, e.g. `"HTTPServer"`
+/// becomes `"http_server"`, `"httpServer"`, `"HttpServer"` or `"http-server"`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|snake_case }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "HTTPServer my-field" }.to_string(),
+///     "<div>http_server_my_field</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn snake_case<S: fmt::Display>(source: S) -> Result<ConvertCase<S>, Infallible> {
+    Ok(ConvertCase(source, Case::Snake))
+}
+
+/// Convert an identifier to `camelCase`, i.e. lower camel case
+///
+/// The value is split into words like in the [`|snake_case`](snake_case) filter. The first word is
+/// lowercased, all following words are capitalized, and the words are joined without a separator.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|camel_case }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "HTTPServer my-field" }.to_string(),
+///     "<div>httpServerMyField</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn camel_case<S: fmt::Display>(source: S) -> Result<ConvertCase<S>, Infallible> {
+    Ok(ConvertCase(source, Case::Camel))
+}
+
+/// Convert an identifier to `PascalCase`, i.e. upper camel case
+///
+/// The value is split into words like in the [`|snake_case`](snake_case) filter. All words are
+/// capitalized, and joined without a separator.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|pascal_case }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "HTTPServer my-field" }.to_string(),
+///     "<div>HttpServerMyField</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn pascal_case<S: fmt::Display>(source: S) -> Result<ConvertCase<S>, Infallible> {
+    Ok(ConvertCase(source, Case::Pascal))
+}
+
+/// Convert an identifier to `kebab-case`
+///
+/// The value is split into words like in the [`|snake_case`](snake_case) filter. The words are
+/// lowercased and joined with `-`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|kebab_case }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "HTTPServer my_field" }.to_string(),
+///     "<div>http-server-my-field</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn kebab_case<S: fmt::Display>(source: S) -> Result<ConvertCase<S>, Infallible> {
+    Ok(ConvertCase(source, Case::Kebab))
+}
+
+pub struct ConvertCase<S>(S, Case);
+
+#[derive(Clone, Copy)]
+enum Case {
+    Snake,
+    Camel,
+    Pascal,
+    Kebab,
+}
+
+impl<S: fmt::Display> fmt::Display for ConvertCase<S> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer;
+        flush_convert_case(dest, try_to_str!(self.0 => buffer), self.1)
+    }
+}
+
+impl<S: FastWritable> FastWritable for ConvertCase<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn crate::Values,
+    ) -> crate::Result<()> {
+        let mut buffer = String::new();
+        self.0.write_into(&mut buffer, values)?;
+        Ok(flush_convert_case(dest, &buffer, self.1)?)
+    }
+}
+
+fn flush_convert_case(dest: &mut (impl fmt::Write + ?Sized), s: &str, case: Case) -> fmt::Result {
+    let mut first = true;
+    for_each_case_word(s, |word| {
+        match case {
+            Case::Snake if !first => dest.write_char('_')?,
+            Case::Kebab if !first => dest.write_char('-')?,
+            _ => {}
+        }
+        let mut chars = word.chars();
+        if matches!(case, Case::Pascal) || (matches!(case, Case::Camel) && !first) {
+            if let Some(c) = chars.next() {
+                for c in c.to_uppercase() {
+                    dest.write_char(c)?;
+                }
+            }
+        }
+        for c in chars.flat_map(char::to_lowercase) {
+            dest.write_char(c)?;
+        }
+        first = false;
+        Ok(())
+    })
+}
+
+/// Splits an identifier into words, see [`snake_case()`].
+fn for_each_case_word(s: &str, mut f: impl FnMut(&str) -> fmt::Result) -> fmt::Result {
+    for segment in s.split(|c: char| !c.is_alphanumeric()) {
+        if segment.is_empty() {
+            continue;
+        }
+        let mut start = 0;
+        let mut prev_upper = true;
+        let mut chars = segment.char_indices().peekable();
+        while let Some((idx, c)) = chars.next() {
+            // `prev_upper` is `true` for the first character, so it never starts a new word.
+            let upper = c.is_uppercase();
+            if upper
+                && (!prev_upper || chars.peek().is_some_and(|&(_, next)| next.is_lowercase()))
+                && idx > 0
+            {
+                f(&segment[start..idx])?;
+                start = idx;
+            }
+            prev_upper = upper;
+        }
+        f(&segment[start..])?;
+    }
+    Ok(())
+}
+
+/// Limit the text to `count` whitespace-separated words, appends `suffix` if truncated
+///
+/// If the text is truncated, the remaining words are separated by a single space. Otherwise the
+/// text is returned unchanged.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|truncatewords(2) }}</div>
+/// /// <div>{{ example|truncatewords(3, suffix = " [more]") }}</div>
+/// /// <div>{{ example|truncatewords(4) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "The quick  brown\tfox" }.to_string(),
+///     "<div>The quick...</div>\n\
+///      <div>The quick brown [more]</div>\n\
+///      <div>The quick  brown\tfox</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn truncatewords<S: fmt::Display, T: fmt::Display>(
+    source: S,
+    count: usize,
+    suffix: T,
+) -> Result<TruncateWords<S, T>, Infallible> {
+    Ok(TruncateWords {
+        source,
+        count,
+        suffix,
+    })
+}
+
+pub struct TruncateWords<S, T> {
+    source: S,
+    count: usize,
+    suffix: T,
+}
+
+impl<S: fmt::Display, T: fmt::Display> fmt::Display for TruncateWords<S, T> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer;
+        if flush_truncatewords(dest, try_to_str!(self.source => buffer), self.count)? {
+            write!(dest, "{}", self.suffix)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: FastWritable, T: FastWritable> FastWritable for TruncateWords<S, T> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn crate::Values,
+    ) -> crate::Result<()> {
+        let mut buffer = String::new();
+        self.source.write_into(&mut buffer, values)?;
+        if flush_truncatewords(dest, &buffer, self.count)? {
+            self.suffix.write_into(dest, values)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns `true` if the text was truncated, so the suffix needs to be written.
+fn flush_truncatewords(
+    dest: &mut (impl fmt::Write + ?Sized),
+    s: &str,
+    count: usize,
+) -> Result<bool, fmt::Error> {
+    if s.split_whitespace().nth(count).is_none() {
+        dest.write_str(s)?;
+        return Ok(false);
+    }
+    for (idx, word) in s.split_whitespace().take(count).enumerate() {
+        if idx > 0 {
+            dest.write_char(' ')?;
+        }
+        dest.write_str(word)?;
+    }
+    Ok(true)
+}
+
+/// Strips HTML from the text, and limits it to `count` words, appends `suffix` if truncated
+///
+/// This filter is meant for search result snippets and similar plaintext previews of HTML
+/// content: tags, comments and the content of `<script>` and `<style>` elements are removed,
+/// character references like `&amp;` are decoded, and all whitespace is collapsed into single
+/// spaces. Block-level tags like `<p>` or `<br>` separate words.
+///
+/// The output is plaintext, so it is escaped like any other value.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ body|excerpt(4) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     body: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example {
+///         body: "<h1>Fish &amp; Chips</h1>\n<p>A <em>classic</em> dish.</p>",
+///     }.to_string(),
+///     "<div>Fish &#38; Chips A...</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn excerpt<S: fmt::Display, T: fmt::Display>(
+    source: S,
+    count: usize,
+    suffix: T,
+) -> Result<Excerpt<S, T>, Infallible> {
+    Ok(Excerpt {
+        source,
+        count,
+        suffix,
+    })
 }
 
-pub struct Wordcount<S>(Cell<Option<WordcountInner<S>>>);
-
-struct WordcountInner<S> {
+pub struct Excerpt<S, T> {
     source: S,
-    buffer: String,
+    count: usize,
+    suffix: T,
 }
 
-impl<S: fmt::Display> fmt::Display for Wordcount<S> {
+impl<S: fmt::Display, T: fmt::Display> fmt::Display for Excerpt<S, T> {
     #[inline]
-    fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if let Some(mut inner) = self.0.take() {
-            write!(inner.buffer, "{}", inner.source)?;
-            self.0.set(Some(inner));
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer;
+        if flush_excerpt(dest, try_to_str!(self.source => buffer), self.count)? {
+            write!(dest, "{}", self.suffix)?;
         }
         Ok(())
     }
 }
 
-impl<S: FastWritable> FastWritable for Wordcount<S> {
+impl<S: FastWritable, T: FastWritable> FastWritable for Excerpt<S, T> {
     #[inline]
     fn write_into<W: fmt::Write + ?Sized>(
         &self,
-        _: &mut W,
+        dest: &mut W,
         values: &dyn crate::Values,
     ) -> crate::Result<()> {
-        if let Some(mut inner) = self.0.take() {
-            inner.source.write_into(&mut inner.buffer, values)?;
-            self.0.set(Some(inner));
+        let mut buffer = String::new();
+        self.source.write_into(&mut buffer, values)?;
+        if flush_excerpt(dest, &buffer, self.count)? {
+            self.suffix.write_into(dest, values)?;
         }
         Ok(())
     }
 }
 
-impl<S> Wordcount<S> {
-    pub fn into_count(self) -> usize {
-        if let Some(inner) = self.0.into_inner() {
-            inner.buffer.split_whitespace().count()
+/// Returns `true` if the text was truncated, so the suffix needs to be written.
+fn flush_excerpt(
+    dest: &mut (impl fmt::Write + ?Sized),
+    s: &str,
+    count: usize,
+) -> Result<bool, fmt::Error> {
+    let text = strip_html(s);
+    let mut words = text.split_whitespace();
+    for (idx, word) in words.by_ref().take(count).enumerate() {
+        if idx > 0 {
+            dest.write_char(' ')?;
+        }
+        dest.write_str(word)?;
+    }
+    Ok(words.next().is_some())
+}
+
+/// Elements that are replaced by a space, so the words before and after them are not joined.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "td",
+    "th",
+    "tr",
+    "ul",
+];
+
+/// Removes tags, comments and the content of `<script>` and `<style>` elements from HTML text,
+/// and decodes character references.
+fn strip_html(mut s: &str) -> String {
+    let mut text = String::with_capacity(s.len());
+    while let Some(pos) = s.find(['<', '&']) {
+        text.push_str(&s[..pos]);
+        s = &s[pos..];
+        if let Some(comment) = s.strip_prefix("<!--") {
+            s = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+        } else if let Some(tag) = s.strip_prefix('<').filter(|tag| {
+            tag.starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!')
+        }) {
+            let (tag, rest) = tag.split_once('>').unwrap_or((tag, ""));
+            let is_end_tag = tag.starts_with('/');
+            let name = tag
+                .trim_start_matches('/')
+                .split(|c: char| !c.is_ascii_alphanumeric())
+                .next()
+                .unwrap_or_default();
+            s = rest;
+            if !is_end_tag
+                && ["script", "style"]
+                    .iter()
+                    .any(|e| name.eq_ignore_ascii_case(e))
+            {
+                s = skip_raw_text(s, name);
+            } else if BLOCK_ELEMENTS.iter().any(|e| name.eq_ignore_ascii_case(e)) {
+                text.push(' ');
+            }
+        } else if let Some((c, len)) = decode_char_ref(s) {
+            text.push(c);
+            s = &s[len..];
         } else {
-            0
+            text.push_str(&s[..1]);
+            s = &s[1..];
         }
     }
+    text.push_str(s);
+    text
 }
 
-/// Return a title cased version of the value. Words will start with uppercase letters, all
-/// remaining characters are lowercase.
+/// Returns the text after the end tag `</{name}>`.
+fn skip_raw_text<'a>(mut s: &'a str, name: &str) -> &'a str {
+    while let Some(pos) = s.find("</") {
+        s = &s[pos + 2..];
+        if s.get(..name.len())
+            .is_some_and(|n| n.eq_ignore_ascii_case(name))
+        {
+            return s.split_once('>').map_or("", |(_, rest)| rest);
+        }
+    }
+    ""
+}
+
+/// Decodes a character reference like `&amp;`, `&#38;` or `&#x26;` at the start of `s`, and
+/// returns the decoded character and the length of the reference.
+fn decode_char_ref(s: &str) -> Option<(char, usize)> {
+    let end = s.bytes().take(12).position(|b| b == b';')?;
+    let c = match &s[1..end] {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        name => {
+            let num = name.strip_prefix('#')?;
+            let code = match num.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => num.parse().ok()?,
+            };
+            char::from_u32(code)?
+        }
+    };
+    Some((c, end + 1))
+}
+
+/// Formats a number with the digit grouping and decimal separator of a [`Locale`]
+///
+/// The locale is read from the runtime values under the key `"locale"`
+/// (see [`Template::render_with_values()`][crate::Template::render_with_values]).
+/// If no locale was supplied, [`Locale::EN`] is used. Input that does not look like a
+/// number, e.g. `"NaN"`, is passed through unchanged.
 ///
 /// ```
 /// # #[cfg(feature = "code-in-doc")] {
+/// # use std::any::Any;
 /// # use askama::Template;
+/// # use askama::filters::Locale;
 /// /// ```jinja
-/// /// <div>{{ example|title }}</div>
+/// /// <div>{{ amount|localize_number }}</div>
 /// /// ```
 /// #[derive(Template)]
 /// #[template(ext = "html", in_doc = true)]
-/// struct Example<'a> {
-///     example: &'a str,
+/// struct Example {
+///     amount: f64,
 /// }
 ///
+/// let tmpl = Example { amount: 1234567.5 };
+/// assert_eq!(tmpl.to_string(), "<div>1,234,567.5</div>");
 /// assert_eq!(
-///     Example { example: "hello WORLD" }.to_string(),
-///     "<div>Hello World</div>"
+///     tmpl.render_with_values(&("locale", &Locale::DE as &dyn Any)).unwrap(),
+///     "<div>1.234.567,5</div>"
 /// );
 /// # }
 /// ```
 #[inline]
-pub fn title<S: fmt::Display>(source: S) -> Result<Title<S>, Infallible> {
-    Ok(Title(source))
+pub fn localize_number<S: fmt::Display>(source: S) -> Result<LocalizeNumber<S>, Infallible> {
+    Ok(LocalizeNumber(source))
 }
 
-pub struct Title<S>(S);
+pub struct LocalizeNumber<S>(S);
 
-impl<S: fmt::Display> fmt::Display for Title<S> {
+impl<S: fmt::Display> fmt::Display for LocalizeNumber<S> {
     #[inline]
     fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut buffer;
-        flush_title(dest, try_to_str!(self.0 => buffer))
+        flush_localize_number(dest, try_to_str!(self.0 => buffer), Locale::default())
     }
 }
 
-impl<S: FastWritable> FastWritable for Title<S> {
+// Floats don't implement `FastWritable`, so `S: fmt::Display` is used here to make sure that the
+// runtime values, and thus the locale, are accessible for every kind of number.
+impl<S: fmt::Display> FastWritable for LocalizeNumber<S> {
     #[inline]
     fn write_into<W: fmt::Write + ?Sized>(
         &self,
         dest: &mut W,
         values: &dyn crate::Values,
     ) -> crate::Result<()> {
-        let mut buffer = String::new();
-        self.0.write_into(&mut buffer, values)?;
-        Ok(flush_title(dest, &buffer)?)
+        let locale = match crate::get_value::<Locale>(values, Locale::KEY) {
+            Ok(locale) => *locale,
+            Err(crate::Error::ValueMissing) => Locale::default(),
+            Err(err) => return Err(err),
+        };
+        let mut buffer;
+        Ok(flush_localize_number(
+            dest,
+            try_to_str!(self.0 => buffer),
+            locale,
+        )?)
     }
 }
 
-fn flush_title(dest: &mut (impl fmt::Write + ?Sized), s: &str) -> fmt::Result {
-    for word in s.split_inclusive(char::is_whitespace) {
-        flush_capitalize(dest, word)?;
+fn flush_localize_number(
+    dest: &mut (impl fmt::Write + ?Sized),
+    s: &str,
+    locale: Locale,
+) -> fmt::Result {
+    let (sign, number) = match s.as_bytes().first() {
+        Some(b'-' | b'+') => s.split_at(1),
+        _ => ("", s),
+    };
+    let (int, frac) = match number.split_once('.') {
+        Some((int, frac)) => (int, Some(frac)),
+        None => (number, None),
+    };
+    let is_digits = |s: &str| s.bytes().all(|c| c.is_ascii_digit());
+    if int.is_empty() || !is_digits(int) || !frac.is_none_or(is_digits) {
+        return dest.write_str(s);
+    }
+
+    dest.write_str(sign)?;
+    let (head, mut tail) = int.split_at(match int.len() % 3 {
+        0 => 3,
+        n => n,
+    });
+    dest.write_str(head)?;
+    while !tail.is_empty() {
+        let (group, rest) = tail.split_at(3);
+        dest.write_char(locale.group_separator)?;
+        dest.write_str(group)?;
+        tail = rest;
+    }
+    if let Some(frac) = frac {
+        dest.write_char(locale.decimal_separator)?;
+        dest.write_str(frac)?;
     }
     Ok(())
 }
 
-/// Return a title cased version of the value. Alias for the [`|title`](title) filter.
-///
-/// ```
-/// # #[cfg(feature = "code-in-doc")] {
-/// # use askama::Template;
-/// /// ```jinja
-/// /// <div>{{ example|titlecase }}</div>
-/// /// ```
-/// #[derive(Template)]
-/// #[template(ext = "html", in_doc = true)]
-/// struct Example<'a> {
-///     example: &'a str,
-/// }
+/// Number formatting conventions used by the [`localize_number`] filter
 ///
-/// assert_eq!(
-///     Example { example: "hello WORLD" }.to_string(),
-///     "<div>Hello World</div>"
-/// );
-/// # }
-/// ```
-#[inline]
-pub fn titlecase<S: fmt::Display>(source: S) -> Result<Title<S>, Infallible> {
-    title(source)
+/// To select a locale for a template, pass it as runtime value with the key [`Locale::KEY`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    group_separator: char,
+    decimal_separator: char,
+}
+
+impl Locale {
+    /// The key that is used to look up the locale in the runtime values.
+    pub const KEY: &'static str = "locale";
+
+    /// `1,234,567.89`, e.g. used in English, Chinese or Japanese
+    pub const EN: Self = Self::new(',', '.');
+
+    /// `1.234.567,89`, e.g. used in German, Spanish or Italian
+    pub const DE: Self = Self::new('.', ',');
+
+    /// `1 234 567,89` (using a narrow no-break space), e.g. used in French or Polish
+    pub const FR: Self = Self::new('\u{202f}', ',');
+
+    /// `1'234'567.89`, used in Switzerland
+    pub const CH: Self = Self::new('\'', '.');
+
+    /// A locale using custom separators
+    #[inline]
+    pub const fn new(group_separator: char, decimal_separator: char) -> Self {
+        Self {
+            group_separator,
+            decimal_separator,
+        }
+    }
+
+    /// Find the conventions for a language tag, e.g. `"de"` or `"en-US"`
+    ///
+    /// Only the language subtag is inspected, with the exception of `*-CH`.
+    /// Returns `None` for unknown languages.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let (language, region) = match tag.split_once(['-', '_']) {
+            Some((language, region)) => (language, Some(region)),
+            None => (tag, None),
+        };
+        if region.is_some_and(|region| region.eq_ignore_ascii_case("ch")) {
+            return Some(Self::CH);
+        }
+        let locale = match language.to_ascii_lowercase().as_str() {
+            "en" | "ja" | "ko" | "th" | "zh" => Self::EN,
+            "da" | "de" | "el" | "es" | "id" | "it" | "nl" | "pt" | "ro" | "tr" => Self::DE,
+            "cs" | "fi" | "fr" | "hu" | "nb" | "no" | "pl" | "ru" | "sk" | "sv" | "uk" => Self::FR,
+            _ => return None,
+        };
+        Some(locale)
+    }
+
+    /// The character inserted between groups of thousands
+    #[inline]
+    pub const fn group_separator(&self) -> char {
+        self.group_separator
+    }
+
+    /// The character between the integral and the fractional part
+    #[inline]
+    pub const fn decimal_separator(&self) -> char {
+        self.decimal_separator
+    }
+}
+
+impl Default for Locale {
+    #[inline]
+    fn default() -> Self {
+        Self::EN
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use alloc::string::ToString;
+    use alloc::vec;
 
     use super::*;
     use crate::NO_VALUES;
@@ -750,6 +2527,216 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_chunks() {
+        let chunked = |size| chunks(1..=5, size).unwrap().collect::<Vec<_>>();
+        assert_eq!(chunked(2), [vec![1, 2], vec![3, 4], vec![5]]);
+        assert_eq!(chunked(5), [vec![1, 2, 3, 4, 5]]);
+        assert_eq!(chunked(9), [vec![1, 2, 3, 4, 5]]);
+        assert_eq!(chunked(0), Vec::<Vec<i32>>::new());
+        assert_eq!(chunks(0..0, 3).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_columns() {
+        let sizes = |len, count| {
+            columns(0..len, count)
+                .unwrap()
+                .map(|column| column.len())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(sizes(7, 3), [3, 2, 2]);
+        assert_eq!(sizes(6, 3), [2, 2, 2]);
+        assert_eq!(sizes(2, 4), [1, 1, 0, 0]);
+        assert_eq!(sizes(0, 2), [0, 0]);
+        assert_eq!(sizes(5, 0), Vec::<usize>::new());
+        assert_eq!(sizes(5, 1), [5]);
+
+        assert_eq!(
+            columns(1..=7, 3).unwrap().collect::<Vec<_>>(),
+            [vec![1, 2, 3], vec![4, 5], vec![6, 7]]
+        );
+        assert_eq!(columns(1..=7, 3).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_group_consecutive() {
+        let grouped = |items: &[(u32, char)]| {
+            group_consecutive(items.iter().copied(), |&(key, _)| key)
+                .unwrap()
+                .map(|(key, run)| (key, run.into_iter().map(|(_, c)| c).collect::<String>()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(grouped(&[]), []);
+        assert_eq!(grouped(&[(1, 'a')]), [(1, "a".to_string())]);
+        // Equal keys that are not adjacent produce separate runs.
+        assert_eq!(
+            grouped(&[(1, 'a'), (1, 'b'), (2, 'c'), (2, 'd'), (2, 'e'), (1, 'f')]),
+            [
+                (1, "ab".to_string()),
+                (2, "cde".to_string()),
+                (1, "f".to_string())
+            ]
+        );
+
+        // The input is consumed lazily.
+        let mut consumed = 0;
+        let mut runs =
+            group_consecutive([1, 1, 2, 3].into_iter().inspect(|_| consumed += 1), |&x| x).unwrap();
+        assert_eq!(runs.next(), Some((1, vec![1, 1])));
+        drop(runs);
+        assert_eq!(consumed, 3);
+    }
+
+    #[test]
+    fn test_table() {
+        let rows = [vec!["a", "bbb", "c"], vec!["dddd", "e", "ffffff"]];
+        assert_eq!(
+            table(&rows, false).unwrap(),
+            "a     bbb  c\ndddd  e    ffffff"
+        );
+        assert_eq!(
+            table(&rows, true).unwrap(),
+            "a     bbb  c\n----  ---  ------\ndddd  e    ffffff"
+        );
+
+        let rows = [vec!["x", "", ""], vec!["äöü"], vec![], vec!["y", "z"]];
+        assert_eq!(table(rows, false).unwrap(), "x\näöü\n\ny    z");
+        assert_eq!(
+            table([[1, 22], [333, 4]], true).unwrap(),
+            "1    22\n---  --\n333  4"
+        );
+        assert_eq!(table(Vec::<Vec<&str>>::new(), true).unwrap(), "");
+    }
+
+    #[test]
+    fn test_text_box() {
+        assert_eq!(
+            text_box("a\nbcd", BoxStyle::Unicode, "").unwrap(),
+            "┌─────┐\n│ a   │\n│ bcd │\n└─────┘"
+        );
+        assert_eq!(
+            text_box("a\nbcd", BoxStyle::Ascii, "").unwrap(),
+            "+-----+\n| a   |\n| bcd |\n+-----+"
+        );
+        assert_eq!(
+            text_box("a", BoxStyle::Unicode, "title").unwrap(),
+            "┌─ title ─┐\n│ a       │\n└─────────┘"
+        );
+        assert_eq!(
+            text_box("日本", BoxStyle::Unicode, "").unwrap(),
+            "┌──────┐\n│ 日本 │\n└──────┘"
+        );
+        assert_eq!(
+            text_box("\u{1f680}\nab", BoxStyle::Ascii, "").unwrap(),
+            "+----+\n| \u{1f680} |\n| ab |\n+----+"
+        );
+        assert_eq!(
+            text_box("", BoxStyle::Ascii, "").unwrap(),
+            "+--+\n|  |\n+--+"
+        );
+    }
+
+    #[test]
+    fn test_convert_case() {
+        for (source, snake, camel, pascal, kebab) in [
+            (
+                "HTTPServer",
+                "http_server",
+                "httpServer",
+                "HttpServer",
+                "http-server",
+            ),
+            (
+                "my-field name",
+                "my_field_name",
+                "myFieldName",
+                "MyFieldName",
+                "my-field-name",
+            ),
+            (
+                "getHTTP2Response",
+                "get_http2_response",
+                "getHttp2Response",
+                "GetHttp2Response",
+                "get-http2-response",
+            ),
+            (
+                "__already_snake__",
+                "already_snake",
+                "alreadySnake",
+                "AlreadySnake",
+                "already-snake",
+            ),
+            (
+                "ÄrgerÜber",
+                "ärger_über",
+                "ärgerÜber",
+                "ÄrgerÜber",
+                "ärger-über",
+            ),
+            ("A", "a", "a", "A", "a"),
+            ("", "", "", "", ""),
+        ] {
+            assert_eq!(snake_case(source).unwrap().to_string(), snake);
+            assert_eq!(camel_case(source).unwrap().to_string(), camel);
+            assert_eq!(pascal_case(source).unwrap().to_string(), pascal);
+            assert_eq!(kebab_case(source).unwrap().to_string(), kebab);
+        }
+    }
+
+    #[test]
+    fn test_highlight() {
+        assert_eq!(
+            highlight("Rust & rusty <tools>", "RUST")
+                .unwrap()
+                .to_string(),
+            "<mark>Rust</mark> &#38; <mark>rust</mark>y &#60;tools&#62;"
+        );
+        assert_eq!(
+            highlight("a < b", "<").unwrap().to_string(),
+            "a <mark>&#60;</mark> b"
+        );
+        assert_eq!(
+            highlight("aaa", "aa").unwrap().to_string(),
+            "<mark>aa</mark>a"
+        );
+        assert_eq!(highlight("<x>", "").unwrap().to_string(), "&#60;x&#62;");
+        assert_eq!(highlight("abc", "abcd").unwrap().to_string(), "abc");
+        assert_eq!(
+            highlight("Größe GRÖSSE", "größe").unwrap().to_string(),
+            "<mark>Größe</mark> GRÖSSE"
+        );
+        assert_eq!(
+            highlight("x1x11", 11).unwrap().to_string(),
+            "x1x<mark>11</mark>"
+        );
+    }
+
+    #[test]
+    fn test_escape_once() {
+        assert_eq!(escape_once("a & b").unwrap().to_string(), "a &#38; b");
+        assert_eq!(
+            escape_once("a &amp; b &#38; c &#x26; d &#X26;")
+                .unwrap()
+                .to_string(),
+            "a &amp; b &#38; c &#x26; d &#X26;"
+        );
+        assert_eq!(
+            escape_once("<a href=\"?x=1&y=2\">Tom &amp; Jerry's</a>")
+                .unwrap()
+                .to_string(),
+            "&#60;a href=&#34;?x=1&#38;y=2&#34;&#62;Tom &amp; Jerry&#39;s&#60;/a&#62;"
+        );
+        assert_eq!(escape_once("&frac12;").unwrap().to_string(), "&frac12;");
+        assert_eq!(
+            escape_once("& &; &#; &#x; &#12 &1; &&amp;")
+                .unwrap()
+                .to_string(),
+            "&#38; &#38;; &#38;#; &#38;#x; &#38;#12 &#38;1; &#38;&amp;"
+        );
+    }
+
     #[test]
     fn test_paragraphbreaks() {
         assert_eq!(
@@ -789,6 +2776,21 @@ mod tests {
         assert_eq!(trim(" Hello\tworld\t").unwrap().to_string(), "Hello\tworld");
     }
 
+    #[test]
+    fn test_trim_lines() {
+        let source = "  fn main() {  \n\t\tbody();\t\n\n   \r\n}  ";
+        assert_eq!(
+            trim_lines(source, false).unwrap().to_string(),
+            "fn main() {\nbody();\n\n\r\n}"
+        );
+        assert_eq!(
+            trim_lines(source, true).unwrap().to_string(),
+            "  fn main() {\n\t\tbody();\n\n\r\n}"
+        );
+        assert_eq!(trim_lines("", false).unwrap().to_string(), "");
+        assert_eq!(trim_lines(" \n ", false).unwrap().to_string(), "\n");
+    }
+
     #[test]
     fn test_capitalize() {
         assert_eq!(capitalize("foo").unwrap().to_string(), "Foo".to_string());
@@ -808,6 +2810,42 @@ mod tests {
         assert_eq!(capitalize("ßß").unwrap().to_string(), "SSß".to_string());
     }
 
+    #[test]
+    fn test_ellipsize_middle() {
+        assert_eq!(ellipsize_middle("abcdef", 6).unwrap().to_string(), "abcdef");
+        assert_eq!(
+            ellipsize_middle("abcdef", 10).unwrap().to_string(),
+            "abcdef"
+        );
+        assert_eq!(ellipsize_middle("abcdef", 5).unwrap().to_string(), "ab…ef");
+        assert_eq!(ellipsize_middle("abcdef", 4).unwrap().to_string(), "a…ef");
+        assert_eq!(ellipsize_middle("abcdef", 3).unwrap().to_string(), "a…f");
+        assert_eq!(ellipsize_middle("abcdef", 2).unwrap().to_string(), "…f");
+        assert_eq!(ellipsize_middle("abcdef", 1).unwrap().to_string(), "…");
+        assert_eq!(ellipsize_middle("abcdef", 0).unwrap().to_string(), "");
+        assert_eq!(ellipsize_middle("", 0).unwrap().to_string(), "");
+        assert_eq!(ellipsize_middle("äöüßéè", 4).unwrap().to_string(), "ä…éè");
+        assert_eq!(ellipsize_middle(1234567, 4).unwrap().to_string(), "1…67");
+    }
+
+    #[test]
+    fn test_redact() {
+        let redacted = |s, last, first, mask| redact(s, last, first, mask).unwrap().to_string();
+        assert_eq!(redacted("4111111111111234", 4, 0, '*'), "************1234");
+        assert_eq!(redacted("4111111111111234", 4, 2, '*'), "41**********1234");
+        assert_eq!(redacted("secret", 0, 0, '*'), "******");
+        assert_eq!(redacted("secret", 2, 0, '•'), "••••et");
+        // Values that are not longer than the revealed characters are masked completely.
+        assert_eq!(redacted("1234", 4, 0, '*'), "****");
+        assert_eq!(redacted("123", 4, 0, '*'), "***");
+        assert_eq!(redacted("12345", 3, 2, '*'), "*****");
+        assert_eq!(redacted("12345", usize::MAX, 1, '*'), "*****");
+        assert_eq!(redacted("", 4, 0, '*'), "");
+        // Characters are counted as `char`s.
+        assert_eq!(redacted("äöüßéè", 2, 1, 'x'), "äxxxéè");
+        assert_eq!(redact(1234567, 3, 0, '*').unwrap().to_string(), "****567");
+    }
+
     #[test]
     fn test_wordcount() {
         for &(word, count) in &[
@@ -845,4 +2883,159 @@ mod tests {
             "Fo\x0bOo\x0cOo\u{2002}Oo\u{3000}Bar"
         );
     }
+
+    #[test]
+    fn test_contains() {
+        assert!(contains("/admin/users", "/admin/").unwrap());
+        assert!(!contains("/Admin/users", "/admin/").unwrap());
+        assert!(!contains("/users", "/admin/").unwrap());
+        assert!(contains("abc", "").unwrap());
+        assert!(contains(12345, "234").unwrap());
+    }
+
+    #[test]
+    fn test_count_matches() {
+        assert_eq!(count_matches("a, b, c", ", ").unwrap(), 2);
+        assert_eq!(count_matches("aaaaa", "aa").unwrap(), 2);
+        assert_eq!(count_matches("Aa", "a").unwrap(), 1);
+        assert_eq!(count_matches("abc", "d").unwrap(), 0);
+        assert_eq!(count_matches("abc", "").unwrap(), 0);
+        assert_eq!(count_matches(1001, "0").unwrap(), 2);
+    }
+
+    #[test]
+    fn test_truncatewords() {
+        let text = "  Lorem ipsum\tdolor\n sit  amet ";
+        assert_eq!(
+            truncatewords(text, 3, "...").unwrap().to_string(),
+            "Lorem ipsum dolor..."
+        );
+        assert_eq!(
+            truncatewords(text, 1, " (more)").unwrap().to_string(),
+            "Lorem (more)"
+        );
+        assert_eq!(truncatewords(text, 0, "...").unwrap().to_string(), "...");
+        // Texts with at most `count` words are not changed.
+        assert_eq!(truncatewords(text, 5, "...").unwrap().to_string(), text);
+        assert_eq!(truncatewords(text, 50, "...").unwrap().to_string(), text);
+        assert_eq!(truncatewords("", 0, "...").unwrap().to_string(), "");
+        assert_eq!(truncatewords(" \t", 0, "...").unwrap().to_string(), " \t");
+
+        let mut output = String::new();
+        truncatewords(text, 2, "...")
+            .unwrap()
+            .write_into(&mut output, NO_VALUES)
+            .unwrap();
+        assert_eq!(output, "Lorem ipsum...");
+    }
+
+    #[test]
+    fn test_excerpt() {
+        let html = "<article>\n  <h1>Title</h1>\n  <p>Some   <b>bold</b>\ttext,<br>and &lt;more&gt;\
+                    &nbsp;&#8230;</p><!-- a <comment> -->\n<script>let x = '<p>';</script>\
+                    <STYLE>p { color: red }</style><p>The end.</p></article>";
+        assert_eq!(
+            excerpt(html, 100, "...").unwrap().to_string(),
+            "Title Some bold text, and <more> \u{2026} The end."
+        );
+        assert_eq!(
+            excerpt(html, 4, "...").unwrap().to_string(),
+            "Title Some bold text,..."
+        );
+        assert_eq!(excerpt(html, 0, "...").unwrap().to_string(), "...");
+        // Not a tag or character reference.
+        assert_eq!(
+            excerpt("1 < 2 && 3 > 2 &unknown; &#xZZ;", 10, "...")
+                .unwrap()
+                .to_string(),
+            "1 < 2 && 3 > 2 &unknown; &#xZZ;"
+        );
+        // An unterminated tag extends to the end of the text.
+        assert_eq!(
+            excerpt("text <p unclosed", 10, "...").unwrap().to_string(),
+            "text"
+        );
+        assert_eq!(excerpt("", 10, "...").unwrap().to_string(), "");
+
+        let mut output = String::new();
+        excerpt("<p>a</p><p>b</p><p>c</p>", 2, " [more]")
+            .unwrap()
+            .write_into(&mut output, NO_VALUES)
+            .unwrap();
+        assert_eq!(output, "a b [more]");
+    }
+
+    #[test]
+    fn test_normalize_ws() {
+        assert_eq!(normalize_ws("").unwrap().to_string(), "");
+        assert_eq!(normalize_ws(" \n\t\n ").unwrap().to_string(), "");
+        assert_eq!(normalize_ws("  a  b\t\tc  ").unwrap().to_string(), "a b c");
+        assert_eq!(
+            normalize_ws("\r\n\r\n first\r\n  line \r\n \t \r\n\r\nsecond\u{a0} para\r\n")
+                .unwrap()
+                .to_string(),
+            "first\nline\n\nsecond para"
+        );
+
+        let mut output = String::new();
+        normalize_ws("a\n\n\n\nb")
+            .unwrap()
+            .write_into(&mut output, NO_VALUES)
+            .unwrap();
+        assert_eq!(output, "a\n\nb");
+    }
+
+    #[test]
+    fn test_dedent() {
+        assert_eq!(dedent("").unwrap().to_string(), "");
+        assert_eq!(dedent("a\n  b").unwrap().to_string(), "a\n  b");
+        assert_eq!(
+            dedent("        a\n          b\n        c\n")
+                .unwrap()
+                .to_string(),
+            "a\n  b\nc\n"
+        );
+        assert_eq!(
+            dedent("\n    a\n  \n\n      b\n    ").unwrap().to_string(),
+            "\na\n\n\n  b\n"
+        );
+        assert_eq!(
+            dedent("\t\ta\r\n\t b\r\n").unwrap().to_string(),
+            "\ta\r\n b\r\n"
+        );
+        assert_eq!(dedent("  a\n\tb").unwrap().to_string(), "  a\n\tb");
+
+        let mut output = String::new();
+        dedent("   x\n    y")
+            .unwrap()
+            .write_into(&mut output, NO_VALUES)
+            .unwrap();
+        assert_eq!(output, "x\n y");
+    }
+
+    #[test]
+    fn test_localize_number() {
+        fn render(value: impl fmt::Display, locale: &dyn core::any::Any) -> Result<String> {
+            let mut dest = String::new();
+            localize_number(value)
+                .unwrap()
+                .write_into(&mut dest, &(Locale::KEY, locale))?;
+            Ok(dest)
+        }
+
+        let de = &Locale::from_tag("de").unwrap();
+        let us = &Locale::from_tag("en-US").unwrap();
+        assert_eq!(render(1234567.25, de).unwrap(), "1.234.567,25");
+        assert_eq!(render(1234567.25, us).unwrap(), "1,234,567.25");
+        assert_eq!(render(-1000, de).unwrap(), "-1.000");
+        assert_eq!(render(999, us).unwrap(), "999");
+        assert_eq!(render(123456, us).unwrap(), "123,456");
+        assert_eq!(render(0.5, de).unwrap(), "0,5");
+        assert_eq!(render(f64::NAN, de).unwrap(), "NaN");
+        assert_eq!(render("12a3", us).unwrap(), "12a3");
+        assert!(matches!(render(1234, &"de"), Err(crate::Error::ValueType)));
+        assert_eq!(localize_number(1234).unwrap().to_string(), "1,234");
+        assert_eq!(Locale::from_tag("fr_CH"), Some(Locale::CH));
+        assert_eq!(Locale::from_tag("tlh"), None);
+    }
 }
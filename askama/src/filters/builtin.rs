@@ -1,11 +1,13 @@
 use core::cell::Cell;
+use core::cmp::Ordering;
 use core::convert::Infallible;
 use core::fmt::{self, Write};
 use core::ops::Deref;
 use core::pin::Pin;
 
-use super::MAX_LEN;
-use crate::{Error, FastWritable, Result, Values};
+use super::escape::EscapeDisplay;
+use super::{Escaper, Html, HtmlSafe, MAX_LEN};
+use crate::{Error, FastWritable, PrimitiveType, Result, Values};
 
 /// Limit string length, appends '...' if truncated
 ///
@@ -117,6 +119,141 @@ impl<W: fmt::Write> fmt::Write for TruncateWriter<W> {
     }
 }
 
+/// Repeat a value `count` times
+///
+/// A `count` of `0` yields an empty string. If the count is computed at runtime, then a negative
+/// count yields an empty string, too; a negative literal is rejected at compile time.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ "=-"|repeat(count) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example {
+///     count: usize,
+/// }
+///
+/// assert_eq!(
+///     Example { count: 3 }.to_string(),
+///     "<div>=-=-=-</div>"
+/// );
+/// assert_eq!(
+///     Example { count: 0 }.to_string(),
+///     "<div></div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn repeat<S: fmt::Display>(source: S, count: usize) -> Result<Repeat<S>, Infallible> {
+    Ok(Repeat { source, count })
+}
+
+pub struct Repeat<S> {
+    source: S,
+    count: usize,
+}
+
+impl<S: fmt::Display> fmt::Display for Repeat<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for _ in 0..self.count {
+            write!(f, "{}", self.source)?;
+        }
+        Ok(())
+    }
+}
+
+impl<S: FastWritable> FastWritable for Repeat<S> {
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        for _ in 0..self.count {
+            self.source.write_into(dest, values)?;
+        }
+        Ok(())
+    }
+}
+
+/// Replaces control characters with visible representations
+///
+/// Log lines or strings that were decoded from arbitrary bytes can contain control characters,
+/// e.g. `ESC` or `NUL`, that mangle terminals or hide parts of the text. This filter replaces
+/// every C0 control character and `DEL` with its Unicode control picture, e.g. `NUL` with `␀` and
+/// `ESC` with `␛`. The C1 control characters have no control pictures, so they are replaced with
+/// their escaped code point, e.g. `\u{9b}`. Tabs and line feeds are kept as they are.
+///
+/// The output is escaped like any other value, so the filter can be combined with HTML escaping.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <pre>{{ line|escape_control }}</pre>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     line: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { line: "\x1b[1m<b>\x1b[0m\0" }.to_string(),
+///     "<pre>␛[1m&#60;b&#62;␛[0m␀</pre>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn escape_control<S: fmt::Display>(source: S) -> Result<EscapeControl<S>, Infallible> {
+    Ok(EscapeControl(source))
+}
+
+pub struct EscapeControl<S>(S);
+
+impl<S: fmt::Display> fmt::Display for EscapeControl<S> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(EscapeControlWriter(f), "{}", self.0)
+    }
+}
+
+impl<S: FastWritable> FastWritable for EscapeControl<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        self.0.write_into(&mut EscapeControlWriter(dest), values)
+    }
+}
+
+struct EscapeControlWriter<W>(W);
+
+impl<W: fmt::Write> fmt::Write for EscapeControlWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut last = 0;
+        for (idx, c) in s.char_indices() {
+            if !c.is_control() || matches!(c, '\t' | '\n') {
+                continue;
+            }
+            self.0.write_str(&s[last..idx])?;
+            last = idx + c.len_utf8();
+            match c {
+                '\0'..='\x1f' => self
+                    .0
+                    .write_char(char::from_u32(0x2400 + c as u32).unwrap())?,
+                '\x7f' => self.0.write_char('\u{2421}')?,
+                _ => write!(self.0, "\\u{{{:x}}}", c as u32)?,
+            }
+        }
+        self.0.write_str(&s[last..])
+    }
+}
+
 /// Joins iterable into a string separated by provided argument
 ///
 /// ```
@@ -181,276 +318,1910 @@ where
     }
 }
 
-/// Centers the value in a field of a given width
+/// Joins an iterable into a natural-language list, e.g. `"a, b and c"`
+///
+/// The `separator` is put between the elements, except for the last two elements, which are
+/// joined by the `conjunction`. Two elements are only joined by the `conjunction`, e.g.
+/// `"a and b"`, and a single element is rendered as is.
+///
+/// With `oxford = true`, the separator without its trailing whitespace is put before the
+/// conjunction if there are three or more elements, e.g. `"a, b, and c"`.
 ///
 /// ```
 /// # #[cfg(feature = "code-in-doc")] {
 /// # use askama::Template;
 /// /// ```jinja
-/// /// <div>-{{ example|center(5) }}-</div>
+/// /// <div>{{ names|join_human(", ", " and ") }}</div>
+/// /// <div>{{ names|join_human(", ", " and ", oxford = true) }}</div>
 /// /// ```
 /// #[derive(Template)]
 /// #[template(ext = "html", in_doc = true)]
 /// struct Example<'a> {
-///     example: &'a str,
+///     names: &'a [&'a str],
 /// }
 ///
 /// assert_eq!(
-///     Example { example: "a" }.to_string(),
-///     "<div>-  a  -</div>"
+///     Example { names: &["Alice", "Bob", "Carol"] }.to_string(),
+///     "<div>Alice, Bob and Carol</div>\n<div>Alice, Bob, and Carol</div>"
 /// );
 /// # }
 /// ```
 #[inline]
-pub fn center<T: fmt::Display>(src: T, width: usize) -> Result<Center<T>, Infallible> {
-    Ok(Center { src, width })
+pub fn join_human<I, S, C>(
+    input: I,
+    separator: S,
+    conjunction: C,
+    oxford: bool,
+) -> Result<JoinHuman<I, S, C>, Infallible>
+where
+    I: IntoIterator,
+    I::Item: fmt::Display,
+    S: AsRef<str>,
+    C: AsRef<str>,
+{
+    Ok(JoinHuman {
+        input: Cell::new(Some(input)),
+        separator,
+        conjunction,
+        oxford,
+    })
 }
 
-pub struct Center<T> {
-    src: T,
-    width: usize,
+/// Result of the filter [`join_human()`].
+///
+/// ## Note
+///
+/// Like [`JoinFilter`], this struct only produces a string once, because the iterator is consumed.
+pub struct JoinHuman<I, S, C> {
+    input: Cell<Option<I>>,
+    separator: S,
+    conjunction: C,
+    oxford: bool,
 }
 
-impl<T: fmt::Display> fmt::Display for Center<T> {
+impl<I, S, C> fmt::Display for JoinHuman<I, S, C>
+where
+    I: IntoIterator,
+    I::Item: fmt::Display,
+    S: AsRef<str>,
+    C: AsRef<str>,
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.width < MAX_LEN {
-            write!(f, "{: ^1$}", self.src, self.width)
-        } else {
-            write!(f, "{}", self.src)
+        let Some(iter) = self.input.take() else {
+            return Ok(());
+        };
+        let mut iter = iter.into_iter().peekable();
+        let mut idx = 0;
+        while let Some(token) = iter.next() {
+            if idx > 0 {
+                if iter.peek().is_some() {
+                    f.write_str(self.separator.as_ref())?;
+                } else {
+                    if self.oxford && idx > 1 {
+                        f.write_str(self.separator.as_ref().trim_end())?;
+                    }
+                    f.write_str(self.conjunction.as_ref())?;
+                }
+            }
+            f.write_fmt(format_args!("{token}"))?;
+            idx += 1;
         }
+        Ok(())
     }
 }
 
-/// For a value of `±1` by default an empty string `""` is returned, otherwise `"s"`.
+/// Flattens an iterable of iterables
 ///
-/// # Examples
+/// The elements of the inner iterables are yielded one after the other, lazily, so the result can
+/// be used in a `{% for %}` loop or be passed to another filter, e.g. [`join()`]. Empty inner
+/// iterables are skipped.
 ///
-/// ## With default arguments
+/// An optional `depth` argument flattens more than one level of nesting: `flatten(2)` turns a
+/// `Vec<Vec<Vec<T>>>` into a sequence of `T`. The depth must be an integer literal.
 ///
 /// ```
 /// # #[cfg(feature = "code-in-doc")] {
 /// # use askama::Template;
 /// /// ```jinja
-/// /// I have {{dogs}} dog{{dogs|pluralize}} and {{cats}} cat{{cats|pluralize}}.
+/// /// <div>{{ rows|flatten|join(", ") }}</div>
+/// /// <div>{{ cube|flatten(2)|join(", ") }}</div>
 /// /// ```
 /// #[derive(Template)]
 /// #[template(ext = "html", in_doc = true)]
-/// struct Pets {
-///     dogs: i8,
-///     cats: i8,
+/// struct Example {
+///     rows: Vec<Vec<i32>>,
+///     cube: Vec<Vec<Vec<i32>>>,
 /// }
 ///
 /// assert_eq!(
-///     Pets { dogs: 0, cats: 0 }.to_string(),
-///     "I have 0 dogs and 0 cats."
-/// );
-/// assert_eq!(
-///     Pets { dogs: 1, cats: 1 }.to_string(),
-///     "I have 1 dog and 1 cat."
-/// );
-/// assert_eq!(
-///     Pets { dogs: -1, cats: 99 }.to_string(),
-///     "I have -1 dog and 99 cats."
+///     Example {
+///         rows: vec![vec![1, 2], vec![], vec![3]],
+///         cube: vec![vec![vec![1], vec![2, 3]], vec![vec![4]]],
+///     }.to_string(),
+///     "<div>1, 2, 3</div>\n<div>1, 2, 3, 4</div>"
 /// );
 /// # }
 /// ```
+#[inline]
+pub fn flatten<I>(input: I) -> Result<Flatten<I::IntoIter>, Infallible>
+where
+    I: IntoIterator,
+    I::Item: IntoIterator,
+{
+    Ok(Flatten(input.into_iter()))
+}
+
+/// Result of the filter [`flatten()`].
 ///
-/// ## Overriding the singular case
+/// Iterating a reference to this struct clones the underlying iterator, so the flattened
+/// sequence can be iterated more than once.
+#[derive(Debug, Clone)]
+pub struct Flatten<I>(I);
+
+impl<I> IntoIterator for Flatten<I>
+where
+    I: Iterator,
+    I::Item: IntoIterator,
+{
+    type Item = <I::Item as IntoIterator>::Item;
+    type IntoIter = core::iter::Flatten<I>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.flatten()
+    }
+}
+
+impl<I> IntoIterator for &Flatten<I>
+where
+    I: Iterator + Clone,
+    I::Item: IntoIterator,
+{
+    type Item = <I::Item as IntoIterator>::Item;
+    type IntoIter = core::iter::Flatten<I>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.clone().flatten()
+    }
+}
+
+/// Returns the elements of the 1-based `page` of an iterable, with `per_page` elements per page
+///
+/// The last page may contain fewer than `per_page` elements. A `page` that is `0` or after the
+/// last page yields no elements, as does a `per_page` of `0`. Use [`page_count()`] to get the
+/// number of pages.
 ///
 /// ```
 /// # #[cfg(feature = "code-in-doc")] {
 /// # use askama::Template;
 /// /// ```jinja
-/// /// I have {{dogs}} dog{{ dogs|pluralize("go") }}.
+/// /// <ul>{% for item in items|paginate(page, 2) %}<li>{{ item }}</li>{% endfor %}</ul>
+/// /// <p>Page {{ page }} of {{ items|page_count(2) }}</p>
 /// /// ```
 /// #[derive(Template)]
 /// #[template(ext = "html", in_doc = true)]
-/// struct Dog {
-///     dogs: i8,
+/// struct Example<'a> {
+///     items: Vec<&'a str>,
+///     page: usize,
 /// }
 ///
 /// assert_eq!(
-///     Dog { dogs: 0 }.to_string(),
-///     "I have 0 dogs."
-/// );
-/// assert_eq!(
-///     Dog { dogs: 1 }.to_string(),
-///     "I have 1 doggo."
+///     Example { items: vec!["a", "b", "c", "d", "e"], page: 3 }.to_string(),
+///     "<ul><li>e</li></ul>\n<p>Page 3 of 3</p>"
 /// );
 /// # }
 /// ```
+#[inline]
+pub fn paginate<I: IntoIterator>(
+    iterable: I,
+    page: usize,
+    per_page: usize,
+) -> Result<Paginate<I::IntoIter>, Infallible> {
+    let skip = page
+        .checked_sub(1)
+        .and_then(|page| page.checked_mul(per_page));
+    let (skip, take) = match skip {
+        Some(skip) => (skip, per_page),
+        None => (0, 0),
+    };
+    Ok(Paginate {
+        iter: iterable.into_iter(),
+        skip,
+        take,
+    })
+}
+
+/// Result of the filter [`paginate()`].
 ///
-/// ## Overriding singular and plural cases
+/// Iterating a reference to this struct clones the underlying iterator, so the page can be
+/// iterated more than once.
+#[derive(Debug, Clone)]
+pub struct Paginate<I> {
+    iter: I,
+    skip: usize,
+    take: usize,
+}
+
+impl<I: Iterator> IntoIterator for Paginate<I> {
+    type Item = I::Item;
+    type IntoIter = core::iter::Take<core::iter::Skip<I>>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter.skip(self.skip).take(self.take)
+    }
+}
+
+impl<I: Iterator + Clone> IntoIterator for &Paginate<I> {
+    type Item = I::Item;
+    type IntoIter = core::iter::Take<core::iter::Skip<I>>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter.clone().skip(self.skip).take(self.take)
+    }
+}
+
+/// Returns the number of pages of an iterable with `per_page` elements per page
+///
+/// An empty iterable has no pages, and a `per_page` of `0` results in `0` pages, too.
+/// See [`paginate()`] for an example.
+#[inline]
+pub fn page_count<I: IntoIterator>(iterable: I, per_page: usize) -> Result<usize, Infallible> {
+    Ok(match per_page {
+        0 => 0,
+        per_page => iterable.into_iter().count().div_ceil(per_page),
+    })
+}
+
+/// Pairs up the elements of two iterables
+///
+/// The `n`-th element of the result is a tuple of the `n`-th elements of `iterable` and
+/// `other`. The iteration stops at the end of the shorter iterable, so the remaining elements of
+/// the longer one are dropped, and not padded.
 ///
 /// ```
 /// # #[cfg(feature = "code-in-doc")] {
 /// # use askama::Template;
 /// /// ```jinja
-/// /// I have {{mice}} {{ mice|pluralize("mouse", "mice") }}.
+/// /// <dl>
+/// /// {%- for (label, value) in labels|zip(values) %}<dt>{{ label }}</dt><dd>{{ value }}</dd>{% endfor -%}
+/// /// </dl>
 /// /// ```
 /// #[derive(Template)]
 /// #[template(ext = "html", in_doc = true)]
-/// struct Mice {
-///     mice: i8,
+/// struct Example<'a> {
+///     labels: Vec<&'a str>,
+///     values: Vec<u32>,
 /// }
 ///
 /// assert_eq!(
-///     Mice { mice: 42 }.to_string(),
-///     "I have 42 mice."
-/// );
-/// assert_eq!(
-///     Mice { mice: 1 }.to_string(),
-///     "I have 1 mouse."
+///     Example { labels: vec!["width", "height", "depth"], values: vec![640, 480] }.to_string(),
+///     "<dl><dt>width</dt><dd>640</dd><dt>height</dt><dd>480</dd></dl>"
 /// );
 /// # }
 /// ```
+#[inline]
+pub fn zip<A: IntoIterator, B: IntoIterator>(
+    iterable: A,
+    other: B,
+) -> Result<Zip<A::IntoIter, B::IntoIter>, Infallible> {
+    Ok(Zip(iterable.into_iter(), other.into_iter()))
+}
+
+/// Result of the filter [`zip()`].
 ///
-/// ## Arguments get escaped
+/// Iterating a reference to this struct clones the underlying iterators, so the pairs can be
+/// iterated more than once.
+#[derive(Debug, Clone)]
+pub struct Zip<A, B>(A, B);
+
+impl<A: Iterator, B: Iterator> IntoIterator for Zip<A, B> {
+    type Item = (A::Item, B::Item);
+    type IntoIter = core::iter::Zip<A, B>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.zip(self.1)
+    }
+}
+
+impl<A: Iterator + Clone, B: Iterator + Clone> IntoIterator for &Zip<A, B> {
+    type Item = (A::Item, B::Item);
+    type IntoIter = core::iter::Zip<A, B>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.clone().zip(self.1.clone())
+    }
+}
+
+/// Formats every element of an iterable with a format string containing exactly one `{}`
+///
+/// The `{}` is replaced with the element, which is escaped with the escaper of the template. The
+/// format string must be a string literal, and is not escaped. Like in [`format!()`], `{{` and `}}`
+/// are literal braces. The result can be iterated, or be joined with [`join()`].
 ///
 /// ```
 /// # #[cfg(feature = "code-in-doc")] {
 /// # use askama::Template;
 /// /// ```jinja
-/// /// You are number {{ number|pluralize("<b>ONE</b>", number) }}!
+/// /// <ul>{{ items|format_each("<li>{}</li>")|join("")|safe }}</ul>
+/// /// <ul>{% for item in items|format_each("<li>{}</li>") %}{{ item }}{% endfor %}</ul>
 /// /// ```
 /// #[derive(Template)]
 /// #[template(ext = "html", in_doc = true)]
-/// struct Number {
-///     number: usize
+/// struct Example<'a> {
+///     items: Vec<&'a str>,
 /// }
 ///
 /// assert_eq!(
-///     Number { number: 1 }.to_string(),
-///     "You are number &#60;b&#62;ONE&#60;/b&#62;!",
-/// );
-/// assert_eq!(
-///     Number { number: 9000 }.to_string(),
-///     "You are number 9000!",
+///     Example { items: vec!["salt", "<pepper>"] }.to_string(),
+///     "<ul><li>salt</li><li>&#60;pepper&#62;</li></ul>\n\
+///      <ul><li>salt</li><li>&#60;pepper&#62;</li></ul>"
 /// );
 /// # }
 /// ```
+///
+/// [`format!()`]: alloc::format
 #[inline]
-pub fn pluralize<C, S, P>(count: C, singular: S, plural: P) -> Result<Pluralize<S, P>, C::Error>
-where
-    C: PluralizeCount,
-{
-    match count.is_singular()? {
-        true => Ok(Pluralize::Singular(singular)),
-        false => Ok(Pluralize::Plural(plural)),
-    }
+pub fn format_each<I: IntoIterator, E: Escaper>(
+    iterable: I,
+    prefix: &'static str,
+    suffix: &'static str,
+    escaper: E,
+) -> Result<FormatEach<I::IntoIter, E>, Infallible> {
+    Ok(FormatEach {
+        iter: iterable.into_iter(),
+        prefix,
+        suffix,
+        escaper,
+    })
 }
 
-/// An integer that can have the value `+1` and maybe `-1`.
-pub trait PluralizeCount {
-    /// A possible error that can occur while checking the value.
-    type Error: Into<Error>;
-
-    /// Returns `true` if and only if the value is `±1`.
-    fn is_singular(&self) -> Result<bool, Self::Error>;
+/// Result of the filter [`format_each()`].
+///
+/// Iterating a reference to this struct clones the underlying iterator, so the elements can be
+/// iterated more than once.
+#[derive(Debug, Clone)]
+pub struct FormatEach<I, E> {
+    iter: I,
+    prefix: &'static str,
+    suffix: &'static str,
+    escaper: E,
 }
 
-const _: () = {
-    crate::impl_for_ref! {
-        impl PluralizeCount for T {
-            type Error = T::Error;
+impl<I: Iterator, E: Escaper> Iterator for FormatEach<I, E> {
+    type Item = Formatted<I::Item, E>;
 
-            #[inline]
-            fn is_singular(&self) -> Result<bool, Self::Error> {
-                <T>::is_singular(self)
-            }
-        }
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(Formatted {
+            value: self.iter.next()?,
+            prefix: self.prefix,
+            suffix: self.suffix,
+            escaper: self.escaper,
+        })
     }
 
-    impl<T> PluralizeCount for Pin<T>
-    where
-        T: Deref,
-        <T as Deref>::Target: PluralizeCount,
-    {
-        type Error = <<T as Deref>::Target as PluralizeCount>::Error;
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: Iterator + Clone, E: Escaper> IntoIterator for &FormatEach<I, E> {
+    type Item = Formatted<I::Item, E>;
+    type IntoIter = FormatEach<I, E>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.clone()
+    }
+}
+
+/// An element formatted by the filter [`format_each()`].
+#[derive(Debug, Clone, Copy)]
+pub struct Formatted<T, E> {
+    value: T,
+    prefix: &'static str,
+    suffix: &'static str,
+    escaper: E,
+}
+
+impl<T: fmt::Display, E: Escaper> fmt::Display for Formatted<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.prefix)?;
+        write!(f, "{}", EscapeDisplay(&self.value, self.escaper))?;
+        f.write_str(self.suffix)
+    }
+}
+
+impl<T: FastWritable, E: Escaper> FastWritable for Formatted<T, E> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        dest.write_str(self.prefix)?;
+        EscapeDisplay(&self.value, self.escaper).write_into(dest, values)?;
+        Ok(dest.write_str(self.suffix)?)
+    }
+}
+
+// The element was escaped, and the format string comes from the template.
+impl<T: fmt::Display> HtmlSafe for Formatted<T, Html> {}
+
+/// Bounds a number to the inclusive range `min..=max`
+///
+/// Works for integers and floats, and returns a value of the same type as the input.
+/// A value that is `NaN` is returned unchanged. If `min` is greater than `max`, then values less
+/// than `min` are raised to `min`, and all other values greater than `max` are lowered to `max`.
+/// If both bounds are literals, then `min > max` is a compile time error.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <progress value="{{ pct|clamp(0, 100) }}" max="100"></progress>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example {
+///     pct: i32,
+/// }
+///
+/// assert_eq!(
+///     Example { pct: 120 }.to_string(),
+///     r#"<progress value="100" max="100"></progress>"#
+/// );
+/// assert_eq!(
+///     Example { pct: 42 }.to_string(),
+///     r#"<progress value="42" max="100"></progress>"#
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn clamp<T: PartialOrd>(value: T, min: T, max: T) -> Result<T, Infallible> {
+    Ok(if value < min {
+        min
+    } else if value > max {
+        max
+    } else {
+        value
+    })
+}
+
+/// Returns the sign of a number as `-1`, `0` or `1`
+///
+/// Works for integers and floats. For floats, both `0.0` and `-0.0` are `0`, and `NaN` is `0`,
+/// too.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <span class="trend{{ delta|sign }}">{{ delta }}</span>
+/// /// {% if delta|sign == -1 %}(down){% endif %}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example {
+///     delta: f64,
+/// }
+///
+/// assert_eq!(
+///     Example { delta: -2.5 }.to_string(),
+///     "<span class=\"trend-1\">-2.5</span>\n(down)"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn sign<T: Sign>(value: T) -> Result<i32, Infallible> {
+    Ok(value.sign())
+}
+
+/// A number that has a sign, used by the filter [`sign()`]
+pub trait Sign {
+    /// Returns `-1` for negative numbers, `1` for positive numbers, and `0` otherwise.
+    fn sign(&self) -> i32;
+}
+
+const _: () = {
+    crate::impl_for_ref! {
+        impl Sign for T {
+            #[inline]
+            fn sign(&self) -> i32 {
+                <T>::sign(self)
+            }
+        }
+    }
+
+    impl<T> Sign for Pin<T>
+    where
+        T: Deref,
+        <T as Deref>::Target: Sign,
+    {
+        #[inline]
+        fn sign(&self) -> i32 {
+            self.as_ref().get_ref().sign()
+        }
+    }
+
+    macro_rules! impl_sign_for_unsigned_int {
+        ($($ty:ty)*) => { $(
+            impl Sign for $ty {
+                #[inline]
+                fn sign(&self) -> i32 {
+                    (*self != 0) as i32
+                }
+            }
+        )* };
+    }
+
+    impl_sign_for_unsigned_int!(u8 u16 u32 u64 u128 usize);
+
+    macro_rules! impl_sign_for_signed_int {
+        ($($ty:ty)*) => { $(
+            impl Sign for $ty {
+                #[inline]
+                fn sign(&self) -> i32 {
+                    self.signum() as i32
+                }
+            }
+        )* };
+    }
+
+    impl_sign_for_signed_int!(i8 i16 i32 i64 i128 isize);
+
+    macro_rules! impl_sign_for_float {
+        ($($ty:ty)*) => { $(
+            impl Sign for $ty {
+                #[inline]
+                fn sign(&self) -> i32 {
+                    if *self > 0.0 {
+                        1
+                    } else if *self < 0.0 {
+                        -1
+                    } else {
+                        // zero, negative zero, or NaN
+                        0
+                    }
+                }
+            }
+        )* };
+    }
+
+    impl_sign_for_float!(f32 f64);
+};
+
+/// Formats an integer as an English ordinal number, e.g. `1st`, `2nd`, `3rd` or `11th`
+///
+/// Negative numbers keep their sign, e.g. `-2nd`. If the optional argument `words` is `true`,
+/// then the numbers `0` to `20` are spelled out, e.g. `first` or `twelfth`. All other numbers,
+/// including negative numbers, are written with digits in any case.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ place|ordinal }} / {{ place|ordinal(true) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example {
+///     place: u32,
+/// }
+///
+/// assert_eq!(
+///     Example { place: 3 }.to_string(),
+///     "<div>3rd / third</div>"
+/// );
+/// assert_eq!(
+///     Example { place: 112 }.to_string(),
+///     "<div>112th / 112th</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn ordinal<T: OrdinalNumber>(value: T, words: bool) -> Result<Ordinal, Infallible> {
+    let (negative, value) = value.ordinal_parts();
+    Ok(Ordinal {
+        negative,
+        value,
+        words,
+    })
+}
+
+/// An integer that can be formatted by the filter [`ordinal()`]
+pub trait OrdinalNumber {
+    /// Returns whether the number is negative, and its absolute value.
+    fn ordinal_parts(&self) -> (bool, u128);
+}
+
+const _: () = {
+    crate::impl_for_ref! {
+        impl OrdinalNumber for T {
+            #[inline]
+            fn ordinal_parts(&self) -> (bool, u128) {
+                <T>::ordinal_parts(self)
+            }
+        }
+    }
+
+    impl<T> OrdinalNumber for Pin<T>
+    where
+        T: Deref,
+        <T as Deref>::Target: OrdinalNumber,
+    {
+        #[inline]
+        fn ordinal_parts(&self) -> (bool, u128) {
+            self.as_ref().get_ref().ordinal_parts()
+        }
+    }
+
+    macro_rules! impl_ordinal_for_unsigned_int {
+        ($($ty:ty)*) => { $(
+            impl OrdinalNumber for $ty {
+                #[inline]
+                fn ordinal_parts(&self) -> (bool, u128) {
+                    (false, *self as u128)
+                }
+            }
+        )* };
+    }
+
+    impl_ordinal_for_unsigned_int!(u8 u16 u32 u64 u128 usize);
+
+    macro_rules! impl_ordinal_for_signed_int {
+        ($($ty:ty)*) => { $(
+            impl OrdinalNumber for $ty {
+                #[inline]
+                fn ordinal_parts(&self) -> (bool, u128) {
+                    (*self < 0, self.unsigned_abs() as u128)
+                }
+            }
+        )* };
+    }
+
+    impl_ordinal_for_signed_int!(i8 i16 i32 i64 i128 isize);
+};
+
+/// The output of the filter [`ordinal()`]
+pub struct Ordinal {
+    negative: bool,
+    value: u128,
+    words: bool,
+}
+
+impl fmt::Display for Ordinal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const WORDS: [&str; 21] = [
+            "zeroth",
+            "first",
+            "second",
+            "third",
+            "fourth",
+            "fifth",
+            "sixth",
+            "seventh",
+            "eighth",
+            "ninth",
+            "tenth",
+            "eleventh",
+            "twelfth",
+            "thirteenth",
+            "fourteenth",
+            "fifteenth",
+            "sixteenth",
+            "seventeenth",
+            "eighteenth",
+            "nineteenth",
+            "twentieth",
+        ];
+
+        if self.words && !self.negative && self.value < WORDS.len() as u128 {
+            return f.write_str(WORDS[self.value as usize]);
+        }
+
+        let suffix = match (self.value % 100, self.value % 10) {
+            (11..=13, _) => "th",
+            (_, 1) => "st",
+            (_, 2) => "nd",
+            (_, 3) => "rd",
+            _ => "th",
+        };
+        if self.negative {
+            f.write_char('-')?;
+        }
+        write!(f, "{}{suffix}", self.value)
+    }
+}
+
+impl HtmlSafe for Ordinal {}
+
+/// Sums up the numbers of an iterable
+///
+/// The result has the same type as the elements, and the sum of an empty iterable is zero.
+/// An integer overflow is a [`SumOverflow`] error.
+///
+/// In a template, an optional argument selects a field of the elements that gets summed up,
+/// e.g. `{{ orders|sum("amount") }}`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ prices|sum }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example {
+///     prices: Vec<u32>,
+/// }
+///
+/// assert_eq!(
+///     Example { prices: vec![3, 5, 8] }.to_string(),
+///     "<div>16</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn sum<I>(iterable: I) -> Result<<I::Item as PrimitiveType>::Value, SumOverflow>
+where
+    I: IntoIterator,
+    I::Item: PrimitiveType,
+    <I::Item as PrimitiveType>::Value: Summable,
+{
+    iterable
+        .into_iter()
+        .try_fold(Summable::ZERO, |sum, value| {
+            Summable::checked_add(sum, value.get())
+        })
+        .ok_or(SumOverflow)
+}
+
+/// Error of the filter [`sum`] if the sum of integers overflows
+///
+/// In a template, it is converted into an [`Error::Custom`], or into an [`Error::Fmt`] without
+/// the feature `"alloc"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SumOverflow;
+
+impl fmt::Display for SumOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("integer overflow in the `sum` filter")
+    }
+}
+
+impl core::error::Error for SumOverflow {}
+
+impl From<SumOverflow> for Error {
+    #[inline]
+    fn from(err: SumOverflow) -> Self {
+        #[cfg(feature = "alloc")]
+        {
+            Error::custom(err)
+        }
+        #[cfg(not(feature = "alloc"))]
+        {
+            let _ = err;
+            Error::Fmt
+        }
+    }
+}
+
+/// Calculates the average of the numbers of an iterable
+///
+/// The result is an [`f64`]. The average of an empty iterable is [`f64::NAN`], which gets
+/// rendered as `NaN`.
+///
+/// In a template, an optional argument selects a field of the elements that gets averaged,
+/// e.g. `{{ students|avg("score") }}`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ scores|avg }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example {
+///     scores: Vec<f32>,
+/// }
+///
+/// assert_eq!(
+///     Example { scores: vec![1.5, 2.0, 4.0] }.to_string(),
+///     "<div>2.5</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn avg<I>(iterable: I) -> Result<f64, Infallible>
+where
+    I: IntoIterator,
+    I::Item: PrimitiveType,
+    <I::Item as PrimitiveType>::Value: Summable,
+{
+    let (sum, count) = iterable
+        .into_iter()
+        .fold((0.0, 0usize), |(sum, count), value| {
+            (sum + Summable::to_f64(value.get()), count + 1)
+        });
+    Ok(match count {
+        0 => f64::NAN,
+        count => sum / count as f64,
+    })
+}
+
+/// A number that can be added up by the filters [`sum`] and [`avg`]
+pub trait Summable: Copy {
+    /// The value `0` of this type
+    const ZERO: Self;
+
+    /// Adds two numbers, returning `None` if the result overflows
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// Converts the number to a (possibly rounded) float
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_summable {
+    (int: $($ty:ty),*; float: $($fty:ty),*) => {
+        $(
+            impl Summable for $ty {
+                const ZERO: Self = 0;
+
+                #[inline]
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$ty>::checked_add(self, other)
+                }
+
+                #[inline]
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+        $(
+            impl Summable for $fty {
+                const ZERO: Self = 0.0;
+
+                #[inline]
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    Some(self + other)
+                }
+
+                #[inline]
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_summable!(
+    int: i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize;
+    float: f32, f64
+);
+
+/// Centers the value in a field of a given width
+///
+/// The width is counted in characters. Use [`center_ansi()`] to count terminal columns instead.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>-{{ example|center(5) }}-</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "a" }.to_string(),
+///     "<div>-  a  -</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn center<T: fmt::Display>(src: T, width: usize) -> Result<Center<T>, Infallible> {
+    Ok(Center {
+        src,
+        width,
+        ansi_width: false,
+    })
+}
+
+/// Centers the value in a field of a given width, which is counted in terminal columns
+///
+/// Like [`center()`], but the width is counted like in [`visible_len()`]: ANSI escape sequences,
+/// e.g. color codes like `"\x1b[31m"`, are not counted, because they are not visible in a
+/// terminal, and wide characters, e.g. CJK ideographs, count twice.
+///
+/// In a template, use `center` with the argument `ansi_width = true`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// -{{ example|center(5, ansi_width = true) }}-
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "\x1b[31ma\x1b[0m" }.to_string(),
+///     "-  \x1b[31ma\x1b[0m  -"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn center_ansi<T: fmt::Display>(src: T, width: usize) -> Result<Center<T>, Infallible> {
+    Ok(Center {
+        src,
+        width,
+        ansi_width: true,
+    })
+}
+
+pub struct Center<T> {
+    src: T,
+    width: usize,
+    ansi_width: bool,
+}
+
+impl<T: fmt::Display> fmt::Display for Center<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.width >= MAX_LEN {
+            write!(f, "{}", self.src)
+        } else if !self.ansi_width {
+            write!(f, "{: ^1$}", self.src, self.width)
+        } else {
+            let mut len = DisplayWidth::new(true);
+            write!(len, "{}", self.src)?;
+            let padding = self.width.saturating_sub(len.width);
+            let left = padding / 2;
+            write!(f, "{0:1$}{2}{0:3$}", "", left, self.src, padding - left)
+        }
+    }
+}
+
+/// Left-justifies the value in a field of a given width
+///
+/// The field is filled up with `fill` characters, which is a space by default.
+/// The width is counted in characters. Values that are longer than the field are not truncated.
+/// Use [`ljust_ansi()`] to count terminal columns instead.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>-{{ example|ljust(5) }}-{{ example|ljust(5, '.') }}-</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "a" }.to_string(),
+///     "<div>-a    -a....-</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn ljust<T: fmt::Display>(src: T, width: usize, fill: char) -> Result<Justify<T>, Infallible> {
+    Ok(Justify {
+        src,
+        width,
+        fill,
+        right: false,
+        ansi_width: false,
+    })
+}
+
+/// Like [`ljust()`], but the width is counted in terminal columns, see [`center_ansi()`]
+///
+/// In a template, use `ljust` with the argument `ansi_width = true`.
+#[inline]
+pub fn ljust_ansi<T: fmt::Display>(
+    src: T,
+    width: usize,
+    fill: char,
+) -> Result<Justify<T>, Infallible> {
+    Ok(Justify {
+        src,
+        width,
+        fill,
+        right: false,
+        ansi_width: true,
+    })
+}
+
+/// Right-justifies the value in a field of a given width
+///
+/// The field is filled up with `fill` characters, which is a space by default.
+/// The width is counted in characters. Values that are longer than the field are not truncated.
+/// Use [`rjust_ansi()`] to count terminal columns instead.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>-{{ example|rjust(5) }}-{{ example|rjust(5, '0') }}-</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example {
+///     example: u32,
+/// }
+///
+/// assert_eq!(
+///     Example { example: 42 }.to_string(),
+///     "<div>-   42-00042-</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn rjust<T: fmt::Display>(src: T, width: usize, fill: char) -> Result<Justify<T>, Infallible> {
+    Ok(Justify {
+        src,
+        width,
+        fill,
+        right: true,
+        ansi_width: false,
+    })
+}
+
+/// Like [`rjust()`], but the width is counted in terminal columns, see [`center_ansi()`]
+///
+/// In a template, use `rjust` with the argument `ansi_width = true`.
+#[inline]
+pub fn rjust_ansi<T: fmt::Display>(
+    src: T,
+    width: usize,
+    fill: char,
+) -> Result<Justify<T>, Infallible> {
+    Ok(Justify {
+        src,
+        width,
+        fill,
+        right: true,
+        ansi_width: true,
+    })
+}
+
+pub struct Justify<T> {
+    src: T,
+    width: usize,
+    fill: char,
+    right: bool,
+    ansi_width: bool,
+}
+
+impl<T: fmt::Display> fmt::Display for Justify<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.width >= MAX_LEN {
+            return write!(f, "{}", self.src);
+        }
+
+        let mut len = DisplayWidth::new(self.ansi_width);
+        write!(len, "{}", self.src)?;
+        let padding = self.width.saturating_sub(len.width);
+        if !self.right {
+            write!(f, "{}", self.src)?;
+        }
+        for _ in 0..padding {
+            f.write_char(self.fill)?;
+        }
+        if self.right {
+            write!(f, "{}", self.src)?;
+        }
+        Ok(())
+    }
+}
+
+/// Counts the characters that are written into it, or with `ansi_width` the columns they take up
+/// in a terminal, i.e. without ANSI escape sequences, see [`char_width()`].
+struct DisplayWidth {
+    width: usize,
+    ansi: Option<AnsiState>,
+}
+
+#[derive(Clone, Copy)]
+enum AnsiState {
+    Text,
+    /// After an `ESC` character.
+    Escape,
+    /// Inside a control sequence `ESC [ …`, which is ended by a character in `'@'..='~'`.
+    Csi,
+}
+
+impl DisplayWidth {
+    fn new(ansi_width: bool) -> Self {
+        Self {
+            width: 0,
+            ansi: ansi_width.then_some(AnsiState::Text),
+        }
+    }
+}
+
+impl fmt::Write for DisplayWidth {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let Some(mut state) = self.ansi else {
+            self.width += s.chars().count();
+            return Ok(());
+        };
+        for c in s.chars() {
+            state = match (state, c) {
+                (AnsiState::Text, '\x1b') => AnsiState::Escape,
+                (AnsiState::Text, c) => {
+                    self.width += char_width(c);
+                    AnsiState::Text
+                }
+                (AnsiState::Escape, '[') => AnsiState::Csi,
+                (AnsiState::Escape, _) | (AnsiState::Csi, '@'..='~') => AnsiState::Text,
+                (AnsiState::Csi, _) => AnsiState::Csi,
+            };
+        }
+        self.ansi = Some(state);
+        Ok(())
+    }
+}
+
+/// The number of columns a character takes up in a terminal.
+///
+/// Characters with the East Asian Width property `W` (wide) or `F` (fullwidth), e.g. CJK
+/// ideographs, Hangul and emoji like U+1F680 🚀, take up two columns. Combining diacritical marks,
+/// variation selectors and zero-width spaces take up none. All other characters take up one
+/// column.
+pub(crate) fn char_width(c: char) -> usize {
+    let c = c as u32;
+    match c {
+        0x0300..=0x036f | 0x200b..=0x200f | 0x20d0..=0x20ff | 0xfe00..=0xfe0f | 0xfe20..=0xfe2f => {
+            0
+        }
+        _ if WIDE_CHARS
+            .binary_search_by(|&(start, end)| {
+                if end < c {
+                    Ordering::Less
+                } else if start > c {
+                    Ordering::Greater
+                } else {
+                    Ordering::Equal
+                }
+            })
+            .is_ok() =>
+        {
+            2
+        }
+        _ => 1,
+    }
+}
+
+/// The sorted ranges of characters with the East Asian Width property `W` or `F`.
+const WIDE_CHARS: &[(u32, u32)] = &[
+    (0x1100, 0x115f),
+    (0x231a, 0x231b),
+    (0x2329, 0x232a),
+    (0x23e9, 0x23ec),
+    (0x23f0, 0x23f0),
+    (0x23f3, 0x23f3),
+    (0x25fd, 0x25fe),
+    (0x2614, 0x2615),
+    (0x2630, 0x2637),
+    (0x2648, 0x2653),
+    (0x267f, 0x267f),
+    (0x268a, 0x268f),
+    (0x2693, 0x2693),
+    (0x26a1, 0x26a1),
+    (0x26aa, 0x26ab),
+    (0x26bd, 0x26be),
+    (0x26c4, 0x26c5),
+    (0x26ce, 0x26ce),
+    (0x26d4, 0x26d4),
+    (0x26ea, 0x26ea),
+    (0x26f2, 0x26f3),
+    (0x26f5, 0x26f5),
+    (0x26fa, 0x26fa),
+    (0x26fd, 0x26fd),
+    (0x2705, 0x2705),
+    (0x270a, 0x270b),
+    (0x2728, 0x2728),
+    (0x274c, 0x274c),
+    (0x274e, 0x274e),
+    (0x2753, 0x2755),
+    (0x2757, 0x2757),
+    (0x2795, 0x2797),
+    (0x27b0, 0x27b0),
+    (0x27bf, 0x27bf),
+    (0x2b1b, 0x2b1c),
+    (0x2b50, 0x2b50),
+    (0x2b55, 0x2b55),
+    (0x2e80, 0x2e99),
+    (0x2e9b, 0x2ef3),
+    (0x2f00, 0x2fd5),
+    (0x2ff0, 0x3029),
+    (0x3030, 0x303e),
+    (0x3041, 0x3096),
+    (0x309b, 0x30ff),
+    (0x3105, 0x312f),
+    (0x3131, 0x3163),
+    (0x3165, 0x318e),
+    (0x3190, 0x31e5),
+    (0x31ef, 0x321e),
+    (0x3220, 0x3247),
+    (0x3250, 0xa48c),
+    (0xa490, 0xa4c6),
+    (0xa960, 0xa97c),
+    (0xac00, 0xd7a3),
+    (0xf900, 0xfaff),
+    (0xfe10, 0xfe19),
+    (0xfe30, 0xfe52),
+    (0xfe54, 0xfe66),
+    (0xfe68, 0xfe6b),
+    (0xff01, 0xff60),
+    (0xffe0, 0xffe6),
+    (0x16fe0, 0x16fe3),
+    (0x16ff2, 0x16ff6),
+    (0x17000, 0x18cd5),
+    (0x18cff, 0x18d1e),
+    (0x18d80, 0x18df2),
+    (0x1aff0, 0x1aff3),
+    (0x1aff5, 0x1affb),
+    (0x1affd, 0x1affe),
+    (0x1b000, 0x1b122),
+    (0x1b132, 0x1b132),
+    (0x1b150, 0x1b152),
+    (0x1b155, 0x1b155),
+    (0x1b164, 0x1b167),
+    (0x1b170, 0x1b2fb),
+    (0x1d300, 0x1d356),
+    (0x1d360, 0x1d376),
+    (0x1f004, 0x1f004),
+    (0x1f0cf, 0x1f0cf),
+    (0x1f18e, 0x1f18e),
+    (0x1f191, 0x1f19a),
+    (0x1f200, 0x1f202),
+    (0x1f210, 0x1f23b),
+    (0x1f240, 0x1f248),
+    (0x1f250, 0x1f251),
+    (0x1f260, 0x1f265),
+    (0x1f300, 0x1f320),
+    (0x1f32d, 0x1f335),
+    (0x1f337, 0x1f37c),
+    (0x1f37e, 0x1f393),
+    (0x1f3a0, 0x1f3ca),
+    (0x1f3cf, 0x1f3d3),
+    (0x1f3e0, 0x1f3f0),
+    (0x1f3f4, 0x1f3f4),
+    (0x1f3f8, 0x1f43e),
+    (0x1f440, 0x1f440),
+    (0x1f442, 0x1f4fc),
+    (0x1f4ff, 0x1f53d),
+    (0x1f54b, 0x1f54e),
+    (0x1f550, 0x1f567),
+    (0x1f57a, 0x1f57a),
+    (0x1f595, 0x1f596),
+    (0x1f5a4, 0x1f5a4),
+    (0x1f5fb, 0x1f64f),
+    (0x1f680, 0x1f6c5),
+    (0x1f6cc, 0x1f6cc),
+    (0x1f6d0, 0x1f6d2),
+    (0x1f6d5, 0x1f6d8),
+    (0x1f6dc, 0x1f6df),
+    (0x1f6eb, 0x1f6ec),
+    (0x1f6f4, 0x1f6fc),
+    (0x1f7e0, 0x1f7eb),
+    (0x1f7f0, 0x1f7f0),
+    (0x1f90c, 0x1f93a),
+    (0x1f93c, 0x1f945),
+    (0x1f947, 0x1f9ff),
+    (0x1fa70, 0x1fa7c),
+    (0x1fa80, 0x1fa8a),
+    (0x1fa8e, 0x1fac6),
+    (0x1fac8, 0x1fac8),
+    (0x1facd, 0x1fadc),
+    (0x1fadf, 0x1faea),
+    (0x1faef, 0x1faf8),
+    (0x20000, 0x2fffd),
+    (0x30000, 0x3fffd),
+];
+
+/// Returns the number of columns a string takes up in a terminal
+///
+/// ANSI escape sequences, e.g. color codes like `"\x1b[31m"`, are not counted, because they are
+/// not visible. East Asian wide and fullwidth characters, e.g. CJK ideographs, take up two
+/// columns, and combining marks take up none. This is the width that [`center_ansi()`],
+/// [`ljust_ansi()`] and [`rjust_ansi()`] use.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {{ status }}{% if status|visible_len < 8 %} (short){% endif %}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example<'a> {
+///     status: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { status: "\x1b[32m完了\x1b[0m" }.to_string(),
+///     "\x1b[32m完了\x1b[0m (short)"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn visible_len<S>(source: S) -> VisibleLen<S> {
+    VisibleLen {
+        source,
+        width: Cell::new(0),
+    }
+}
+
+pub struct VisibleLen<S> {
+    source: S,
+    width: Cell<usize>,
+}
+
+impl<S: fmt::Display> fmt::Display for VisibleLen<S> {
+    #[inline]
+    fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut width = DisplayWidth::new(true);
+        write!(width, "{}", self.source)?;
+        self.width.set(width.width);
+        Ok(())
+    }
+}
+
+impl<S: FastWritable> FastWritable for VisibleLen<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        _: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        let mut width = DisplayWidth::new(true);
+        self.source.write_into(&mut width, values)?;
+        self.width.set(width.width);
+        Ok(())
+    }
+}
+
+impl<S> VisibleLen<S> {
+    pub fn into_count(self) -> usize {
+        self.width.into_inner()
+    }
+}
+
+/// For a value of `±1` by default an empty string `""` is returned, otherwise `"s"`.
+///
+/// # Examples
+///
+/// ## With default arguments
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// I have {{dogs}} dog{{dogs|pluralize}} and {{cats}} cat{{cats|pluralize}}.
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Pets {
+///     dogs: i8,
+///     cats: i8,
+/// }
+///
+/// assert_eq!(
+///     Pets { dogs: 0, cats: 0 }.to_string(),
+///     "I have 0 dogs and 0 cats."
+/// );
+/// assert_eq!(
+///     Pets { dogs: 1, cats: 1 }.to_string(),
+///     "I have 1 dog and 1 cat."
+/// );
+/// assert_eq!(
+///     Pets { dogs: -1, cats: 99 }.to_string(),
+///     "I have -1 dog and 99 cats."
+/// );
+/// # }
+/// ```
+///
+/// ## Overriding the singular case
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// I have {{dogs}} dog{{ dogs|pluralize("go") }}.
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Dog {
+///     dogs: i8,
+/// }
+///
+/// assert_eq!(
+///     Dog { dogs: 0 }.to_string(),
+///     "I have 0 dogs."
+/// );
+/// assert_eq!(
+///     Dog { dogs: 1 }.to_string(),
+///     "I have 1 doggo."
+/// );
+/// # }
+/// ```
+///
+/// ## Overriding singular and plural cases
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// I have {{mice}} {{ mice|pluralize("mouse", "mice") }}.
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Mice {
+///     mice: i8,
+/// }
+///
+/// assert_eq!(
+///     Mice { mice: 42 }.to_string(),
+///     "I have 42 mice."
+/// );
+/// assert_eq!(
+///     Mice { mice: 1 }.to_string(),
+///     "I have 1 mouse."
+/// );
+/// # }
+/// ```
+///
+/// ## Arguments get escaped
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// You are number {{ number|pluralize("<b>ONE</b>", number) }}!
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Number {
+///     number: usize
+/// }
+///
+/// assert_eq!(
+///     Number { number: 1 }.to_string(),
+///     "You are number &#60;b&#62;ONE&#60;/b&#62;!",
+/// );
+/// assert_eq!(
+///     Number { number: 9000 }.to_string(),
+///     "You are number 9000!",
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn pluralize<C, S, P>(count: C, singular: S, plural: P) -> Result<Pluralize<S, P>, C::Error>
+where
+    C: PluralizeCount,
+{
+    match count.is_singular()? {
+        true => Ok(Pluralize::Singular(singular)),
+        false => Ok(Pluralize::Plural(plural)),
+    }
+}
+
+/// An integer that can have the value `+1` and maybe `-1`.
+pub trait PluralizeCount {
+    /// A possible error that can occur while checking the value.
+    type Error: Into<Error>;
+
+    /// Returns `true` if and only if the value is `±1`.
+    fn is_singular(&self) -> Result<bool, Self::Error>;
+}
+
+const _: () = {
+    crate::impl_for_ref! {
+        impl PluralizeCount for T {
+            type Error = T::Error;
+
+            #[inline]
+            fn is_singular(&self) -> Result<bool, Self::Error> {
+                <T>::is_singular(self)
+            }
+        }
+    }
+
+    impl<T> PluralizeCount for Pin<T>
+    where
+        T: Deref,
+        <T as Deref>::Target: PluralizeCount,
+    {
+        type Error = <<T as Deref>::Target as PluralizeCount>::Error;
+
+        #[inline]
+        fn is_singular(&self) -> Result<bool, Self::Error> {
+            self.as_ref().get_ref().is_singular()
+        }
+    }
+
+    /// implement `PluralizeCount` for unsigned integer types
+    macro_rules! impl_pluralize_for_unsigned_int {
+        ($($ty:ty)*) => { $(
+            impl PluralizeCount for $ty {
+                type Error = Infallible;
+
+                #[inline]
+                fn is_singular(&self) -> Result<bool, Self::Error> {
+                    Ok(*self == 1)
+                }
+            }
+        )* };
+    }
+
+    impl_pluralize_for_unsigned_int!(u8 u16 u32 u64 u128 usize);
+
+    /// implement `PluralizeCount` for signed integer types
+    macro_rules! impl_pluralize_for_signed_int {
+        ($($ty:ty)*) => { $(
+            impl PluralizeCount for $ty {
+                type Error = Infallible;
+
+                #[inline]
+                fn is_singular(&self) -> Result<bool, Self::Error> {
+                    Ok(*self == 1 || *self == -1)
+                }
+            }
+        )* };
+    }
+
+    impl_pluralize_for_signed_int!(i8 i16 i32 i64 i128 isize);
+
+    /// implement `PluralizeCount` for non-zero integer types
+    macro_rules! impl_pluralize_for_non_zero {
+        ($($ty:ident)*) => { $(
+            impl PluralizeCount for core::num::$ty {
+                type Error = Infallible;
+
+                #[inline]
+                fn is_singular(&self) -> Result<bool, Self::Error> {
+                    self.get().is_singular()
+                }
+            }
+        )* };
+    }
+
+    impl_pluralize_for_non_zero! {
+        NonZeroI8 NonZeroI16 NonZeroI32 NonZeroI64 NonZeroI128 NonZeroIsize
+        NonZeroU8 NonZeroU16 NonZeroU32 NonZeroU64 NonZeroU128 NonZeroUsize
+    }
+};
+
+pub enum Pluralize<S, P> {
+    Singular(S),
+    Plural(P),
+}
+
+impl<S: fmt::Display, P: fmt::Display> fmt::Display for Pluralize<S, P> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pluralize::Singular(value) => write!(f, "{value}"),
+            Pluralize::Plural(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl<S: FastWritable, P: FastWritable> FastWritable for Pluralize<S, P> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        match self {
+            Pluralize::Singular(value) => value.write_into(dest, values),
+            Pluralize::Plural(value) => value.write_into(dest, values),
+        }
+    }
+}
+
+/// Returns the contained value of an [`Option`], or `default` if it is [`None`]
+///
+/// Unlike a test for truthiness, only a missing value is replaced: an empty string or a zero
+/// are rendered unchanged.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ nickname|default_if_none("n/a") }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     nickname: Option<&'a str>,
+/// }
+///
+/// assert_eq!(
+///     Example { nickname: Some("Bob") }.to_string(),
+///     "<div>Bob</div>"
+/// );
+/// assert_eq!(
+///     Example { nickname: Some("") }.to_string(),
+///     "<div></div>"
+/// );
+/// assert_eq!(
+///     Example { nickname: None }.to_string(),
+///     "<div>n/a</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn default_if_none<O: AsOption, D>(
+    value: O,
+    default: D,
+) -> Result<DefaultIfNone<O, D>, Infallible> {
+    Ok(DefaultIfNone { value, default })
+}
+
+/// A value that may be absent, like an [`Option`], used by the filter [`default_if_none()`]
+pub trait AsOption {
+    /// The type of the contained value.
+    type Value: ?Sized;
+
+    /// Returns the contained value, or [`None`] if it is absent.
+    fn as_option(&self) -> Option<&Self::Value>;
+}
+
+impl<T> AsOption for Option<T> {
+    type Value = T;
+
+    #[inline]
+    fn as_option(&self) -> Option<&Self::Value> {
+        self.as_ref()
+    }
+}
+
+crate::impl_for_ref! {
+    impl AsOption for T {
+        type Value = T::Value;
 
         #[inline]
-        fn is_singular(&self) -> Result<bool, Self::Error> {
-            self.as_ref().get_ref().is_singular()
+        fn as_option(&self) -> Option<&Self::Value> {
+            <T>::as_option(self)
         }
     }
+}
 
-    /// implement `PluralizeCount` for unsigned integer types
-    macro_rules! impl_pluralize_for_unsigned_int {
-        ($($ty:ty)*) => { $(
-            impl PluralizeCount for $ty {
-                type Error = Infallible;
+pub struct DefaultIfNone<O, D> {
+    value: O,
+    default: D,
+}
 
-                #[inline]
-                fn is_singular(&self) -> Result<bool, Self::Error> {
-                    Ok(*self == 1)
-                }
-            }
-        )* };
+impl<O, D> fmt::Display for DefaultIfNone<O, D>
+where
+    O: AsOption,
+    O::Value: fmt::Display,
+    D: fmt::Display,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.value.as_option() {
+            Some(value) => value.fmt(f),
+            None => self.default.fmt(f),
+        }
     }
+}
 
-    impl_pluralize_for_unsigned_int!(u8 u16 u32 u64 u128 usize);
+impl<O, D> FastWritable for DefaultIfNone<O, D>
+where
+    O: AsOption,
+    O::Value: FastWritable,
+    D: FastWritable,
+{
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        match self.value.as_option() {
+            Some(value) => value.write_into(dest, values),
+            None => self.default.write_into(dest, values),
+        }
+    }
+}
 
-    /// implement `PluralizeCount` for signed integer types
-    macro_rules! impl_pluralize_for_signed_int {
-        ($($ty:ty)*) => { $(
-            impl PluralizeCount for $ty {
-                type Error = Infallible;
+/// Returns the first of several values that is not empty
+///
+/// [`None`] and empty strings are empty, any other value is not. An [`Option`] that contains an
+/// empty string is empty, too. If all values are empty, nothing is rendered.
+///
+/// All values must contain the same type: e.g. a `&str`, a [`String`](alloc::string::String),
+/// a [`Cow<str>`](alloc::borrow::Cow) and an `Option` of any of them can be mixed, because they
+/// all contain a [`str`]. Numbers, [`bool`] and [`char`] are never empty.
+///
+/// In a template, the filter accepts any number of fallback values, which are tried in order.
+/// A number literal needs a suffix that matches the other values, e.g. `count|coalesce(0u32)`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ nickname|coalesce(username, email, "anonymous") }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     nickname: Option<&'a str>,
+///     username: &'a str,
+///     email: Option<String>,
+/// }
+///
+/// assert_eq!(
+///     Example { nickname: Some(""), username: "bob", email: None }.to_string(),
+///     "<div>bob</div>"
+/// );
+/// assert_eq!(
+///     Example { nickname: None, username: "", email: None }.to_string(),
+///     "<div>anonymous</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn coalesce<A, B>(value: A, fallback: B) -> Result<Coalesce<A, B>, Infallible>
+where
+    A: MaybeEmpty,
+    B: MaybeEmpty<Value = A::Value>,
+{
+    Ok(Coalesce { value, fallback })
+}
 
-                #[inline]
-                fn is_singular(&self) -> Result<bool, Self::Error> {
-                    Ok(*self == 1 || *self == -1)
-                }
+/// A value that may be empty, used by the filter [`coalesce()`]
+pub trait MaybeEmpty {
+    /// The type of the contained value.
+    type Value: ?Sized;
+
+    /// Returns the contained value, or [`None`] if it is empty.
+    fn non_empty(&self) -> Option<&Self::Value>;
+}
+
+const _: () = {
+    crate::impl_for_ref! {
+        impl MaybeEmpty for T {
+            type Value = T::Value;
+
+            #[inline]
+            fn non_empty(&self) -> Option<&Self::Value> {
+                <T>::non_empty(self)
             }
-        )* };
+        }
     }
 
-    impl_pluralize_for_signed_int!(i8 i16 i32 i64 i128 isize);
+    impl<T: MaybeEmpty> MaybeEmpty for Option<T> {
+        type Value = T::Value;
 
-    /// implement `PluralizeCount` for non-zero integer types
-    macro_rules! impl_pluralize_for_non_zero {
-        ($($ty:ident)*) => { $(
-            impl PluralizeCount for core::num::$ty {
-                type Error = Infallible;
+        #[inline]
+        fn non_empty(&self) -> Option<&Self::Value> {
+            self.as_ref()?.non_empty()
+        }
+    }
+
+    impl MaybeEmpty for str {
+        type Value = str;
+
+        #[inline]
+        fn non_empty(&self) -> Option<&Self::Value> {
+            (!self.is_empty()).then_some(self)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl MaybeEmpty for alloc::string::String {
+        type Value = str;
+
+        #[inline]
+        fn non_empty(&self) -> Option<&Self::Value> {
+            self.as_str().non_empty()
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<T> MaybeEmpty for alloc::borrow::Cow<'_, T>
+    where
+        T: MaybeEmpty + alloc::borrow::ToOwned + ?Sized,
+    {
+        type Value = T::Value;
+
+        #[inline]
+        fn non_empty(&self) -> Option<&Self::Value> {
+            T::non_empty(self)
+        }
+    }
+
+    impl<A, B> MaybeEmpty for Coalesce<A, B>
+    where
+        A: MaybeEmpty,
+        B: MaybeEmpty<Value = A::Value>,
+    {
+        type Value = A::Value;
+
+        #[inline]
+        fn non_empty(&self) -> Option<&Self::Value> {
+            self.value.non_empty().or_else(|| self.fallback.non_empty())
+        }
+    }
+
+    macro_rules! impl_maybe_empty_for_never_empty {
+        ($($ty:ty)*) => { $(
+            impl MaybeEmpty for $ty {
+                type Value = $ty;
 
                 #[inline]
-                fn is_singular(&self) -> Result<bool, Self::Error> {
-                    self.get().is_singular()
+                fn non_empty(&self) -> Option<&Self::Value> {
+                    Some(self)
                 }
             }
         )* };
     }
 
-    impl_pluralize_for_non_zero! {
-        NonZeroI8 NonZeroI16 NonZeroI32 NonZeroI64 NonZeroI128 NonZeroIsize
-        NonZeroU8 NonZeroU16 NonZeroU32 NonZeroU64 NonZeroU128 NonZeroUsize
-    }
+    impl_maybe_empty_for_never_empty!(
+        u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize f32 f64 bool char
+    );
 };
 
-pub enum Pluralize<S, P> {
-    Singular(S),
-    Plural(P),
+pub struct Coalesce<A, B> {
+    value: A,
+    fallback: B,
 }
 
-impl<S: fmt::Display, P: fmt::Display> fmt::Display for Pluralize<S, P> {
+impl<A, B> fmt::Display for Coalesce<A, B>
+where
+    A: MaybeEmpty,
+    A::Value: fmt::Display,
+    B: MaybeEmpty<Value = A::Value>,
+{
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Pluralize::Singular(value) => write!(f, "{value}"),
-            Pluralize::Plural(value) => write!(f, "{value}"),
+        match self.non_empty() {
+            Some(value) => value.fmt(f),
+            None => Ok(()),
         }
     }
 }
 
-impl<S: FastWritable, P: FastWritable> FastWritable for Pluralize<S, P> {
+impl<A, B> FastWritable for Coalesce<A, B>
+where
+    A: MaybeEmpty,
+    A::Value: FastWritable,
+    B: MaybeEmpty<Value = A::Value>,
+{
     #[inline]
     fn write_into<W: fmt::Write + ?Sized>(
         &self,
         dest: &mut W,
         values: &dyn Values,
     ) -> crate::Result<()> {
-        match self {
-            Pluralize::Singular(value) => value.write_into(dest, values),
-            Pluralize::Plural(value) => value.write_into(dest, values),
+        match self.non_empty() {
+            Some(value) => value.write_into(dest, values),
+            None => Ok(()),
         }
     }
 }
@@ -462,6 +2233,111 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_clamp() {
+        assert_eq!(clamp(-5, 0, 100).unwrap(), 0);
+        assert_eq!(clamp(42, 0, 100).unwrap(), 42);
+        assert_eq!(clamp(105, 0, 100).unwrap(), 100);
+        assert_eq!(clamp(0, 0, 100).unwrap(), 0);
+        assert_eq!(clamp(100, 0, 100).unwrap(), 100);
+        assert_eq!(clamp(7u8, 7, 7).unwrap(), 7);
+
+        assert_eq!(clamp(-0.5, 0.0, 1.0).unwrap(), 0.0);
+        assert_eq!(clamp(0.25, 0.0, 1.0).unwrap(), 0.25);
+        assert_eq!(clamp(1.5, 0.0, 1.0).unwrap(), 1.0);
+        assert_eq!(clamp(1.0, 0.0, 1.0).unwrap(), 1.0);
+        assert!(clamp(f64::NAN, 0.0, 1.0).unwrap().is_nan());
+        assert_eq!(clamp(f64::INFINITY, 0.0, 1.0).unwrap(), 1.0);
+    }
+
+    #[test]
+    fn test_ordinal() {
+        assert_eq!(ordinal(1, false).unwrap().to_string(), "1st");
+        assert_eq!(ordinal(2_u8, false).unwrap().to_string(), "2nd");
+        assert_eq!(ordinal(3_i64, false).unwrap().to_string(), "3rd");
+        assert_eq!(ordinal(4, false).unwrap().to_string(), "4th");
+        assert_eq!(ordinal(11, false).unwrap().to_string(), "11th");
+        assert_eq!(ordinal(12, false).unwrap().to_string(), "12th");
+        assert_eq!(ordinal(13, false).unwrap().to_string(), "13th");
+        assert_eq!(ordinal(21, false).unwrap().to_string(), "21st");
+        assert_eq!(ordinal(113, false).unwrap().to_string(), "113th");
+        assert_eq!(ordinal(0, false).unwrap().to_string(), "0th");
+        assert_eq!(ordinal(-1, false).unwrap().to_string(), "-1st");
+        assert_eq!(ordinal(-12, false).unwrap().to_string(), "-12th");
+        assert_eq!(
+            ordinal(i128::MIN, false).unwrap().to_string(),
+            "-170141183460469231731687303715884105728th"
+        );
+        assert_eq!(
+            ordinal(u128::MAX, false).unwrap().to_string(),
+            "340282366920938463463374607431768211455th"
+        );
+
+        assert_eq!(ordinal(1, true).unwrap().to_string(), "first");
+        assert_eq!(ordinal(12, true).unwrap().to_string(), "twelfth");
+        assert_eq!(ordinal(20, true).unwrap().to_string(), "twentieth");
+        assert_eq!(ordinal(21, true).unwrap().to_string(), "21st");
+        assert_eq!(ordinal(-3, true).unwrap().to_string(), "-3rd");
+        assert_eq!(ordinal(5_usize, true).unwrap().to_string(), "fifth");
+    }
+
+    #[test]
+    fn test_sign() {
+        assert_eq!(sign(-7).unwrap(), -1);
+        assert_eq!(sign(0).unwrap(), 0);
+        assert_eq!(sign(42_i128).unwrap(), 1);
+        assert_eq!(sign(i64::MIN).unwrap(), -1);
+        assert_eq!(sign(0_u8).unwrap(), 0);
+        assert_eq!(sign(u128::MAX).unwrap(), 1);
+        assert_eq!(sign(-0.5_f32).unwrap(), -1);
+        assert_eq!(sign(0.0).unwrap(), 0);
+        assert_eq!(sign(-0.0).unwrap(), 0);
+        assert_eq!(sign(f64::MIN_POSITIVE).unwrap(), 1);
+        assert_eq!(sign(f64::NEG_INFINITY).unwrap(), -1);
+        assert_eq!(sign(f64::NAN).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_sum_avg() {
+        assert_eq!(sum([1, 2, 3]).unwrap(), 6);
+        assert_eq!(sum([1u8, 2, 3].iter()).unwrap(), 6u8);
+        assert_eq!(sum(Vec::<i64>::new()).unwrap(), 0);
+        assert_eq!(sum([0.5, 0.25]).unwrap(), 0.75);
+        assert_eq!(sum([200u8, 100]), Err(SumOverflow));
+        assert_eq!(sum([i8::MIN, -1]), Err(SumOverflow));
+
+        assert_eq!(avg([1, 2, 3, 4]).unwrap(), 2.5);
+        assert_eq!(avg([1.5f32, 2.5].iter()).unwrap(), 2.0);
+        assert!(avg(Vec::<f64>::new()).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_repeat() {
+        assert_eq!(repeat("=", 5).unwrap().to_string(), "=====");
+        assert_eq!(repeat("ab", 2).unwrap().to_string(), "abab");
+        assert_eq!(repeat(7, 3).unwrap().to_string(), "777");
+        assert_eq!(repeat("=", 0).unwrap().to_string(), "");
+        assert_eq!(repeat("", 10).unwrap().to_string(), "");
+    }
+
+    #[test]
+    fn test_escape_control() {
+        assert_eq!(
+            escape_control("a\x1b[31mb\0c").unwrap().to_string(),
+            "a␛[31mb␀c"
+        );
+        assert_eq!(
+            escape_control("tab\tline\nreturn\r").unwrap().to_string(),
+            "tab\tline\nreturn␍"
+        );
+        assert_eq!(
+            escape_control("\x7f\u{85}\u{9b}ä").unwrap().to_string(),
+            "␡\\u{85}\\u{9b}ä"
+        );
+        assert_eq!(escape_control(42).unwrap().to_string(), "42");
+        assert_eq!(escape_control("").unwrap().to_string(), "");
+    }
+
     #[allow(clippy::needless_borrow)]
     #[test]
     fn test_join() {
@@ -497,6 +2373,177 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_join_human() {
+        fn render(items: &[&str], oxford: bool) -> String {
+            join_human(items, ", ", " and ", oxford)
+                .unwrap()
+                .to_string()
+        }
+
+        assert_eq!(render(&[], false), "");
+        assert_eq!(render(&["a"], false), "a");
+        assert_eq!(render(&["a"], true), "a");
+        assert_eq!(render(&["a", "b"], false), "a and b");
+        assert_eq!(render(&["a", "b"], true), "a and b");
+        assert_eq!(render(&["a", "b", "c"], false), "a, b and c");
+        assert_eq!(render(&["a", "b", "c"], true), "a, b, and c");
+        assert_eq!(render(&["a", "b", "c", "d"], false), "a, b, c and d");
+        assert_eq!(render(&["a", "b", "c", "d"], true), "a, b, c, and d");
+        assert_eq!(
+            join_human([1, 2, 3], "; ", " or ", true)
+                .unwrap()
+                .to_string(),
+            "1; 2; or 3"
+        );
+    }
+
+    #[test]
+    fn test_paginate() {
+        let page = |page, per_page| {
+            paginate(1..=7, page, per_page)
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(page(1, 3), [1, 2, 3]);
+        assert_eq!(page(2, 3), [4, 5, 6]);
+        assert_eq!(page(3, 3), [7]);
+        assert!(page(4, 3).is_empty());
+        assert!(page(0, 3).is_empty());
+        assert!(page(1, 0).is_empty());
+        assert_eq!(page(1, 10), [1, 2, 3, 4, 5, 6, 7]);
+        assert!(page(usize::MAX, 2).is_empty());
+
+        let items = [1, 2, 3];
+        let second = paginate(&items, 2, 2).unwrap();
+        assert_eq!(join(&second, ",").unwrap().to_string(), "3");
+        assert_eq!((&second).into_iter().count(), 1);
+
+        assert_eq!(page_count(1..=7, 3).unwrap(), 3);
+        assert_eq!(page_count(1..=6, 3).unwrap(), 2);
+        assert_eq!(page_count(1..=7, 10).unwrap(), 1);
+        assert_eq!(page_count(0..0, 3).unwrap(), 0);
+        assert_eq!(page_count(1..=7, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_zip() {
+        let pairs = zip(["a", "b", "c"], [1, 2]).unwrap();
+        assert_eq!((&pairs).into_iter().count(), 2);
+        assert_eq!(pairs.into_iter().collect::<Vec<_>>(), [("a", 1), ("b", 2)]);
+        assert_eq!(zip(0..2, 5..).unwrap().into_iter().last(), Some((1, 6)));
+        assert_eq!(zip(0..0, 0..3).unwrap().into_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_format_each() {
+        let items = format_each(["a", "<b>"], "<li>", "</li>", Html).unwrap();
+        assert_eq!(
+            join(&items, "").unwrap().to_string(),
+            "<li>a</li><li>&#60;b&#62;</li>"
+        );
+        // Iterating by reference can be repeated.
+        assert_eq!((&items).into_iter().count(), 2);
+        assert_eq!(
+            format_each([1, 2], "", "%", crate::filters::Text)
+                .unwrap()
+                .map(|item| item.to_string())
+                .collect::<Vec<_>>(),
+            ["1%", "2%"]
+        );
+        assert_eq!(
+            format_each(Vec::<u32>::new(), "[", "]", Html)
+                .unwrap()
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_flatten() {
+        let rows = alloc::vec![alloc::vec![1, 2], alloc::vec![], alloc::vec![3]];
+        let flat = flatten(&rows).unwrap();
+        assert_eq!(flat.clone().into_iter().collect::<Vec<_>>(), [&1, &2, &3]);
+        // Iterating by reference can be repeated.
+        assert_eq!((&flat).into_iter().count(), 3);
+        assert_eq!((&flat).into_iter().count(), 3);
+        assert_eq!(join(&flat, ", ").unwrap().to_string(), "1, 2, 3");
+
+        let empty: Vec<Vec<i32>> = alloc::vec![alloc::vec![], alloc::vec![]];
+        assert_eq!(
+            join(flatten(&empty).unwrap(), ", ").unwrap().to_string(),
+            ""
+        );
+
+        let cube = alloc::vec![
+            alloc::vec![alloc::vec!["a"], alloc::vec![]],
+            alloc::vec![alloc::vec!["b", "c"]]
+        ];
+        let flat = flatten(flatten(&cube).unwrap()).unwrap();
+        assert_eq!(join(flat, "").unwrap().to_string(), "abc");
+    }
+
+    #[test]
+    fn test_ljust_rjust() {
+        assert_eq!(ljust("f", 3, ' ').unwrap().to_string(), "f  ");
+        assert_eq!(ljust("f", 3, '*').unwrap().to_string(), "f**");
+        assert_eq!(rjust("f", 3, ' ').unwrap().to_string(), "  f");
+        assert_eq!(rjust(7, 3, '0').unwrap().to_string(), "007");
+        assert_eq!(ljust("äö", 4, '·').unwrap().to_string(), "äö··");
+        assert_eq!(ljust("foo bar", 3, ' ').unwrap().to_string(), "foo bar");
+        assert_eq!(rjust("foo bar", 7, ' ').unwrap().to_string(), "foo bar");
+        assert_eq!(
+            rjust("foo", 111_669_149_696, ' ').unwrap().to_string(),
+            "foo"
+        );
+
+        let red = "\x1b[31mred\x1b[0m";
+        assert_eq!(
+            ljust_ansi(red, 5, '.').unwrap().to_string(),
+            "\x1b[31mred\x1b[0m.."
+        );
+        assert_eq!(
+            rjust_ansi(red, 5, ' ').unwrap().to_string(),
+            "  \x1b[31mred\x1b[0m"
+        );
+        assert_eq!(rjust(red, 5, ' ').unwrap().to_string(), red);
+        assert_eq!(
+            ljust_ansi("\x1b[1;4mab\x1bc", 3, '.').unwrap().to_string(),
+            "\x1b[1;4mab\x1bc."
+        );
+    }
+
+    #[test]
+    fn test_visible_len() {
+        let len = |s| {
+            let len = visible_len(s);
+            let _ = len.to_string();
+            len.into_count()
+        };
+        assert_eq!(len(""), 0);
+        assert_eq!(len("abc"), 3);
+        assert_eq!(len("äöü"), 3);
+        assert_eq!(len("\x1b[1;31mred\x1b[0m"), 3);
+        assert_eq!(len("\x1b[32m表\x1b[0m-x"), 4);
+        assert_eq!(len("日本語"), 6);
+        assert_eq!(len("ｆｕｌｌ"), 8);
+        assert_eq!(len("한글"), 4);
+        assert_eq!(len("e\u{301}"), 1);
+        assert_eq!(len("a\u{200b}b"), 2);
+        assert_eq!(len("🦀"), 2);
+        assert_eq!(len("\u{1f680}"), 2);
+        assert_eq!(len("\u{1fa70}\u{2614}\u{26a1}"), 6);
+        assert_eq!(len("\u{2603}"), 1);
+    }
+
+    #[test]
+    fn test_wide_chars_sorted() {
+        for pair in WIDE_CHARS.windows(2) {
+            assert!(pair[0].0 <= pair[0].1 && pair[0].1 < pair[1].0, "{pair:x?}");
+        }
+    }
+
     #[test]
     fn test_center() {
         assert_eq!(center("f", 3).unwrap().to_string(), " f ".to_string());
@@ -510,5 +2557,48 @@ mod tests {
             center("foo", 111_669_149_696).unwrap().to_string(),
             "foo".to_string()
         );
+        assert_eq!(
+            center_ansi("\x1b[32mok\x1b[0m", 5).unwrap().to_string(),
+            " \x1b[32mok\x1b[0m  "
+        );
+        assert_eq!(center_ansi("日本", 8).unwrap().to_string(), "  日本  ");
+        assert_eq!(center("日本", 8).unwrap().to_string(), "   日本   ");
+    }
+
+    #[test]
+    fn test_coalesce() {
+        assert_eq!(coalesce("a", "b").unwrap().to_string(), "a");
+        assert_eq!(coalesce("", "b").unwrap().to_string(), "b");
+        assert_eq!(coalesce(None::<&str>, "b").unwrap().to_string(), "b");
+        assert_eq!(coalesce(Some(""), "b").unwrap().to_string(), "b");
+        assert_eq!(coalesce(Some("a"), "b").unwrap().to_string(), "a");
+        assert_eq!(coalesce("", "").unwrap().to_string(), "");
+        assert_eq!(
+            coalesce(&Some(String::new()), coalesce("", "c").unwrap())
+                .unwrap()
+                .to_string(),
+            "c"
+        );
+        assert_eq!(coalesce(None::<u8>, 0_u8).unwrap().to_string(), "0");
+        assert_eq!(coalesce(Some(1_u8), 0_u8).unwrap().to_string(), "1");
+    }
+
+    #[test]
+    fn test_default_if_none() {
+        assert_eq!(default_if_none(Some("a"), "-").unwrap().to_string(), "a");
+        assert_eq!(default_if_none(Some(""), "-").unwrap().to_string(), "");
+        assert_eq!(default_if_none(Some(0), "-").unwrap().to_string(), "0");
+        assert_eq!(default_if_none(None::<u32>, "-").unwrap().to_string(), "-");
+        assert_eq!(
+            default_if_none(&&Some(false), 1).unwrap().to_string(),
+            "false"
+        );
+
+        let mut output = String::new();
+        default_if_none(&None::<&str>, "missing")
+            .unwrap()
+            .write_into(&mut output, crate::NO_VALUES)
+            .unwrap();
+        assert_eq!(output, "missing");
     }
 }
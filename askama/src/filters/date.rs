@@ -0,0 +1,212 @@
+use core::convert::Infallible;
+use core::fmt;
+
+use chrono::format::{DelayedFormat, StrftimeItems};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+
+use crate::{FastWritable, Values};
+
+/// Formats a date with a [`strftime`](chrono::format::strftime)-like format string
+///
+/// The value can be any [`chrono`] date or time type, i.e. [`NaiveDate`], [`NaiveDateTime`],
+/// [`NaiveTime`] or [`DateTime`], or a reference to it.
+/// Without an argument, the date is formatted as `"%Y-%m-%d"`, e.g. `2025-03-08`.
+///
+/// Rendering fails with [`Error::Fmt`](crate::Error::Fmt) if the format string is invalid, or if
+/// it requests a field the value does not have, e.g. the hour of a [`NaiveDate`].
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// # use chrono::NaiveDate;
+/// /// ```jinja
+/// /// <div>{{ day|date }}</div>
+/// /// <div>{{ day|date("%d.%m.%Y") }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example {
+///     day: NaiveDate,
+/// }
+///
+/// let day = NaiveDate::from_ymd_opt(2025, 3, 8).unwrap();
+/// assert_eq!(
+///     Example { day }.to_string(),
+///     "<div>2025-03-08</div>\n<div>08.03.2025</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn date<T: FormatDate, F: AsRef<str>>(
+    value: T,
+    format: F,
+) -> Result<DateFormat<T, F>, Infallible> {
+    Ok(DateFormat { value, format })
+}
+
+/// Formats a date and time with a [`strftime`](chrono::format::strftime)-like format string
+///
+/// This is the same filter as [`date()`], but without an argument, the value is formatted as
+/// `"%Y-%m-%d %H:%M:%S"`, e.g. `2025-03-08 14:05:00`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// # use chrono::{NaiveDate, NaiveDateTime};
+/// /// ```jinja
+/// /// <div>{{ created_at|datetime }}</div>
+/// /// <div>{{ created_at|datetime("%H:%M") }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example {
+///     created_at: NaiveDateTime,
+/// }
+///
+/// let created_at = NaiveDate::from_ymd_opt(2025, 3, 8)
+///     .unwrap()
+///     .and_hms_opt(14, 5, 0)
+///     .unwrap();
+/// assert_eq!(
+///     Example { created_at }.to_string(),
+///     "<div>2025-03-08 14:05:00</div>\n<div>14:05</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn datetime<T: FormatDate, F: AsRef<str>>(
+    value: T,
+    format: F,
+) -> Result<DateFormat<T, F>, Infallible> {
+    date(value, format)
+}
+
+pub struct DateFormat<T, F> {
+    value: T,
+    format: F,
+}
+
+impl<T: FormatDate, F: AsRef<str>> fmt::Display for DateFormat<T, F> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value.format_date(self.format.as_ref()))
+    }
+}
+
+impl<T: FormatDate, F: AsRef<str>> FastWritable for DateFormat<T, F> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        _: &dyn Values,
+    ) -> crate::Result<()> {
+        Ok(write!(
+            dest,
+            "{}",
+            self.value.format_date(self.format.as_ref())
+        )?)
+    }
+}
+
+/// A date or time that can be formatted by the filters [`date()`] and [`datetime()`]
+pub trait FormatDate {
+    /// Formats the value with a [`strftime`](chrono::format::strftime)-like format string.
+    fn format_date<'a>(&self, format: &'a str) -> DelayedFormat<StrftimeItems<'a>>;
+}
+
+crate::impl_for_ref! {
+    impl FormatDate for T {
+        #[inline]
+        fn format_date<'a>(&self, format: &'a str) -> DelayedFormat<StrftimeItems<'a>> {
+            T::format_date(self, format)
+        }
+    }
+}
+
+impl FormatDate for NaiveDate {
+    #[inline]
+    fn format_date<'a>(&self, format: &'a str) -> DelayedFormat<StrftimeItems<'a>> {
+        self.format(format)
+    }
+}
+
+impl FormatDate for NaiveDateTime {
+    #[inline]
+    fn format_date<'a>(&self, format: &'a str) -> DelayedFormat<StrftimeItems<'a>> {
+        self.format(format)
+    }
+}
+
+impl FormatDate for NaiveTime {
+    #[inline]
+    fn format_date<'a>(&self, format: &'a str) -> DelayedFormat<StrftimeItems<'a>> {
+        self.format(format)
+    }
+}
+
+impl<Tz: TimeZone> FormatDate for DateTime<Tz>
+where
+    Tz::Offset: fmt::Display,
+{
+    #[inline]
+    fn format_date<'a>(&self, format: &'a str) -> DelayedFormat<StrftimeItems<'a>> {
+        self.format(format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use core::fmt::Write;
+
+    use chrono::FixedOffset;
+
+    use super::*;
+
+    fn day() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, 8).unwrap()
+    }
+
+    #[test]
+    fn test_date() {
+        assert_eq!(date(day(), "%Y-%m-%d").unwrap().to_string(), "2025-03-08");
+        assert_eq!(date(&day(), "%d/%m/%y").unwrap().to_string(), "08/03/25");
+        assert_eq!(
+            date(day(), "%A, %B %-d").unwrap().to_string(),
+            "Saturday, March 8"
+        );
+    }
+
+    #[test]
+    fn test_datetime() {
+        let naive = day().and_hms_opt(14, 5, 9).unwrap();
+        assert_eq!(
+            datetime(naive, "%Y-%m-%d %H:%M:%S").unwrap().to_string(),
+            "2025-03-08 14:05:09"
+        );
+        assert_eq!(
+            datetime(naive.time(), "%H:%M").unwrap().to_string(),
+            "14:05"
+        );
+
+        let utc = naive.and_utc();
+        assert_eq!(
+            datetime(utc, "%Y-%m-%dT%H:%M:%S%:z").unwrap().to_string(),
+            "2025-03-08T14:05:09+00:00"
+        );
+        let offset = utc.with_timezone(&FixedOffset::east_opt(3600).unwrap());
+        assert_eq!(
+            datetime(offset, "%H:%M %z").unwrap().to_string(),
+            "15:05 +0100"
+        );
+    }
+
+    #[test]
+    fn test_date_invalid() {
+        let mut buf = alloc::string::String::new();
+        // a `NaiveDate` has no hours
+        assert!(write!(buf, "{}", date(day(), "%H").unwrap()).is_err());
+        // unknown specifier
+        assert!(write!(buf, "{}", date(day(), "%Q").unwrap()).is_err());
+    }
+}
@@ -38,6 +38,46 @@ pub fn safe<T, E>(text: T, escaper: E) -> Result<Safe<T>, Infallible> {
     Ok(Safe(text))
 }
 
+/// Marks a string (or other `Display` type) as safe if the condition is `true`
+///
+/// Unlike [`safe()`], the decision is made at runtime: if `cond` is `false`, then the
+/// expression's contents are escaped as usual.
+///
+/// Askama will automatically insert the second (`Escaper`) argument,
+/// so this filter only takes the value and the condition.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|safe_if(trusted) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+///     trusted: bool,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "<b>bold</b>", trusted: true }.to_string(),
+///     "<div><b>bold</b></div>"
+/// );
+/// assert_eq!(
+///     Example { example: "<b>bold</b>", trusted: false }.to_string(),
+///     "<div>&#60;b&#62;bold&#60;/b&#62;</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn safe_if<T, E>(text: T, escaper: E, cond: bool) -> Result<MaybeSafe<T>, Infallible> {
+    let _ = escaper; // it should not be part of the interface that the `escaper` is unused
+    Ok(match cond {
+        true => MaybeSafe::Safe(text),
+        false => MaybeSafe::NeedsEscaping(text),
+    })
+}
+
 /// Escapes strings according to the escape mode.
 ///
 /// Askama will automatically insert the first (`Escaper`) argument,
@@ -70,12 +110,12 @@ pub fn escape<T, E>(text: T, escaper: E) -> Result<Safe<EscapeDisplay<T, E>>, In
     Ok(Safe(EscapeDisplay(text, escaper)))
 }
 
-pub struct EscapeDisplay<T, E>(T, E);
+pub struct EscapeDisplay<T, E>(pub(crate) T, pub(crate) E);
 
 impl<T: fmt::Display, E: Escaper> fmt::Display for EscapeDisplay<T, E> {
     #[inline]
     fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
-        write!(EscapeWriter(fmt, self.1), "{}", &self.0)
+        write!(EscapeWriter::new(fmt, self.1), "{}", &self.0)
     }
 }
 
@@ -86,21 +126,79 @@ impl<T: FastWritable, E: Escaper> FastWritable for EscapeDisplay<T, E> {
         dest: &mut W,
         values: &dyn Values,
     ) -> crate::Result<()> {
-        self.0.write_into(&mut EscapeWriter(dest, self.1), values)
+        let mut dest = EscapeWriter::new(dest, self.1);
+        match self.0.write_into(&mut dest, values) {
+            Err(crate::Error::Fmt) if dest.escaper_failed => Err(crate::Error::Escape),
+            result => result,
+        }
     }
 }
 
-struct EscapeWriter<W, E>(W, E);
+struct EscapeWriter<W, E> {
+    dest: W,
+    escaper: E,
+    /// The escaper returned an error, but not because writing to `dest` failed.
+    escaper_failed: bool,
+}
+
+impl<W, E> EscapeWriter<W, E> {
+    #[inline]
+    fn new(dest: W, escaper: E) -> Self {
+        Self {
+            dest,
+            escaper,
+            escaper_failed: false,
+        }
+    }
+}
 
 impl<W: Write, E: Escaper> Write for EscapeWriter<W, E> {
     #[inline]
     fn write_str(&mut self, s: &str) -> fmt::Result {
-        self.1.write_escaped_str(&mut self.0, s)
+        let mut dest = FailureTracker::new(&mut self.dest);
+        let result = self.escaper.write_escaped_str(&mut dest, s);
+        self.escaper_failed |= result.is_err() && !dest.failed;
+        result
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        let mut dest = FailureTracker::new(&mut self.dest);
+        let result = self.escaper.write_escaped_char(&mut dest, c);
+        self.escaper_failed |= result.is_err() && !dest.failed;
+        result
+    }
+}
+
+/// Remembers if writing to the wrapped writer failed.
+struct FailureTracker<W> {
+    dest: W,
+    failed: bool,
+}
+
+impl<W> FailureTracker<W> {
+    #[inline]
+    fn new(dest: W) -> Self {
+        Self {
+            dest,
+            failed: false,
+        }
+    }
+}
+
+impl<W: Write> Write for FailureTracker<W> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let result = self.dest.write_str(s);
+        self.failed |= result.is_err();
+        result
     }
 
     #[inline]
     fn write_char(&mut self, c: char) -> fmt::Result {
-        self.1.write_escaped_char(&mut self.0, c)
+        let result = self.dest.write_char(c);
+        self.failed |= result.is_err();
+        result
     }
 }
 
@@ -167,6 +265,124 @@ impl Escaper for Text {
     }
 }
 
+/// Quote strings as arguments for a POSIX `sh` compatible shell
+///
+/// Every string is put in single quotes, and embedded single quotes are written as `'\''`.
+/// Inside of single quotes, the shell does not interpret any character, so a value that is
+/// interpolated as a bare, unquoted word cannot inject commands, e.g. `; rm -rf /` stays a
+/// literal string.
+///
+/// The escaper does not know where in the script the value ends up, though. Inside of double
+/// quotes, e.g. `echo "{{ value }}"`, the added single quotes are literal characters, so `$(…)`
+/// and backticks in the value are still executed. Inside of single quotes, e.g.
+/// `echo 'x{{ value }}'`, the added quotes end the quoted string, so the value is not quoted at
+/// all. Never put an interpolation inside of quotes, or in a heredoc.
+///
+/// Please note that a value is not guaranteed to be quoted in one piece, e.g. `'a''b'`, which
+/// the shell concatenates to a single argument. A value that writes nothing does not produce an
+/// argument. Use the filter [`shell_escape()`] to always get exactly one argument.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Shell;
+
+impl Escaper for Shell {
+    #[inline]
+    fn write_escaped_str<W: Write>(&self, mut dest: W, string: &str) -> fmt::Result {
+        dest.write_char('\'')?;
+        write_shell_quoted_content(&mut dest, string)?;
+        dest.write_char('\'')
+    }
+}
+
+fn write_shell_quoted_content<W: Write + ?Sized>(dest: &mut W, string: &str) -> fmt::Result {
+    let mut parts = string.split('\'');
+    if let Some(part) = parts.next() {
+        dest.write_str(part)?;
+    }
+    for part in parts {
+        dest.write_str("'\\''")?;
+        dest.write_str(part)?;
+    }
+    Ok(())
+}
+
+/// Quote a value as a single argument for a POSIX `sh` compatible shell
+///
+/// The value is put in single quotes, and embedded single quotes are written as `'\''`, so a
+/// value that is interpolated as a bare, unquoted word cannot inject shell commands. An empty
+/// value becomes `''`. Like the [`Shell`] escaper, the filter does not protect a value that is
+/// interpolated inside of quotes, e.g. `"{{ value|shell_escape }}"`.
+///
+/// In a template that uses the [`Shell`] escaper, e.g. with the extension `.sh`, the output of
+/// this filter is not escaped again. In other templates, it is escaped as usual.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// git commit -m {{ message|shell_escape }}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example<'a> {
+///     message: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { message: "it's done; rm -rf /" }.to_string(),
+///     "git commit -m 'it'\\''s done; rm -rf /'"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn shell_escape<T: fmt::Display>(text: T) -> Result<ShellQuoted<T>, Infallible> {
+    Ok(ShellQuoted(text))
+}
+
+/// Result of the filter [`shell_escape()`]; like [`Safe`], but only for [`Shell`] output
+pub struct ShellQuoted<T>(T);
+
+impl<T: fmt::Display> fmt::Display for ShellQuoted<T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_char('\'')?;
+        write!(ShellQuotedWriter(&mut *f), "{}", self.0)?;
+        f.write_char('\'')
+    }
+}
+
+impl<T: FastWritable> FastWritable for ShellQuoted<T> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        dest.write_char('\'')?;
+        self.0
+            .write_into(&mut ShellQuotedWriter(&mut *dest), values)?;
+        Ok(dest.write_char('\'')?)
+    }
+}
+
+impl<'a, T: fmt::Display> AutoEscape for &AutoEscaper<'a, ShellQuoted<T>, Shell> {
+    type Escaped = &'a ShellQuoted<T>;
+    type Error = Infallible;
+
+    #[inline]
+    fn askama_auto_escape(&self) -> Result<Self::Escaped, Self::Error> {
+        Ok(self.text)
+    }
+}
+
+struct ShellQuotedWriter<W>(W);
+
+impl<W: Write> Write for ShellQuotedWriter<W> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        write_shell_quoted_content(&mut self.0, s)
+    }
+}
+
 /// Escapers are used to make generated text safe for printing in some context.
 ///
 /// E.g. in an [`Html`] context, any and all generated text can be used in HTML/XML text nodes and
@@ -371,10 +587,12 @@ const _: () = {
     }
 };
 
-/// Mark the output of a filter as "safe"
+/// Mark a value or the output of a filter as "safe"
 ///
 /// This struct can be used as a transparent return type of custom filters that want to mark their
 /// output as "safe" no matter what, i.e. that their output does not need to be escaped.
+/// A template field or any other value wrapped in `Safe` is not escaped either, just like a value
+/// passed through the [`|safe`](crate::filters::safe) filter.
 ///
 /// If the filter is not used as the last element in the filter chain, then any assumption is void.
 /// Let the next filter decide if the output is safe or not.
@@ -533,6 +751,94 @@ fn test_escape() {
     assert_eq!(escape("bla&", Text).unwrap().to_string(), "bla&");
     assert_eq!(escape("<foo", Text).unwrap().to_string(), "<foo");
     assert_eq!(escape("bla&h", Text).unwrap().to_string(), "bla&h");
+
+    assert_eq!(escape("", Shell).unwrap().to_string(), "''");
+    assert_eq!(escape("a b", Shell).unwrap().to_string(), "'a b'");
+    assert_eq!(escape("it's", Shell).unwrap().to_string(), r"'it'\''s'");
+    assert_eq!(escape("''", Shell).unwrap().to_string(), r"''\'''\'''");
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_escape_error() {
+    use alloc::string::String;
+
+    #[derive(Clone, Copy)]
+    struct NoNul;
+
+    impl Escaper for NoNul {
+        fn write_escaped_str<W: Write>(&self, mut dest: W, string: &str) -> fmt::Result {
+            if string.contains('\0') {
+                return Err(fmt::Error);
+            }
+            dest.write_str(string)
+        }
+    }
+
+    struct Failing;
+
+    impl Write for Failing {
+        fn write_str(&mut self, _: &str) -> fmt::Result {
+            Err(fmt::Error)
+        }
+    }
+
+    let mut dest = String::new();
+    assert!(
+        escape("a", NoNul)
+            .unwrap()
+            .write_into(&mut dest, &())
+            .is_ok()
+    );
+    assert_eq!(dest, "a");
+    assert!(matches!(
+        escape("a\0", NoNul).unwrap().write_into(&mut dest, &()),
+        Err(crate::Error::Escape)
+    ));
+
+    // A failing writer is not reported as a failing escaper.
+    assert!(matches!(
+        escape("a", NoNul).unwrap().write_into(&mut Failing, &()),
+        Err(crate::Error::Fmt)
+    ));
+    assert!(matches!(
+        escape("<a>", Html).unwrap().write_into(&mut Failing, &()),
+        Err(crate::Error::Fmt)
+    ));
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_shell_escape() {
+    use alloc::string::ToString;
+
+    assert_eq!(shell_escape("").unwrap().to_string(), "''");
+    assert_eq!(
+        shell_escape("$HOME `id`").unwrap().to_string(),
+        "'$HOME `id`'"
+    );
+    assert_eq!(
+        shell_escape("x'; rm -rf /; echo '").unwrap().to_string(),
+        r"'x'\''; rm -rf /; echo '\'''"
+    );
+    assert_eq!(shell_escape(42).unwrap().to_string(), "'42'");
+
+    // Not quoted twice by the `Shell` escaper, but escaped by other escapers.
+    let quoted = shell_escape("<it's>").unwrap();
+    assert_eq!(
+        (&&AutoEscaper::new(&quoted, Shell))
+            .askama_auto_escape()
+            .unwrap()
+            .to_string(),
+        r"'<it'\''s>'"
+    );
+    assert_eq!(
+        (&&AutoEscaper::new(&quoted, Html))
+            .askama_auto_escape()
+            .unwrap()
+            .to_string(),
+        "&#39;&#60;it&#39;\\&#39;&#39;s&#62;&#39;"
+    );
 }
 
 #[test]
@@ -0,0 +1,251 @@
+use std::convert::Infallible;
+use std::fmt::{self, Write};
+use std::string::String;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{FastWritable, Values};
+
+/// Limit string length to `length` grapheme clusters, appends '...' if truncated
+///
+/// In contrast to [`truncate()`][super::truncate], the length is counted in user-perceived
+/// characters, so an emoji that consists of multiple code points is never split.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|truncate_graphemes(2) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "🇩🇪🇫🇷🇮🇹" }.to_string(),
+///     "<div>🇩🇪🇫🇷...</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn truncate_graphemes<S: fmt::Display>(
+    source: S,
+    length: usize,
+) -> Result<TruncateGraphemes<S>, Infallible> {
+    Ok(TruncateGraphemes { source, length })
+}
+
+pub struct TruncateGraphemes<S> {
+    source: S,
+    length: usize,
+}
+
+impl<S: fmt::Display> fmt::Display for TruncateGraphemes<S> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer = String::new();
+        write!(buffer, "{}", self.source)?;
+        write_truncated_graphemes(dest, &buffer, self.length)
+    }
+}
+
+impl<S: FastWritable> FastWritable for TruncateGraphemes<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        let mut buffer = String::new();
+        self.source.write_into(&mut buffer, values)?;
+        Ok(write_truncated_graphemes(dest, &buffer, self.length)?)
+    }
+}
+
+fn write_truncated_graphemes<W: fmt::Write + ?Sized>(
+    dest: &mut W,
+    s: &str,
+    length: usize,
+) -> fmt::Result {
+    match s.grapheme_indices(true).nth(length) {
+        Some((end, _)) => {
+            dest.write_str(&s[..end])?;
+            dest.write_str("...")
+        }
+        None => dest.write_str(s),
+    }
+}
+
+/// Count the grapheme clusters, i.e. the user-perceived characters, in that string
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|length_graphemes }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "👨‍👩‍👧 family" }.to_string(),
+///     "<div>8</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn length_graphemes<S>(source: S) -> LengthGraphemes<S> {
+    LengthGraphemes {
+        source,
+        buffer: Default::default(),
+    }
+}
+
+pub struct LengthGraphemes<S> {
+    source: S,
+    buffer: core::cell::RefCell<String>,
+}
+
+impl<S: fmt::Display> fmt::Display for LengthGraphemes<S> {
+    #[inline]
+    fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer = self.buffer.borrow_mut();
+        buffer.clear();
+        write!(buffer, "{}", self.source)
+    }
+}
+
+impl<S: FastWritable> FastWritable for LengthGraphemes<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        _: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        let mut buffer = self.buffer.borrow_mut();
+        buffer.clear();
+        self.source.write_into(&mut *buffer, values)
+    }
+}
+
+impl<S> LengthGraphemes<S> {
+    pub fn into_count(self) -> usize {
+        self.buffer.into_inner().graphemes(true).count()
+    }
+}
+
+/// Reverse the order of the grapheme clusters, i.e. the user-perceived characters, in that string
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|reverse_graphemes }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "ae\u{301}🇩🇪" }.to_string(),
+///     "<div>🇩🇪e\u{301}a</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn reverse_graphemes<S: fmt::Display>(source: S) -> Result<ReverseGraphemes<S>, Infallible> {
+    Ok(ReverseGraphemes { source })
+}
+
+pub struct ReverseGraphemes<S> {
+    source: S,
+}
+
+impl<S: fmt::Display> fmt::Display for ReverseGraphemes<S> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer = String::new();
+        write!(buffer, "{}", self.source)?;
+        write_reversed_graphemes(dest, &buffer)
+    }
+}
+
+impl<S: FastWritable> FastWritable for ReverseGraphemes<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        let mut buffer = String::new();
+        self.source.write_into(&mut buffer, values)?;
+        Ok(write_reversed_graphemes(dest, &buffer)?)
+    }
+}
+
+fn write_reversed_graphemes<W: fmt::Write + ?Sized>(dest: &mut W, s: &str) -> fmt::Result {
+    s.graphemes(true).rev().try_for_each(|g| dest.write_str(g))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::ToString;
+
+    use super::*;
+    use crate::NO_VALUES;
+
+    const FAMILY: &str = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}";
+
+    #[test]
+    fn test_truncate_graphemes() {
+        assert_eq!(truncate_graphemes("hello", 2).unwrap().to_string(), "he...");
+        assert_eq!(truncate_graphemes("hello", 5).unwrap().to_string(), "hello");
+        assert_eq!(truncate_graphemes("hello", 0).unwrap().to_string(), "...");
+        assert_eq!(truncate_graphemes("", 0).unwrap().to_string(), "");
+
+        let s = std::format!("{FAMILY}{FAMILY}!");
+        assert_eq!(
+            truncate_graphemes(&s, 1).unwrap().to_string(),
+            std::format!("{FAMILY}...")
+        );
+        assert_eq!(truncate_graphemes(&s, 3).unwrap().to_string(), s);
+
+        let mut buf = String::new();
+        truncate_graphemes("e\u{301}e\u{301}", 1)
+            .unwrap()
+            .write_into(&mut buf, NO_VALUES)
+            .unwrap();
+        assert_eq!(buf, "e\u{301}...");
+    }
+
+    #[test]
+    fn test_length_graphemes() {
+        let count = |s: &str| {
+            let filter = length_graphemes(s);
+            filter.write_into(&mut String::new(), NO_VALUES).unwrap();
+            filter.into_count()
+        };
+        assert_eq!(count(""), 0);
+        assert_eq!(count("hello"), 5);
+        assert_eq!(count(FAMILY), 1);
+        assert_eq!(count("\u{1f1e9}\u{1f1ea}e\u{301}"), 2);
+    }
+
+    #[test]
+    fn test_reverse_graphemes() {
+        assert_eq!(reverse_graphemes("abc").unwrap().to_string(), "cba");
+        assert_eq!(
+            reverse_graphemes(std::format!("a{FAMILY}e\u{301}"))
+                .unwrap()
+                .to_string(),
+            std::format!("e\u{301}{FAMILY}a")
+        );
+    }
+}
@@ -4,7 +4,7 @@ use std::{fmt, io, str};
 use serde::Serialize;
 use serde_json::ser::{CompactFormatter, PrettyFormatter, Serializer};
 
-use super::AsIndent;
+use super::{AsIndent, HtmlSafeOutput};
 use crate::ascii_str::{AsciiChar, AsciiStr};
 use crate::{FastWritable, NO_VALUES, Values};
 
@@ -89,11 +89,49 @@ pub fn json_pretty(
     Ok(ToJsonPretty { value, indent })
 }
 
+/// Serialize to JSON that can be embedded in any HTML attribute (requires `json` feature)
+///
+/// Like [`json()`], the generated string does not contain ampersands `&`, chevrons `< >`, or
+/// apostrophes `'`. Additionally, quotation marks `"` are replaced by the HTML entity `&#34;`,
+/// which the browser decodes again when it reads the attribute. This way, the output can be
+/// used in attributes that are delimited by either quotation marks or apostrophes.
+/// The output is marked as HTML safe, so no further escaping is needed.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div data-config="{{ config|json_attr }}" data-other='{{ config|json_attr }}'></div>
+/// /// ```
+///
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     config: Vec<&'a str>,
+/// }
+///
+/// assert_eq!(
+///     Example { config: vec!["a\"b", "c'd"] }.to_string(),
+///     "<div data-config=\"[&#34;a\\&#34;b&#34;,&#34;c\\u0027d&#34;]\" \
+///      data-other='[&#34;a\\&#34;b&#34;,&#34;c\\u0027d&#34;]'></div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn json_attr(value: impl Serialize) -> Result<HtmlSafeOutput<impl fmt::Display>, Infallible> {
+    Ok(HtmlSafeOutput(ToJsonAttr { value }))
+}
+
 #[derive(Debug, Clone)]
 struct ToJson<S> {
     value: S,
 }
 
+#[derive(Debug, Clone)]
+struct ToJsonAttr<S> {
+    value: S,
+}
+
 #[derive(Debug, Clone)]
 struct ToJsonPretty<S, I> {
     value: S,
@@ -114,6 +152,35 @@ impl<S: Serialize> fmt::Display for ToJson<S> {
     }
 }
 
+impl<S: Serialize> FastWritable for ToJsonAttr<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(&self, f: &mut W, _: &dyn Values) -> crate::Result<()> {
+        serialize(&mut QuotEscaper(f), &self.value, CompactFormatter)
+    }
+}
+
+impl<S: Serialize> fmt::Display for ToJsonAttr<S> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Ok(self.write_into(f, NO_VALUES)?)
+    }
+}
+
+/// Replaces every quotation mark `"` with the HTML entity `&#34;`
+struct QuotEscaper<'a, W: fmt::Write + ?Sized>(&'a mut W);
+
+impl<W: fmt::Write + ?Sized> fmt::Write for QuotEscaper<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut last = 0;
+        for (index, _) in s.match_indices('"') {
+            write_str_if_nonempty(self.0, &s[last..index])?;
+            self.0.write_str("&#34;")?;
+            last = index + 1;
+        }
+        write_str_if_nonempty(self.0, &s[last..])
+    }
+}
+
 impl<S: Serialize, I: AsIndent> FastWritable for ToJsonPretty<S, I> {
     #[inline]
     fn write_into<W: fmt::Write + ?Sized>(&self, f: &mut W, _: &dyn Values) -> crate::Result<()> {
@@ -300,6 +367,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_attr() {
+        assert_eq!(json_attr(true).unwrap().to_string(), "true");
+        assert_eq!(json_attr("foo").unwrap().to_string(), "&#34;foo&#34;");
+        assert_eq!(
+            json_attr(r#"'"<>&"#).unwrap().to_string(),
+            r#"&#34;\u0027\&#34;\u003c\u003e\u0026&#34;"#
+        );
+        assert_eq!(
+            json_attr(vec!["foo", "bar"]).unwrap().to_string(),
+            "[&#34;foo&#34;,&#34;bar&#34;]"
+        );
+    }
+
     #[test]
     fn test_pretty() {
         assert_eq!(json_pretty(true, "").unwrap().to_string(), "true");
@@ -0,0 +1,172 @@
+use std::convert::Infallible;
+use std::fmt::{self, Write};
+use std::string::String;
+
+use pulldown_cmark::{CowStr, Event, Options, Parser, Tag, html};
+
+use crate::filters::HtmlSafeOutput;
+use crate::{FastWritable, Values};
+
+/// Renders markdown to HTML
+///
+/// The output is marked as HTML safe. If the input comes from an untrusted source, keep
+/// `sanitize` enabled (which is the default): raw HTML in the input will then be escaped instead
+/// of being copied verbatim to the output, and links with a `javascript:`, `vbscript:` or `data:`
+/// URL are removed.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ comment|markdown }}</div>
+/// /// <div>{{ trusted|markdown(sanitize = false) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     comment: &'a str,
+///     trusted: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example {
+///         comment: "**bold** <b>tag</b>",
+///         trusted: "**bold** <b>tag</b>",
+///     }.to_string(),
+///     "<div><p><strong>bold</strong> &lt;b&gt;tag&lt;/b&gt;</p>\n</div>\n\
+///      <div><p><strong>bold</strong> <b>tag</b></p>\n</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn markdown<S: fmt::Display>(
+    source: S,
+    sanitize: bool,
+) -> Result<HtmlSafeOutput<Markdown<S>>, Infallible> {
+    Ok(HtmlSafeOutput(Markdown { source, sanitize }))
+}
+
+pub struct Markdown<S> {
+    source: S,
+    sanitize: bool,
+}
+
+impl<S: fmt::Display> fmt::Display for Markdown<S> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer = String::new();
+        write!(buffer, "{}", self.source)?;
+        flush_markdown(dest, &buffer, self.sanitize)
+    }
+}
+
+impl<S: FastWritable> FastWritable for Markdown<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        let mut buffer = String::new();
+        self.source.write_into(&mut buffer, values)?;
+        Ok(flush_markdown(dest, &buffer, self.sanitize)?)
+    }
+}
+
+fn flush_markdown(dest: &mut (impl fmt::Write + ?Sized), s: &str, sanitize: bool) -> fmt::Result {
+    let parser = Parser::new_ext(s, Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH);
+    if sanitize {
+        html::write_html_fmt(dest, parser.map(sanitize_event))
+    } else {
+        html::write_html_fmt(dest, parser)
+    }
+}
+
+fn sanitize_event(event: Event<'_>) -> Event<'_> {
+    match event {
+        // `Text` gets escaped by the HTML writer.
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: sanitize_url(dest_url),
+            title,
+            id,
+        }),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Image {
+            link_type,
+            dest_url: sanitize_url(dest_url),
+            title,
+            id,
+        }),
+        event => event,
+    }
+}
+
+fn sanitize_url(url: CowStr<'_>) -> CowStr<'_> {
+    let scheme = url.trim_start().split_once(':').map(|(scheme, _)| scheme);
+    match scheme {
+        Some(scheme)
+            if ["javascript", "vbscript", "data"]
+                .iter()
+                .any(|s| scheme.trim().eq_ignore_ascii_case(s)) =>
+        {
+            CowStr::Borrowed("")
+        }
+        _ => url,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn test_markdown() {
+        assert_eq!(
+            markdown("**bold**", true).unwrap().to_string(),
+            "<p><strong>bold</strong></p>\n"
+        );
+        assert_eq!(
+            markdown("# Title\n\n* a\n* ~~b~~", true)
+                .unwrap()
+                .to_string(),
+            "<h1>Title</h1>\n<ul>\n<li>a</li>\n<li><del>b</del></li>\n</ul>\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_sanitize() {
+        let source = "<script>alert(1)</script>\n\nHi <em>there</em>!";
+        assert_eq!(
+            markdown(source, true).unwrap().to_string(),
+            "&lt;script&gt;alert(1)&lt;/script&gt;\n<p>Hi &lt;em&gt;there&lt;/em&gt;!</p>\n"
+        );
+        assert_eq!(
+            markdown(source, false).unwrap().to_string(),
+            "<script>alert(1)</script>\n<p>Hi <em>there</em>!</p>\n"
+        );
+
+        let source = "[click](javascript:alert(1)) [ok](https://example.com)";
+        assert_eq!(
+            markdown(source, true).unwrap().to_string(),
+            "<p><a href=\"\">click</a> <a href=\"https://example.com\">ok</a></p>\n"
+        );
+        assert_eq!(
+            markdown(source, false).unwrap().to_string(),
+            "<p><a href=\"javascript:alert(1)\">click</a> \
+             <a href=\"https://example.com\">ok</a></p>\n"
+        );
+    }
+}
@@ -13,11 +13,21 @@
 #[cfg(feature = "alloc")]
 mod alloc;
 mod builtin;
+#[cfg(feature = "chrono")]
+mod date;
 mod escape;
+#[cfg(feature = "grapheme")]
+mod grapheme;
 mod humansize;
 mod indent;
 #[cfg(feature = "serde_json")]
 mod json;
+#[cfg(feature = "markdown")]
+mod markdown;
+#[cfg(feature = "unicode-normalization")]
+mod normalize;
+#[cfg(feature = "regex")]
+mod regex;
 #[cfg(feature = "std")]
 mod std;
 #[cfg(feature = "urlencode")]
@@ -25,18 +35,36 @@ mod urlencode;
 
 #[cfg(feature = "alloc")]
 pub use self::alloc::{
-    capitalize, fmt, format, linebreaks, linebreaksbr, lower, lowercase, paragraphbreaks, title,
-    titlecase, trim, upper, uppercase, wordcount,
+    BoxStyle, Chunks, Columns, GroupConsecutive, Locale, camel_case, capitalize, chunks, columns,
+    contains, count_matches, dedent, ellipsize_middle, escape_once, excerpt, fmt, format,
+    group_consecutive, highlight, kebab_case, linebreaks, linebreaksbr, localize_number, lower,
+    lowercase, normalize_ws, paragraphbreaks, pascal_case, redact, snake_case, table, text_box,
+    title, titlecase, trim, trim_lines, truncatewords, upper, uppercase, wordcount,
 };
-pub use self::builtin::{PluralizeCount, center, join, pluralize, truncate};
+pub use self::builtin::{
+    AsOption, Flatten, FormatEach, Formatted, MaybeEmpty, OrdinalNumber, Paginate, PluralizeCount,
+    Sign, SumOverflow, Summable, Zip, avg, center, center_ansi, clamp, coalesce, default_if_none,
+    escape_control, flatten, format_each, join, join_human, ljust, ljust_ansi, ordinal, page_count,
+    paginate, pluralize, repeat, rjust, rjust_ansi, sign, sum, truncate, visible_len, zip,
+};
+#[cfg(feature = "chrono")]
+pub use self::date::{FormatDate, date, datetime};
 pub use self::escape::{
-    AutoEscape, AutoEscaper, Escaper, Html, HtmlSafe, HtmlSafeOutput, MaybeSafe, Safe, Text,
-    Unsafe, Writable, WriteWritable, e, escape, safe,
+    AutoEscape, AutoEscaper, Escaper, Html, HtmlSafe, HtmlSafeOutput, MaybeSafe, Safe, Shell,
+    ShellQuoted, Text, Unsafe, Writable, WriteWritable, e, escape, safe, safe_if, shell_escape,
 };
+#[cfg(feature = "grapheme")]
+pub use self::grapheme::{length_graphemes, reverse_graphemes, truncate_graphemes};
 pub use self::humansize::filesizeformat;
 pub use self::indent::{AsIndent, indent};
 #[cfg(feature = "serde_json")]
-pub use self::json::{json, json_pretty};
+pub use self::json::{json, json_attr, json_pretty};
+#[cfg(feature = "markdown")]
+pub use self::markdown::markdown;
+#[cfg(feature = "unicode-normalization")]
+pub use self::normalize::{nfc, nfd, nfkc, nfkd};
+#[cfg(feature = "regex")]
+pub use self::regex::{AsRegex, replace_regex};
 #[cfg(feature = "std")]
 pub use self::std::unique;
 #[cfg(feature = "urlencode")]
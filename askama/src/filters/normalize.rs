@@ -0,0 +1,198 @@
+use alloc::string::String;
+use core::convert::Infallible;
+use core::fmt::{self, Write};
+
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{FastWritable, Values};
+
+/// Normalize a string to the Unicode Normalization Form C (canonical composition)
+///
+/// A character that is decomposed into a base character and combining marks, e.g. `"e\u{301}"`,
+/// is composed into a single code point if possible, e.g. `"é"`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|nfc }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "Cafe\u{301}" }.to_string(),
+///     "<div>Caf\u{e9}</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn nfc<S: fmt::Display>(source: S) -> Result<Normalize<S>, Infallible> {
+    Ok(Normalize {
+        source,
+        form: Form::Nfc,
+    })
+}
+
+/// Normalize a string to the Unicode Normalization Form D (canonical decomposition)
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|nfd }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "Caf\u{e9}" }.to_string(),
+///     "<div>Cafe\u{301}</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn nfd<S: fmt::Display>(source: S) -> Result<Normalize<S>, Infallible> {
+    Ok(Normalize {
+        source,
+        form: Form::Nfd,
+    })
+}
+
+/// Normalize a string to the Unicode Normalization Form KC (compatibility composition)
+///
+/// In addition to [`nfc()`], compatibility characters are replaced by their plain equivalent,
+/// e.g. the ligature `"ﬁ"` becomes `"fi"`, and the fullwidth `"Ａ"` becomes `"A"`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|nfkc }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "\u{fb01}le \u{ff21}" }.to_string(),
+///     "<div>file A</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn nfkc<S: fmt::Display>(source: S) -> Result<Normalize<S>, Infallible> {
+    Ok(Normalize {
+        source,
+        form: Form::Nfkc,
+    })
+}
+
+/// Normalize a string to the Unicode Normalization Form KD (compatibility decomposition)
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|nfkd }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "\u{fb01}anc\u{e9}" }.to_string(),
+///     "<div>fiance\u{301}</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn nfkd<S: fmt::Display>(source: S) -> Result<Normalize<S>, Infallible> {
+    Ok(Normalize {
+        source,
+        form: Form::Nfkd,
+    })
+}
+
+pub struct Normalize<S> {
+    source: S,
+    form: Form,
+}
+
+#[derive(Clone, Copy)]
+enum Form {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+impl<S: fmt::Display> fmt::Display for Normalize<S> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer = String::new();
+        write!(buffer, "{}", self.source)?;
+        write_normalized(dest, &buffer, self.form)
+    }
+}
+
+impl<S: FastWritable> FastWritable for Normalize<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        let mut buffer = String::new();
+        self.source.write_into(&mut buffer, values)?;
+        Ok(write_normalized(dest, &buffer, self.form)?)
+    }
+}
+
+fn write_normalized<W: fmt::Write + ?Sized>(dest: &mut W, s: &str, form: Form) -> fmt::Result {
+    match form {
+        Form::Nfc => s.nfc().try_for_each(|c| dest.write_char(c)),
+        Form::Nfd => s.nfd().try_for_each(|c| dest.write_char(c)),
+        Form::Nfkc => s.nfkc().try_for_each(|c| dest.write_char(c)),
+        Form::Nfkd => s.nfkd().try_for_each(|c| dest.write_char(c)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        // "é" as "e" followed by a combining acute accent.
+        let decomposed = "e\u{301}";
+        let composed = nfc(decomposed).unwrap().to_string();
+        assert_eq!(composed, "\u{e9}");
+        assert_eq!(composed.chars().count(), 1);
+        assert_eq!(nfd("\u{e9}").unwrap().to_string(), decomposed);
+
+        // Compatibility forms replace the ligature, the canonical forms keep it.
+        assert_eq!(nfc("\u{fb01}").unwrap().to_string(), "\u{fb01}");
+        assert_eq!(nfkc("\u{fb01}").unwrap().to_string(), "fi");
+        assert_eq!(
+            nfkd("\u{1e9b}\u{323}").unwrap().to_string(),
+            "s\u{323}\u{307}"
+        );
+        assert_eq!(nfkc("\u{1e9b}\u{323}").unwrap().to_string(), "\u{1e69}");
+
+        assert_eq!(nfc("").unwrap().to_string(), "");
+        assert_eq!(nfkc(42).unwrap().to_string(), "42");
+    }
+}
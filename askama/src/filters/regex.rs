@@ -0,0 +1,204 @@
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::fmt::{self, Write};
+use std::string::String;
+
+use regex::Regex;
+
+use crate::{FastWritable, Values};
+
+/// Replaces all matches of a regular expression
+///
+/// The pattern uses the syntax of the [`regex`] crate. In the replacement, `$1` or `${name}`
+/// refer to capture groups, and `$$` is a literal `$`.
+///
+/// If the pattern is a string literal, it is checked at compile time, so an invalid pattern
+/// is a compile error, and it is compiled only once. Otherwise an invalid pattern is a
+/// rendering error.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ text|replace_regex("\\s+", " ") }}</div>
+/// /// <div>{{ date|replace_regex("(\\d+)-(\\d+)-(\\d+)", "$3.$2.$1") }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     text: &'a str,
+///     date: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { text: "a \n\t b   c", date: "2025-03-08" }.to_string(),
+///     "<div>a b c</div>\n<div>08.03.2025</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn replace_regex<S, P, R>(
+    source: S,
+    pattern: P,
+    replacement: R,
+) -> Result<ReplaceRegex<S, P, R>, Infallible>
+where
+    S: fmt::Display,
+    P: AsRegex,
+    R: AsRef<str>,
+{
+    Ok(ReplaceRegex {
+        source,
+        pattern,
+        replacement,
+    })
+}
+
+pub struct ReplaceRegex<S, P, R> {
+    source: S,
+    pattern: P,
+    replacement: R,
+}
+
+impl<S, P, R> fmt::Display for ReplaceRegex<S, P, R>
+where
+    S: fmt::Display,
+    P: AsRegex,
+    R: AsRef<str>,
+{
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let regex = self.pattern.as_regex().map_err(|_| fmt::Error)?;
+        let mut buffer = String::new();
+        write!(buffer, "{}", self.source)?;
+        dest.write_str(&regex.replace_all(&buffer, self.replacement.as_ref()))
+    }
+}
+
+impl<S, P, R> FastWritable for ReplaceRegex<S, P, R>
+where
+    S: FastWritable,
+    P: AsRegex,
+    R: AsRef<str>,
+{
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        let regex = self.pattern.as_regex().map_err(crate::Error::custom)?;
+        let mut buffer = String::new();
+        self.source.write_into(&mut buffer, values)?;
+        Ok(dest.write_str(&regex.replace_all(&buffer, self.replacement.as_ref()))?)
+    }
+}
+
+/// A regular expression, or a pattern that can be compiled into one, used by the filter
+/// [`replace_regex()`]
+pub trait AsRegex {
+    /// Returns the compiled regular expression.
+    fn as_regex(&self) -> Result<Cow<'_, Regex>, regex::Error>;
+}
+
+crate::impl_for_ref! {
+    impl AsRegex for T {
+        #[inline]
+        fn as_regex(&self) -> Result<Cow<'_, Regex>, regex::Error> {
+            <T>::as_regex(self)
+        }
+    }
+}
+
+impl AsRegex for Regex {
+    #[inline]
+    fn as_regex(&self) -> Result<Cow<'_, Regex>, regex::Error> {
+        Ok(Cow::Borrowed(self))
+    }
+}
+
+impl AsRegex for str {
+    #[inline]
+    fn as_regex(&self) -> Result<Cow<'_, Regex>, regex::Error> {
+        Regex::new(self).map(Cow::Owned)
+    }
+}
+
+impl AsRegex for String {
+    #[inline]
+    fn as_regex(&self) -> Result<Cow<'_, Regex>, regex::Error> {
+        self.as_str().as_regex()
+    }
+}
+
+impl AsRegex for Cow<'_, str> {
+    #[inline]
+    fn as_regex(&self) -> Result<Cow<'_, Regex>, regex::Error> {
+        self.as_ref().as_regex()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::ToString;
+
+    use super::*;
+    use crate::NO_VALUES;
+    use crate::helpers::LazyRegex;
+
+    #[test]
+    fn test_replace_regex() {
+        assert_eq!(
+            replace_regex("a \n\t b   c", r"\s+", " ")
+                .unwrap()
+                .to_string(),
+            "a b c"
+        );
+        assert_eq!(
+            replace_regex("2025-03-08", r"(\d+)-(\d+)-(\d+)", "$3.$2.$1")
+                .unwrap()
+                .to_string(),
+            "08.03.2025"
+        );
+        assert_eq!(
+            replace_regex(
+                "John Smith",
+                r"(?<first>\w+) (?<last>\w+)",
+                "${last}, $first"
+            )
+            .unwrap()
+            .to_string(),
+            "Smith, John"
+        );
+        assert_eq!(replace_regex("abc", "x", "y").unwrap().to_string(), "abc");
+        assert_eq!(
+            replace_regex(42, String::from("[24]"), "$$")
+                .unwrap()
+                .to_string(),
+            "$$"
+        );
+
+        static LAZY: LazyRegex = LazyRegex::new(r"o+");
+        assert_eq!(
+            replace_regex("foo boo", &LAZY, "0").unwrap().to_string(),
+            "f0 b0"
+        );
+        assert_eq!(replace_regex("zoo", &LAZY, "").unwrap().to_string(), "z");
+    }
+
+    #[test]
+    fn test_replace_regex_invalid() {
+        let mut buf = String::new();
+        assert!(write!(buf, "{}", replace_regex("a", "(", "").unwrap()).is_err());
+        assert!(matches!(
+            replace_regex("a", "(", "")
+                .unwrap()
+                .write_into(&mut buf, NO_VALUES),
+            Err(crate::Error::Custom(_))
+        ));
+
+        static LAZY: LazyRegex = LazyRegex::new(r"[");
+        assert!(LAZY.as_regex().is_err());
+        assert!(LAZY.as_regex().is_err());
+    }
+}
@@ -13,7 +13,7 @@ use core::ops::Deref;
 use core::pin::Pin;
 
 pub use crate::error::{ErrorMarker, ResultConverter};
-pub use crate::values::get_value;
+pub use crate::values::{DynValue, csp_nonce, get_value, get_value_opt, get_value_or};
 use crate::{FastWritable, Values};
 
 pub struct TemplateLoop<I>
@@ -127,6 +127,7 @@ macro_rules! primitive_type {
 
 primitive_type! {
     bool,
+    char,
     f32, f64,
     i8, i16, i32, i64, i128, isize,
     u8, u16, u32, u64, u128, usize,
@@ -265,6 +266,83 @@ pub fn as_bool<T: PrimitiveType<Value = bool>>(value: T) -> bool {
     value.get()
 }
 
+/// Used by the test `is none`.
+pub trait IsNone {
+    fn askama_is_none(&self) -> bool;
+}
+
+impl<T> IsNone for Option<T> {
+    #[inline]
+    fn askama_is_none(&self) -> bool {
+        self.is_none()
+    }
+}
+
+crate::impl_for_ref! {
+    impl IsNone for T {
+        #[inline]
+        fn askama_is_none(&self) -> bool {
+            <T>::askama_is_none(self)
+        }
+    }
+}
+
+/// Used by the test `is defined` on runtime values.
+pub trait IsDefined {
+    fn askama_is_defined(&self) -> bool;
+}
+
+/// A lookup that failed for any other reason than
+/// [`Error::ValueMissing`][crate::Error::ValueMissing], e.g. because the value has a different
+/// type, is still defined.
+impl<T> IsDefined for Result<T, crate::Error> {
+    #[inline]
+    fn askama_is_defined(&self) -> bool {
+        !matches!(self, Err(crate::Error::ValueMissing))
+    }
+}
+
+crate::impl_for_ref! {
+    impl IsDefined for T {
+        #[inline]
+        fn askama_is_defined(&self) -> bool {
+            <T>::askama_is_defined(self)
+        }
+    }
+}
+
+/// Used by the tests `is even`, `is odd` and `is divisibleby(n)`.
+pub trait IsDivisibleBy: Copy {
+    fn askama_is_divisible_by(self, divisor: Self) -> bool;
+}
+
+macro_rules! is_divisible_by {
+    (int: $($int:ty),* ; float: $($float:ty),*) => {
+        $(
+            impl IsDivisibleBy for $int {
+                #[inline]
+                fn askama_is_divisible_by(self, divisor: Self) -> bool {
+                    // `checked_rem()` fails for `0`, and for `MIN % -1`, which is divisible.
+                    self.checked_rem(divisor).map_or(divisor != 0, |rem| rem == 0)
+                }
+            }
+        )*
+        $(
+            impl IsDivisibleBy for $float {
+                #[inline]
+                fn askama_is_divisible_by(self, divisor: Self) -> bool {
+                    divisor != 0.0 && self % divisor == 0.0
+                }
+            }
+        )*
+    };
+}
+
+is_divisible_by!(
+    int: i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize;
+    float: f32, f64
+);
+
 pub struct Concat<L, R>(pub L, pub R);
 
 impl<L: fmt::Display, R: fmt::Display> fmt::Display for Concat<L, R> {
@@ -287,6 +365,157 @@ impl<L: FastWritable, R: FastWritable> FastWritable for Concat<L, R> {
     }
 }
 
+/// Input of the filter [`chunks()`][crate::filters::chunks], used with autoref-based
+/// specialization: slices are chunked without copying, other iterables are buffered.
+///
+/// The implementation is selected by calling `(&&&ChunksSource(..)).askama_chunks()`.
+#[cfg(feature = "alloc")]
+pub struct ChunksSource<'a, T: ?Sized>(pub &'a T, pub usize);
+
+/// Chunks a slice, or something that dereferences to a slice, e.g. a [`Vec`][alloc::vec::Vec].
+#[cfg(feature = "alloc")]
+pub trait ChunksSlice<'a> {
+    type Item: 'a;
+
+    fn askama_chunks(&self) -> core::slice::Chunks<'a, Self::Item>;
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, U> ChunksSlice<'a> for &&ChunksSource<'a, T>
+where
+    T: Deref<Target = [U]> + ?Sized,
+    U: 'a,
+{
+    type Item = U;
+
+    #[inline]
+    fn askama_chunks(&self) -> core::slice::Chunks<'a, U> {
+        match self.1 {
+            0 => [].chunks(1),
+            size => self.0.chunks(size),
+        }
+    }
+}
+
+/// Chunks a collection that can be iterated by reference.
+#[cfg(feature = "alloc")]
+pub trait ChunksRef {
+    type IntoIter;
+
+    fn askama_chunks(&self) -> crate::filters::Chunks<Self::IntoIter>;
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> ChunksRef for &ChunksSource<'a, T>
+where
+    T: ?Sized,
+    &'a T: IntoIterator,
+{
+    type IntoIter = <&'a T as IntoIterator>::IntoIter;
+
+    #[inline]
+    fn askama_chunks(&self) -> crate::filters::Chunks<Self::IntoIter> {
+        let Ok(chunks) = crate::filters::chunks(self.0, self.1);
+        chunks
+    }
+}
+
+/// Chunks an iterator, e.g. a range.
+#[cfg(feature = "alloc")]
+pub trait ChunksOwned {
+    type IntoIter;
+
+    fn askama_chunks(&self) -> crate::filters::Chunks<Self::IntoIter>;
+}
+
+#[cfg(feature = "alloc")]
+impl<T> ChunksOwned for ChunksSource<'_, T>
+where
+    T: IntoIterator + Clone,
+{
+    type IntoIter = T::IntoIter;
+
+    #[inline]
+    fn askama_chunks(&self) -> crate::filters::Chunks<Self::IntoIter> {
+        let Ok(chunks) = crate::filters::chunks(self.0.clone(), self.1);
+        chunks
+    }
+}
+
+/// Post-processes the output of [`Template::render()`][crate::Template::render]
+#[cfg(feature = "alloc")]
+pub fn apply_trailing_newline(buf: &mut alloc::string::String, policy: crate::TrailingNewline) {
+    if policy == crate::TrailingNewline::Keep {
+        return;
+    }
+    let len = buf.trim_end_matches(['\n', '\r']).len();
+    let newline = if buf[len..].starts_with("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    buf.truncate(len);
+    if policy == crate::TrailingNewline::Ensure {
+        buf.push_str(newline);
+    }
+}
+
+/// The key of the typed global context in the runtime values, that is passed by the
+/// `render_with_context()` method of templates with `#[template(context = …)]`
+pub const CONTEXT_KEY: &str = "askama::context";
+
+/// The key of the error handler in the runtime values, see
+/// [`Template::render_lenient()`][crate::Template::render_lenient]
+pub const ON_ERROR_KEY: &str = "askama::on_error";
+
+/// The error handler of [`Template::render_lenient()`][crate::Template::render_lenient]
+#[cfg(feature = "alloc")]
+pub struct OnError(pub alloc::boxed::Box<dyn Fn(&crate::Error) -> alloc::string::String>);
+
+/// Runtime values with an [`OnError`] handler
+#[cfg(feature = "alloc")]
+pub struct LenientValues<'a> {
+    pub values: &'a dyn Values,
+    pub on_error: OnError,
+}
+
+#[cfg(feature = "alloc")]
+impl Values for LenientValues<'_> {
+    #[inline]
+    fn get_value<'a>(&'a self, key: &str) -> Option<&'a dyn core::any::Any> {
+        match key {
+            ON_ERROR_KEY => Some(&self.on_error),
+            key => self.values.get_value(key),
+        }
+    }
+}
+
+/// Writes the output of an expression of a template with `lenient = true`.
+///
+/// If the template is rendered with
+/// [`Template::render_lenient()`][crate::Template::render_lenient], then the expression is
+/// rendered into a buffer first, and if this fails, the output of the error handler is written
+/// instead. Otherwise, the expression is written directly into `dest`,
+/// like in any other template. Failing to write into `dest` is never recovered.
+#[cfg(feature = "alloc")]
+pub fn write_lenient<W: fmt::Write + ?Sized>(
+    mut dest: &mut W,
+    values: &dyn Values,
+    write: impl FnOnce(&mut dyn fmt::Write) -> crate::Result<()>,
+) -> crate::Result<()> {
+    let Some(OnError(on_error)) = values
+        .get_value(ON_ERROR_KEY)
+        .and_then(|value| value.downcast_ref::<OnError>())
+    else {
+        return write(&mut dest);
+    };
+    let mut buffer = alloc::string::String::new();
+    match write(&mut buffer) {
+        Ok(()) => Ok(dest.write_str(&buffer)?),
+        Err(err) => Ok(dest.write_str(&on_error(&err))?),
+    }
+}
+
 pub trait EnumVariantTemplate {
     fn render_into_with_values<W: fmt::Write + ?Sized>(
         &self,
@@ -294,3 +523,32 @@ pub trait EnumVariantTemplate {
         values: &dyn crate::Values,
     ) -> crate::Result<()>;
 }
+
+/// A literal pattern of the filter `replace_regex`, compiled on first use
+#[cfg(feature = "regex")]
+pub struct LazyRegex {
+    pattern: &'static str,
+    regex: std::sync::OnceLock<Result<regex::Regex, regex::Error>>,
+}
+
+#[cfg(feature = "regex")]
+impl LazyRegex {
+    #[inline]
+    pub const fn new(pattern: &'static str) -> Self {
+        Self {
+            pattern,
+            regex: std::sync::OnceLock::new(),
+        }
+    }
+}
+
+#[cfg(feature = "regex")]
+impl crate::filters::AsRegex for LazyRegex {
+    #[inline]
+    fn as_regex(&self) -> Result<alloc::borrow::Cow<'_, regex::Regex>, regex::Error> {
+        match self.regex.get_or_init(|| regex::Regex::new(self.pattern)) {
+            Ok(regex) => Ok(alloc::borrow::Cow::Borrowed(regex)),
+            Err(err) => Err(err.clone()),
+        }
+    }
+}
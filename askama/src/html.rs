@@ -132,3 +132,18 @@ fn test_simple_html_string_escaping() {
     write_escaped_str(&mut buf, "s<cripcripcripcripcripcripcripcripcripcrip>t").unwrap();
     assert_eq!(buf, "s&#60;cripcripcripcripcripcripcripcripcripcrip&#62;t");
 }
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_html_quote_escaping() {
+    extern crate alloc;
+
+    let mut buf = alloc::string::String::new();
+    write_escaped_str(&mut buf, r#"" onclick='x'"#).unwrap();
+    assert_eq!(buf, "&#34; onclick=&#39;x&#39;");
+
+    buf.clear();
+    write_escaped_char(&mut buf, '"').unwrap();
+    write_escaped_char(&mut buf, '\'').unwrap();
+    assert_eq!(buf, "&#34;&#39;");
+}
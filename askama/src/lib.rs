@@ -82,10 +82,14 @@ use std::io;
 
 #[cfg(feature = "derive")]
 pub use askama_macros::Template;
+#[cfg(all(feature = "alloc", feature = "derive"))]
+pub use askama_macros::render;
 
 pub use crate::error::{Error, Result};
 pub use crate::helpers::PrimitiveType;
-pub use crate::values::{NO_VALUES, Value, Values, get_value};
+pub use crate::values::{
+    CSP_NONCE_KEY, NO_VALUES, Value, Values, csp_nonce, get_value, get_value_opt, get_value_or,
+};
 
 /// Main `Template` trait; implementations are generally derived
 ///
@@ -123,10 +127,80 @@ pub trait Template: fmt::Display + FastWritable {
         let mut buf = String::new();
         let _ = buf.try_reserve(Self::SIZE_HINT);
         self.render_into_with_values(&mut buf, values)?;
+        helpers::apply_trailing_newline(&mut buf, Self::TRAILING_NEWLINE);
         Ok(buf)
     }
 
+    /// Helper method which allocates a new `String` and renders into it with provided
+    /// [`Values`], substituting the output of `on_error` for expressions that fail.
+    ///
+    /// Only templates with `#[template(lenient = true)]` recover from errors, and only from errors
+    /// in expressions like `{{ user.name }}`, e.g. if [`get_value()`] cannot find a value. Then the
+    /// text returned by `on_error` is written verbatim, i.e. not escaped, instead of the output
+    /// of the expression, and the rendering continues. Errors in other places, e.g. in the
+    /// condition of an `{% if %}` block, and errors of templates without `lenient = true` still
+    /// abort the rendering.
+    ///
+    /// ```
+    /// # #[cfg(feature = "derive")] {
+    /// # use askama::Template;
+    /// #[derive(Template)]
+    /// #[template(
+    ///     source = "{{ title }}: {{ askama::get_value::<u32>(\"visitors\")? }} visitors",
+    ///     ext = "txt",
+    ///     lenient = true
+    /// )]
+    /// struct Widget<'a> {
+    ///     title: &'a str,
+    /// }
+    ///
+    /// let widget = Widget { title: "Today" };
+    /// assert_eq!(
+    ///     widget.render_lenient(&[("visitors", &7_u32 as &dyn std::any::Any)], |_| "?".into()).unwrap(),
+    ///     "Today: 7 visitors",
+    /// );
+    /// assert_eq!(
+    ///     widget.render_lenient(askama::NO_VALUES, |_| "?".into()).unwrap(),
+    ///     "Today: ? visitors",
+    /// );
+    /// # }
+    /// ```
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn render_lenient<F>(&self, values: &dyn Values, on_error: F) -> Result<String>
+    where
+        F: Fn(&Error) -> String + 'static,
+    {
+        self.render_with_values(&helpers::LenientValues {
+            values,
+            on_error: helpers::OnError(alloc::boxed::Box::new(on_error)),
+        })
+    }
+
     /// Renders the template to the given `writer` fmt buffer.
+    ///
+    /// The output is appended to `writer`, which is not cleared first. Rendering into a
+    /// [`String`] that is reused, e.g. across the requests of a server, saves an allocation
+    /// per call of [`render()`][Template::render]. If the buffer should only contain the output
+    /// of one template, [`clear()`][String::clear] it before rendering.
+    ///
+    /// ```
+    /// # #[cfg(feature = "derive")] {
+    /// # use askama::Template;
+    /// #[derive(Template)]
+    /// #[template(source = "Hello, {{ name }}!", ext = "txt")]
+    /// struct Greeting<'a> {
+    ///     name: &'a str,
+    /// }
+    ///
+    /// let mut buf = String::new();
+    /// for name in ["Alice", "Bob"] {
+    ///     buf.clear();
+    ///     Greeting { name }.render_into(&mut buf).unwrap();
+    ///     assert_eq!(buf, format!("Hello, {name}!"));
+    /// }
+    /// # }
+    /// ```
     #[inline]
     fn render_into<W: fmt::Write + ?Sized>(&self, writer: &mut W) -> Result<()> {
         self.render_into_with_values(writer, NO_VALUES)
@@ -139,6 +213,49 @@ pub trait Template: fmt::Display + FastWritable {
         values: &dyn Values,
     ) -> Result<()>;
 
+    /// Renders the template into the given [`fmt::Formatter`].
+    ///
+    /// This is useful to embed a template in a manual [`fmt::Display`] or [`fmt::Debug`]
+    /// implementation, because any rendering error is converted into a [`fmt::Error`].
+    ///
+    /// ```
+    /// # #[cfg(feature = "derive")] {
+    /// # use std::fmt;
+    /// # use askama::Template;
+    /// #[derive(Template)]
+    /// #[template(source = "Hello, {{ name }}!", ext = "txt")]
+    /// struct Greeting<'a> {
+    ///     name: &'a str,
+    /// }
+    ///
+    /// struct Card<'a>(Greeting<'a>);
+    ///
+    /// impl fmt::Display for Card<'_> {
+    ///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         f.write_str("[ ")?;
+    ///         self.0.render_into_fmt(f)?;
+    ///         f.write_str(" ]")
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(Card(Greeting { name: "world" }).to_string(), "[ Hello, world! ]");
+    /// # }
+    /// ```
+    #[inline]
+    fn render_into_fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render_into_fmt_with_values(f, NO_VALUES)
+    }
+
+    /// Renders the template into the given [`fmt::Formatter`] with provided [`Values`].
+    #[inline]
+    fn render_into_fmt_with_values(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        values: &dyn Values,
+    ) -> fmt::Result {
+        Ok(self.render_into_with_values(f, values)?)
+    }
+
     /// Renders the template to the given `writer` io buffer.
     #[inline]
     #[cfg(feature = "std")]
@@ -170,6 +287,9 @@ pub trait Template: fmt::Display + FastWritable {
             }
         }
 
+        if Self::BOM {
+            writer.write_all(UTF8_BOM.as_bytes())?;
+        }
         let mut wrapped = Wrapped { writer, err: None };
         if self.render_into_with_values(&mut wrapped, values).is_ok() {
             Ok(())
@@ -179,9 +299,118 @@ pub trait Template: fmt::Display + FastWritable {
         }
     }
 
+    /// Renders the template into the given [`BytesMut`][bytes::BytesMut] buffer.
+    ///
+    /// The output is appended to the buffer, so it can be used e.g. to write directly into the
+    /// body of an HTTP response, without rendering into an intermediate [`String`] first.
+    #[inline]
+    #[cfg(feature = "bytes")]
+    fn render_into_bytes(&self, buf: &mut bytes::BytesMut) -> Result<()> {
+        self.render_into_bytes_with_values(buf, NO_VALUES)
+    }
+
+    /// Renders the template into the given [`BytesMut`][bytes::BytesMut] buffer with provided
+    /// [`Values`].
+    #[inline]
+    #[cfg(feature = "bytes")]
+    fn render_into_bytes_with_values(
+        &self,
+        buf: &mut bytes::BytesMut,
+        values: &dyn Values,
+    ) -> Result<()> {
+        buf.reserve(Self::SIZE_HINT);
+        if Self::BOM {
+            buf.extend_from_slice(UTF8_BOM.as_bytes());
+        }
+        // `BytesMut` implements `fmt::Write` by appending the UTF-8 bytes of the written strings.
+        self.render_into_with_values(buf, values)
+    }
+
+    /// Renders the template into the given byte slice, without allocating
+    ///
+    /// Returns the number of bytes that were written, and whether the output was truncated
+    /// because it did not fit into `buf`. The output is cut at a character boundary, so the
+    /// written bytes are always valid UTF-8. The rendering stops as soon as the buffer is full.
+    /// Other rendering errors, e.g. a missing [value][Values], are returned as an error.
+    ///
+    /// ```
+    /// # #[cfg(feature = "derive")] {
+    /// # use askama::Template;
+    /// #[derive(Template)]
+    /// #[template(source = "Hello, {{ name }}!", ext = "txt")]
+    /// struct Greeting<'a> {
+    ///     name: &'a str,
+    /// }
+    ///
+    /// let mut buf = [0; 16];
+    /// let (len, truncated) = Greeting { name: "world" }.render_into_slice(&mut buf).unwrap();
+    /// assert_eq!((&buf[..len], truncated), (&b"Hello, world!"[..], false));
+    ///
+    /// let (len, truncated) = Greeting { name: "everybody" }.render_into_slice(&mut buf).unwrap();
+    /// assert_eq!((&buf[..len], truncated), (&b"Hello, everybody"[..], true));
+    /// # }
+    /// ```
+    #[inline]
+    fn render_into_slice(&self, buf: &mut [u8]) -> Result<(usize, bool)> {
+        self.render_into_slice_with_values(buf, NO_VALUES)
+    }
+
+    /// Renders the template into the given byte slice with provided [`Values`], see
+    /// [`render_into_slice()`][Template::render_into_slice].
+    fn render_into_slice_with_values(
+        &self,
+        buf: &mut [u8],
+        values: &dyn Values,
+    ) -> Result<(usize, bool)> {
+        struct SliceWriter<'a> {
+            buf: &'a mut [u8],
+            len: usize,
+            truncated: bool,
+        }
+
+        impl fmt::Write for SliceWriter<'_> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                let free = self.buf.len() - self.len;
+                let end = if s.len() <= free {
+                    s.len()
+                } else {
+                    // Only write complete characters, then stop the rendering.
+                    self.truncated = true;
+                    (0..=free)
+                        .rev()
+                        .find(|&end| s.is_char_boundary(end))
+                        .unwrap_or(0)
+                };
+                self.buf[self.len..][..end].copy_from_slice(&s.as_bytes()[..end]);
+                self.len += end;
+                match self.truncated {
+                    false => Ok(()),
+                    true => Err(fmt::Error),
+                }
+            }
+        }
+
+        let mut writer = SliceWriter {
+            buf,
+            len: 0,
+            truncated: false,
+        };
+        let result = if Self::BOM && fmt::Write::write_str(&mut writer, UTF8_BOM).is_err() {
+            Err(Error::Fmt)
+        } else {
+            self.render_into_with_values(&mut writer, values)
+        };
+        match result {
+            Ok(()) => Ok((writer.len, false)),
+            Err(_) if writer.truncated => Ok((writer.len, true)),
+            Err(err) => Err(err),
+        }
+    }
+
     /// Provides a rough estimate of the expanded length of the rendered template. Larger
     /// values result in higher memory usage but fewer reallocations. Smaller values result in the
-    /// opposite. This value only affects [`render`]. It does not take effect when calling
+    /// opposite. This value only affects [`render`] (and `render_into_bytes`, if the feature
+    /// `"bytes"` is enabled). It does not take effect when calling
     /// [`render_into`], [`write_into`], the [`fmt::Display`] implementation, or the blanket
     /// [`ToString::to_string`] implementation.
     ///
@@ -190,6 +419,62 @@ pub trait Template: fmt::Display + FastWritable {
     /// [`write_into`]: Template::write_into
     /// [`ToString::to_string`]: alloc::string::ToString::to_string
     const SIZE_HINT: usize;
+
+    /// The MIME type of the rendered template, e.g. to be used as the `Content-Type` of an HTTP
+    /// response.
+    ///
+    /// The derive macro determines it from the template's file extension, so a template
+    /// `index.html` has the MIME type `"text/html"`, and a template `data.json` has the MIME type
+    /// `"application/json"`. It can be overridden with `#[template(mime_type = "…")]`.
+    /// If the extension is unknown, the MIME type is `"application/octet-stream"`.
+    const MIME_TYPE: &'static str = "application/octet-stream";
+
+    /// Whether a UTF-8 byte order mark (`EF BB BF`) is written before the rendered content.
+    ///
+    /// Some legacy consumers, e.g. certain Windows tools, expect the BOM in UTF-8 encoded files.
+    /// It is enabled with `#[template(bom = true)]`.
+    ///
+    /// The BOM is only written by the methods that output bytes, i.e. [`write_into`],
+    /// [`render_into_slice`] and `render_into_bytes`. The methods that produce text, e.g. [`render`] and [`render_into`],
+    /// and the [`fmt::Display`] implementation never emit it, because a BOM is usually unwanted
+    /// inside a [`String`].
+    ///
+    /// [`render`]: Template::render
+    /// [`render_into`]: Template::render_into
+    /// [`render_into_slice`]: Template::render_into_slice
+    /// [`write_into`]: Template::write_into
+    const BOM: bool = false;
+
+    /// How the end of the output of [`render`] is post-processed.
+    ///
+    /// Generated files, e.g. source code or configuration files, often need to end with exactly
+    /// one newline, or must not end with a newline at all. It is set with
+    /// `#[template(trailing_newline = "ensure")]` or `#[template(trailing_newline = "trim")]`.
+    ///
+    /// The policy is only applied once to the whole output of [`render`] (and
+    /// [`DynTemplate::dyn_render`]). The methods that append to a buffer, e.g. [`render_into`],
+    /// and the [`fmt::Display`] implementation leave the output as it is.
+    ///
+    /// [`render`]: Template::render
+    /// [`render_into`]: Template::render_into
+    const TRAILING_NEWLINE: TrailingNewline = TrailingNewline::Keep;
+}
+
+const UTF8_BOM: &str = "\u{feff}";
+
+/// How the end of the rendered output is post-processed, see [`Template::TRAILING_NEWLINE`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrailingNewline {
+    /// Leave the output as it is.
+    #[default]
+    Keep,
+    /// Make the output end with exactly one newline, replacing any trailing newlines.
+    ///
+    /// If the output already ends with `\r\n`, then this line ending is kept, otherwise `\n`
+    /// is used.
+    Ensure,
+    /// Remove all trailing newlines, i.e. `\n` and `\r\n`, from the output.
+    Trim,
 }
 
 impl<T: Template + ?Sized> Template for &T {
@@ -219,6 +504,20 @@ impl<T: Template + ?Sized> Template for &T {
         <T as Template>::render_into_with_values(self, writer, values)
     }
 
+    #[inline]
+    fn render_into_fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        <T as Template>::render_into_fmt(self, f)
+    }
+
+    #[inline]
+    fn render_into_fmt_with_values(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        values: &dyn Values,
+    ) -> fmt::Result {
+        <T as Template>::render_into_fmt_with_values(self, f, values)
+    }
+
     #[inline]
     #[cfg(feature = "std")]
     fn write_into<W: io::Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
@@ -235,7 +534,43 @@ impl<T: Template + ?Sized> Template for &T {
         <T as Template>::write_into_with_values(self, writer, values)
     }
 
+    #[inline]
+    #[cfg(feature = "bytes")]
+    fn render_into_bytes(&self, buf: &mut bytes::BytesMut) -> Result<()> {
+        <T as Template>::render_into_bytes(self, buf)
+    }
+
+    #[inline]
+    #[cfg(feature = "bytes")]
+    fn render_into_bytes_with_values(
+        &self,
+        buf: &mut bytes::BytesMut,
+        values: &dyn Values,
+    ) -> Result<()> {
+        <T as Template>::render_into_bytes_with_values(self, buf, values)
+    }
+
+    #[inline]
+    fn render_into_slice(&self, buf: &mut [u8]) -> Result<(usize, bool)> {
+        <T as Template>::render_into_slice(self, buf)
+    }
+
+    #[inline]
+    fn render_into_slice_with_values(
+        &self,
+        buf: &mut [u8],
+        values: &dyn Values,
+    ) -> Result<(usize, bool)> {
+        <T as Template>::render_into_slice_with_values(self, buf, values)
+    }
+
     const SIZE_HINT: usize = T::SIZE_HINT;
+
+    const MIME_TYPE: &'static str = T::MIME_TYPE;
+
+    const BOM: bool = T::BOM;
+
+    const TRAILING_NEWLINE: TrailingNewline = T::TRAILING_NEWLINE;
 }
 
 /// [`dyn`-compatible] wrapper trait around [`Template`] implementers
@@ -276,6 +611,9 @@ pub trait DynTemplate {
 
     /// Provides a conservative estimate of the expanded length of the rendered template.
     fn size_hint(&self) -> usize;
+
+    /// The MIME type of the rendered template, see [`Template::MIME_TYPE`].
+    fn mime_type(&self) -> &'static str;
 }
 
 impl<T: Template> DynTemplate for T {
@@ -325,6 +663,11 @@ impl<T: Template> DynTemplate for T {
     fn size_hint(&self) -> usize {
         <Self as Template>::SIZE_HINT
     }
+
+    #[inline]
+    fn mime_type(&self) -> &'static str {
+        <Self as Template>::MIME_TYPE
+    }
 }
 
 impl fmt::Display for dyn DynTemplate {
@@ -334,6 +677,51 @@ impl fmt::Display for dyn DynTemplate {
     }
 }
 
+/// Renders a list of templates, one after another, into a single `String`
+///
+/// This is useful to assemble a page from components that are only known at runtime.
+/// All templates are rendered with the same `values`; use [`NO_VALUES`] if you have none.
+/// Every template is escaped by its own escaper, as if it was rendered on its own.
+///
+/// ```
+/// # #[cfg(feature = "derive")] {
+/// use askama::{DynTemplate, NO_VALUES, Template};
+///
+/// #[derive(Template)]
+/// #[template(source = "<h1>{{ title }}</h1>", ext = "html")]
+/// struct Header<'a> {
+///     title: &'a str,
+/// }
+///
+/// #[derive(Template)]
+/// #[template(source = "<p>{{ text }}</p>", ext = "html")]
+/// struct Paragraph<'a> {
+///     text: &'a str,
+/// }
+///
+/// let components: Vec<Box<dyn DynTemplate>> = vec![
+///     Box::new(Header { title: "Fish & Chips" }),
+///     Box::new(Paragraph { text: "A classic." }),
+/// ];
+/// assert_eq!(
+///     askama::render_all(&components, NO_VALUES).unwrap(),
+///     "<h1>Fish &#38; Chips</h1><p>A classic.</p>",
+/// );
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+pub fn render_all<'a, T: Deref<Target = dyn DynTemplate + 'a>>(
+    templates: &[T],
+    values: &dyn Values,
+) -> Result<String> {
+    let mut buf = String::new();
+    let _ = buf.try_reserve(templates.iter().map(|t| t.size_hint()).sum());
+    for template in templates {
+        template.dyn_render_into_with_values(&mut buf, values)?;
+    }
+    Ok(buf)
+}
+
 /// Implement the trait `$Trait` for a list of reference (wrapper) types to `$T: $Trait + ?Sized`
 macro_rules! impl_for_ref {
     (impl $Trait:ident for $T:ident $body:tt) => {
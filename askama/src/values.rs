@@ -1,7 +1,9 @@
 use core::any::Any;
 use core::borrow::Borrow;
+use core::fmt;
 
-use crate::Error;
+use crate::helpers::{IsDefined, IsNone, PrimitiveType};
+use crate::{Error, FastWritable};
 
 /// No runtime values provided.
 pub const NO_VALUES: &dyn Values = &();
@@ -15,6 +17,99 @@ pub fn get_value<T: Any>(values: &dyn Values, key: impl AsRef<str>) -> Result<&T
         .and_then(convert_value)
 }
 
+/// Try to find `key` in `values`, and return a clone of the value, or `default` if it cannot be
+/// found or has a different type than `T`.
+///
+/// A value of a different type is not reported as an error, but silently replaced by `default`.
+/// Use [`get_value()`] if a mistyped value should fail with [`Error::ValueType`].
+///
+/// In templates, you can call the function without the `values` argument:
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use std::any::Any;
+/// # use askama::Template;
+/// /// ```jinja
+/// /// Hello, {{ askama::get_value_or::<String>("who", "guest".to_owned()) }}!
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example;
+///
+/// assert_eq!(Example.render().unwrap(), "Hello, guest!");
+///
+/// let values: (&str, &dyn Any) = ("who", &"world".to_owned());
+/// assert_eq!(Example.render_with_values(&values).unwrap(), "Hello, world!");
+/// # }
+/// ```
+#[inline]
+pub fn get_value_or<T: Any + Clone>(values: &dyn Values, key: impl AsRef<str>, default: T) -> T {
+    get_value_opt(values, key).unwrap_or(default)
+}
+
+/// Try to find `key` in `values`, and return a clone of the value, or [`None`] if it cannot be
+/// found or has a different type than `T`.
+///
+/// Like for [`get_value_or()`], a value of a different type is not reported as an error.
+#[inline]
+pub fn get_value_opt<T: Any + Clone>(values: &dyn Values, key: impl AsRef<str>) -> Option<T> {
+    get_value(values, key).ok().cloned()
+}
+
+/// The key that is used to look up the nonce of [`csp_nonce()`] in the runtime values.
+pub const CSP_NONCE_KEY: &str = "csp_nonce";
+
+/// Returns the [Content-Security-Policy] nonce of the current request
+///
+/// The nonce is read from `values` with the key [`CSP_NONCE_KEY`], i.e. `"csp_nonce"`, and has to
+/// be a string, e.g. a `&'static str` or a `String`. A missing nonce is an
+/// [`Error::ValueMissing`], a nonce of any other type is an [`Error::ValueType`].
+///
+/// In templates, you can call the function without the `values` argument. The nonce gets escaped
+/// like any other string, so in an HTML template it can safely be used as attribute value:
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use std::any::Any;
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <script nonce="{{ askama::csp_nonce() }}">init();</script>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example;
+///
+/// let values: (&str, &dyn Any) = (askama::CSP_NONCE_KEY, &"rAnd0m".to_owned());
+/// assert_eq!(
+///     Example.render_with_values(&values).unwrap(),
+///     r#"<script nonce="rAnd0m">init();</script>"#
+/// );
+/// assert!(matches!(Example.render(), Err(askama::Error::ValueMissing)));
+/// # }
+/// ```
+///
+/// [Content-Security-Policy]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Security-Policy
+pub fn csp_nonce(values: &dyn Values) -> Result<&str, Error> {
+    let value = values.get_value(CSP_NONCE_KEY).ok_or(Error::ValueMissing)?;
+    macro_rules! try_as {
+        ($($ty:ty),* $(,)?) => {$(
+            if let Ok(value) = convert_value::<$ty>(value) {
+                return Ok(AsRef::<str>::as_ref(value));
+            }
+        )*};
+    }
+    try_as!(&'static str);
+    #[cfg(feature = "alloc")]
+    try_as!(
+        alloc::string::String,
+        alloc::borrow::Cow<'static, str>,
+        alloc::boxed::Box<str>,
+        alloc::rc::Rc<str>,
+        alloc::sync::Arc<str>,
+    );
+    Err(Error::ValueType)
+}
+
 fn convert_value<T: Any>(src: &dyn Any) -> Result<&T, Error> {
     if let Some(value) = src.downcast_ref::<T>() {
         return Ok(value);
@@ -34,6 +129,137 @@ fn convert_value<T: Any>(src: &dyn Any) -> Result<&T, Error> {
     Err(Error::ValueType)
 }
 
+/// A runtime value of unknown type, used for unknown identifiers in `values_only = true` templates.
+///
+/// The value can be rendered if it is a string, a number, a `bool`, or a `char`. In conditions,
+/// missing values, `false`, empty strings and zero are falsy; all other values are truthy.
+#[derive(Clone, Copy)]
+pub struct DynValue<'a>(Option<&'a dyn Any>);
+
+impl<'a> DynValue<'a> {
+    /// Looks up `key` in `values`.
+    #[inline]
+    pub fn new(values: &'a dyn Values, key: &str) -> Self {
+        Self(values.get_value(key))
+    }
+
+    /// Returns `true` if the value is missing.
+    #[inline]
+    pub fn is_none(&self) -> bool {
+        self.0.is_none()
+    }
+
+    fn write(&self, dest: &mut (impl fmt::Write + ?Sized)) -> Result<(), Error> {
+        let value = self.0.ok_or(Error::ValueMissing)?;
+        macro_rules! write_as {
+            ($($ty:ty),* $(,)?) => {$(
+                if let Ok(value) = convert_value::<$ty>(value) {
+                    return Ok(write!(dest, "{value}")?);
+                }
+            )*};
+        }
+        write_as!(
+            &'static str,
+            bool,
+            char,
+            f32,
+            f64,
+            i8,
+            i16,
+            i32,
+            i64,
+            i128,
+            isize,
+            u8,
+            u16,
+            u32,
+            u64,
+            u128,
+            usize,
+        );
+        #[cfg(feature = "alloc")]
+        write_as!(
+            alloc::string::String,
+            alloc::borrow::Cow<'static, str>,
+            alloc::boxed::Box<str>,
+            alloc::rc::Rc<str>,
+            alloc::sync::Arc<str>,
+        );
+        Err(Error::ValueType)
+    }
+
+    fn is_truthy(&self) -> bool {
+        let Some(value) = self.0 else {
+            return false;
+        };
+        macro_rules! check_as {
+            ($($ty:ty),* => |$value:ident| $truthy:expr) => {$(
+                if let Ok($value) = convert_value::<$ty>(value) {
+                    return $truthy;
+                }
+            )*};
+        }
+        check_as!(bool => |value| *value);
+        check_as!(&'static str => |value| !value.is_empty());
+        check_as!(
+            i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+            => |value| *value != 0
+        );
+        check_as!(f32, f64 => |value| *value != 0.0);
+        #[cfg(feature = "alloc")]
+        check_as!(
+            alloc::string::String,
+            alloc::borrow::Cow<'static, str>,
+            alloc::boxed::Box<str>,
+            alloc::rc::Rc<str>,
+            alloc::sync::Arc<str>
+            => |value| !value.is_empty()
+        );
+        true
+    }
+}
+
+impl fmt::Display for DynValue<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f).map_err(|_| fmt::Error)
+    }
+}
+
+impl FastWritable for DynValue<'_> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        _: &dyn Values,
+    ) -> crate::Result<()> {
+        self.write(dest)
+    }
+}
+
+impl PrimitiveType for DynValue<'_> {
+    type Value = bool;
+
+    #[inline]
+    fn get(&self) -> Self::Value {
+        self.is_truthy()
+    }
+}
+
+impl IsNone for DynValue<'_> {
+    #[inline]
+    fn askama_is_none(&self) -> bool {
+        self.is_none()
+    }
+}
+
+impl IsDefined for DynValue<'_> {
+    #[inline]
+    fn askama_is_defined(&self) -> bool {
+        !self.is_none()
+    }
+}
+
 /// A runtime value store for [`Template::render_with_values()`][crate::Template::render_with_values].
 pub trait Values {
     /// Try to find `key` in this store.
@@ -200,6 +426,14 @@ impl Value for dyn Any {
     }
 }
 
+// Values that can be shared with other threads, e.g. for async templates.
+impl Value for dyn Any + Send + Sync {
+    #[inline]
+    fn ref_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+}
+
 impl<T: Value> Value for Option<T> {
     #[inline]
     fn ref_any(&self) -> Option<&dyn Any> {
@@ -308,4 +542,19 @@ mod tests {
         assert_matches!(get_value::<i32>(&tuple, "a"), Err(Error::ValueType));
         assert_matches!(get_value::<i32>(&tuple, "b"), Err(Error::ValueMissing));
     }
+
+    #[test]
+    fn values_with_fallback() {
+        let slice: &[(&str, &dyn Any)] = &[("a", &12u32), ("c", &"blam")];
+
+        assert_eq!(get_value_or::<u32>(&slice, "a", 1), 12);
+        assert_eq!(get_value_or::<u32>(&slice, "b", 1), 1);
+        assert_eq!(get_value_or::<u8>(&slice, "a", 1), 1);
+        assert_eq!(get_value_or::<&str>(&slice, "c", "boom"), "blam");
+
+        assert_eq!(get_value_opt::<u32>(&slice, "a"), Some(12));
+        assert_eq!(get_value_opt::<u32>(&slice, "b"), None);
+        assert_eq!(get_value_opt::<u8>(&slice, "a"), None);
+        assert_eq!(get_value_opt::<u32>(NO_VALUES, "a"), None);
+    }
 }
@@ -2,7 +2,7 @@ use criterion::{BatchSize, Criterion, Throughput, criterion_group, criterion_mai
 use quote::quote;
 
 criterion_main!(benches);
-criterion_group!(benches, hello_world, librustdoc);
+criterion_group!(benches, hello_world, includes, many_escapers, librustdoc);
 
 fn extern_crate_askama() -> proc_macro2::TokenStream {
     quote! {
@@ -35,6 +35,57 @@ fn hello_world(c: &mut Criterion) {
     g.finish();
 }
 
+fn includes(c: &mut Criterion) {
+    // `include1.html` includes `include2.html` twice, which includes `include3.html` twice, so
+    // the same partials are resolved over and over again.
+    let source = r#"{% include "include1.html" %}{% include "include1.html" %}"#;
+    let ts = quote! {
+        #[derive(Template)]
+        #[template(
+            source = #source,
+            ext = "html"
+        )]
+        struct Includes;
+    };
+
+    let mut g = c.benchmark_group("synthetic");
+    g.bench_function("includes", |b| {
+        b.iter_batched(
+            || ts.clone(),
+            |input| askama_derive::derive_template(input, extern_crate_askama),
+            BatchSize::LargeInput,
+        );
+    });
+    g.finish();
+}
+
+fn many_escapers(c: &mut Criterion) {
+    // `many-escapers.toml` defines 50 escapers with 4 extensions each, and the last one is used.
+    let ts = quote! {
+        #[derive(Template)]
+        #[template(
+            source = "{{ a|escape }}{{ b|escape(\"ext0a\") }}{{ c|escape(\"ext25c\") }}",
+            ext = "ext49d",
+            config = "benches/many-escapers.toml"
+        )]
+        struct ManyEscapers<'a> {
+            a: &'a str,
+            b: &'a str,
+            c: &'a str,
+        }
+    };
+
+    let mut g = c.benchmark_group("synthetic");
+    g.bench_function("many_escapers", |b| {
+        b.iter_batched(
+            || ts.clone(),
+            |input| askama_derive::derive_template(input, extern_crate_askama),
+            BatchSize::LargeInput,
+        );
+    });
+    g.finish();
+}
+
 fn librustdoc(c: &mut Criterion) {
     let mut g = c.benchmark_group("librustdoc");
 
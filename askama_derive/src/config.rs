@@ -1,14 +1,19 @@
 use std::borrow::{Borrow, Cow};
+use std::collections::HashMap;
 use std::collections::btree_map::{BTreeMap, Entry};
+use std::convert::Infallible;
+use std::ffi::OsString;
 use std::mem::ManuallyDrop;
 use std::ops::Deref;
-use std::path::{Path, PathBuf};
-use std::sync::{Arc, OnceLock};
-use std::{env, fs};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::{env, fs, iter};
 
 use parser::node::Whitespace;
 use parser::{ParseError, Parsed, Syntax, SyntaxBuilder};
 use proc_macro2::Span;
+use rustc_hash::FxBuildHasher;
 #[cfg(feature = "config")]
 use serde_derive::Deserialize;
 
@@ -21,7 +26,18 @@ pub(crate) struct Config {
     pub(crate) default_syntax: &'static str,
     pub(crate) escapers: Vec<(Vec<Cow<'static, str>>, Cow<'static, str>)>,
     pub(crate) whitespace: Whitespace,
+    /// Whitespace handling for templates with the given extensions, overriding `whitespace`.
+    pub(crate) whitespaces: Vec<(Vec<Cow<'static, str>>, Whitespace)>,
+    /// The `whitespace` argument of the template, overriding both `whitespace` and `whitespaces`.
+    template_whitespace: Option<Whitespace>,
+    /// If `false`, templates are not escaped unless they have an `escape` attribute.
+    pub(crate) autoescape: bool,
+    /// The maximum number of nested `include`s, and of templates in an `extends` chain.
+    pub(crate) max_include_depth: usize,
     pub(crate) full_config_path: Option<PathBuf>,
+    resolved_templates: Mutex<ResolvedTemplates>,
+    /// Maps a lowercase extension to the index of its escaper in `escapers`.
+    resolved_escapers: OnceMap<String, Option<usize>>,
     // `Config` is self referential and `_key` owns it data, so it must come last
     _key: OwnedConfigKey,
 }
@@ -42,6 +58,9 @@ struct ConfigKey<'a> {
     source: Cow<'a, str>,
     config_path: Option<Cow<'a, str>>,
     template_whitespace: Option<Whitespace>,
+    /// The values of the environment variables that `source` refers to as `${NAME}`, so a
+    /// changed value, e.g. a new `OUT_DIR`, does not reuse a configuration with stale `dirs`.
+    env_vars: Vec<(String, Option<OsString>)>,
 }
 
 impl ToOwned for ConfigKey<'_> {
@@ -56,6 +75,7 @@ impl ToOwned for ConfigKey<'_> {
                 .as_ref()
                 .map(|s| Cow::Owned(s.as_ref().to_owned())),
             template_whitespace: self.template_whitespace,
+            env_vars: self.env_vars.clone(),
         };
         OwnedConfigKey(Box::leak(Box::new(owned_key)))
     }
@@ -84,6 +104,7 @@ impl Config {
                 source: source.into(),
                 config_path: config_path.map(Cow::Borrowed),
                 template_whitespace,
+                env_vars: referenced_env_vars(source),
             },
             |key| {
                 let config = Config::new_uncached(key.to_owned(), config_span, full_config_path)?;
@@ -116,22 +137,48 @@ impl Config {
             RawConfig::from_toml_str(s)?
         };
 
-        let (dirs, default_syntax, whitespace) = match raw.general {
+        let file_info = config_path.map(|path| FileInfo::new(Path::new(path), None, None));
+        let (dirs, default_syntax, whitespace, autoescape, max_include_depth) = match raw.general {
             Some(General {
                 dirs,
                 default_syntax,
                 whitespace,
+                autoescape,
+                max_include_depth,
             }) => (
-                dirs.map_or(default_dirs, |v| {
-                    v.into_iter().map(|dir| root.join(dir)).collect()
-                }),
+                match dirs {
+                    Some(dirs) => dirs
+                        .into_iter()
+                        .map(|dir| match expand_env_vars(dir) {
+                            Ok(dir) => Ok(root.join(&*dir)),
+                            Err(err) => {
+                                Err(CompileError::new_with_span(err, file_info, config_span))
+                            }
+                        })
+                        .collect::<Result<_, _>>()?,
+                    None => default_dirs,
+                },
                 default_syntax.unwrap_or(DEFAULT_SYNTAX_NAME),
                 whitespace,
+                autoescape.unwrap_or(true),
+                max_include_depth.unwrap_or(DEFAULT_MAX_INCLUDE_DEPTH),
+            ),
+            None => (
+                default_dirs,
+                DEFAULT_SYNTAX_NAME,
+                Whitespace::default(),
+                true,
+                DEFAULT_MAX_INCLUDE_DEPTH,
             ),
-            None => (default_dirs, DEFAULT_SYNTAX_NAME, Whitespace::default()),
         };
-        let file_info = config_path.map(|path| FileInfo::new(Path::new(path), None, None));
-        let whitespace = key.0.template_whitespace.unwrap_or(whitespace);
+        let template_whitespace = key.0.template_whitespace;
+        let whitespace = template_whitespace.unwrap_or(whitespace);
+        let whitespaces = raw
+            .whitespace
+            .into_iter()
+            .flatten()
+            .map(|ws| (str_set(&ws.extensions), ws.mode))
+            .collect();
 
         if let Some(raw_syntaxes) = raw.syntax {
             for raw_s in raw_syntaxes {
@@ -178,48 +225,222 @@ impl Config {
             default_syntax,
             escapers,
             whitespace,
+            whitespaces,
+            template_whitespace,
+            autoescape,
+            max_include_depth,
             full_config_path,
+            resolved_templates: Mutex::default(),
+            resolved_escapers: OnceMap::default(),
             _key: key,
         })
     }
 
+    /// Returns the path of the escaper registered for `extension`.
+    ///
+    /// Extensions are compared case-insensitively, so `.HTML` files use the same escaper as
+    /// `.html` files.
+    pub(crate) fn find_escaper(&self, extension: &str) -> Option<&str> {
+        // Every template and every `escape` filter looks up an escaper, so cache the result
+        // instead of scanning all configured escapers again.
+        let extension = if extension.bytes().any(|b| b.is_ascii_uppercase()) {
+            Cow::Owned(extension.to_ascii_lowercase())
+        } else {
+            Cow::Borrowed(extension)
+        };
+        let Ok(index) = self.resolved_escapers.get_or_try_insert(
+            extension.as_ref(),
+            |extension| {
+                let index = self.escapers.iter().position(|(extensions, _)| {
+                    extensions
+                        .iter()
+                        .any(|ext| ext.eq_ignore_ascii_case(extension))
+                });
+                Ok::<_, Infallible>((extension.to_owned(), index))
+            },
+            |index| *index,
+        );
+        index.map(|index| self.escapers[index].1.as_ref())
+    }
+
+    /// Returns the whitespace handling of templates with the given `extension`.
+    ///
+    /// A `whitespace` argument of the template takes precedence over a `[[whitespace]]` entry
+    /// of the configuration, which takes precedence over the general `whitespace` setting.
+    pub(crate) fn whitespace_for(&self, extension: &str) -> Whitespace {
+        if let Some(whitespace) = self.template_whitespace {
+            return whitespace;
+        }
+        self.whitespaces
+            .iter()
+            .find_map(|(extensions, whitespace)| {
+                extensions
+                    .iter()
+                    .any(|ext| ext.eq_ignore_ascii_case(extension))
+                    .then_some(*whitespace)
+            })
+            .unwrap_or(self.whitespace)
+    }
+
     pub(crate) fn find_template(
         &self,
         path: &str,
         start_at: Option<&Path>,
         file_info: Option<FileInfo<'_>>,
     ) -> Result<Arc<Path>, CompileError> {
+        // The same partials are typically looked up many times while a template is derived, so
+        // cache the resolved paths to not query the file system over and over again. The cache is
+        // cleared for every derived template, because a long-lived process, e.g. rust-analyzer,
+        // sees templates being added, moved and removed. Failed lookups are cached, too.
+        let key = FindTemplateKey {
+            path: Cow::Borrowed(path),
+            start_at: start_at.map(Cow::Borrowed),
+        };
+        let generation = DERIVE_GENERATION.load(Ordering::Relaxed);
+        let cached = {
+            let mut resolved = self.resolved_templates.lock().unwrap();
+            if resolved.generation != generation {
+                resolved.generation = generation;
+                resolved.paths.clear();
+                None
+            } else {
+                resolved.paths.get(&key).cloned()
+            }
+        };
+        let result = match cached {
+            Some(result) => result,
+            None => {
+                let result = self.find_template_uncached(path, start_at);
+                let key = OwnedFindTemplateKey(FindTemplateKey {
+                    path: Cow::Owned(path.to_owned()),
+                    start_at: start_at.map(|p| Cow::Owned(p.to_owned())),
+                });
+                let mut cache = self.resolved_templates.lock().unwrap();
+                if cache.generation == generation {
+                    cache.paths.insert(key, result.clone());
+                }
+                result
+            }
+        };
+
+        match result {
+            Ok(resolved) => Ok(resolved),
+            Err(FindTemplateError::NotFoundRelative) => Err(CompileError::new(
+                format_args!(
+                    "template {:?} not found relative to {:?}",
+                    path,
+                    start_at.and_then(Path::parent).unwrap_or(Path::new("")),
+                ),
+                file_info,
+            )),
+            Err(FindTemplateError::NotFound) => Err(CompileError::new(
+                format_args!(
+                    "template {:?} not found in directories {:?}",
+                    path, self.dirs,
+                ),
+                file_info,
+            )),
+            Err(FindTemplateError::Canonicalize(msg)) => Err(CompileError::new(msg, file_info)),
+        }
+    }
+
+    fn find_template_uncached(
+        &self,
+        path: &str,
+        start_at: Option<&Path>,
+    ) -> Result<Arc<Path>, FindTemplateError> {
         let path = 'find_path: {
             if let Some(root) = start_at {
                 let relative = root.with_file_name(path);
-                if relative.exists() {
+                if path_exists(&relative) {
                     break 'find_path relative;
                 }
+                // A path starting with `./` or `../` is only looked up next to the including
+                // template. The path of a `source` template is synthetic, though, so its lookup
+                // falls back to the template directories.
+                if root.is_absolute() && is_explicitly_relative(path) {
+                    return Err(FindTemplateError::NotFoundRelative);
+                }
             }
             for dir in &self.dirs {
                 let rooted = dir.join(path);
-                if rooted.exists() {
+                if path_exists(&rooted) {
                     break 'find_path rooted;
                 }
             }
-            return Err(CompileError::new(
-                format_args!(
-                    "template {:?} not found in directories {:?}",
-                    path, self.dirs,
-                ),
-                file_info,
-            ));
+            return Err(FindTemplateError::NotFound);
         };
         match path.canonicalize() {
             Ok(path) => Ok(path.into()),
-            Err(err) => Err(CompileError::new(
-                format_args!("could not canonicalize path {path:?}: {err}"),
-                file_info,
-            )),
+            Err(err) => Err(FindTemplateError::Canonicalize(format!(
+                "could not canonicalize path {path:?}: {err}"
+            ))),
         }
     }
 }
 
+#[cfg(test)]
+thread_local! {
+    /// Number of [`path_exists()`] calls in the current thread.
+    static PATH_EXISTS_CALLS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+fn path_exists(path: &Path) -> bool {
+    #[cfg(test)]
+    PATH_EXISTS_CALLS.with(|calls| calls.set(calls.get() + 1));
+    path.exists()
+}
+
+/// Incremented for every derived template, see [`start_derive()`].
+static DERIVE_GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// Invalidates the cached template lookups, successful or not, of all configs.
+///
+/// Must be called before a template is derived, so that templates that were added, moved or
+/// removed since the last derivation are found where they are now.
+pub(crate) fn start_derive() {
+    DERIVE_GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+/// The template paths resolved while deriving the current template, see [`start_derive()`].
+#[derive(Debug, Default)]
+struct ResolvedTemplates {
+    generation: usize,
+    paths: HashMap<OwnedFindTemplateKey, Result<Arc<Path>, FindTemplateError>, FxBuildHasher>,
+}
+
+/// Outcome of a failed [`Config::find_template_uncached()`] call.
+///
+/// The error message is built by the caller, because it needs the [`FileInfo`].
+#[derive(Debug, Clone)]
+enum FindTemplateError {
+    NotFound,
+    /// An explicitly relative path, e.g. `"./partial.html"`, was not found next to the includer.
+    NotFoundRelative,
+    Canonicalize(String),
+}
+
+/// Whether a template path starts with `./` or `../`.
+fn is_explicitly_relative(path: &str) -> bool {
+    path.starts_with("./") || path.starts_with("../")
+}
+
+#[derive(Debug, Hash, PartialEq, Eq)]
+struct OwnedFindTemplateKey(FindTemplateKey<'static>);
+
+#[derive(Debug, Hash, PartialEq, Eq)]
+struct FindTemplateKey<'a> {
+    path: Cow<'a, str>,
+    start_at: Option<Cow<'a, Path>>,
+}
+
+impl<'a> Borrow<FindTemplateKey<'a>> for OwnedFindTemplateKey {
+    #[inline]
+    fn borrow(&self) -> &FindTemplateKey<'a> {
+        &self.0
+    }
+}
+
 #[derive(Debug, Default)]
 pub(crate) struct SyntaxAndCache<'a> {
     syntax: Syntax<'a>,
@@ -302,6 +523,7 @@ struct RawConfig<'a> {
     general: Option<General<'a>>,
     syntax: Option<Vec<SyntaxBuilder<'a>>>,
     escaper: Option<Vec<RawEscaper<'a>>>,
+    whitespace: Option<Vec<RawWhitespace<'a>>>,
 }
 
 impl RawConfig<'_> {
@@ -328,6 +550,8 @@ struct General<'a> {
     default_syntax: Option<&'a str>,
     #[cfg_attr(feature = "config", serde(default))]
     whitespace: Whitespace,
+    autoescape: Option<bool>,
+    max_include_depth: Option<usize>,
 }
 
 #[cfg_attr(feature = "config", derive(Deserialize))]
@@ -336,6 +560,13 @@ struct RawEscaper<'a> {
     extensions: Vec<&'a str>,
 }
 
+#[cfg_attr(feature = "config", derive(Deserialize))]
+struct RawWhitespace<'a> {
+    #[cfg_attr(feature = "config", serde(borrow))]
+    extensions: Vec<&'a str>,
+    mode: Whitespace,
+}
+
 pub(crate) fn read_config_file(
     config_path: Option<&str>,
     span: Option<Span>,
@@ -364,16 +595,87 @@ pub(crate) fn read_config_file(
     }
 }
 
-fn manifest_root() -> PathBuf {
+pub(crate) fn manifest_root() -> PathBuf {
     env::var_os("CARGO_MANIFEST_DIR").map_or_else(|| PathBuf::from("."), PathBuf::from)
 }
 
+/// The path of a template file relative to `root`, with `/` as separator on all platforms.
+///
+/// The template paths are canonicalized, so `root` is canonicalized, too, e.g. if the crate is
+/// in a symlinked directory, or on Windows with its `\\?\` prefix. If the template is not inside
+/// of `root`, its path relative to its template directory in `dirs` is used, or else only its file
+/// name. The result is never absolute, so no path of the build machine ends up in the binary.
+pub(crate) fn relative_template_name(path: &Path, root: &Path, dirs: &[PathBuf]) -> String {
+    let relative = iter::once(root)
+        .chain(dirs.iter().map(PathBuf::as_path))
+        .find_map(|dir| path.strip_prefix(dir.canonicalize().ok()?).ok())
+        .or_else(|| path.file_name().map(Path::new))
+        .unwrap_or(Path::new(""));
+    relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Returns the names and current values of all environment variables that `source` refers to
+/// as `${NAME}`.
+fn referenced_env_vars(source: &str) -> Vec<(String, Option<OsString>)> {
+    let mut vars = Vec::new();
+    let mut rest = source;
+    while let Some((_, tail)) = rest.split_once("${") {
+        let Some((name, tail)) = tail.split_once('}') else {
+            break;
+        };
+        if !vars.iter().any(|(var, _)| var == name) {
+            vars.push((name.to_owned(), env::var_os(name)));
+        }
+        rest = tail;
+    }
+    vars
+}
+
+/// Replaces every `${NAME}` in a template directory with the value of the environment
+/// variable `NAME`, e.g. `${OUT_DIR}` to use templates that were written by a build script.
+fn expand_env_vars(dir: &str) -> Result<Cow<'_, str>, String> {
+    let Some((head, _)) = dir.split_once("${") else {
+        return Ok(Cow::Borrowed(dir));
+    };
+
+    let mut expanded = String::from(head);
+    let mut rest = &dir[head.len()..];
+    while let Some(tail) = rest.strip_prefix("${") {
+        let Some((name, tail)) = tail.split_once('}') else {
+            return Err(format!(
+                "unterminated variable in template directory {dir:?}"
+            ));
+        };
+        match env::var(name) {
+            Ok(value) => expanded.push_str(&value),
+            Err(_) => {
+                return Err(format!(
+                    "environment variable `{name}` used in template directory {dir:?} is not set \
+                     or not valid UTF-8"
+                ));
+            }
+        }
+        let (text, tail) = tail.split_at(tail.find("${").unwrap_or(tail.len()));
+        expanded.push_str(text);
+        rest = tail;
+    }
+    Ok(Cow::Owned(expanded))
+}
+
 fn str_set(vals: &[&'static str]) -> Vec<Cow<'static, str>> {
     vals.iter().map(|s| Cow::Borrowed(*s)).collect()
 }
 
 static CONFIG_FILE_NAME: &str = "askama.toml";
 static DEFAULT_SYNTAX_NAME: &str = "default";
+const DEFAULT_MAX_INCLUDE_DEPTH: usize = 64;
 static DEFAULT_ESCAPERS: &[(&[&str], &str)] = &[
     (
         &[
@@ -382,6 +684,7 @@ static DEFAULT_ESCAPERS: &[(&[&str], &str)] = &[
         "Html",
     ),
     (&["md", "none", "txt", "yml", ""], "Text"),
+    (&["sh"], "Shell"),
 ];
 
 #[cfg(test)]
@@ -407,6 +710,63 @@ mod tests {
         assert_eq!(config.dirs, vec![root]);
     }
 
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_config_dirs_env_vars() {
+        let mut root = manifest_root();
+        root.push("templates");
+        let config = Config::new(
+            "[general]\ndirs = [\"${CARGO_MANIFEST_DIR}/templates\"]",
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(config.dirs, vec![root]);
+
+        assert_eq!(expand_env_vars("tpl").unwrap(), "tpl");
+        assert_eq!(
+            expand_env_vars("${CARGO_PKG_NAME}/${CARGO_PKG_NAME}/x").unwrap(),
+            "askama_derive/askama_derive/x",
+        );
+        assert!(expand_env_vars("${CARGO_PKG_NAME").is_err());
+        assert!(expand_env_vars("${ASKAMA_SURELY_NOT_SET}/tpl").is_err());
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_config_dirs_env_vars_not_stale() {
+        // The same configuration must not reuse `dirs` that were expanded with an old value.
+        const VAR: &str = "ASKAMA_TEST_CONFIG_DIRS_NOT_STALE";
+        let source = format!("[general]\ndirs = [\"${{{VAR}}}/templates\"]");
+        let root = manifest_root();
+
+        env::set_var(VAR, "first");
+        let config = Config::new(&source, None, None, None, None).unwrap();
+        assert_eq!(config.dirs, vec![root.join("first/templates")]);
+
+        env::set_var(VAR, "second");
+        let config = Config::new(&source, None, None, None, None).unwrap();
+        assert_eq!(config.dirs, vec![root.join("second/templates")]);
+
+        // The error for an unset variable is not reused once the variable is set.
+        env::remove_var(VAR);
+        assert!(Config::new(&source, None, None, None, None).is_err());
+        env::set_var(VAR, "third");
+        let config = Config::new(&source, None, None, None, None).unwrap();
+        assert_eq!(config.dirs, vec![root.join("third/templates")]);
+        env::remove_var(VAR);
+
+        assert_eq!(
+            referenced_env_vars("${A}/${B}/${A}/${unterminated"),
+            vec![
+                ("A".to_owned(), env::var_os("A")),
+                ("B".to_owned(), env::var_os("B")),
+            ],
+        );
+    }
+
     fn assert_eq_rooted(actual: &Path, expected: &str) {
         let mut root = manifest_root().canonicalize().unwrap();
         root.push("templates");
@@ -433,6 +793,96 @@ mod tests {
         config.find_template("c.html", Some(&root), None).unwrap();
     }
 
+    #[test]
+    fn test_relative_template_name() {
+        let config = Config::new("", None, None, None, None).unwrap();
+        let path = config.find_template("sub/b.html", None, None).unwrap();
+        let root = manifest_root();
+        let name = relative_template_name(&path, &root, &config.dirs);
+        assert_eq!(name, "templates/sub/b.html");
+
+        // The crate root is found even if it is reached through a symlink.
+        #[cfg(unix)]
+        {
+            let link = std::env::temp_dir().join(format!("askama-root-{}", std::process::id()));
+            let _ = fs::remove_file(&link);
+            std::os::unix::fs::symlink(&root, &link).unwrap();
+            let name = relative_template_name(&path, &link, &config.dirs);
+            fs::remove_file(&link).unwrap();
+            assert_eq!(name, "templates/sub/b.html");
+        }
+
+        // Outside of the crate root, the path relative to the template directory is used.
+        let dirs = [root.join("templates").join("sub")];
+        let name = relative_template_name(&path, Path::new("/nonexistent"), &dirs);
+        assert_eq!(name, "b.html");
+
+        // Otherwise only the file name is used, so the result is never absolute.
+        let name = relative_template_name(&path, Path::new("/nonexistent"), &[]);
+        assert_eq!(name, "b.html");
+        for root in [Path::new("/nonexistent"), &root] {
+            for dirs in [&[][..], &config.dirs] {
+                let name = relative_template_name(&path, root, dirs);
+                assert!(!Path::new(&name).is_absolute());
+                assert!(!name.starts_with('/'));
+            }
+        }
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn find_template_not_cached_across_derives() {
+        let base = std::env::temp_dir().join(format!("askama-find-{}", std::process::id()));
+        let (first, second) = (base.join("first"), base.join("second"));
+        fs::create_dir_all(&first).unwrap();
+        fs::create_dir_all(&second).unwrap();
+        let raw_config = format!("[general]\ndirs = [{:?}, {:?}]", first, second);
+        let config = Config::new(&raw_config, None, None, None, None).unwrap();
+
+        // A failed lookup is remembered until the next derivation.
+        assert!(config.find_template("late.html", None, None).is_err());
+        fs::write(second.join("late.html"), "").unwrap();
+        assert!(config.find_template("late.html", None, None).is_err());
+        start_derive();
+        let path = config.find_template("late.html", None, None).unwrap();
+        assert!(path.starts_with(second.canonicalize().unwrap()));
+
+        // In the next derivation, a moved template is found at its new location.
+        fs::write(first.join("late.html"), "").unwrap();
+        fs::remove_file(second.join("late.html")).unwrap();
+        start_derive();
+        let path = config.find_template("late.html", None, None).unwrap();
+        assert!(path.starts_with(first.canonicalize().unwrap()));
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn find_template_cached() {
+        let base = std::env::temp_dir().join(format!("askama-cached-{}", std::process::id()));
+        let dirs = ["a", "b", "c"].map(|dir| base.join(dir));
+        for dir in &dirs {
+            fs::create_dir_all(dir).unwrap();
+        }
+        fs::write(dirs[2].join("partial.html"), "").unwrap();
+        let raw_config = format!("[general]\ndirs = {dirs:?}");
+        let config = Config::new(&raw_config, None, None, None, None).unwrap();
+
+        // Resolve a partial and a missing template 100 times each, like a template with many
+        // includes would. Without the cache, this would query the file system 600 times.
+        let calls = || PATH_EXISTS_CALLS.with(|calls| calls.get());
+        let before = calls();
+        for _ in 0..100 {
+            assert!(config.find_template("partial.html", None, None).is_ok());
+            assert!(config.find_template("missing.html", None, None).is_err());
+        }
+        // Another test could start a new derivation in between, which clears the cache.
+        assert!(calls() - before < 60, "{} calls", calls() - before);
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
     #[test]
     fn find_relative() {
         let config = Config::new("", None, None, None, None).unwrap();
@@ -451,6 +901,62 @@ mod tests {
         assert_eq_rooted(&path, "sub/sub1/d.html");
     }
 
+    #[test]
+    fn find_explicitly_relative() {
+        let config = Config::new("", None, None, None, None).unwrap();
+        let root = config.find_template("sub/b.html", None, None).unwrap();
+        let path = config.find_template("./c.html", Some(&root), None).unwrap();
+        assert_eq_rooted(&path, "sub/c.html");
+        let path = config
+            .find_template("./sub1/d.html", Some(&root), None)
+            .unwrap();
+        assert_eq_rooted(&path, "sub/sub1/d.html");
+        let path = config
+            .find_template("../a.html", Some(&root), None)
+            .unwrap();
+        assert_eq_rooted(&path, "a.html");
+
+        // A bare path falls back to the template directories, an explicitly relative one does not.
+        let path = config.find_template("a.html", Some(&root), None).unwrap();
+        assert_eq_rooted(&path, "a.html");
+        let err = config
+            .find_template("./a.html", Some(&root), None)
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains(r#"template "./a.html" not found relative to"#),
+            "{err}",
+        );
+
+        // Without an including template, the path is looked up in the template directories.
+        let path = config.find_template("./a.html", None, None).unwrap();
+        assert_eq_rooted(&path, "a.html");
+    }
+
+    #[test]
+    fn find_cached() {
+        let config = Config::new("", None, None, None, None).unwrap();
+        let root = config.find_template("sub/b.html", None, None).unwrap();
+        let first = config.find_template("c.html", Some(&root), None).unwrap();
+        let second = config.find_template("c.html", Some(&root), None).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        // A relative lookup from another template must not reuse the cached result.
+        let root = config.find_template("a.html", None, None).unwrap();
+        assert!(config.find_template("c.html", Some(&root), None).is_err());
+
+        // Failed lookups are cached, too, but still report the caller's file info.
+        for _ in 0..2 {
+            let err = config
+                .find_template("missing.html", None, None)
+                .unwrap_err();
+            assert!(
+                err.to_string()
+                    .contains(r#"template "missing.html" not found"#)
+            );
+        }
+    }
+
     #[cfg(feature = "config")]
     #[test]
     fn add_syntax() {
@@ -594,6 +1100,68 @@ mod tests {
             expect_err(config).msg,
             r#"an opening delimiter may not be the prefix of another delimiter. The block delimiter ("{{") clashes with the expression delimiter ("{{$")"#,
         );
+
+        let raw_config = r#"
+        [[syntax]]
+        name = "end_is_start"
+        block_start = "<%"
+        block_end = "%>"
+        expr_start = "%>="
+        expr_end = "=%"
+        "#;
+        let config = Config::new(raw_config, None, None, None, None);
+        assert_eq!(
+            expect_err(config).msg,
+            r#"an opening delimiter may not be the prefix of a closing delimiter of another kind, or vice versa. The opening expression delimiter ("%>=") clashes with the closing block delimiter ("%>")"#,
+        );
+
+        let raw_config = r#"
+        [[syntax]]
+        name = "start_is_end"
+        block_start = "[["
+        block_end = "]]"
+        expr_start = "{{"
+        expr_end = "[[="
+        "#;
+        let config = Config::new(raw_config, None, None, None, None);
+        assert_eq!(
+            expect_err(config).msg,
+            r#"an opening delimiter may not be the prefix of a closing delimiter of another kind, or vice versa. The opening block delimiter ("[[") clashes with the closing expression delimiter ("[[=")"#,
+        );
+
+        let raw_config = r#"
+        [[syntax]]
+        name = "comment_end_is_block_end"
+        comment_start = "{*"
+        comment_end = "%}"
+        "#;
+        let config = Config::new(raw_config, None, None, None, None);
+        assert_eq!(
+            expect_err(config).msg,
+            r#"the closing comment delimiter may not be the prefix of another closing delimiter, or vice versa. The closing comment delimiter ("%}") clashes with the closing block delimiter ("%}")"#,
+        );
+
+        let raw_config = r#"
+        [[syntax]]
+        name = "comment_end_is_expr_end_prefix"
+        comment_end = "}}}"
+        "#;
+        let config = Config::new(raw_config, None, None, None, None);
+        assert_eq!(
+            expect_err(config).msg,
+            r#"the closing comment delimiter may not be the prefix of another closing delimiter, or vice versa. The closing comment delimiter ("}}}") clashes with the closing expression delimiter ("}}")"#,
+        );
+
+        // The same delimiters for the start and the end of a block are fine.
+        let raw_config = r#"
+        [[syntax]]
+        name = "symmetric"
+        block_start = "%%"
+        block_end = "%%"
+        comment_start = "%#"
+        expr_start = "%{"
+        "#;
+        Config::new(raw_config, None, None, None, None).unwrap();
     }
 
     #[cfg(feature = "config")]
@@ -660,10 +1228,63 @@ mod tests {
                     str_set(&["md", "none", "txt", "yml", ""]),
                     "askama::filters::Text".into()
                 ),
+                (str_set(&["sh"]), "askama::filters::Shell".into()),
             ]
         );
     }
 
+    #[test]
+    fn find_escaper_ignores_case() {
+        let config = Config::new("", None, None, None, None).unwrap();
+        for ext in ["html", "HTML", "Svg", "xMl"] {
+            assert_eq!(config.find_escaper(ext), Some("askama::filters::Html"));
+        }
+        assert_eq!(config.find_escaper("TXT"), Some("askama::filters::Text"));
+        assert_eq!(config.find_escaper("sh"), Some("askama::filters::Shell"));
+        assert_eq!(config.find_escaper("js"), None);
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn find_escaper_is_cached() {
+        let config = Config::new(
+            r#"
+            [[escaper]]
+            path = "my_crate::Js"
+            extensions = ["js", "mjs"]
+
+            [[escaper]]
+            path = "my_crate::Css"
+            extensions = ["css"]
+
+            [[escaper]]
+            path = "my_crate::Html"
+            extensions = ["html"]
+            "#,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+        for _ in 0..3 {
+            assert_eq!(config.find_escaper("js"), Some("my_crate::Js"));
+            assert_eq!(config.find_escaper("MJS"), Some("my_crate::Js"));
+            assert_eq!(config.find_escaper("css"), Some("my_crate::Css"));
+            // Configured escapers come before the default ones.
+            assert_eq!(config.find_escaper("html"), Some("my_crate::Html"));
+            assert_eq!(config.find_escaper("txt"), Some("askama::filters::Text"));
+            assert_eq!(config.find_escaper("unknown"), None);
+        }
+
+        // The cached result is the same string as in `escapers`, not a copy of it.
+        let first = config.find_escaper("mjs").unwrap();
+        let second = config.find_escaper("Mjs").unwrap();
+        assert!(std::ptr::eq(first, second));
+        assert!(std::ptr::eq(first, config.escapers[0].1.as_ref()));
+    }
+
     #[cfg(feature = "config")]
     #[test]
     fn test_whitespace_parsing() {
@@ -710,6 +1331,46 @@ mod tests {
         assert_eq!(config.whitespace, Whitespace::Minimize);
     }
 
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_autoescape_parsing() {
+        let config = Config::new(r#""#, None, None, None, None).unwrap();
+        assert!(config.autoescape);
+
+        let config = Config::new(
+            r#"
+            [general]
+            autoescape = false
+            "#,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert!(!config.autoescape);
+    }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_max_include_depth_parsing() {
+        let config = Config::new(r#""#, None, None, None, None).unwrap();
+        assert_eq!(config.max_include_depth, DEFAULT_MAX_INCLUDE_DEPTH);
+
+        let config = Config::new(
+            r#"
+            [general]
+            max_include_depth = 3
+            "#,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(config.max_include_depth, 3);
+    }
+
     #[cfg(feature = "config")]
     #[test]
     fn test_whitespace_in_template() {
@@ -732,4 +1393,28 @@ mod tests {
         let config = Config::new(r#""#, None, Some(Whitespace::Minimize), None, None).unwrap();
         assert_eq!(config.whitespace, Whitespace::Minimize);
     }
+
+    #[cfg(feature = "config")]
+    #[test]
+    fn test_whitespace_per_extension() {
+        let source = r#"
+            [general]
+            whitespace = "minimize"
+
+            [[whitespace]]
+            extensions = ["md", "markdown"]
+            mode = "preserve"
+            "#;
+
+        let config = Config::new(source, None, None, None, None).unwrap();
+        assert_eq!(config.whitespace_for("md"), Whitespace::Preserve);
+        assert_eq!(config.whitespace_for("MD"), Whitespace::Preserve);
+        assert_eq!(config.whitespace_for("markdown"), Whitespace::Preserve);
+        assert_eq!(config.whitespace_for("txt"), Whitespace::Minimize);
+        assert_eq!(config.whitespace_for(""), Whitespace::Minimize);
+
+        // The `whitespace` argument of the template has precedence.
+        let config = Config::new(source, None, Some(Whitespace::Suppress), None, None).unwrap();
+        assert_eq!(config.whitespace_for("md"), Whitespace::Suppress);
+    }
 }
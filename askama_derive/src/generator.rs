@@ -19,7 +19,7 @@ use crate::ascii_str::{AsciiChar, AsciiStr};
 use crate::heritage::{Context, Heritage};
 use crate::html::write_escaped_str;
 use crate::input::{Source, TemplateInput};
-use crate::integration::{Buffer, impl_everything, write_header};
+use crate::integration::{Buffer, impl_everything, impl_render_with_context, write_header};
 use crate::{CompileError, FileInfo};
 
 pub(crate) fn template_to_string(
@@ -45,12 +45,37 @@ pub(crate) fn template_to_string(
         result => result,
     }?;
 
-    if tmpl_kind == TmplKind::Struct {
+    if tmpl_kind == TmplKind::Struct && !input.is_async {
         impl_everything(input.ast, buf);
     }
     Ok(size_hint)
 }
 
+/// Generates an expression that renders the template into an `askama::Result<String>`.
+///
+/// There is no context struct: the template can only access the given `variables`, which must be
+/// declared by the caller before the expression.
+pub(crate) fn template_to_inline_string(
+    buf: &mut Buffer,
+    input: &TemplateInput<'_>,
+    contexts: &HashMap<&Arc<Path>, Context<'_>, FxBuildHasher>,
+    heritage: Option<&Heritage<'_, '_>>,
+    variables: &[String],
+) -> Result<usize, CompileError> {
+    let mut locals = MapChain::default();
+    for variable in variables {
+        locals.insert(Cow::Owned(variable.clone()), LocalMeta::initialized());
+    }
+    let generator = Generator::new(input, contexts, heritage, locals, input.block.is_some(), 0);
+    match generator.impl_inline(buf) {
+        Err(mut err) if err.span.is_none() => {
+            err.span = input.source_span;
+            Err(err)
+        }
+        result => result,
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum TmplKind<'a> {
     /// [`askama::Template`]
@@ -78,6 +103,8 @@ struct Generator<'a, 'h> {
     /// Whitespace suppression from the previous non-literal. Will be used to
     /// determine whether to flush prefix whitespace from the next literal.
     skip_ws: Whitespace,
+    /// The default whitespace handling of the template that is currently being rendered.
+    whitespace: Whitespace,
     /// If currently in a block, this will contain the name of a potential parent block
     super_block: Option<(&'a str, usize)>,
     /// Buffer for writable
@@ -88,6 +115,8 @@ struct Generator<'a, 'h> {
     seen_callers: Vec<(&'a Call<'a>, &'a Macro<'a>, Option<FileInfo<'a>>)>,
     /// the active caller within the macro.
     active_caller: Option<&'a Call<'a>>,
+    /// The templates that are currently being included, innermost last.
+    include_chain: Vec<Arc<Path>>,
 }
 
 impl<'a, 'h> Generator<'a, 'h> {
@@ -106,6 +135,7 @@ impl<'a, 'h> Generator<'a, 'h> {
             locals,
             next_ws: None,
             skip_ws: Whitespace::Preserve,
+            whitespace: input.whitespace,
             super_block: None,
             buf_writable: WritableBuffer {
                 discard: buf_writable_discard,
@@ -114,6 +144,7 @@ impl<'a, 'h> Generator<'a, 'h> {
             is_in_filter_block,
             seen_callers: Vec::new(),
             active_caller: None,
+            include_chain: Vec::new(),
         }
     }
 
@@ -123,6 +154,10 @@ impl<'a, 'h> Generator<'a, 'h> {
         buf: &mut Buffer,
         tmpl_kind: TmplKind<'a>,
     ) -> Result<usize, CompileError> {
+        if self.input.is_async {
+            return self.impl_template_async(buf);
+        }
+
         let ctx = &self.contexts[&self.input.path];
 
         let target = match tmpl_kind {
@@ -147,6 +182,134 @@ impl<'a, 'h> Generator<'a, 'h> {
                 };",
         );
 
+        self.write_dependencies(buf);
+        let size_hint = self.impl_template_inner(ctx, buf)?;
+
+        buf.write("askama::Result::Ok(()) }");
+        if tmpl_kind == TmplKind::Struct {
+            buf.write(format_args!(
+                "const SIZE_HINT: askama::helpers::core::primitive::usize = {size_hint}usize;\
+                const MIME_TYPE: &'static askama::helpers::core::primitive::str = {:?};",
+                self.input.mime_type,
+            ));
+            if self.input.bom {
+                buf.write("const BOM: askama::helpers::core::primitive::bool = true;");
+            }
+            if let Some(trailing_newline) = self.input.trailing_newline {
+                buf.write(format_args!(
+                    "const TRAILING_NEWLINE: askama::TrailingNewline = {};",
+                    trailing_newline.as_path(),
+                ));
+            }
+        }
+
+        buf.write('}');
+
+        if tmpl_kind == TmplKind::Struct {
+            if let Some(context) = &self.input.context {
+                impl_render_with_context(self.input.ast, buf, context);
+            }
+        }
+
+        #[cfg(feature = "blocks")]
+        for block in self.input.blocks {
+            self.impl_block(buf, block)?;
+        }
+
+        Ok(size_hint)
+    }
+
+    // Implement `render_async()` and friends as inherent methods of the context struct. The body
+    // of the template can contain `.await`, so it cannot be used to implement `Template`.
+    // The values are held across `.await`s, so they must be `Sync` for the futures to be `Send`.
+    fn impl_template_async(mut self, buf: &mut Buffer) -> Result<usize, CompileError> {
+        let ctx = &self.contexts[&self.input.path];
+
+        let ast = self.input.ast;
+        let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+        let ident = &ast.ident;
+        buf.write(format_args!(
+            "#[allow(dead_code)]\
+            impl {} {} {{\
+                #[doc = \"Renders the template asynchronously into the given `writer`.\"]\
+                pub async fn render_into_async_with_values<AskamaW>(\
+                    &self,\
+                    __askama_writer: &mut AskamaW,\
+                    __askama_values: &(dyn askama::Values + askama::helpers::core::marker::Sync)\
+                ) -> askama::Result<()>\
+                where \
+                    AskamaW: askama::helpers::core::fmt::Write + ?askama::helpers::core::marker::Sized\
+                {{\
+                    #[allow(unused_imports)]\
+                    use askama::{{\
+                        filters::{{AutoEscape as _, WriteWritable as _}},\
+                        helpers::{{ResultConverter as _, core::fmt::Write as _}},\
+                    }};",
+            quote::quote!(#impl_generics),
+            quote::quote!(#ident #ty_generics #where_clause),
+        ));
+
+        self.write_dependencies(buf);
+        let size_hint = self.impl_template_inner(ctx, buf)?;
+
+        buf.write(format_args!(
+            "\
+                    askama::Result::Ok(())\
+                }}\
+                #[doc = \"Renders the template asynchronously into a new `String`.\"]\
+                #[inline]\
+                pub async fn render_async(\
+                    &self\
+                ) -> askama::Result<askama::helpers::alloc::string::String> {{\
+                    self.render_async_with_values(&()).await\
+                }}\
+                #[doc = \"Renders the template asynchronously into a new `String` with the \
+                    provided `Values`.\"]\
+                pub async fn render_async_with_values(\
+                    &self,\
+                    __askama_values: &(dyn askama::Values + askama::helpers::core::marker::Sync)\
+                ) -> askama::Result<askama::helpers::alloc::string::String> {{\
+                    let mut __askama_buf = askama::helpers::alloc::string::String::new();\
+                    let _ = __askama_buf.try_reserve({size_hint}usize);\
+                    self.render_into_async_with_values(&mut __askama_buf, __askama_values).await?;\
+                    askama::helpers::apply_trailing_newline(&mut __askama_buf, {});\
+                    askama::Result::Ok(__askama_buf)\
+                }}\
+            }}",
+            self.input
+                .trailing_newline
+                .map_or("askama::TrailingNewline::Keep", |t| t.as_path()),
+        ));
+        Ok(size_hint)
+    }
+
+    // Render the template into a `String`, using the pre-defined locals instead of a context struct.
+    fn impl_inline(mut self, buf: &mut Buffer) -> Result<usize, CompileError> {
+        let ctx = &self.contexts[&self.input.path];
+
+        let mut body = Buffer::new();
+        self.write_dependencies(&mut body);
+        let size_hint = self.impl_template_inner(ctx, &mut body)?;
+
+        buf.write(format_args!(
+            "(|| -> askama::Result<askama::helpers::alloc::string::String> {{\
+                #[allow(unused_imports)]\
+                use askama::{{\
+                    filters::{{AutoEscape as _, WriteWritable as _}},\
+                    helpers::{{ResultConverter as _, core::fmt::Write as _}},\
+                }};\
+                let mut __askama_buf = \
+                    askama::helpers::alloc::string::String::with_capacity({size_hint}usize);\
+                let __askama_writer = &mut __askama_buf;\
+                let __askama_values: &dyn askama::Values = askama::NO_VALUES;",
+        ));
+        buf.write(body.as_str());
+        buf.write("askama::Result::Ok(__askama_buf) })()");
+        Ok(size_hint)
+    }
+
+    // Make sure the compiler understands that the generated code depends on the template files.
+    fn write_dependencies(&self, buf: &mut Buffer) {
         if let Some(full_config_path) = &self.input.config.full_config_path {
             buf.write(format_args!(
                 "const _: &[askama::helpers::core::primitive::u8] =\
@@ -155,7 +318,6 @@ impl<'a, 'h> Generator<'a, 'h> {
             ));
         }
 
-        // Make sure the compiler understands that the generated code depends on the template files.
         let mut paths = self
             .contexts
             .keys()
@@ -177,24 +339,6 @@ impl<'a, 'h> Generator<'a, 'h> {
                 ));
             }
         }
-
-        let size_hint = self.impl_template_inner(ctx, buf)?;
-
-        buf.write("askama::Result::Ok(()) }");
-        if tmpl_kind == TmplKind::Struct {
-            buf.write(format_args!(
-                "const SIZE_HINT: askama::helpers::core::primitive::usize = {size_hint}usize;",
-            ));
-        }
-
-        buf.write('}');
-
-        #[cfg(feature = "blocks")]
-        for block in self.input.blocks {
-            self.impl_block(buf, block)?;
-        }
-
-        Ok(size_hint)
     }
 
     #[cfg(feature = "blocks")]
@@ -346,7 +490,13 @@ impl<'a, 'h> Generator<'a, 'h> {
     }
 
     fn is_var_defined(&self, var_name: &str) -> bool {
-        self.locals.get(var_name).is_some() || self.input.fields.iter().any(|f| f == var_name)
+        self.locals.get(var_name).is_some()
+            || (!self.locals.isolated && self.input.fields.iter().any(|f| f == var_name))
+    }
+
+    /// With `values_only = true`, unknown identifiers are looked up in the runtime values.
+    fn is_value_lookup(&self, var_name: &str) -> bool {
+        self.input.values_only && !self.is_var_defined(var_name)
     }
 }
 
@@ -515,11 +665,24 @@ impl LocalMeta {
 
 struct MapChain<'a> {
     scopes: Vec<HashMap<Cow<'a, str>, LocalMeta, FxBuildHasher>>,
+    /// If `true`, variables are not looked up in the template struct, e.g. in templates that
+    /// were included with `{% include ... only %}`.
+    isolated: bool,
 }
 
 impl<'a> MapChain<'a> {
     fn new_empty() -> Self {
-        Self { scopes: vec![] }
+        Self {
+            scopes: vec![],
+            isolated: false,
+        }
+    }
+
+    fn new_isolated() -> Self {
+        Self {
+            scopes: vec![HashMap::default()],
+            isolated: true,
+        }
     }
 
     /// Iterates the scopes in reverse and returns `Some(LocalMeta)`
@@ -564,6 +727,7 @@ impl Default for MapChain<'_> {
     fn default() -> Self {
         Self {
             scopes: vec![HashMap::default()],
+            isolated: false,
         }
     }
 }
@@ -580,7 +744,8 @@ fn is_copyable_within_op(expr: &Expr<'_>, within_op: bool) -> bool {
         | Expr::NumLit(_, _)
         | Expr::StrLit(_)
         | Expr::CharLit(_)
-        | Expr::BinOp(_, _, _) => true,
+        | Expr::BinOp(_, _, _)
+        | Expr::Compare(_, _) => true,
         Expr::Unary(.., expr) => is_copyable_within_op(expr, true),
         Expr::Range(..) => true,
         // The result of a call likely doesn't need to be borrowed,
@@ -1,15 +1,21 @@
 use std::borrow::Cow;
+use std::path::Path;
 
 use parser::node::CondTest;
-use parser::{Attr, CharLit, CharPrefix, Expr, Filter, Span, StrLit, Target, TyGenerics, WithSpan};
+use parser::{
+    Attr, CharLit, CharPrefix, Expr, Filter, Span, StrLit, Target, Test, TyGenerics, WithSpan,
+};
 use quote::quote;
 
+use super::filter::closest_name;
 use super::{
     DisplayWrap, FILTER_SOURCE, Generator, LocalMeta, Writable, compile_time_escape, is_copyable,
     normalize_identifier,
 };
 use crate::CompileError;
+use crate::config::{manifest_root, relative_template_name};
 use crate::heritage::Context;
+use crate::input::Source;
 use crate::integration::Buffer;
 
 impl<'a> Generator<'a, '_> {
@@ -34,9 +40,10 @@ impl<'a> Generator<'a, '_> {
             Expr::NumLit(s, _) => self.visit_num_lit(buf, s),
             Expr::StrLit(ref s) => self.visit_str_lit(buf, s),
             Expr::CharLit(ref s) => self.visit_char_lit(buf, s),
-            Expr::Var(s) => self.visit_var(buf, s),
+            Expr::Var(s) => self.visit_var(ctx, buf, s, expr.span())?,
             Expr::Path(ref path) => self.visit_path(buf, path),
             Expr::Array(ref elements) => self.visit_array(ctx, buf, elements)?,
+            Expr::Map(ref entries) => self.visit_map(ctx, buf, entries, expr.span())?,
             Expr::Attr(ref obj, ref attr) => self.visit_attr(ctx, buf, obj, attr)?,
             Expr::Index(ref obj, ref key) => self.visit_index(ctx, buf, obj, key)?,
             Expr::Filter(Filter {
@@ -62,8 +69,14 @@ impl<'a> Generator<'a, '_> {
             Expr::FilterSource => self.visit_filter_source(buf),
             Expr::IsDefined(var_name) => self.visit_is_defined(buf, true, var_name)?,
             Expr::IsNotDefined(var_name) => self.visit_is_defined(buf, false, var_name)?,
+            Expr::Is {
+                ref expr,
+                negated,
+                ref test,
+            } => self.visit_is_test(ctx, buf, expr, negated, test)?,
             Expr::As(ref expr, target) => self.visit_as(ctx, buf, expr, target)?,
             Expr::Concat(ref exprs) => self.visit_concat(ctx, buf, exprs)?,
+            Expr::Compare(ref first, ref chain) => self.visit_compare(ctx, buf, first, chain)?,
             Expr::LetCond(ref cond) => self.visit_let_cond(ctx, buf, cond)?,
             Expr::ArgumentPlaceholder => DisplayWrap::Unwrapped,
         })
@@ -158,6 +171,17 @@ impl<'a> Generator<'a, '_> {
         is_defined: bool,
         left: &str,
     ) -> Result<DisplayWrap, CompileError> {
+        if self.is_value_lookup(left) {
+            if !is_defined {
+                buf.write('!');
+            }
+            buf.write(format_args!(
+                "askama::helpers::IsDefined::askama_is_defined(\
+                    &askama::helpers::DynValue::new(__askama_values, {left:?})\
+                )"
+            ));
+            return Ok(DisplayWrap::Unwrapped);
+        }
         match (is_defined, self.is_var_defined(left)) {
             (true, true) | (false, false) => buf.write("true"),
             _ => buf.write("false"),
@@ -165,6 +189,54 @@ impl<'a> Generator<'a, '_> {
         Ok(DisplayWrap::Unwrapped)
     }
 
+    fn visit_is_test(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        expr: &WithSpan<'a, Expr<'a>>,
+        negated: bool,
+        test: &Test<'a>,
+    ) -> Result<DisplayWrap, CompileError> {
+        // `is odd` is the same as `is not even`.
+        if negated != matches!(test, Test::Odd) {
+            buf.write('!');
+        }
+        match test {
+            Test::Defined => {
+                buf.write("askama::helpers::IsDefined::askama_is_defined(&(");
+                self.visit_expr(ctx, buf, expr)?;
+                buf.write("))");
+            }
+            Test::None => {
+                buf.write("askama::helpers::IsNone::askama_is_none(&(");
+                self.visit_expr(ctx, buf, expr)?;
+                buf.write("))");
+            }
+            Test::Empty => {
+                buf.write("(&(");
+                self.visit_expr(ctx, buf, expr)?;
+                buf.write(")).is_empty()");
+            }
+            Test::Even | Test::Odd | Test::DivisibleBy(_) => {
+                buf.write(
+                    "askama::helpers::IsDivisibleBy::askama_is_divisible_by(\
+                        askama::helpers::get_primitive_value(&(",
+                );
+                self.visit_expr(ctx, buf, expr)?;
+                buf.write(")),");
+                if let Test::DivisibleBy(divisor) = test {
+                    buf.write("askama::helpers::get_primitive_value(&(");
+                    self.visit_expr(ctx, buf, divisor)?;
+                    buf.write("))");
+                } else {
+                    buf.write('2');
+                }
+                buf.write(')');
+            }
+        }
+        Ok(DisplayWrap::Unwrapped)
+    }
+
     fn visit_as(
         &mut self,
         ctx: &Context<'_>,
@@ -259,27 +331,52 @@ impl<'a> Generator<'a, '_> {
         args: &[WithSpan<'a, Expr<'a>>],
         generics: &[WithSpan<'a, TyGenerics<'a>>],
         node: Span<'_>,
-        kind: &str,
+        getter: ValueGetter<'_>,
     ) -> Result<DisplayWrap, CompileError> {
-        let [key] = args else {
-            return Err(ctx.generate_error(
-                format_args!("{kind} only takes one argument, found {}", args.len()),
-                node,
-            ));
-        };
-        let [gen] = generics else {
-            return Err(ctx.generate_error(
-                format_args!("{kind} expects one generic, found {}", generics.len()),
-                node,
-            ));
+        let ValueGetter { function, kind } = getter;
+        let (key, default) = match (function, args) {
+            ("get_value_or", [key, default]) => (key, Some(default)),
+            ("get_value_or", _) => {
+                return Err(ctx.generate_error(
+                    format_args!("{kind} takes two arguments, found {}", args.len()),
+                    node,
+                ));
+            }
+            (_, [key]) => (key, None),
+            _ => {
+                return Err(ctx.generate_error(
+                    format_args!("{kind} only takes one argument, found {}", args.len()),
+                    node,
+                ));
+            }
         };
-        buf.write("askama::helpers::get_value");
-        buf.write("::<");
-        self.visit_ty_generic(buf, gen);
-        buf.write('>');
+        buf.write(format_args!("askama::helpers::{function}"));
+        match generics {
+            // Without a generic, the type is inferred from how the value is used.
+            [] => {}
+            [generic] => {
+                buf.write("::<");
+                self.visit_ty_generic(buf, generic);
+                buf.write('>');
+            }
+            _ => {
+                return Err(ctx.generate_error(
+                    format_args!(
+                        "{kind} expects at most one generic, found {}",
+                        generics.len()
+                    ),
+                    node,
+                ));
+            }
+        }
         buf.write("(&__askama_values, &(");
         self.visit_arg(ctx, buf, key)?;
-        buf.write("))");
+        buf.write(')');
+        if let Some(default) = default {
+            buf.write(',');
+            self.visit_arg(ctx, buf, default)?;
+        }
+        buf.write(')');
         Ok(DisplayWrap::Unwrapped)
     }
 
@@ -391,6 +488,17 @@ impl<'a> Generator<'a, '_> {
                 }
             }
         }
+        if self.input.is_async && attr.name == "await" && attr.generics.is_empty() {
+            if self.is_in_filter_block > 0 {
+                return Err(ctx.generate_error(
+                    "`.await` cannot be used inside of a filter block",
+                    obj.span(),
+                ));
+            }
+            self.visit_expr(ctx, buf, obj)?;
+            buf.write(".await");
+            return Ok(DisplayWrap::Unwrapped);
+        }
         self.visit_expr(ctx, buf, obj)?;
         buf.write(format_args!(".{}", normalize_identifier(attr.name)));
         self.visit_call_generics(buf, &attr.generics);
@@ -468,37 +576,46 @@ impl<'a> Generator<'a, '_> {
                             ));
                         }
                         match args {
-                            [arg] => {
-                                if matches!(**arg, Expr::Array(ref arr) if arr.is_empty()) {
-                                    return Err(ctx.generate_error(
-                                        "loop.cycle(…) cannot use an empty array",
-                                        arg.span(),
-                                    ));
-                                }
-                                buf.write(
-                                    "\
-                                ({\
-                                    let _cycle = &(",
-                                );
-                                self.visit_expr(ctx, buf, arg)?;
-                                buf.write(
-                                "\
-                                    );\
-                                    let _len = _cycle.len();\
-                                    if _len == 0 {\
-                                        return askama::helpers::core::result::Result::Err(askama::Error::Fmt);\
-                                    }\
-                                    _cycle[__askama_item.index % _len]\
-                                })",
-                            );
+                            [] => {
+                                return Err(ctx.generate_error(
+                                    "loop.cycle(…) expects an array or at least one value",
+                                    left.span(),
+                                ));
                             }
-                            _ => {
+                            [arg] if matches!(**arg, Expr::Array(ref arr) if arr.is_empty()) => {
                                 return Err(ctx.generate_error(
                                     "loop.cycle(…) cannot use an empty array",
-                                    left.span(),
+                                    arg.span(),
                                 ));
                             }
+                            _ => {}
+                        }
+                        buf.write(
+                            "\
+                            ({\
+                                let _cycle = &(",
+                        );
+                        if let [arg] = args {
+                            self.visit_expr(ctx, buf, arg)?;
+                        } else {
+                            // `loop.cycle(a, b)` is the same as `loop.cycle([a, b])`
+                            buf.write('[');
+                            for arg in args {
+                                self.visit_expr(ctx, buf, arg)?;
+                                buf.write(',');
+                            }
+                            buf.write(']');
                         }
+                        buf.write(
+                            "\
+                                );\
+                                let _len = _cycle.len();\
+                                if _len == 0 {\
+                                    return askama::helpers::core::result::Result::Err(askama::Error::Fmt);\
+                                }\
+                                _cycle[__askama_item.index % _len]\
+                            })",
+                        );
                     }
                     s => {
                         return Err(ctx.generate_error(
@@ -508,17 +625,46 @@ impl<'a> Generator<'a, '_> {
                     }
                 }
             }
-            // We special-case "askama::get_value".
-            Expr::Path(path) if path == &["askama", "get_value"] => {
+            // We special-case "askama::get_value" and its variants.
+            Expr::Path(path)
+                if matches!(
+                    path[..],
+                    ["askama", "get_value" | "get_value_or" | "get_value_opt"]
+                ) =>
+            {
+                let function = path[1];
                 self.visit_value(
                     ctx,
                     buf,
                     args,
                     generics,
                     left.span(),
-                    "`get_value` function",
+                    ValueGetter {
+                        function,
+                        kind: &format!("`{function}` function"),
+                    },
                 )?;
             }
+            // "askama::csp_nonce" reads the nonce from the runtime values, too.
+            Expr::Path(path) if matches!(path[..], ["askama", "csp_nonce"]) => {
+                if !args.is_empty() || !generics.is_empty() {
+                    return Err(ctx.generate_error(
+                        "`csp_nonce` function takes no arguments and no generics",
+                        left.span(),
+                    ));
+                }
+                buf.write("askama::helpers::csp_nonce(&__askama_values)?");
+            }
+            // "askama::template_name" is known at compile time.
+            Expr::Path(path) if matches!(path[..], ["askama", "template_name"]) => {
+                if !args.is_empty() || !generics.is_empty() {
+                    return Err(ctx.generate_error(
+                        "`template_name` function takes no arguments and no generics",
+                        left.span(),
+                    ));
+                }
+                buf.write(format_args!("{:?}", self.template_name(ctx.path)));
+            }
             sub_left => {
                 match sub_left {
                     Expr::Var(name) => match self.locals.resolve(name) {
@@ -582,6 +728,37 @@ impl<'a> Generator<'a, '_> {
         Ok(DisplayWrap::Unwrapped)
     }
 
+    /// `a < b <= c` becomes `a < b && b <= c`, but every operand is evaluated at most once, so
+    /// they are bound to variables: `(match &(a) { __askama_cmp0 => match &(b) { __askama_cmp1 =>
+    /// __askama_cmp0 < __askama_cmp1 && match &(c) { __askama_cmp2 => ... } } })`.
+    fn visit_compare(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        first: &WithSpan<'a, Expr<'a>>,
+        chain: &[(&'a str, WithSpan<'a, Expr<'a>>)],
+    ) -> Result<DisplayWrap, CompileError> {
+        buf.write("(match &(");
+        self.visit_expr(ctx, buf, first)?;
+        buf.write(") { __askama_cmp0 => ");
+        for (idx, (op, expr)) in chain.iter().enumerate() {
+            if idx > 0 {
+                buf.write(" && ");
+            }
+            buf.write("match &(");
+            self.visit_expr(ctx, buf, expr)?;
+            buf.write(format_args!(
+                ") {{ __askama_cmp{next} => __askama_cmp{idx} {op} __askama_cmp{next}",
+                next = idx + 1,
+            ));
+        }
+        for _ in 0..=chain.len() {
+            buf.write(" }");
+        }
+        buf.write(')');
+        Ok(DisplayWrap::Unwrapped)
+    }
+
     fn visit_group(
         &mut self,
         ctx: &Context<'_>,
@@ -639,6 +816,31 @@ impl<'a> Generator<'a, '_> {
         Ok(DisplayWrap::Unwrapped)
     }
 
+    fn visit_map(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        entries: &[(WithSpan<'a, Expr<'a>>, WithSpan<'a, Expr<'a>>)],
+        span: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        if !cfg!(feature = "alloc") {
+            return Err(ctx.generate_error(
+                "map literals require the `alloc` feature to be enabled",
+                span,
+            ));
+        }
+        buf.write("askama::helpers::alloc::collections::BTreeMap::from([");
+        for (key, value) in entries {
+            buf.write('(');
+            self.visit_expr(ctx, buf, key)?;
+            buf.write(',');
+            self.visit_expr(ctx, buf, value)?;
+            buf.write("),");
+        }
+        buf.write("])");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
     pub(super) fn visit_path(&mut self, buf: &mut Buffer, path: &[&str]) -> DisplayWrap {
         for (i, part) in path.iter().copied().enumerate() {
             if i > 0 {
@@ -657,14 +859,72 @@ impl<'a> Generator<'a, '_> {
         DisplayWrap::Unwrapped
     }
 
-    fn visit_var(&mut self, buf: &mut Buffer, s: &str) -> DisplayWrap {
+    fn visit_var(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        s: &str,
+        span: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
         if s == "self" {
             buf.write(s);
-            return DisplayWrap::Unwrapped;
+            return Ok(DisplayWrap::Unwrapped);
+        }
+        if let Some(context) = &self.input.context {
+            if s == "ctx" && self.locals.get(s).is_none() {
+                // The typed global context is passed with the runtime values.
+                buf.write(format_args!(
+                    "askama::get_value::<{context}>(\
+                        __askama_values,\
+                        askama::helpers::CONTEXT_KEY,\
+                    )?"
+                ));
+                return Ok(DisplayWrap::Unwrapped);
+            }
+        }
+        if self.is_value_lookup(s) {
+            // With `values_only = true`, unknown identifiers are looked up in the runtime values.
+            buf.write(format_args!(
+                "askama::helpers::DynValue::new(__askama_values, {s:?})"
+            ));
+            return Ok(DisplayWrap::Unwrapped);
+        }
+        if self.locals.isolated && !self.is_var_defined(s) {
+            return Err(ctx.generate_error(
+                format_args!(
+                    "variable `{s}` is not defined; templates included with `only` can only \
+                     access the variables passed with `with`"
+                ),
+                span,
+            ));
+        }
+        if self.input.strict && !self.is_var_defined(s) {
+            let locals = self.locals.scopes.iter().flat_map(|scope| scope.keys());
+            let fields = self.input.fields.iter();
+            let candidates = locals.map(|name| &**name).chain(fields.map(String::as_str));
+            let suggestion = closest_name(s, candidates)
+                .map(|name| format!(", did you mean `{name}`?"))
+                .unwrap_or_default();
+            let name = match self.input.enum_ast {
+                Some(enum_ast) => {
+                    let prefix = format!("__Askama__{}__", enum_ast.ident);
+                    let ident = self.input.ast.ident.to_string();
+                    let variant = ident.strip_prefix(&prefix).unwrap_or(&ident);
+                    format!("{}::{variant}", enum_ast.ident)
+                }
+                None => self.input.ast.ident.to_string(),
+            };
+            return Err(ctx.generate_error(
+                format_args!(
+                    "`{s}` is neither a variable of the template nor a field of `{name}`\
+                     {suggestion}",
+                ),
+                span,
+            ));
         }
 
         buf.write(normalize_identifier(&self.locals.resolve_or_self(s)));
-        DisplayWrap::Unwrapped
+        Ok(DisplayWrap::Unwrapped)
     }
 
     fn visit_filter_source(&mut self, buf: &mut Buffer) -> DisplayWrap {
@@ -806,4 +1066,26 @@ impl<'a> Generator<'a, '_> {
             }
         }
     }
+
+    /// The path of a template file relative to the crate root, or `"<inline>"` for a template
+    /// that is not read from a file, see [`relative_template_name()`].
+    fn template_name(&self, path: Option<&Path>) -> String {
+        let path = match (path, &self.input.source) {
+            // Skip the fake path of templates defined in rust source.
+            (Some(path), Source::Source(_)) if path == &*self.input.path => None,
+            (path, _) => path,
+        };
+        let Some(path) = path else {
+            return "<inline>".to_owned();
+        };
+        relative_template_name(path, &manifest_root(), &self.input.config.dirs)
+    }
+}
+
+/// A function of `askama::helpers` that reads a runtime value, see [`Generator::visit_value()`].
+pub(super) struct ValueGetter<'g> {
+    /// E.g. `get_value` or `get_value_or`.
+    pub(super) function: &'g str,
+    /// How the getter is called in error messages, e.g. "`value` filter".
+    pub(super) kind: &'g str,
 }
@@ -1,11 +1,14 @@
-use std::borrow::Cow;
 use std::fmt::{self, Write};
 use std::mem::replace;
 
-use parser::{Expr, IntKind, Num, PathOrIdentifier, Span, StrLit, StrPrefix, TyGenerics, WithSpan};
+use parser::{
+    CharLit, Expr, IntKind, Num, PathOrIdentifier, Span, StrLit, StrPrefix, TyGenerics, WithSpan,
+};
 
-use super::{DisplayWrap, Generator, TargetIsize, TargetUsize};
+use super::expr::ValueGetter;
+use super::{DisplayWrap, Generator, TargetIsize, TargetUsize, normalize_identifier};
 use crate::heritage::Context;
+use crate::input::LintLevel;
 use crate::integration::Buffer;
 use crate::{CompileError, MsgValidEscapers, fmt_left, fmt_right};
 
@@ -26,26 +29,85 @@ impl<'a> Generator<'a, '_> {
             PathOrIdentifier::Identifier(name) => *name,
         };
         let filter = match name {
+            "avg" => Self::visit_avg_filter,
+            "box" => Self::visit_box_filter,
             "center" => Self::visit_center_filter,
+            "chunks" => Self::visit_chunks_filter,
+            "clamp" => Self::visit_clamp_filter,
+            "coalesce" => Self::visit_coalesce_filter,
+            "columns" => Self::visit_columns_filter,
+            "contains" => Self::visit_contains_filter,
+            "count_matches" => Self::visit_count_matches_filter,
+            "date" | "datetime" if cfg!(not(feature = "chrono")) => {
+                // Without `chrono`, a custom filter with this name can be used.
+                return self.visit_custom_filter(ctx, buf, name, args, generics, node);
+            }
+            "date" => Self::visit_date_filter,
+            "datetime" => Self::visit_datetime_filter,
+            "default_if_none" => Self::visit_default_if_none_filter,
             "deref" => Self::visit_deref_filter,
+            "ellipsize_middle" => Self::visit_ellipsize_middle_filter,
             "escape" | "e" => Self::visit_escape_filter,
+            "escape_once" => Self::visit_escape_once_filter,
+            "excerpt" => Self::visit_excerpt_filter,
             "filesizeformat" => Self::visit_humansize,
+            "flatten" => Self::visit_flatten_filter,
             "fmt" => Self::visit_fmt_filter,
             "format" => Self::visit_format_filter,
+            "format_each" => Self::visit_format_each_filter,
+            "group_consecutive" => Self::visit_group_consecutive_filter,
+            "highlight" => Self::visit_highlight_filter,
             "indent" => Self::visit_indent_filter,
             "join" => Self::visit_join_filter,
+            "join_human" => Self::visit_join_human_filter,
             "json" | "tojson" => Self::visit_json_filter,
+            "json_attr" => Self::visit_json_attr_filter,
+            "length_graphemes" => Self::visit_length_graphemes_filter,
             "linebreaks" => Self::visit_linebreaks_filter,
             "linebreaksbr" => Self::visit_linebreaksbr_filter,
+            "ljust" => Self::visit_ljust_filter,
+            "markdown" => Self::visit_markdown_filter,
+            "nfc" => Self::visit_nfc_filter,
+            "nfd" => Self::visit_nfd_filter,
+            "nfkc" => Self::visit_nfkc_filter,
+            "nfkd" => Self::visit_nfkd_filter,
+            "ordinal" => Self::visit_ordinal_filter,
+            "page_count" => Self::visit_page_count_filter,
+            "paginate" => Self::visit_paginate_filter,
             "paragraphbreaks" => Self::visit_paragraphbreaks_filter,
             "pluralize" => Self::visit_pluralize_filter,
+            "redact" => Self::visit_redact_filter,
             "ref" => Self::visit_ref_filter,
+            "repeat" => Self::visit_repeat_filter,
+            "replace_regex" => Self::visit_replace_regex_filter,
+            "reverse_graphemes" => Self::visit_reverse_graphemes_filter,
+            "rjust" => Self::visit_rjust_filter,
             "safe" => Self::visit_safe_filter,
+            "safe_if" => Self::visit_safe_if_filter,
+            "sum" => Self::visit_sum_filter,
+            "table" => Self::visit_table_filter,
+            "trim_lines" => Self::visit_trim_lines_filter,
             "truncate" => Self::visit_truncate_filter,
+            "truncate_graphemes" => Self::visit_truncate_graphemes_filter,
+            "truncatewords" => Self::visit_truncatewords_filter,
             "urlencode" => Self::visit_urlencode_filter,
             "urlencode_strict" => Self::visit_urlencode_strict_filter,
-            "value" => return self.visit_value(ctx, buf, args, generics, node, "`value` filter"),
+            "value" => {
+                return self.visit_value(
+                    ctx,
+                    buf,
+                    args,
+                    generics,
+                    node,
+                    ValueGetter {
+                        function: "get_value",
+                        kind: "`value` filter",
+                    },
+                );
+            }
+            "visible_len" => Self::visit_visible_len_filter,
             "wordcount" => Self::visit_wordcount_filter,
+            "zip" => Self::visit_zip_filter,
             name => {
                 let filter = match () {
                     _ if BUILTIN_FILTERS.contains(&name) => Self::visit_builtin_filter,
@@ -94,8 +156,22 @@ impl<'a> Generator<'a, '_> {
         node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
         ensure_no_named_arguments(ctx, name, args, node)?;
-        buf.write(format_args!("filters::{name}"));
-        self.visit_call_generics(buf, generics);
+        let unresolved_message = match name {
+            "date" | "datetime" => Some(format!(
+                "the `{name}` filter requires the `chrono` feature to be enabled"
+            )),
+            _ => closest_builtin_filter(name)
+                .map(|suggestion| format!("unknown filter `{name}`, did you mean `{suggestion}`?")),
+        };
+        match unresolved_message {
+            Some(message) if generics.is_empty() => {
+                write_custom_filter_with_fallback(buf, name, &message, args.len());
+            }
+            _ => {
+                buf.write(format_args!("filters::{name}"));
+                self.visit_call_generics(buf, generics);
+            }
+        }
         buf.write('(');
         self.visit_arg(ctx, buf, &args[0])?;
         buf.write(",__askama_values");
@@ -223,6 +299,140 @@ impl<'a> Generator<'a, '_> {
         Ok(DisplayWrap::Unwrapped)
     }
 
+    fn visit_visible_len_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        _node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let arg = no_arguments(ctx, "visible_len", args)?;
+        buf.write("match askama::filters::visible_len(&(");
+        self.visit_arg(ctx, buf, arg)?;
+        buf.write(
+            ")) {\
+                expr0 => {\
+                    (&&&askama::filters::Writable(&expr0)).\
+                        askama_write(&mut askama::helpers::Empty, __askama_values)?;\
+                    expr0.into_count()\
+                }\
+            }\
+        ",
+        );
+
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_length_graphemes_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        ensure_filter_has_feature_grapheme(ctx, "length_graphemes", node)?;
+
+        let arg = no_arguments(ctx, "length_graphemes", args)?;
+        buf.write("match askama::filters::length_graphemes(&(");
+        self.visit_arg(ctx, buf, arg)?;
+        buf.write(
+            ")) {\
+                expr0 => {\
+                    (&&&askama::filters::Writable(&expr0)).\
+                        askama_write(&mut askama::helpers::Empty, __askama_values)?;\
+                    expr0.into_count()\
+                }\
+            }\
+        ",
+        );
+
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_reverse_graphemes_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        ensure_filter_has_feature_grapheme(ctx, "reverse_graphemes", node)?;
+
+        let arg = no_arguments(ctx, "reverse_graphemes", args)?;
+        buf.write("askama::filters::reverse_graphemes(");
+        self.visit_arg(ctx, buf, arg)?;
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_truncate_graphemes_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        ensure_filter_has_feature_grapheme(ctx, "truncate_graphemes", node)?;
+        self.visit_truncate_filters(ctx, buf, args, node, "truncate_graphemes")
+    }
+
+    fn visit_nfc_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_normalize_filter(ctx, buf, args, node, "nfc")
+    }
+
+    fn visit_nfd_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_normalize_filter(ctx, buf, args, node, "nfd")
+    }
+
+    fn visit_nfkc_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_normalize_filter(ctx, buf, args, node, "nfkc")
+    }
+
+    fn visit_nfkd_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_normalize_filter(ctx, buf, args, node, "nfkd")
+    }
+
+    fn visit_normalize_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+        name: &str,
+    ) -> Result<DisplayWrap, CompileError> {
+        ensure_filter_has_feature_unicode_normalization(ctx, name, node)?;
+
+        let arg = no_arguments(ctx, name, args)?;
+        buf.write(format_args!("askama::filters::{name}("));
+        self.visit_arg(ctx, buf, arg)?;
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
     fn visit_humansize(
         &mut self,
         ctx: &Context<'_>,
@@ -400,7 +610,7 @@ impl<'a> Generator<'a, '_> {
         Ok(DisplayWrap::Unwrapped)
     }
 
-    fn visit_indent_filter(
+    fn visit_table_filter(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
@@ -409,146 +619,96 @@ impl<'a> Generator<'a, '_> {
     ) -> Result<DisplayWrap, CompileError> {
         const FALSE: &WithSpan<'static, Expr<'static>> =
             &WithSpan::new_without_span(Expr::BoolLit(false));
-        const ARGUMENTS: &[&FilterArgument; 4] = &[
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
             FILTER_SOURCE,
             &FilterArgument {
-                name: "width",
-                default_value: None,
-            },
-            &FilterArgument {
-                name: "first",
-                default_value: Some(FALSE),
-            },
-            &FilterArgument {
-                name: "blank",
+                name: "header",
                 default_value: Some(FALSE),
             },
         ];
 
-        ensure_filter_has_feature_alloc(ctx, "indent", node)?;
-        let [source, indent, first, blank] =
-            collect_filter_args(ctx, "indent", node, args, ARGUMENTS)?;
-        buf.write("askama::filters::indent(");
-        self.visit_arg(ctx, buf, source)?;
-        buf.write(",");
-        self.visit_arg(ctx, buf, indent)?;
-        buf.write(", askama::helpers::as_bool(&(");
-        self.visit_arg(ctx, buf, first)?;
-        buf.write(")), askama::helpers::as_bool(&(");
-        self.visit_arg(ctx, buf, blank)?;
+        ensure_filter_has_feature_alloc(ctx, "table", node)?;
+        let [rows, header] = collect_filter_args(ctx, "table", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::table((&(");
+        self.visit_arg(ctx, buf, rows)?;
+        buf.write(")).into_iter(), askama::helpers::as_bool(&(");
+        self.visit_arg(ctx, buf, header)?;
         buf.write(")))?");
         Ok(DisplayWrap::Unwrapped)
     }
 
-    fn visit_safe_filter(
-        &mut self,
-        ctx: &Context<'_>,
-        buf: &mut Buffer,
-        args: &[WithSpan<'a, Expr<'a>>],
-        _node: Span<'_>,
-    ) -> Result<DisplayWrap, CompileError> {
-        let arg = no_arguments(ctx, "safe", args)?;
-        buf.write("askama::filters::safe(");
-        self.visit_arg(ctx, buf, arg)?;
-        buf.write(format_args!(", {})?", self.input.escaper));
-        Ok(DisplayWrap::Wrapped)
-    }
-
-    fn visit_escape_filter(
+    fn visit_box_filter(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
         args: &[WithSpan<'a, Expr<'a>>],
         node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
-        const ARGUMENTS: &[&FilterArgument; 2] = &[
+        const UNICODE: &WithSpan<'static, Expr<'static>> =
+            &WithSpan::new_without_span(Expr::StrLit(StrLit {
+                prefix: None,
+                content: "unicode",
+            }));
+        const EMPTY: &WithSpan<'static, Expr<'static>> =
+            &WithSpan::new_without_span(Expr::StrLit(StrLit {
+                prefix: None,
+                content: "",
+            }));
+        const ARGUMENTS: &[&FilterArgument; 3] = &[
             FILTER_SOURCE,
             &FilterArgument {
-                name: "escaper",
-                default_value: Some(ARGUMENT_PLACEHOLDER),
+                name: "style",
+                default_value: Some(UNICODE),
+            },
+            &FilterArgument {
+                name: "title",
+                default_value: Some(EMPTY),
             },
         ];
 
-        let [source, opt_escaper] = collect_filter_args(ctx, "escape", node, args, ARGUMENTS)?;
-        let opt_escaper = if !is_argument_placeholder(opt_escaper) {
-            let Expr::StrLit(StrLit { prefix, content }) = **opt_escaper else {
-                return Err(ctx.generate_error("invalid escaper type for escape filter", node));
-            };
-            if let Some(prefix) = prefix {
-                let kind = if prefix == StrPrefix::Binary {
-                    "slice"
-                } else {
-                    "CStr"
-                };
+        ensure_filter_has_feature_alloc(ctx, "box", node)?;
+        let [source, style, title] = collect_filter_args(ctx, "box", node, args, ARGUMENTS)?;
+        let style = match **style {
+            Expr::StrLit(StrLit {
+                prefix: None,
+                content: "unicode",
+            }) => "Unicode",
+            Expr::StrLit(StrLit {
+                prefix: None,
+                content: "ascii",
+            }) => "Ascii",
+            _ => {
                 return Err(ctx.generate_error(
-                    format_args!(
-                        "invalid escaper `b{content:?}`. Expected a string, found a {kind}"
-                    ),
-                    opt_escaper.span(),
+                    "the `style` argument of the `box` filter must be \"unicode\" or \"ascii\"",
+                    style.span(),
                 ));
             }
-            Some(content)
-        } else {
-            None
-        };
-
-        let escaper = match opt_escaper {
-            Some(name) => self
-                .input
-                .config
-                .escapers
-                .iter()
-                .find_map(|(extensions, path)| {
-                    extensions
-                        .contains(&Cow::Borrowed(name))
-                        .then_some(path.as_ref())
-                })
-                .ok_or_else(|| {
-                    ctx.generate_error(
-                        format_args!(
-                            "invalid escaper `{}` for `escape` filter. {}",
-                            name.escape_debug(),
-                            MsgValidEscapers(&self.input.config.escapers),
-                        ),
-                        node,
-                    )
-                })?,
-            None => self.input.escaper,
         };
-        buf.write("askama::filters::escape(");
+        buf.write("askama::filters::text_box(&(");
         self.visit_arg(ctx, buf, source)?;
-        buf.write(format_args!(", {escaper})?"));
-        Ok(DisplayWrap::Wrapped)
+        buf.write(format_args!("), askama::filters::BoxStyle::{style}, &("));
+        self.visit_arg(ctx, buf, title)?;
+        buf.write("))?");
+        Ok(DisplayWrap::Unwrapped)
     }
 
-    fn visit_format_filter(
+    fn visit_escape_once_filter(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
         args: &[WithSpan<'a, Expr<'a>>],
         node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
-        ensure_filter_has_feature_alloc(ctx, "format", node)?;
-        ensure_no_named_arguments(ctx, "format", args, node)?;
-        if !args.is_empty() {
-            if let Expr::StrLit(ref fmt) = *args[0] {
-                buf.write("askama::helpers::alloc::format!(");
-                self.visit_str_lit(buf, fmt);
-                if args.len() > 1 {
-                    buf.write(',');
-                    self.visit_args(ctx, buf, &args[1..])?;
-                }
-                buf.write(')');
-                return Ok(DisplayWrap::Unwrapped);
-            }
-        }
-        Err(ctx.generate_error(
-            r#"use `format` filter like `"a={} b={}"|format(a, b)`"#,
-            node,
-        ))
+        ensure_filter_has_feature_alloc(ctx, "escape_once", node)?;
+        let arg = no_arguments(ctx, "escape_once", args)?;
+        // The input must not be escaped before, otherwise existing entities would be escaped:
+        buf.write("askama::filters::escape_once(&(");
+        self.visit_arg(ctx, buf, arg)?;
+        buf.write("))?");
+        Ok(DisplayWrap::Unwrapped)
     }
 
-    fn visit_fmt_filter(
+    fn visit_highlight_filter(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
@@ -558,70 +718,1375 @@ impl<'a> Generator<'a, '_> {
         const ARGUMENTS: &[&FilterArgument; 2] = &[
             FILTER_SOURCE,
             &FilterArgument {
-                name: "format",
+                name: "query",
                 default_value: None,
             },
         ];
 
-        ensure_filter_has_feature_alloc(ctx, "fmt", node)?;
-        let [source, fmt] = collect_filter_args(ctx, "fmt", node, args, ARGUMENTS)?;
-        let Expr::StrLit(ref fmt) = **fmt else {
-            return Err(ctx.generate_error(r#"use `fmt` filter like `value|fmt("{:?}")`"#, node));
-        };
-        buf.write("askama::helpers::alloc::format!(");
-        self.visit_str_lit(buf, fmt);
-        buf.write(',');
+        ensure_filter_has_feature_alloc(ctx, "highlight", node)?;
+        let [source, query] = collect_filter_args(ctx, "highlight", node, args, ARGUMENTS)?;
+        // Neither the input nor the query must be escaped before, they are escaped by the filter:
+        buf.write("askama::filters::highlight(&(");
         self.visit_arg(ctx, buf, source)?;
-        buf.write(')');
+        buf.write("), &(");
+        self.visit_arg(ctx, buf, query)?;
+        buf.write("))?");
         Ok(DisplayWrap::Unwrapped)
     }
 
-    // Force type coercion on first argument to `join` filter (see #39).
-    fn visit_join_filter(
+    fn visit_json_attr_filter(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
         args: &[WithSpan<'a, Expr<'a>>],
         node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
-        const ARGUMENTS: &[&FilterArgument; 2] = &[
-            FILTER_SOURCE,
-            &FilterArgument {
-                name: "separator",
-                default_value: None,
-            },
-        ];
+        if cfg!(not(feature = "serde_json")) {
+            return Err(ctx.generate_error(
+                "the `json_attr` filter requires the `serde_json` feature to be enabled",
+                node,
+            ));
+        }
 
-        let [iterable, separator] = collect_filter_args(ctx, "join", node, args, ARGUMENTS)?;
-        buf.write("askama::filters::join((&(");
-        self.visit_arg(ctx, buf, iterable)?;
-        buf.write(")).into_iter(),");
-        self.visit_arg(ctx, buf, separator)?;
+        let [value] = collect_filter_args(ctx, "json_attr", node, args, &[FILTER_SOURCE])?;
+        buf.write("askama::filters::json_attr(");
+        self.visit_arg(ctx, buf, value)?;
         buf.write(")?");
         Ok(DisplayWrap::Unwrapped)
     }
 
-    fn visit_center_filter(
+    fn visit_markdown_filter(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
         args: &[WithSpan<'a, Expr<'a>>],
         node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
-        self.visit_center_truncate_filter(ctx, buf, args, node, "center")
-    }
-
-    fn visit_truncate_filter(
-        &mut self,
+        const TRUE: &WithSpan<'static, Expr<'static>> =
+            &WithSpan::new_without_span(Expr::BoolLit(true));
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "sanitize",
+                default_value: Some(TRUE),
+            },
+        ];
+
+        if cfg!(not(feature = "markdown")) {
+            return Err(ctx.generate_error(
+                "the `markdown` filter requires the `markdown` feature to be enabled",
+                node,
+            ));
+        }
+
+        let [source, sanitize] = collect_filter_args(ctx, "markdown", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::markdown(");
+        self.visit_arg(ctx, buf, source)?;
+        buf.write(", askama::helpers::as_bool(&(");
+        self.visit_arg(ctx, buf, sanitize)?;
+        buf.write(")))?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_replace_regex_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 3] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "pattern",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "replacement",
+                default_value: None,
+            },
+        ];
+
+        if cfg!(not(feature = "regex")) {
+            return Err(ctx.generate_error(
+                "the `replace_regex` filter requires the `regex` feature to be enabled",
+                node,
+            ));
+        }
+
+        let [source, pattern, replacement] =
+            collect_filter_args(ctx, "replace_regex", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::replace_regex(");
+        self.visit_arg(ctx, buf, source)?;
+        buf.write(',');
+        if let Expr::StrLit(lit @ StrLit { prefix: None, .. }) = &**pattern {
+            // A literal pattern is validated now, and compiled only once at runtime.
+            #[cfg(feature = "regex")]
+            validate_regex(ctx, lit, pattern.span())?;
+            buf.write(
+                "{\
+                    static __ASKAMA_REGEX: askama::helpers::LazyRegex = \
+                        askama::helpers::LazyRegex::new(",
+            );
+            self.visit_str_lit(buf, lit);
+            buf.write("); &__ASKAMA_REGEX }");
+        } else {
+            self.visit_arg(ctx, buf, pattern)?;
+        }
+        buf.write(',');
+        self.visit_arg(ctx, buf, replacement)?;
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_date_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const FORMAT: &WithSpan<'static, Expr<'static>> =
+            &WithSpan::new_without_span(Expr::StrLit(StrLit {
+                prefix: None,
+                content: "%Y-%m-%d",
+            }));
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "format",
+                default_value: Some(FORMAT),
+            },
+        ];
+        self.visit_date_datetime_filter(ctx, buf, args, node, "date", ARGUMENTS)
+    }
+
+    fn visit_datetime_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const FORMAT: &WithSpan<'static, Expr<'static>> =
+            &WithSpan::new_without_span(Expr::StrLit(StrLit {
+                prefix: None,
+                content: "%Y-%m-%d %H:%M:%S",
+            }));
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "format",
+                default_value: Some(FORMAT),
+            },
+        ];
+        self.visit_date_datetime_filter(ctx, buf, args, node, "datetime", ARGUMENTS)
+    }
+
+    fn visit_date_datetime_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+        name: &str,
+        arguments: &'static [&'static FilterArgument; 2],
+    ) -> Result<DisplayWrap, CompileError> {
+        let [source, format] = collect_filter_args(ctx, name, node, args, arguments)?;
+        buf.write(format_args!("askama::filters::{name}("));
+        self.visit_arg(ctx, buf, source)?;
+        buf.write(',');
+        self.visit_arg(ctx, buf, format)?;
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_indent_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const FALSE: &WithSpan<'static, Expr<'static>> =
+            &WithSpan::new_without_span(Expr::BoolLit(false));
+        const ARGUMENTS: &[&FilterArgument; 4] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "width",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "first",
+                default_value: Some(FALSE),
+            },
+            &FilterArgument {
+                name: "blank",
+                default_value: Some(FALSE),
+            },
+        ];
+
+        ensure_filter_has_feature_alloc(ctx, "indent", node)?;
+        let [source, indent, first, blank] =
+            collect_filter_args(ctx, "indent", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::indent(");
+        self.visit_arg(ctx, buf, source)?;
+        buf.write(",");
+        self.visit_arg(ctx, buf, indent)?;
+        buf.write(", askama::helpers::as_bool(&(");
+        self.visit_arg(ctx, buf, first)?;
+        buf.write(")), askama::helpers::as_bool(&(");
+        self.visit_arg(ctx, buf, blank)?;
+        buf.write(")))?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_trim_lines_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const FALSE: &WithSpan<'static, Expr<'static>> =
+            &WithSpan::new_without_span(Expr::BoolLit(false));
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "trailing_only",
+                default_value: Some(FALSE),
+            },
+        ];
+
+        ensure_filter_has_feature_alloc(ctx, "trim_lines", node)?;
+        let [source, trailing_only] =
+            collect_filter_args(ctx, "trim_lines", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::trim_lines(");
+        self.visit_arg(ctx, buf, source)?;
+        buf.write(", askama::helpers::as_bool(&(");
+        self.visit_arg(ctx, buf, trailing_only)?;
+        buf.write(")))?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_safe_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let arg = no_arguments(ctx, "safe", args)?;
+        let lint = match reads_runtime_value(arg) {
+            true => self.input.lint_safe_values,
+            false => None,
+        };
+        match lint {
+            Some(LintLevel::Deny) => return Err(ctx.generate_error(SAFE_RUNTIME_VALUE, node)),
+            Some(LintLevel::Warn) => {
+                let warning = ctx.generate_error(SAFE_RUNTIME_VALUE, node).to_string();
+                let mut warnings = self.input.warnings.borrow_mut();
+                if !warnings.contains(&warning) {
+                    warnings.push(warning);
+                }
+            }
+            None => {}
+        }
+        buf.write("askama::filters::safe(");
+        self.visit_arg(ctx, buf, arg)?;
+        buf.write(format_args!(", {})?", self.input.escaper));
+        Ok(DisplayWrap::Wrapped)
+    }
+
+    fn visit_safe_if_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "cond",
+                default_value: None,
+            },
+        ];
+
+        let [source, cond] = collect_filter_args(ctx, "safe_if", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::safe_if(");
+        self.visit_arg(ctx, buf, source)?;
+        buf.write(format_args!(
+            ", {}, askama::helpers::as_bool(&(",
+            self.input.escaper
+        ));
+        self.visit_arg(ctx, buf, cond)?;
+        buf.write(")))?");
+        // The output is wrapped in `MaybeSafe`, so the escaping is decided at runtime.
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_default_if_none_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "default",
+                default_value: None,
+            },
+        ];
+
+        let [source, default] = collect_filter_args(ctx, "default_if_none", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::default_if_none(");
+        self.visit_arg(ctx, buf, source)?;
+        buf.write(',');
+        self.visit_arg(ctx, buf, default)?;
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_coalesce_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        ensure_no_named_arguments(ctx, "coalesce", args, node)?;
+        let Some((last, values)) = args.split_last().filter(|(_, values)| !values.is_empty())
+        else {
+            return Err(ctx.generate_error(
+                r#"use `coalesce` filter like `value|coalesce(fallback, "default")`"#,
+                node,
+            ));
+        };
+        // `a|coalesce(b, c)` becomes `coalesce(a, coalesce(b, c)?)?`
+        for value in values {
+            buf.write("askama::filters::coalesce(");
+            self.visit_arg(ctx, buf, value)?;
+            buf.write(',');
+        }
+        self.visit_arg(ctx, buf, last)?;
+        for _ in values {
+            buf.write(")?");
+        }
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_escape_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "escaper",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+        ];
+
+        let [source, opt_escaper] = collect_filter_args(ctx, "escape", node, args, ARGUMENTS)?;
+        let opt_escaper = if !is_argument_placeholder(opt_escaper) {
+            let Expr::StrLit(StrLit { prefix, content }) = **opt_escaper else {
+                return Err(ctx.generate_error("invalid escaper type for escape filter", node));
+            };
+            if let Some(prefix) = prefix {
+                let kind = if prefix == StrPrefix::Binary {
+                    "slice"
+                } else {
+                    "CStr"
+                };
+                return Err(ctx.generate_error(
+                    format_args!(
+                        "invalid escaper `b{content:?}`. Expected a string, found a {kind}"
+                    ),
+                    opt_escaper.span(),
+                ));
+            }
+            Some(content)
+        } else {
+            None
+        };
+
+        let escaper = match opt_escaper {
+            Some(name) => self.input.config.find_escaper(name).ok_or_else(|| {
+                ctx.generate_error(
+                    format_args!(
+                        "invalid escaper `{}` for `escape` filter. {}",
+                        name.escape_debug(),
+                        MsgValidEscapers(&self.input.config.escapers),
+                    ),
+                    node,
+                )
+            })?,
+            None => self.input.escaper,
+        };
+        buf.write("askama::filters::escape(");
+        self.visit_arg(ctx, buf, source)?;
+        buf.write(format_args!(", {escaper})?"));
+        Ok(DisplayWrap::Wrapped)
+    }
+
+    fn visit_format_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        ensure_filter_has_feature_alloc(ctx, "format", node)?;
+        ensure_no_named_arguments(ctx, "format", args, node)?;
+        if !args.is_empty() {
+            if let Expr::StrLit(ref fmt) = *args[0] {
+                buf.write("askama::helpers::alloc::format!(");
+                self.visit_str_lit(buf, fmt);
+                if args.len() > 1 {
+                    buf.write(',');
+                    self.visit_args(ctx, buf, &args[1..])?;
+                }
+                buf.write(')');
+                return Ok(DisplayWrap::Unwrapped);
+            }
+        }
+        Err(ctx.generate_error(
+            r#"use `format` filter like `"a={} b={}"|format(a, b)`"#,
+            node,
+        ))
+    }
+
+    fn visit_format_each_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "format",
+                default_value: None,
+            },
+        ];
+
+        let [iterable, format] = collect_filter_args(ctx, "format_each", node, args, ARGUMENTS)?;
+        let Expr::StrLit(StrLit {
+            prefix: None,
+            content,
+        }) = **format
+        else {
+            return Err(ctx.generate_error(
+                "the `format` argument of the `format_each` filter must be a string literal",
+                format.span(),
+            ));
+        };
+        let (prefix, suffix) =
+            split_format_each(content).map_err(|msg| ctx.generate_error(msg, format.span()))?;
+        buf.write("askama::filters::format_each((&(");
+        self.visit_arg(ctx, buf, iterable)?;
+        buf.write(format_args!(
+            ")).into_iter(), {prefix:?}, {suffix:?}, {})?",
+            self.input.escaper,
+        ));
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_fmt_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "format",
+                default_value: None,
+            },
+        ];
+
+        ensure_filter_has_feature_alloc(ctx, "fmt", node)?;
+        let [source, fmt] = collect_filter_args(ctx, "fmt", node, args, ARGUMENTS)?;
+        let Expr::StrLit(ref fmt) = **fmt else {
+            return Err(ctx.generate_error(r#"use `fmt` filter like `value|fmt("{:?}")`"#, node));
+        };
+        buf.write("askama::helpers::alloc::format!(");
+        self.visit_str_lit(buf, fmt);
+        buf.write(',');
+        self.visit_arg(ctx, buf, source)?;
+        buf.write(')');
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    // Force type coercion on first argument to `join` filter (see #39).
+    fn visit_join_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "separator",
+                default_value: None,
+            },
+        ];
+
+        let [iterable, separator] = collect_filter_args(ctx, "join", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::join((&(");
+        self.visit_arg(ctx, buf, iterable)?;
+        buf.write(")).into_iter(),");
+        self.visit_arg(ctx, buf, separator)?;
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_join_human_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const FALSE: &WithSpan<'static, Expr<'static>> =
+            &WithSpan::new_without_span(Expr::BoolLit(false));
+        const ARGUMENTS: &[&FilterArgument; 4] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "separator",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "conjunction",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "oxford",
+                default_value: Some(FALSE),
+            },
+        ];
+
+        let [iterable, separator, conjunction, oxford] =
+            collect_filter_args(ctx, "join_human", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::join_human((&(");
+        self.visit_arg(ctx, buf, iterable)?;
+        buf.write(")).into_iter(),");
+        self.visit_arg(ctx, buf, separator)?;
+        buf.write(',');
+        self.visit_arg(ctx, buf, conjunction)?;
+        buf.write(", askama::helpers::as_bool(&(");
+        self.visit_arg(ctx, buf, oxford)?;
+        buf.write(")))?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_sum_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_aggregate_filter(ctx, buf, args, node, "sum")
+    }
+
+    fn visit_avg_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_aggregate_filter(ctx, buf, args, node, "avg")
+    }
+
+    fn visit_aggregate_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+        name: &str,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "attribute",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+        ];
+
+        let [iterable, attribute] = collect_filter_args(ctx, name, node, args, ARGUMENTS)?;
+        buf.write(format_args!("askama::filters::{name}((&("));
+        self.visit_arg(ctx, buf, iterable)?;
+        buf.write(")).into_iter()");
+        if !is_argument_placeholder(attribute) {
+            let fields = attribute_fields(ctx, name, attribute)?;
+            buf.write(".map(|__askama_item| &__askama_item");
+            for field in fields {
+                buf.write(format_args!(".{}", normalize_identifier(field)));
+            }
+            buf.write(')');
+        }
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_contains_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_substring_filter(ctx, buf, args, node, "contains")
+    }
+
+    fn visit_count_matches_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_substring_filter(ctx, buf, args, node, "count_matches")
+    }
+
+    fn visit_substring_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+        name: &str,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "needle",
+                default_value: None,
+            },
+        ];
+
+        ensure_filter_has_feature_alloc(ctx, name, node)?;
+        let [source, needle] = collect_filter_args(ctx, name, node, args, ARGUMENTS)?;
+        if name == "count_matches" {
+            if let Expr::StrLit(StrLit { content: "", .. }) = **needle {
+                return Err(ctx
+                    .generate_error("cannot count the matches of an empty string", needle.span()));
+            }
+        }
+        buf.write(format_args!("askama::filters::{name}(&("));
+        self.visit_arg(ctx, buf, source)?;
+        buf.write("), &(");
+        self.visit_arg(ctx, buf, needle)?;
+        buf.write("))?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_chunks_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "size",
+                default_value: None,
+            },
+        ];
+
+        ensure_filter_has_feature_alloc(ctx, "chunks", node)?;
+        let [iterable, size] = collect_filter_args(ctx, "chunks", node, args, ARGUMENTS)?;
+        if num_lit_value(size) == Some(0.0) {
+            return Err(ctx.generate_error(
+                "the `size` argument of the `chunks` filter must not be `0`",
+                size.span(),
+            ));
+        }
+
+        // Slices are chunked without copying, other iterables get buffered. Which implementation
+        // is used is decided with autoref-based specialization.
+        buf.write(
+            "{\
+                use askama::helpers::{ChunksOwned as _, ChunksRef as _, ChunksSlice as _};\
+                (&&&askama::helpers::ChunksSource(&(",
+        );
+        self.visit_expr(ctx, buf, iterable)?;
+        buf.write(
+            "\
+                ),\
+                askama::helpers::core::primitive::usize::try_from(\
+                    askama::helpers::get_primitive_value(&(",
+        );
+        self.visit_arg(ctx, buf, size)?;
+        buf.write(
+            "\
+                    ))\
+                ).map_err(|_| askama::Error::Fmt)?\
+                )).askama_chunks()\
+            }",
+        );
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_columns_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "count",
+                default_value: None,
+            },
+        ];
+
+        ensure_filter_has_feature_alloc(ctx, "columns", node)?;
+        let [iterable, count] = collect_filter_args(ctx, "columns", node, args, ARGUMENTS)?;
+        if num_lit_value(count) == Some(0.0) {
+            return Err(ctx.generate_error(
+                "the `count` argument of the `columns` filter must not be `0`",
+                count.span(),
+            ));
+        }
+
+        buf.write("askama::filters::columns((&(");
+        self.visit_arg(ctx, buf, iterable)?;
+        buf.write(
+            "\
+            )).into_iter(),\
+            askama::helpers::core::primitive::usize::try_from(\
+                askama::helpers::get_primitive_value(&(",
+        );
+        self.visit_arg(ctx, buf, count)?;
+        buf.write(
+            "\
+                ))\
+            ).map_err(|_| askama::Error::Fmt)?\
+            )?",
+        );
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_clamp_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 3] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "min",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "max",
+                default_value: None,
+            },
+        ];
+
+        let [value, min, max] = collect_filter_args(ctx, "clamp", node, args, ARGUMENTS)?;
+        if let (Some(min_value), Some(max_value)) = (num_lit_value(min), num_lit_value(max)) {
+            if min_value > max_value {
+                return Err(ctx.generate_error(
+                    "the `min` argument of the `clamp` filter must not be greater than `max`",
+                    node,
+                ));
+            }
+        }
+
+        buf.write("askama::filters::clamp(askama::helpers::get_primitive_value(&(");
+        self.visit_arg(ctx, buf, value)?;
+        buf.write(")),");
+        for bound in [min, max] {
+            // Literals are emitted as they are, so their type gets inferred from the value.
+            if num_lit_value(bound).is_some() {
+                buf.write('(');
+                self.visit_arg(ctx, buf, bound)?;
+                buf.write("),");
+            } else {
+                buf.write("askama::helpers::get_primitive_value(&(");
+                self.visit_arg(ctx, buf, bound)?;
+                buf.write(")),");
+            }
+        }
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_flatten_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ONE: &WithSpan<'static, Expr<'static>> =
+            &WithSpan::new_without_span(Expr::NumLit("1", Num::Int("1", None)));
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "depth",
+                default_value: Some(ONE),
+            },
+        ];
+
+        let [iterable, depth] = collect_filter_args(ctx, "flatten", node, args, ARGUMENTS)?;
+        // Every level of nesting needs a differently typed iterator, so the depth must be known
+        // at compile time.
+        let depth = match **depth {
+            Expr::NumLit(_, Num::Int(value, None | Some(IntKind::Usize))) => {
+                value.parse::<usize>().ok().filter(|&depth| depth > 0)
+            }
+            _ => None,
+        };
+        let Some(depth) = depth else {
+            return Err(ctx.generate_error(
+                "the `depth` argument of the `flatten` filter must be a positive integer literal",
+                node,
+            ));
+        };
+        if depth > MAX_FLATTEN_DEPTH {
+            return Err(ctx.generate_error(
+                format_args!(
+                    "the `depth` argument of the `flatten` filter must not exceed \
+                     {MAX_FLATTEN_DEPTH}, found {depth}"
+                ),
+                node,
+            ));
+        }
+
+        for _ in 0..depth {
+            buf.write("askama::filters::flatten(");
+        }
+        buf.write("(&(");
+        self.visit_arg(ctx, buf, iterable)?;
+        buf.write(")).into_iter()");
+        for _ in 0..depth {
+            buf.write(")?");
+        }
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_paginate_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 3] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "page",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "per_page",
+                default_value: None,
+            },
+        ];
+
+        let [iterable, page, per_page] =
+            collect_filter_args(ctx, "paginate", node, args, ARGUMENTS)?;
+        ensure_per_page_not_zero(ctx, "paginate", per_page)?;
+
+        // A page that does not fit into a `usize`, e.g. a negative one, is out of range.
+        buf.write("askama::filters::paginate((&(");
+        self.visit_arg(ctx, buf, iterable)?;
+        buf.write(
+            "\
+            )).into_iter(),\
+            askama::helpers::core::primitive::usize::try_from(\
+                askama::helpers::get_primitive_value(&(",
+        );
+        self.visit_arg(ctx, buf, page)?;
+        buf.write(
+            "\
+                ))\
+            ).unwrap_or(0),",
+        );
+        self.visit_per_page_arg(ctx, buf, per_page)?;
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_zip_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "other",
+                default_value: None,
+            },
+        ];
+
+        let [iterable, other] = collect_filter_args(ctx, "zip", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::zip((&(");
+        self.visit_arg(ctx, buf, iterable)?;
+        buf.write(")).into_iter(), (&(");
+        self.visit_arg(ctx, buf, other)?;
+        buf.write(")).into_iter())?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_group_consecutive_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "attribute",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+        ];
+
+        ensure_filter_has_feature_alloc(ctx, "group_consecutive", node)?;
+        let [iterable, attribute] =
+            collect_filter_args(ctx, "group_consecutive", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::group_consecutive((&(");
+        self.visit_arg(ctx, buf, iterable)?;
+        // The elements are references, so the key can borrow from the element itself.
+        buf.write(")).into_iter(), |&__askama_item| ");
+        if is_argument_placeholder(attribute) {
+            buf.write("__askama_item");
+        } else {
+            buf.write("&__askama_item");
+            for field in attribute_fields(ctx, "group_consecutive", attribute)? {
+                buf.write(format_args!(".{}", normalize_identifier(field)));
+            }
+        }
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_ordinal_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const FALSE: &WithSpan<'static, Expr<'static>> =
+            &WithSpan::new_without_span(Expr::BoolLit(false));
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "words",
+                default_value: Some(FALSE),
+            },
+        ];
+
+        let [arg, words] = collect_filter_args(ctx, "ordinal", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::ordinal(");
+        self.visit_arg(ctx, buf, arg)?;
+        buf.write(",askama::helpers::as_bool(&(");
+        self.visit_arg(ctx, buf, words)?;
+        buf.write(")))?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_page_count_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "per_page",
+                default_value: None,
+            },
+        ];
+
+        let [iterable, per_page] = collect_filter_args(ctx, "page_count", node, args, ARGUMENTS)?;
+        ensure_per_page_not_zero(ctx, "page_count", per_page)?;
+
+        buf.write("askama::filters::page_count((&(");
+        self.visit_arg(ctx, buf, iterable)?;
+        buf.write(")).into_iter(),");
+        self.visit_per_page_arg(ctx, buf, per_page)?;
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_per_page_arg(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        per_page: &WithSpan<'a, Expr<'a>>,
+    ) -> Result<(), CompileError> {
+        buf.write(
+            "\
+            askama::helpers::core::primitive::usize::try_from(\
+                askama::helpers::get_primitive_value(&(",
+        );
+        self.visit_arg(ctx, buf, per_page)?;
+        buf.write(
+            "\
+                ))\
+            ).map_err(|_| askama::Error::Fmt)?",
+        );
+        Ok(())
+    }
+
+    fn visit_center_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const FALSE: &WithSpan<'static, Expr<'static>> =
+            &WithSpan::new_without_span(Expr::BoolLit(false));
+        const ARGUMENTS: &[&FilterArgument; 3] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "length",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "ansi_width",
+                default_value: Some(FALSE),
+            },
+        ];
+
+        ensure_filter_has_feature_alloc(ctx, "center", node)?;
+        let [arg, length, ansi_width] = collect_filter_args(ctx, "center", node, args, ARGUMENTS)?;
+        let mut call_args = Buffer::new();
+        self.visit_arg(ctx, &mut call_args, arg)?;
+        call_args.write(
+            "\
+                ,\
+                askama::helpers::core::primitive::usize::try_from(\
+                    askama::helpers::get_primitive_value(&(",
+        );
+        self.visit_arg(ctx, &mut call_args, length)?;
+        call_args.write(
+            "\
+                    ))\
+                ).map_err(|_| askama::Error::Fmt)?",
+        );
+        self.write_ansi_width_call(ctx, buf, "center", ansi_width, call_args.as_str())
+    }
+
+    /// Calls the filter `name`, or `{name}_ansi` if `ansi_width` is `true`.
+    fn write_ansi_width_call(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        ansi_width: &WithSpan<'a, Expr<'a>>,
+        call_args: &str,
+    ) -> Result<DisplayWrap, CompileError> {
+        match **ansi_width {
+            Expr::BoolLit(false) => {
+                buf.write(format_args!("askama::filters::{name}({call_args})?"))
+            }
+            Expr::BoolLit(true) => {
+                buf.write(format_args!("askama::filters::{name}_ansi({call_args})?"));
+            }
+            _ => {
+                // Both functions return the same type, so the choice can be made at runtime.
+                buf.write("match askama::helpers::as_bool(&(");
+                self.visit_arg(ctx, buf, ansi_width)?;
+                buf.write(format_args!(
+                    ")) {{\
+                        true => askama::filters::{name}_ansi({call_args})?,\
+                        false => askama::filters::{name}({call_args})?,\
+                    }}",
+                ));
+            }
+        }
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_ellipsize_middle_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_truncate_filters(ctx, buf, args, node, "ellipsize_middle")
+    }
+
+    fn visit_truncate_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_truncate_filters(ctx, buf, args, node, "truncate")
+    }
+
+    fn visit_ljust_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_justify_filter(ctx, buf, args, node, "ljust")
+    }
+
+    fn visit_rjust_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_justify_filter(ctx, buf, args, node, "rjust")
+    }
+
+    fn visit_justify_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+        name: &str,
+    ) -> Result<DisplayWrap, CompileError> {
+        const FILL: &WithSpan<'static, Expr<'static>> =
+            &WithSpan::new_without_span(Expr::CharLit(CharLit {
+                prefix: None,
+                content: " ",
+            }));
+        const FALSE: &WithSpan<'static, Expr<'static>> =
+            &WithSpan::new_without_span(Expr::BoolLit(false));
+        const ARGUMENTS: &[&FilterArgument; 4] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "width",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "fill",
+                default_value: Some(FILL),
+            },
+            &FilterArgument {
+                name: "ansi_width",
+                default_value: Some(FALSE),
+            },
+        ];
+
+        let [arg, width, fill, ansi_width] = collect_filter_args(ctx, name, node, args, ARGUMENTS)?;
+        let mut call_args = Buffer::new();
+        self.visit_arg(ctx, &mut call_args, arg)?;
+        call_args.write(
+            "\
+                ,\
+                askama::helpers::core::primitive::usize::try_from(\
+                    askama::helpers::get_primitive_value(&(",
+        );
+        self.visit_arg(ctx, &mut call_args, width)?;
+        call_args.write(
+            "\
+                    ))\
+                ).map_err(|_| askama::Error::Fmt)?,\
+                askama::helpers::get_primitive_value(&(",
+        );
+        self.visit_arg(ctx, &mut call_args, fill)?;
+        call_args.write("))");
+        self.write_ansi_width_call(ctx, buf, name, ansi_width, call_args.as_str())
+    }
+
+    fn visit_redact_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ZERO: &WithSpan<'static, Expr<'static>> =
+            &WithSpan::new_without_span(Expr::NumLit("0", Num::Int("0", None)));
+        const MASK: &WithSpan<'static, Expr<'static>> =
+            &WithSpan::new_without_span(Expr::CharLit(CharLit {
+                prefix: None,
+                content: "*",
+            }));
+        const ARGUMENTS: &[&FilterArgument; 4] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "last",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "first",
+                default_value: Some(ZERO),
+            },
+            &FilterArgument {
+                name: "mask",
+                default_value: Some(MASK),
+            },
+        ];
+
+        ensure_filter_has_feature_alloc(ctx, "redact", node)?;
+        let [arg, last, first, mask] = collect_filter_args(ctx, "redact", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::redact(");
+        self.visit_arg(ctx, buf, arg)?;
+        for count in [last, first] {
+            buf.write(
+                "\
+                    ,\
+                    askama::helpers::core::primitive::usize::try_from(\
+                        askama::helpers::get_primitive_value(&(",
+            );
+            self.visit_arg(ctx, buf, count)?;
+            buf.write(
+                "\
+                        ))\
+                    ).map_err(|_| askama::Error::Fmt)?",
+            );
+        }
+        buf.write(",askama::helpers::get_primitive_value(&(");
+        self.visit_arg(ctx, buf, mask)?;
+        buf.write(")))?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_repeat_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "count",
+                default_value: None,
+            },
+        ];
+
+        let [arg, count] = collect_filter_args(ctx, "repeat", node, args, ARGUMENTS)?;
+        if num_lit_value(count).is_some_and(|count| count < 0.0) {
+            return Err(ctx.generate_error(
+                "the `count` argument of the `repeat` filter must not be negative",
+                count.span(),
+            ));
+        }
+
+        // A negative count that is only known at runtime yields an empty string.
+        buf.write("askama::filters::repeat(");
+        self.visit_arg(ctx, buf, arg)?;
+        buf.write(
+            "\
+                ,\
+                askama::helpers::core::primitive::usize::try_from(\
+                    askama::helpers::get_primitive_value(&(",
+        );
+        self.visit_arg(ctx, buf, count)?;
+        buf.write(
+            "\
+                    ))\
+                ).unwrap_or_default()\
+            )?",
+        );
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_excerpt_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_word_limit_filter(ctx, buf, args, node, "excerpt")
+    }
+
+    fn visit_truncatewords_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Expr<'a>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_word_limit_filter(ctx, buf, args, node, "truncatewords")
+    }
+
+    fn visit_word_limit_filter(
+        &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
         args: &[WithSpan<'a, Expr<'a>>],
         node: Span<'_>,
+        name: &str,
     ) -> Result<DisplayWrap, CompileError> {
-        self.visit_center_truncate_filter(ctx, buf, args, node, "truncate")
+        const SUFFIX: &WithSpan<'static, Expr<'static>> =
+            &WithSpan::new_without_span(Expr::StrLit(StrLit {
+                prefix: None,
+                content: "...",
+            }));
+        const ARGUMENTS: &[&FilterArgument; 3] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "count",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "suffix",
+                default_value: Some(SUFFIX),
+            },
+        ];
+
+        ensure_filter_has_feature_alloc(ctx, name, node)?;
+        let [arg, count, suffix] = collect_filter_args(ctx, name, node, args, ARGUMENTS)?;
+        buf.write(format_args!("askama::filters::{name}("));
+        self.visit_arg(ctx, buf, arg)?;
+        buf.write(
+            "\
+                ,\
+                askama::helpers::core::primitive::usize::try_from(\
+                    askama::helpers::get_primitive_value(&(",
+        );
+        self.visit_arg(ctx, buf, count)?;
+        buf.write(
+            "\
+                    ))\
+                ).map_err(|_| askama::Error::Fmt)?,",
+        );
+        self.visit_arg(ctx, buf, suffix)?;
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
     }
 
-    fn visit_center_truncate_filter(
+    fn visit_truncate_filters(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
@@ -658,6 +2123,221 @@ impl<'a> Generator<'a, '_> {
     }
 }
 
+/// The name of a custom filter looks like a typo of a built-in filter, or is the name of a built-in
+/// filter whose feature is not enabled.
+///
+/// If the user defined a filter with this name, then it gets called. Otherwise the fallback
+/// function generated here is used, that fails to compile with `message`.
+fn write_custom_filter_with_fallback(buf: &mut Buffer, name: &str, message: &str, args_len: usize) {
+    buf.write(format_args!(
+        "({{\
+            #[diagnostic::on_unimplemented(\
+                message = {message:?},\
+                label = \"unknown filter\",\
+                note = \"to use a custom filter, add a function `{name}` to your `filters` module\",\
+            )]\
+            #[allow(dead_code)]\
+            trait __AskamaUnknownFilter {{}}\
+            #[allow(dead_code)]\
+            fn {name}<__Source: __AskamaUnknownFilter, __Values"
+    ));
+    for idx in 1..args_len {
+        buf.write(format_args!(", __Arg{idx}"));
+    }
+    buf.write(">(_: __Source, _: __Values");
+    for idx in 1..args_len {
+        buf.write(format_args!(", _: __Arg{idx}"));
+    }
+    buf.write(format_args!(
+        ") -> askama::Result<askama::helpers::Empty> {{\
+                askama::helpers::core::unreachable!()\
+            }}\
+            {{\
+                #[allow(unused_imports)]\
+                use filters::*;\
+                {name}\
+            }}\
+        }})"
+    ));
+}
+
+/// Find the built-in filter with the smallest edit distance to `name`, if it is close enough.
+fn closest_builtin_filter(name: &str) -> Option<&'static str> {
+    closest_name(
+        name,
+        BUILTIN_FILTERS_OTHER
+            .iter()
+            .chain(BUILTIN_FILTERS)
+            .chain(BUILTIN_FILTERS_ALLOC)
+            .chain(BUILTIN_FILTERS_STD)
+            .copied(),
+    )
+}
+
+/// Find the candidate with the smallest edit distance to `name`, if it is close enough.
+pub(crate) fn closest_name<'n>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'n str>,
+) -> Option<&'n str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// Splits the format string of the `format_each` filter at its only placeholder `{}`.
+fn split_format_each(content: &str) -> Result<(String, String), String> {
+    let content = if content.contains('\\') {
+        syn::parse_str::<syn::LitStr>(&format!(r#""{content}""#))
+            .map_err(|err| format!("invalid string literal: {err}"))?
+            .value()
+    } else {
+        content.to_owned()
+    };
+
+    let mut parts = [String::new(), String::new()];
+    let mut placeholders = 0;
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+            }
+            ('{', Some('}')) => {
+                chars.next();
+                placeholders += 1;
+                continue;
+            }
+            ('{' | '}', _) => {
+                return Err(
+                    "the format string of the `format_each` filter only supports `{}`, use `{{` \
+                     and `}}` for literal braces"
+                        .into(),
+                );
+            }
+            _ => {}
+        }
+        parts[placeholders.min(1)].push(c);
+    }
+    match placeholders {
+        1 => {
+            let [prefix, suffix] = parts;
+            Ok((prefix, suffix))
+        }
+        _ => Err(format!(
+            "the format string of the `format_each` filter must contain exactly one `{{}}`, \
+             found {placeholders}"
+        )),
+    }
+}
+
+/// A named field, e.g. `amount`, or the index of a tuple field, e.g. `0`.
+fn is_field_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_digit() => s.bytes().all(|b| b.is_ascii_digit()),
+        Some(c) if c == '_' || c.is_alphabetic() => {
+            chars.all(|c| c == '_' || c.is_alphanumeric()) && s != "_"
+        }
+        _ => false,
+    }
+}
+
+const SAFE_RUNTIME_VALUE: &str = "the `safe` filter is applied to a value of the runtime values, \
+    which could be untrusted data, so it is written without escaping";
+
+/// The expression reads from the runtime values, e.g. `askama::get_value::<T>("key")?` or
+/// `"key"|value::<T>`.
+fn reads_runtime_value(expr: &Expr<'_>) -> bool {
+    match expr {
+        Expr::Try(expr) | Expr::Group(expr) => reads_runtime_value(expr),
+        Expr::Call { path, .. } => matches!(
+            &***path,
+            Expr::Path(path)
+                if matches!(path[..], ["askama", "get_value" | "get_value_or" | "get_value_opt"])
+        ),
+        Expr::Filter(filter) => matches!(filter.name, PathOrIdentifier::Identifier("value")),
+        _ => false,
+    }
+}
+
+/// The value of a, possibly negated, number literal.
+fn num_lit_value(expr: &Expr<'_>) -> Option<f64> {
+    match expr {
+        Expr::NumLit(_, Num::Int(value, _)) => {
+            let value = value.replace('_', "");
+            let (negative, value) = match value.strip_prefix('-') {
+                Some(value) => (true, value),
+                None => (false, value.as_str()),
+            };
+            let (radix, digits) = match value.get(..2) {
+                Some("0b") => (2, &value[2..]),
+                Some("0o") => (8, &value[2..]),
+                Some("0x") => (16, &value[2..]),
+                _ => (10, value),
+            };
+            let value = u128::from_str_radix(digits, radix).ok()? as f64;
+            Some(if negative { -value } else { value })
+        }
+        Expr::NumLit(_, Num::Float(value, _)) => value.replace('_', "").parse().ok(),
+        Expr::Unary("-", expr) => num_lit_value(expr).map(|value| -value),
+        Expr::Group(expr) => num_lit_value(expr),
+        _ => None,
+    }
+}
+
+/// The (nested) field names of an `attribute` argument, e.g. `"amount"` or `"price.net"`.
+fn attribute_fields<'a>(
+    ctx: &Context<'_>,
+    name: &str,
+    attribute: &WithSpan<'a, Expr<'a>>,
+) -> Result<Vec<&'a str>, CompileError> {
+    let fields = match **attribute {
+        Expr::StrLit(StrLit {
+            prefix: None,
+            content,
+        }) => Some(content.split('.').collect::<Vec<_>>()),
+        _ => None,
+    };
+    fields
+        .filter(|fields| fields.iter().all(|f| is_field_name(f)))
+        .ok_or_else(|| {
+            ctx.generate_error(
+                format_args!(
+                    "the `attribute` argument of the `{name}` filter must be a string literal \
+                     containing a field name, e.g. `\"amount\"`"
+                ),
+                attribute.span(),
+            )
+        })
+}
+
+/// Edit distance between `a` and `b`, where swapping two adjacent characters counts as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut before = vec![0; b.len() + 1];
+    let mut last = (0..=b.len()).collect::<Vec<_>>();
+    for i in 0..a.len() {
+        let mut row = vec![i + 1; b.len() + 1];
+        for j in 0..b.len() {
+            row[j + 1] = if a[i] == b[j] {
+                last[j]
+            } else {
+                1 + last[j].min(last[j + 1]).min(row[j])
+            };
+            if i > 0 && j > 0 && a[i] == b[j - 1] && a[i - 1] == b[j] {
+                row[j + 1] = row[j + 1].min(before[j - 1] + 1);
+            }
+        }
+        before = replace(&mut last, row);
+    }
+    last[b.len()]
+}
+
 fn ensure_filter_has_feature_alloc(
     ctx: &Context<'_>,
     name: &str,
@@ -672,6 +2352,36 @@ fn ensure_filter_has_feature_alloc(
     Ok(())
 }
 
+fn ensure_filter_has_feature_grapheme(
+    ctx: &Context<'_>,
+    name: &str,
+    node: Span<'_>,
+) -> Result<(), CompileError> {
+    if !cfg!(feature = "grapheme") {
+        return Err(ctx.generate_error(
+            format_args!("the `{name}` filter requires the `grapheme` feature to be enabled"),
+            node,
+        ));
+    }
+    Ok(())
+}
+
+fn ensure_filter_has_feature_unicode_normalization(
+    ctx: &Context<'_>,
+    name: &str,
+    node: Span<'_>,
+) -> Result<(), CompileError> {
+    if !cfg!(feature = "unicode-normalization") {
+        return Err(ctx.generate_error(
+            format_args!(
+                "the `{name}` filter requires the `unicode-normalization` feature to be enabled"
+            ),
+            node,
+        ));
+    }
+    Ok(())
+}
+
 fn ensure_filter_has_feature_std(
     ctx: &Context<'_>,
     name: &str,
@@ -918,6 +2628,21 @@ impl fmt::Display for ItsArgumentsAre {
     }
 }
 
+#[cfg(feature = "regex")]
+fn validate_regex(ctx: &Context<'_>, lit: &StrLit<'_>, span: Span<'_>) -> Result<(), CompileError> {
+    let Ok(pattern) = syn::parse_str::<syn::LitStr>(&format!("\"{}\"", lit.content)) else {
+        // The literal itself is invalid, let rustc report the error.
+        return Ok(());
+    };
+    match regex_syntax::Parser::new().parse(&pattern.value()) {
+        Ok(_) => Ok(()),
+        Err(err) => Err(ctx.generate_error(
+            format_args!("invalid regular expression in `replace_regex` filter:\n{err}"),
+            span,
+        )),
+    }
+}
+
 fn ensure_no_named_arguments(
     ctx: &Context<'_>,
     name: &str,
@@ -938,14 +2663,107 @@ fn ensure_no_named_arguments(
     Ok(())
 }
 
+fn ensure_per_page_not_zero(
+    ctx: &Context<'_>,
+    name: &str,
+    per_page: &WithSpan<'_, Expr<'_>>,
+) -> Result<(), CompileError> {
+    if num_lit_value(per_page) == Some(0.0) {
+        return Err(ctx.generate_error(
+            format_args!("the `per_page` argument of the `{name}` filter must not be `0`"),
+            per_page.span(),
+        ));
+    }
+    Ok(())
+}
+
+/// Every level of the `flatten` filter is a nested call in the generated code.
+const MAX_FLATTEN_DEPTH: usize = 16;
+
+// These built-in filters are handled individually in `visit_filter()`.
+const BUILTIN_FILTERS_OTHER: &[&str] = &[
+    "avg",
+    "box",
+    "center",
+    "chunks",
+    "clamp",
+    "coalesce",
+    "columns",
+    "contains",
+    "count_matches",
+    "date",
+    "datetime",
+    "default_if_none",
+    "deref",
+    "e",
+    "ellipsize_middle",
+    "escape",
+    "escape_once",
+    "excerpt",
+    "filesizeformat",
+    "flatten",
+    "fmt",
+    "format",
+    "format_each",
+    "group_consecutive",
+    "highlight",
+    "indent",
+    "join",
+    "join_human",
+    "json",
+    "json_attr",
+    "length_graphemes",
+    "linebreaks",
+    "linebreaksbr",
+    "ljust",
+    "markdown",
+    "nfc",
+    "nfd",
+    "nfkc",
+    "nfkd",
+    "ordinal",
+    "page_count",
+    "paginate",
+    "paragraphbreaks",
+    "pluralize",
+    "redact",
+    "ref",
+    "repeat",
+    "replace_regex",
+    "reverse_graphemes",
+    "rjust",
+    "safe",
+    "safe_if",
+    "sum",
+    "table",
+    "tojson",
+    "trim_lines",
+    "truncate",
+    "truncate_graphemes",
+    "truncatewords",
+    "urlencode",
+    "urlencode_strict",
+    "value",
+    "visible_len",
+    "wordcount",
+    "zip",
+];
+
 // These built-in filters take no arguments, no generics, and are not feature gated.
-const BUILTIN_FILTERS: &[&str] = &[];
+const BUILTIN_FILTERS: &[&str] = &["escape_control", "shell_escape", "sign"];
 
 // These built-in filters take no arguments, no generics, and need `features = ["alloc"]`.
 const BUILTIN_FILTERS_ALLOC: &[&str] = &[
+    "camel_case",
     "capitalize",
+    "dedent",
+    "kebab_case",
+    "localize_number",
     "lower",
     "lowercase",
+    "normalize_ws",
+    "pascal_case",
+    "snake_case",
     "title",
     "titlecase",
     "trim",
@@ -1,13 +1,13 @@
 use std::borrow::Cow;
 use std::collections::hash_map::{Entry, HashMap};
 use std::fmt::{self, Debug, Write};
-use std::mem;
+use std::{iter, mem};
 
 use parser::node::{
-    Call, Comment, Cond, CondTest, FilterBlock, If, Include, Let, Lit, Loop, Macro, Match,
-    Whitespace, Ws,
+    Call, Cfg, Comment, Cond, CondTest, Do, FilterBlock, If, Include, Let, Lit, Loop, Macro, Match,
+    Whitespace, With, Ws,
 };
-use parser::{Expr, Filter, Node, Span, Target, WithSpan};
+use parser::{Expr, Filter, Node, Span, Target, Test, WithSpan};
 use rustc_hash::FxBuildHasher;
 
 use super::{
@@ -15,7 +15,7 @@ use super::{
     normalize_identifier,
 };
 use crate::generator::Writable;
-use crate::heritage::{Context, Heritage};
+use crate::heritage::{Context, Heritage, depth_error_message};
 use crate::integration::Buffer;
 use crate::{CompileError, FileInfo, fmt_left, fmt_right};
 
@@ -27,6 +27,7 @@ impl<'a> Generator<'a, '_> {
     ) -> Result<usize, CompileError> {
         buf.set_discard(self.buf_writable.discard);
         let size_hint = if let Some(heritage) = self.heritage {
+            self.whitespace = self.whitespace_of(heritage.root);
             self.handle(heritage.root, heritage.root.nodes, buf, AstLevel::Top)
         } else {
             self.handle(ctx, ctx.nodes, buf, AstLevel::Top)
@@ -58,6 +59,7 @@ impl<'a> Generator<'a, '_> {
 
         let buf_writable = mem::take(&mut self.buf_writable);
         let locals = mem::replace(&mut self.locals, MapChain::new_empty());
+        let include_chain = mem::take(&mut self.include_chain);
 
         let mut child = Generator::new(
             self.input,
@@ -68,10 +70,12 @@ impl<'a> Generator<'a, '_> {
             self.is_in_filter_block,
         );
         child.buf_writable = buf_writable;
+        child.include_chain = include_chain;
         let res = callback(&mut child);
         Generator {
             locals: self.locals,
             buf_writable: self.buf_writable,
+            include_chain: self.include_chain,
             ..
         } = child;
 
@@ -101,6 +105,9 @@ impl<'a> Generator<'a, '_> {
                 Node::Let(ref l) => {
                     self.write_let(ctx, buf, l)?;
                 }
+                Node::Do(ref d) => {
+                    self.write_do(ctx, buf, d)?;
+                }
                 Node::If(ref i) => {
                     size_hint += self.write_if(ctx, buf, i)?;
                 }
@@ -123,6 +130,12 @@ impl<'a> Generator<'a, '_> {
                 Node::FilterBlock(ref filter) => {
                     size_hint += self.write_filter_block(ctx, buf, filter)?;
                 }
+                Node::With(ref with) => {
+                    size_hint += self.write_with(ctx, buf, with)?;
+                }
+                Node::Cfg(ref cfg) => {
+                    size_hint += self.write_cfg(ctx, buf, cfg)?;
+                }
                 Node::Macro(ref m) => {
                     if level != AstLevel::Top {
                         return Err(ctx.generate_error(
@@ -147,6 +160,15 @@ impl<'a> Generator<'a, '_> {
                     }
                     self.handle_ws(i.ws);
                 }
+                Node::FromImport(ref i) => {
+                    if level != AstLevel::Top {
+                        return Err(ctx.generate_error(
+                            "from import blocks only allowed at the top level",
+                            i.span(),
+                        ));
+                    }
+                    self.handle_ws(i.ws);
+                }
                 Node::Extends(ref e) => {
                     if level != AstLevel::Top {
                         return Err(ctx.generate_error(
@@ -195,6 +217,8 @@ impl<'a> Generator<'a, '_> {
             | Expr::Var(_)
             | Expr::Path(_)
             | Expr::Array(_)
+            | Expr::Map(_)
+            | Expr::Is { .. }
             | Expr::Attr(_, _)
             | Expr::Index(_, _)
             | Expr::Filter(_)
@@ -207,6 +231,7 @@ impl<'a> Generator<'a, '_> {
             | Expr::FilterSource
             | Expr::As(_, _)
             | Expr::Concat(_)
+            | Expr::Compare(_, _)
             | Expr::LetCond(_)
             | Expr::ArgumentPlaceholder => {
                 *only_contains_is_defined = false;
@@ -304,6 +329,11 @@ impl<'a> Generator<'a, '_> {
                 let (result, expr) = self.evaluate_condition(*inner, only_contains_is_defined);
                 (result, WithSpan::new(Expr::Group(Box::new(expr)), span))
             }
+            Expr::IsDefined(left) | Expr::IsNotDefined(left) if self.is_value_lookup(left) => {
+                // The variable is looked up in the runtime values.
+                *only_contains_is_defined = false;
+                (EvaluatedResult::Unknown, WithSpan::new(expr, span))
+            }
             Expr::IsDefined(left) => {
                 // Variable is defined so we want to keep the condition.
                 if self.is_var_defined(left) {
@@ -630,11 +660,27 @@ impl<'a> Generator<'a, '_> {
                 )
             })?;
             (*def, mctx)
-        } else {
-            let def = ctx.macros.get(name).ok_or_else(|| {
-                ctx.generate_error(format_args!("macro {name:?} not found"), call.span())
-            })?;
+        } else if let Some(def) = ctx.macros.get(name) {
             (*def, ctx)
+        } else if let Some(imported) = ctx.imported_macros.get(name) {
+            let mctx = self.contexts.get(&imported.path).ok_or_else(|| {
+                ctx.generate_error(
+                    format_args!("context for {:?} not found", imported.path),
+                    call.span(),
+                )
+            })?;
+            let def = mctx.macros.get(**imported.name).ok_or_else(|| {
+                ctx.generate_error(
+                    format_args!(
+                        "macro `{}` not found in {:?}",
+                        **imported.name, imported.source_path,
+                    ),
+                    call.span(),
+                )
+            })?;
+            (*def, mctx)
+        } else {
+            return Err(ctx.generate_error(format_args!("macro {name:?} not found"), call.span()));
         };
 
         if self
@@ -842,14 +888,136 @@ impl<'a> Generator<'a, '_> {
         Ok(size_hint)
     }
 
+    fn write_with(
+        &mut self,
+        ctx: &Context<'a>,
+        buf: &mut Buffer,
+        with: &'a WithSpan<'a, With<'_>>,
+    ) -> Result<usize, CompileError> {
+        self.flush_ws(with.ws1);
+        let flushed = self.write_buf_writable(ctx, buf)?;
+        buf.write('{');
+        let size_hint = self.push_locals(|this| {
+            let values = this.visit_binding_values(ctx, &with.bindings)?;
+            this.write_binding_targets(buf, &with.bindings, &values);
+
+            this.prepare_ws(with.ws1);
+            let mut size_hint = this.handle(ctx, &with.nodes, buf, AstLevel::Nested)?;
+            this.flush_ws(with.ws2);
+            size_hint += this.write_buf_writable(ctx, buf)?;
+            Ok(size_hint)
+        })?;
+        buf.write('}');
+        self.prepare_ws(with.ws2);
+        Ok(flushed + size_hint)
+    }
+
+    fn write_cfg(
+        &mut self,
+        ctx: &Context<'a>,
+        buf: &mut Buffer,
+        cfg: &'a WithSpan<'a, Cfg<'_>>,
+    ) -> Result<usize, CompileError> {
+        if let Err(err) = syn::parse_str::<syn::Meta>(cfg.predicate) {
+            return Err(ctx.generate_error(
+                format_args!("invalid predicate in `cfg` block: {err}"),
+                cfg.span(),
+            ));
+        }
+
+        self.flush_ws(cfg.ws1);
+        let flushed = self.write_buf_writable(ctx, buf)?;
+        buf.write(format_args!("#[cfg({})] {{", cfg.predicate));
+        let size_hint = self.push_locals(|this| {
+            this.prepare_ws(cfg.ws1);
+            let mut size_hint = this.handle(ctx, &cfg.nodes, buf, AstLevel::Nested)?;
+            this.flush_ws(cfg.ws2);
+            size_hint += this.write_buf_writable(ctx, buf)?;
+            Ok(size_hint)
+        })?;
+        buf.write('}');
+        self.prepare_ws(cfg.ws2);
+        Ok(flushed + size_hint)
+    }
+
+    /// Evaluates the values of `{% with %}` or `{% include ... with %}` bindings.
+    ///
+    /// All values are evaluated before any variable gets bound, so a value cannot refer to
+    /// a variable of the same binding list.
+    fn visit_binding_values(
+        &mut self,
+        ctx: &Context<'a>,
+        bindings: &'a [(Target<'a>, WithSpan<'a, Expr<'a>>)],
+    ) -> Result<Buffer, CompileError> {
+        let mut values = Buffer::new();
+        for (_, value) in bindings {
+            let mut expr_buf = Buffer::new();
+            self.visit_expr(ctx, &mut expr_buf, value)?;
+            if !matches!(**value, Expr::Try(..))
+                && !matches!(**value, Expr::Var(name) if self.locals.get(name).is_some())
+                && !is_copyable(value)
+            {
+                values.write(format_args!("&({expr_buf}),"));
+            } else {
+                values.write(format_args!("{expr_buf},"));
+            }
+        }
+        Ok(values)
+    }
+
+    /// Binds the `values` returned by [`Self::visit_binding_values()`] in the current scope.
+    fn write_binding_targets(
+        &mut self,
+        buf: &mut Buffer,
+        bindings: &'a [(Target<'a>, WithSpan<'a, Expr<'a>>)],
+        values: &Buffer,
+    ) {
+        buf.write("let (");
+        for (target, _) in bindings {
+            self.visit_target(buf, true, true, target);
+            buf.write(',');
+        }
+        buf.write(format_args!(") = ({values});"));
+    }
+
     fn handle_include(
         &mut self,
         ctx: &Context<'a>,
         buf: &mut Buffer,
-        i: &'a WithSpan<'a, Include<'_>>,
+        i: &'a WithSpan<'a, Include<'a>>,
     ) -> Result<usize, CompileError> {
         self.flush_ws(i.ws);
         self.write_buf_writable(ctx, buf)?;
+        if i.bindings.is_empty() && !i.only {
+            return self.write_include(ctx, buf, i);
+        }
+
+        buf.write('{');
+        let values = self.visit_binding_values(ctx, &i.bindings)?;
+        let size_hint = if i.only {
+            // The included template gets a fresh scope, that does not fall back to the fields of
+            // the template struct.
+            let locals = mem::replace(&mut self.locals, MapChain::new_isolated());
+            self.write_binding_targets(buf, &i.bindings, &values);
+            let size_hint = self.write_include(ctx, buf, i);
+            self.locals = locals;
+            size_hint?
+        } else {
+            self.push_locals(|this| {
+                this.write_binding_targets(buf, &i.bindings, &values);
+                this.write_include(ctx, buf, i)
+            })?
+        };
+        buf.write('}');
+        Ok(size_hint)
+    }
+
+    fn write_include(
+        &mut self,
+        ctx: &Context<'a>,
+        buf: &mut Buffer,
+        i: &'a WithSpan<'a, Include<'a>>,
+    ) -> Result<usize, CompileError> {
         let file_info = ctx
             .path
             .map(|path| FileInfo::of(i.span(), path, ctx.parsed));
@@ -858,12 +1026,25 @@ impl<'a> Generator<'a, '_> {
             .config
             .find_template(i.path, Some(&self.input.path), file_info)?;
 
+        let max_depth = self.input.config.max_include_depth;
+        if self.include_chain.len() >= max_depth {
+            let chain = iter::once(&self.input.path)
+                .chain(&self.include_chain)
+                .chain(iter::once(&path))
+                .map(|path| &**path);
+            return Err(
+                ctx.generate_error(depth_error_message(max_depth, "includes", chain), i.span())
+            );
+        }
+
         // We clone the context of the child in order to preserve their macros and imports.
         // But also add all the imports and macros from this template that don't override the
         // child's ones to preserve this template's context.
         let child_ctx = &mut self.contexts[&path].clone();
         for (name, mac) in &ctx.macros {
-            child_ctx.macros.entry(name).or_insert(mac);
+            if !child_ctx.imported_macros.contains_key(name) {
+                child_ctx.macros.entry(name).or_insert(mac);
+            }
         }
         for (name, import) in &ctx.imports {
             child_ctx
@@ -871,11 +1052,19 @@ impl<'a> Generator<'a, '_> {
                 .entry(name)
                 .or_insert_with(|| import.clone());
         }
+        for (name, imported) in &ctx.imported_macros {
+            if !child_ctx.macros.contains_key(name) {
+                child_ctx
+                    .imported_macros
+                    .entry(name)
+                    .or_insert_with(|| imported.clone());
+            }
+        }
 
         // Create a new generator for the child, and call it like in `impl_template` as if it were
         // a full template, while preserving the context.
         let heritage = if !child_ctx.blocks.is_empty() || child_ctx.extends.is_some() {
-            Some(Heritage::new(child_ctx, self.contexts))
+            Some(Heritage::new(child_ctx, self.contexts, max_depth)?)
         } else {
             None
         };
@@ -885,12 +1074,15 @@ impl<'a> Generator<'a, '_> {
             None => child_ctx,
         };
 
+        self.include_chain.push(path);
         let size_hint = self.with_child(heritage.as_ref(), |child| {
             let mut size_hint = 0;
             size_hint += child.handle(handle_ctx, handle_ctx.nodes, buf, AstLevel::Top)?;
             size_hint += child.write_buf_writable(handle_ctx, buf)?;
             Ok(size_hint)
-        })?;
+        });
+        self.include_chain.pop();
+        let size_hint = size_hint?;
 
         self.prepare_ws(i.ws);
 
@@ -950,6 +1142,21 @@ impl<'a> Generator<'a, '_> {
         }
     }
 
+    fn write_do(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        d: &'a WithSpan<'a, Do<'_>>,
+    ) -> Result<(), CompileError> {
+        self.handle_ws(d.ws);
+        // The expression may have side effects, so everything before it must be written first.
+        self.write_buf_writable(ctx, buf)?;
+        buf.write("let _ = ");
+        self.visit_expr(ctx, buf, &d.expr)?;
+        buf.write(';');
+        Ok(())
+    }
+
     fn write_let(
         &mut self,
         ctx: &Context<'_>,
@@ -962,6 +1169,7 @@ impl<'a> Generator<'a, '_> {
             self.write_buf_writable(ctx, buf)?;
             buf.write("let ");
             self.visit_target(buf, false, true, &l.var);
+            self.write_let_type(buf, l);
             buf.write(';');
             return Ok(());
         };
@@ -970,6 +1178,15 @@ impl<'a> Generator<'a, '_> {
         self.visit_expr(ctx, &mut expr_buf, val)?;
 
         let shadowed = self.is_shadowing_variable(ctx, &l.var, l.span())?;
+        if let Some(kind) = refutable_pattern(&l.var) {
+            return Err(ctx.generate_error(
+                format_args!(
+                    "`let` and `set` need an irrefutable pattern, but this pattern contains \
+                     {kind}; use `{{% if let %}}` instead"
+                ),
+                l.span(),
+            ));
+        }
         if shadowed {
             // Need to flush the buffer if the variable is being shadowed,
             // to ensure the old variable is used.
@@ -980,12 +1197,20 @@ impl<'a> Generator<'a, '_> {
             || matches!(&l.var, Target::Name(name) if self.locals.get(name).is_none())
         {
             buf.write("let ");
+        } else if l.ty.is_some() {
+            return Err(ctx.generate_error(
+                "a type annotation cannot be used when assigning a value to a forward-defined \
+                 variable; add the type to the forward definition instead",
+                l.span(),
+            ));
         }
 
         self.visit_target(buf, true, true, &l.var);
+        self.write_let_type(buf, l);
         // If it's not taking the ownership of a local variable or copyable, then we need to add
-        // a reference.
-        let (before, after) = if !matches!(**val, Expr::Try(..))
+        // a reference. With an explicit type, the value is used as is.
+        let (before, after) = if l.ty.is_none()
+            && !matches!(**val, Expr::Try(..))
             && !matches!(**val, Expr::Var(name) if self.locals.get(name).is_some())
             && !is_copyable(val)
         {
@@ -997,6 +1222,13 @@ impl<'a> Generator<'a, '_> {
         Ok(())
     }
 
+    fn write_let_type(&mut self, buf: &mut Buffer, l: &'a WithSpan<'a, Let<'_>>) {
+        if let Some(ty) = &l.ty {
+            buf.write(':');
+            self.visit_ty_generic(buf, ty);
+        }
+    }
+
     // If `name` is `Some`, this is a call to a block definition, and we have to find
     // the first block for that name from the ancestry chain. If name is `None`, this
     // is from a `super()` call, and we can get the name from `self.super_block`.
@@ -1064,7 +1296,9 @@ impl<'a> Generator<'a, '_> {
         // child's ones to preserve this template's context.
         let mut child_ctx = child_ctx.clone();
         for (name, mac) in &ctx.macros {
-            child_ctx.macros.entry(name).or_insert(mac);
+            if !child_ctx.imported_macros.contains_key(name) {
+                child_ctx.macros.entry(name).or_insert(mac);
+            }
         }
         for (name, import) in &ctx.imports {
             child_ctx
@@ -1072,9 +1306,28 @@ impl<'a> Generator<'a, '_> {
                 .entry(name)
                 .or_insert_with(|| import.clone());
         }
+        for (name, imported) in &ctx.imported_macros {
+            if !child_ctx.macros.contains_key(name) {
+                child_ctx
+                    .imported_macros
+                    .entry(name)
+                    .or_insert_with(|| imported.clone());
+            }
+        }
+
+        // An overriding block uses the whitespace handling of the template it is defined in,
+        // unless it asks for the handling of the template that defines the overridden block.
+        let whitespace = match def.inherit_whitespace {
+            true => {
+                let (base_ctx, _) = heritage.blocks[cur.0].last().unwrap();
+                self.whitespace_of(base_ctx)
+            }
+            false => self.whitespace_of(&child_ctx),
+        };
 
         let size_hint = self.with_child(Some(heritage), |child| {
             // Handle inner whitespace suppression spec and process block nodes
+            child.whitespace = whitespace;
             child.prepare_ws(def.ws1);
 
             child.super_block = Some(cur);
@@ -1289,6 +1542,19 @@ impl<'a> Generator<'a, '_> {
                             self.input.escaper,
                         ),
                     };
+                    if self.input.lenient {
+                        // The expression is evaluated and written separately, so its errors can
+                        // be passed to the error handler.
+                        lines.write(format_args!(
+                            "askama::helpers::write_lenient(\
+                                __askama_writer,\
+                                __askama_values,\
+                                |__askama_writer| (&&&askama::filters::Writable(&({expr}))).\
+                                    askama_write(__askama_writer, __askama_values),\
+                            )?;",
+                        ));
+                        continue;
+                    }
                     let idx = if is_cacheable(s) {
                         match expr_cache.entry(expr) {
                             Entry::Occupied(e) => *e.get(),
@@ -1304,6 +1570,9 @@ impl<'a> Generator<'a, '_> {
                         targets.write(format_args!("expr{idx}, "));
                         idx
                     };
+                    // Adjacent expressions are not written through a single escaper: whether an
+                    // expression is escaped depends on its type (e.g. `HtmlSafe` or `|safe`), which
+                    // is only known to the compiler, and the escaper writer is a zero-cost wrapper.
                     lines.write(format_args!(
                         "(&&&askama::filters::Writable(expr{idx})).\
                              askama_write(__askama_writer, __askama_values)?;",
@@ -1374,7 +1643,20 @@ impl<'a> Generator<'a, '_> {
     }
 
     fn should_trim_ws(&self, ws: Option<Whitespace>) -> Whitespace {
-        ws.unwrap_or(self.input.config.whitespace)
+        ws.unwrap_or(self.whitespace)
+    }
+
+    // The default whitespace handling of a template of the inheritance chain. A parent template
+    // uses the handling that is configured for its own extension.
+    fn whitespace_of(&self, ctx: &Context<'_>) -> Whitespace {
+        match ctx.path {
+            Some(path) if path != &*self.input.path => self.input.config.whitespace_for(
+                path.extension()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or_default(),
+            ),
+            _ => self.input.whitespace,
+        }
     }
 
     // If the previous literal left some trailing whitespace in `next_ws` and the
@@ -1667,12 +1949,26 @@ fn is_cacheable(expr: &WithSpan<'_, Expr<'_>>) -> bool {
         Expr::Path(_) => true,
         // Check recursively:
         Expr::Array(args) => args.iter().all(is_cacheable),
+        Expr::Map(entries) => entries
+            .iter()
+            .all(|(key, value)| is_cacheable(key) && is_cacheable(value)),
+        // `.await` in `async` templates must only be polled once.
+        Expr::Attr(_, attr) if attr.name == "await" => false,
         Expr::Attr(lhs, _) => is_cacheable(lhs),
         Expr::Index(lhs, rhs) => is_cacheable(lhs) && is_cacheable(rhs),
         Expr::Filter(Filter { arguments, .. }) => arguments.iter().all(is_cacheable),
         Expr::Unary(_, arg) => is_cacheable(arg),
         Expr::BinOp(_, lhs, rhs) => is_cacheable(lhs) && is_cacheable(rhs),
         Expr::IsDefined(_) | Expr::IsNotDefined(_) => true,
+        Expr::Is { expr, test, .. } => {
+            is_cacheable(expr)
+                && match test {
+                    Test::DivisibleBy(divisor) => is_cacheable(divisor),
+                    Test::Defined | Test::None | Test::Even | Test::Odd => true,
+                    // Calls the method `is_empty()`, like `Expr::Call`.
+                    Test::Empty => false,
+                }
+        }
         Expr::Range(_, lhs, rhs) => {
             lhs.as_ref().is_none_or(|v| is_cacheable(v))
                 && rhs.as_ref().is_none_or(|v| is_cacheable(v))
@@ -1683,6 +1979,9 @@ fn is_cacheable(expr: &WithSpan<'_, Expr<'_>>) -> bool {
         Expr::As(expr, _) => is_cacheable(expr),
         Expr::Try(expr) => is_cacheable(expr),
         Expr::Concat(args) => args.iter().all(is_cacheable),
+        Expr::Compare(first, chain) => {
+            is_cacheable(first) && chain.iter().all(|(_, expr)| is_cacheable(expr))
+        }
         // Doesn't make sense in this context.
         Expr::LetCond(_) => false,
         // We have too little information to tell if the expression is pure:
@@ -1693,3 +1992,39 @@ fn is_cacheable(expr: &WithSpan<'_, Expr<'_>>) -> bool {
         Expr::ArgumentPlaceholder => unreachable!("ExpressionPlaceholder in expression?"),
     }
 }
+
+/// Returns a description of the first part of `target` that is known to be refutable, if any.
+///
+/// Only literals, `or` patterns, and the variants of `Option` and `Result` are detected. Other
+/// paths could be unit structs or tuple structs, which are irrefutable.
+fn refutable_pattern(target: &Target<'_>) -> Option<&'static str> {
+    match target {
+        Target::Name(_) | Target::Placeholder(_) | Target::Rest(_) => None,
+        Target::NumLit(..) | Target::StrLit(..) | Target::CharLit(..) | Target::BoolLit(..) => {
+            Some("a literal")
+        }
+        Target::OrChain(..) => Some("an `or` pattern"),
+        Target::Path(path) => is_refutable_path(path).then_some("an enum variant"),
+        Target::Tuple(path, targets) | Target::Array(path, targets) => {
+            if is_refutable_path(path) {
+                return Some("an enum variant");
+            }
+            targets.iter().find_map(refutable_pattern)
+        }
+        Target::Struct(path, fields) => {
+            if is_refutable_path(path) {
+                return Some("an enum variant");
+            }
+            fields
+                .iter()
+                .find_map(|(_, target)| refutable_pattern(target))
+        }
+    }
+}
+
+fn is_refutable_path(path: &[&str]) -> bool {
+    matches!(
+        path,
+        [.., "None" | "Some"] | ["Ok" | "Err"] | [.., "Result", "Ok" | "Err"]
+    )
+}
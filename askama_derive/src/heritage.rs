@@ -1,10 +1,10 @@
-use core::fmt;
+use core::fmt::{self, Write};
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 
 use parser::node::{BlockDef, Macro};
-use parser::{Node, Parsed, Span};
+use parser::{Node, Parsed, Span, WithSpan};
 use rustc_hash::FxBuildHasher;
 
 use crate::config::Config;
@@ -19,22 +19,48 @@ impl<'a, 'h> Heritage<'a, 'h> {
     pub(crate) fn new(
         mut root: &'h Context<'a>,
         contexts: &'a HashMap<&'a Arc<Path>, Context<'a>, FxBuildHasher>,
-    ) -> Self {
+        max_depth: usize,
+    ) -> Result<Self, CompileError> {
         let mut blocks: BlockAncestry<'a, 'h> = root
             .blocks
             .iter()
             .map(|(name, def)| (*name, vec![(root, *def)]))
             .collect();
 
+        let mut chain = vec![root.path];
         while let Some(path) = &root.extends {
             root = &contexts[path];
+            chain.push(root.path);
+            if chain.len() > max_depth + 1 {
+                return Err(CompileError::no_file_info(
+                    depth_error_message(max_depth, "extends", chain.iter().copied().flatten()),
+                    None,
+                ));
+            }
             for (name, def) in &root.blocks {
                 blocks.entry(name).or_default().push((root, def));
             }
         }
 
-        Self { root, blocks }
+        Ok(Self { root, blocks })
+    }
+}
+
+/// The error message if a chain of `include`s or `extends` is longer than `max_include_depth`
+pub(crate) fn depth_error_message<'p>(
+    max_depth: usize,
+    verb: &str,
+    chain: impl IntoIterator<Item = &'p Path>,
+) -> String {
+    let mut msg = format!("maximum include depth of {max_depth} exceeded:");
+    for (idx, path) in chain.into_iter().enumerate() {
+        let _ = match idx {
+            0 => write!(msg, " {path:?}"),
+            _ => write!(msg, " {verb} {path:?}"),
+        };
     }
+    msg.push_str("\nthe limit can be raised with `max_include_depth` in the configuration file");
+    msg
 }
 
 type BlockAncestry<'a, 'h> =
@@ -47,10 +73,19 @@ pub(crate) struct Context<'a> {
     pub(crate) blocks: HashMap<&'a str, &'a BlockDef<'a>, FxBuildHasher>,
     pub(crate) macros: HashMap<&'a str, &'a Macro<'a>, FxBuildHasher>,
     pub(crate) imports: HashMap<&'a str, Arc<Path>, FxBuildHasher>,
+    pub(crate) imported_macros: HashMap<&'a str, ImportedMacro<'a>, FxBuildHasher>,
     pub(crate) path: Option<&'a Path>,
     pub(crate) parsed: &'a Parsed,
 }
 
+/// A macro that was imported with `{% from "path" import name %}`
+#[derive(Clone)]
+pub(crate) struct ImportedMacro<'a> {
+    pub(crate) path: Arc<Path>,
+    pub(crate) source_path: &'a str,
+    pub(crate) name: &'a WithSpan<'a, &'a str>,
+}
+
 impl<'a> Context<'a> {
     pub(crate) fn empty(parsed: &Parsed) -> Context<'_> {
         Context {
@@ -59,6 +94,7 @@ impl<'a> Context<'a> {
             blocks: HashMap::default(),
             macros: HashMap::default(),
             imports: HashMap::default(),
+            imported_macros: HashMap::default(),
             path: None,
             parsed,
         }
@@ -73,6 +109,7 @@ impl<'a> Context<'a> {
         let mut blocks = HashMap::default();
         let mut macros = HashMap::default();
         let mut imports = HashMap::default();
+        let mut imported_macros = HashMap::default();
         let mut nested = vec![parsed.nodes()];
         let mut top = true;
 
@@ -106,6 +143,33 @@ impl<'a> Context<'a> {
                         )?;
                         imports.insert(import.scope, path);
                     }
+                    Node::FromImport(import) => {
+                        ensure_top(top, import.span(), path, parsed, "from")?;
+                        let import_path = config.find_template(
+                            import.path,
+                            Some(path),
+                            Some(FileInfo::of(import.span(), path, parsed)),
+                        )?;
+                        for name in &import.names {
+                            let imported = ImportedMacro {
+                                path: Arc::clone(&import_path),
+                                source_path: import.path,
+                                name: &name.name,
+                            };
+                            if imported_macros
+                                .insert(name.bound_name(), imported)
+                                .is_some()
+                            {
+                                return Err(CompileError::new(
+                                    format_args!(
+                                        "macro `{}` is imported more than once",
+                                        name.bound_name(),
+                                    ),
+                                    Some(FileInfo::of(name.name.span(), path, parsed)),
+                                ));
+                            }
+                        }
+                    }
                     Node::BlockDef(b) => {
                         blocks.insert(b.name, &**b);
                         nested.push(&b.nodes);
@@ -119,6 +183,12 @@ impl<'a> Context<'a> {
                         nested.push(&l.body);
                         nested.push(&l.else_nodes);
                     }
+                    Node::With(w) => {
+                        nested.push(&w.nodes);
+                    }
+                    Node::Cfg(c) => {
+                        nested.push(&c.nodes);
+                    }
                     Node::Match(m) => {
                         for arm in &m.arms {
                             nested.push(&arm.nodes);
@@ -130,17 +200,49 @@ impl<'a> Context<'a> {
             top = false;
         }
 
+        for (name, imported) in &imported_macros {
+            if macros.contains_key(name) {
+                return Err(CompileError::new(
+                    format_args!("macro `{name}` is both imported and defined in this template"),
+                    Some(FileInfo::of(imported.name.span(), path, parsed)),
+                ));
+            }
+        }
+
         Ok(Context {
             nodes: parsed.nodes(),
             extends,
             blocks,
             macros,
             imports,
+            imported_macros,
             parsed,
             path: Some(path),
         })
     }
 
+    /// Ensures that all macros imported with `{% from "path" import name %}` exist.
+    pub(crate) fn ensure_imported_macros_exist(
+        &self,
+        contexts: &HashMap<&Arc<Path>, Context<'_>, FxBuildHasher>,
+    ) -> Result<(), CompileError> {
+        for imported in self.imported_macros.values() {
+            if !contexts[&imported.path]
+                .macros
+                .contains_key(**imported.name)
+            {
+                return Err(self.generate_error(
+                    format_args!(
+                        "macro `{}` not found in {:?}",
+                        **imported.name, imported.source_path,
+                    ),
+                    imported.name.span(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
     pub(crate) fn generate_error(&self, msg: impl fmt::Display, node: Span<'_>) -> CompileError {
         CompileError::new(msg, self.file_info_of(node))
     }
@@ -1,6 +1,5 @@
-use std::borrow::Cow;
+use std::cell::RefCell;
 use std::collections::HashMap;
-use std::iter::FusedIterator;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
@@ -8,10 +7,14 @@ use std::sync::Arc;
 use parser::node::Whitespace;
 use parser::{Node, Parsed};
 use proc_macro2::Span;
+use quote::ToTokens;
 use rustc_hash::FxBuildHasher;
+use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{Attribute, Expr, ExprLit, ExprPath, Ident, Lit, LitBool, LitStr, Meta, Token};
+use syn::{
+    Attribute, Expr, ExprLit, ExprPath, Ident, Lit, LitBool, LitStr, Meta, MetaNameValue, Token,
+};
 
 use crate::config::{Config, SyntaxAndCache};
 use crate::{CompileError, FileInfo, MsgValidEscapers};
@@ -29,8 +32,26 @@ pub(crate) struct TemplateInput<'a> {
     pub(crate) blocks: &'a [Block],
     pub(crate) print: Print,
     pub(crate) escaper: &'a str,
+    /// The default whitespace handling, which can depend on the extension of the template.
+    pub(crate) whitespace: Whitespace,
+    pub(crate) mime_type: &'a str,
     pub(crate) path: Arc<Path>,
     pub(crate) fields: Arc<[String]>,
+    pub(crate) values_only: bool,
+    /// Unknown identifiers are a compile error with a suggestion, instead of `self.<ident>`.
+    pub(crate) strict: bool,
+    /// Errors in expressions are passed to the handler of `Template::render_lenient()`.
+    pub(crate) lenient: bool,
+    /// Warn about, or reject, `|safe` applied to a value read from the runtime values.
+    pub(crate) lint_safe_values: Option<LintLevel>,
+    /// Warnings that were found while generating the code.
+    pub(crate) warnings: RefCell<Vec<String>>,
+    /// Generate `render_async()` instead of implementing `Template`, and make `.await` work.
+    pub(crate) is_async: bool,
+    pub(crate) bom: bool,
+    pub(crate) trailing_newline: Option<TrailingNewline>,
+    /// The type of the global context, that can be accessed as `ctx` in the template.
+    pub(crate) context: Option<String>,
 }
 
 impl TemplateInput<'_> {
@@ -52,7 +73,16 @@ impl TemplateInput<'_> {
             escaping,
             ext,
             ext_span,
+            mime_type,
             syntax,
+            values_only,
+            strict,
+            lenient,
+            lint_safe_values,
+            is_async,
+            bom,
+            trailing_newline,
+            context,
             ..
         } = args;
 
@@ -86,31 +116,67 @@ impl TemplateInput<'_> {
             },
         )?;
 
+        // The extension that selects the escaper and the whitespace handling of the template. For
+        // a `source` template, the synthetic path ends with the `ext` argument, so only the last
+        // part of an `ext` like `html.j2` is used. For a `path` template, `ext` is ignored.
+        let extension = path.extension().and_then(|s| s.to_str());
+
         // Match extension against defined output formats
 
-        let escaping = escaping
-            .as_deref()
-            .or_else(|| path.extension().and_then(|s| s.to_str()))
-            .unwrap_or_default();
+        let escaping = match escaping {
+            Some(escaping) => Some(escaping.as_str()),
+            // Without auto-escaping, only templates with an explicit `escape` attribute get escaped.
+            None if !config.autoescape => Some("none"),
+            None => None,
+        };
+        let escaping = escaping.or(extension).unwrap_or_default();
 
-        let escaper = config
-            .escapers
-            .iter()
-            .find_map(|(extensions, path)| {
-                extensions
-                    .contains(&Cow::Borrowed(escaping))
-                    .then_some(path.as_ref())
-            })
-            .ok_or_else(|| {
-                CompileError::no_file_info(
-                    format_args!(
-                        "no escaper defined for extension '{escaping}'. You can define an escaper \
+        let escaper = config.find_escaper(escaping).ok_or_else(|| {
+            CompileError::no_file_info(
+                format_args!(
+                    "no escaper defined for extension '{escaping}'. You can define an escaper \
                         in the config file (named `askama.toml` by default). {}",
-                        MsgValidEscapers(&config.escapers),
-                    ),
-                    *ext_span,
-                )
-            })?;
+                    MsgValidEscapers(&config.escapers),
+                ),
+                *ext_span,
+            )
+        })?;
+
+        let whitespace = config.whitespace_for(extension.unwrap_or_default());
+
+        let mime_type = match mime_type {
+            Some(mime_type) => mime_type.as_str(),
+            None => match ext {
+                Some(ext) => mime_type_for_ext(ext),
+                None => mime_type_for_path(&path),
+            },
+        };
+
+        // An async template does not implement `Template`, and expressions cannot be `.await`ed
+        // inside of the closures that are generated for lenient templates.
+        if *is_async {
+            #[cfg(feature = "blocks")]
+            let has_blocks = !blocks.is_empty();
+            #[cfg(not(feature = "blocks"))]
+            let has_blocks = false;
+            let unsupported = if enum_ast.is_some() || matches!(ast.data, syn::Data::Enum(_)) {
+                Some("enums")
+            } else if block.is_some() || has_blocks {
+                Some("the arguments `block` and `blocks`")
+            } else if *lenient {
+                Some("the argument `lenient`")
+            } else if context.is_some() {
+                Some("the argument `context`")
+            } else {
+                None
+            };
+            if let Some(unsupported) = unsupported {
+                return Err(CompileError::no_file_info(
+                    format_args!("`async` templates do not support {unsupported}"),
+                    None,
+                ));
+            }
+        }
 
         let empty_punctuated = Punctuated::new();
         let fields = match ast.data {
@@ -126,10 +192,21 @@ impl TemplateInput<'_> {
         }
         .iter()
         .map(|f| match &f.ident {
-            Some(ident) => ident.to_string(),
+            Some(ident) => ident,
             None => unreachable!("we checked that we are using a struct"),
-        })
-        .collect::<Vec<_>>();
+        });
+        if let Some(context) = context {
+            if let Some(field) = fields.clone().find(|ident| *ident == "ctx") {
+                return Err(CompileError::no_file_info(
+                    format_args!(
+                        "the field `ctx` cannot be accessed, because the template accesses its \
+                        global context `{context}` as `ctx`; rename the field"
+                    ),
+                    Some(field.span()),
+                ));
+            }
+        }
+        let fields = fields.map(|ident| ident.to_string()).collect::<Vec<_>>();
 
         Ok(TemplateInput {
             ast,
@@ -143,8 +220,19 @@ impl TemplateInput<'_> {
             blocks: blocks.as_slice(),
             print: *print,
             escaper,
+            whitespace,
+            mime_type,
             path,
             fields: fields.into(),
+            values_only: *values_only,
+            strict: *strict,
+            lint_safe_values: *lint_safe_values,
+            warnings: RefCell::default(),
+            lenient: *lenient,
+            is_async: *is_async,
+            bom: *bom,
+            trailing_newline: *trailing_newline,
+            context: context.clone(),
         })
     }
 
@@ -260,9 +348,35 @@ impl TemplateInput<'_> {
                                 add_to_check(import)?;
                             }
                         }
+                        Node::FromImport(import) if top => {
+                            #[cfg(not(feature = "external-sources"))]
+                            {
+                                return node_needs_external_sources(
+                                    "from",
+                                    import.span(),
+                                    &path,
+                                    &parsed,
+                                );
+                            }
+                            #[cfg(feature = "external-sources")]
+                            {
+                                let import = self.config.find_template(
+                                    import.path,
+                                    Some(&path),
+                                    Some(FileInfo::of(import.span(), &path, &parsed)),
+                                )?;
+                                add_to_check(import)?;
+                            }
+                        }
                         Node::FilterBlock(f) => {
                             nested.push(&f.nodes);
                         }
+                        Node::With(w) => {
+                            nested.push(&w.nodes);
+                        }
+                        Node::Cfg(c) => {
+                            nested.push(&c.nodes);
+                        }
                         Node::Include(include) => {
                             #[cfg(not(feature = "external-sources"))]
                             {
@@ -306,7 +420,9 @@ impl TemplateInput<'_> {
                         | Node::Call(_)
                         | Node::Extends(_)
                         | Node::Let(_)
+                        | Node::Do(_)
                         | Node::Import(_)
+                        | Node::FromImport(_)
                         | Node::Macro(_)
                         | Node::Raw(_)
                         | Node::Continue(_)
@@ -419,10 +535,19 @@ pub(crate) struct TemplateArgs {
     escaping: Option<String>,
     ext: Option<String>,
     ext_span: Option<Span>,
+    mime_type: Option<String>,
     syntax: Option<String>,
     config: Option<String>,
     crate_name: Option<ExprPath>,
     pub(crate) whitespace: Option<Whitespace>,
+    values_only: bool,
+    strict: bool,
+    lenient: bool,
+    lint_safe_values: Option<LintLevel>,
+    is_async: bool,
+    bom: bool,
+    trailing_newline: Option<TrailingNewline>,
+    context: Option<String>,
     pub(crate) template_span: Option<Span>,
     pub(crate) config_span: Option<Span>,
 }
@@ -479,10 +604,19 @@ impl TemplateArgs {
             escaping: args.escape.map(|value| value.value()),
             ext: args.ext.as_ref().map(|value| value.value()),
             ext_span: args.ext.as_ref().map(|value| value.span()),
+            mime_type: args.mime_type.map(|value| value.value()),
             syntax: args.syntax.map(|value| value.value()),
             config: args.config.as_ref().map(|value| value.value()),
             crate_name: args.crate_name,
             whitespace: args.whitespace,
+            values_only: args.values_only.is_some_and(|value| value.value()),
+            strict: args.strict.is_some_and(|value| value.value()),
+            lenient: args.lenient.is_some_and(|value| value.value()),
+            lint_safe_values: args.lint_safe_values,
+            is_async: args.is_async.is_some_and(|value| value.value()),
+            bom: args.bom.is_some_and(|value| value.value()),
+            trailing_newline: args.trailing_newline,
+            context: args.context.map(|path| path.to_token_stream().to_string()),
             template_span: Some(args.template.span()),
             config_span: args.config.as_ref().map(|value| value.span()),
         })
@@ -498,20 +632,68 @@ impl TemplateArgs {
             escaping: None,
             ext: Some("txt".to_string()),
             ext_span: None,
+            mime_type: None,
             syntax: None,
             config: None,
             crate_name: None,
             whitespace: None,
+            values_only: false,
+            strict: false,
+            lenient: false,
+            lint_safe_values: None,
+            is_async: false,
+            bom: false,
+            trailing_newline: None,
+            context: None,
             template_span: None,
             config_span: None,
         }
     }
 
+    /// Arguments of an inline template of the `render!()` macro.
+    pub(crate) fn inline(source: &LitStr) -> Self {
+        Self {
+            source: (Source::Source(source.value().into()), Some(source.span())),
+            template_span: Some(source.span()),
+            ..Self::fallback()
+        }
+    }
+
     pub(crate) fn config_path(&self) -> Option<&str> {
         self.config.as_deref()
     }
 }
 
+/// The input of the `render!()` macro: the template source, followed by the template's variables,
+/// e.g. `render!("Hello {{ name }}", name = "world")`.
+pub(crate) struct RenderArgs {
+    pub(crate) source: LitStr,
+    pub(crate) variables: Vec<(Ident, Expr)>,
+}
+
+impl Parse for RenderArgs {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let source = input.parse()?;
+        let mut variables = Vec::<(Ident, Expr)>::new();
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let name: Ident = input.parse()?;
+            if variables.iter().any(|(other, _)| *other == name) {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format_args!("variable `{name}` is assigned more than once"),
+                ));
+            }
+            input.parse::<Token![=]>()?;
+            variables.push((name, input.parse()?));
+        }
+        Ok(Self { source, variables })
+    }
+}
+
 /// Try to find the source in the comment, in a `askama` code block.
 ///
 /// This is only done if no path or source was given in the `#[template]` attribute.
@@ -639,13 +821,12 @@ fn collect_askama_code_blocks(
     let mut had_askama_code = false;
     for e in Parser::new(&source) {
         match (in_askama_code, e) {
-            (false, Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(s)))) => {
+            (false, Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(s))))
                 if s.split(",")
-                    .any(|s| JINJA_EXTENSIONS.contains(&s.trim_ascii()))
-                {
-                    in_askama_code = true;
-                    had_askama_code = true;
-                }
+                    .any(|s| JINJA_EXTENSIONS.contains(&s.trim_ascii())) =>
+            {
+                in_askama_code = true;
+                had_askama_code = true;
             }
             (true, Event::End(TagEnd::CodeBlock)) => in_askama_code = false,
             (true, Event::Text(text)) => tmpl_source.push_str(&text),
@@ -662,30 +843,6 @@ fn collect_askama_code_blocks(
     Ok(Source::Source(tmpl_source.into()))
 }
 
-struct ResultIter<I, E>(Result<I, Option<E>>);
-
-impl<I: IntoIterator, E> From<Result<I, E>> for ResultIter<I::IntoIter, E> {
-    fn from(value: Result<I, E>) -> Self {
-        Self(match value {
-            Ok(i) => Ok(i.into_iter()),
-            Err(e) => Err(Some(e)),
-        })
-    }
-}
-
-impl<I: Iterator, E> Iterator for ResultIter<I, E> {
-    type Item = Result<I::Item, E>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        match &mut self.0 {
-            Ok(iter) => Some(Ok(iter.next()?)),
-            Err(err) => Some(Err(err.take()?)),
-        }
-    }
-}
-
-impl<I: FusedIterator, E> FusedIterator for ResultIter<I, E> {}
-
 #[derive(Debug, Clone, Hash, PartialEq)]
 pub(crate) enum Source {
     #[cfg(feature = "external-sources")]
@@ -693,20 +850,15 @@ pub(crate) enum Source {
     Source(Arc<str>),
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Hash)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Hash)]
 pub(crate) enum Print {
     All,
     Ast,
     Code,
+    #[default]
     None,
 }
 
-impl Default for Print {
-    fn default() -> Self {
-        Self::None
-    }
-}
-
 impl FromStr for Print {
     type Err = String;
 
@@ -721,6 +873,59 @@ impl FromStr for Print {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Hash)]
+pub(crate) enum TrailingNewline {
+    Ensure,
+    Trim,
+}
+
+impl TrailingNewline {
+    /// The path of the matching variant of `askama::TrailingNewline`.
+    pub(crate) fn as_path(self) -> &'static str {
+        match self {
+            Self::Ensure => "askama::TrailingNewline::Ensure",
+            Self::Trim => "askama::TrailingNewline::Trim",
+        }
+    }
+}
+
+impl FromStr for TrailingNewline {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ensure" => Ok(Self::Ensure),
+            "trim" => Ok(Self::Trim),
+            _ => Err(format!(
+                "invalid value for `trailing_newline` option: {s}, expected \"ensure\" or \"trim\""
+            )),
+        }
+    }
+}
+
+/// How a template attribute like `lint_safe_values` reports its findings.
+#[derive(Clone, Copy, Debug, PartialEq, Hash)]
+pub(crate) enum LintLevel {
+    /// Emit a compiler warning.
+    Warn,
+    /// Make it a compile error.
+    Deny,
+}
+
+impl FromStr for LintLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "warn" => Ok(Self::Warn),
+            "deny" => Ok(Self::Deny),
+            _ => Err(format!(
+                "invalid value for `lint_safe_values` option: {s}, expected \"warn\" or \"deny\""
+            )),
+        }
+    }
+}
+
 #[cfg(feature = "external-sources")]
 fn cyclic_graph_error(dependency_graph: &[(Arc<Path>, Arc<Path>)]) -> Result<(), CompileError> {
     Err(CompileError::no_file_info(
@@ -768,6 +973,44 @@ pub(crate) fn get_template_source(
         )
 }
 
+/// Determines the MIME type of a template by the extension of its path.
+///
+/// For a path like `index.html.j2`, the extension in front of the jinja extension is used.
+pub(crate) fn mime_type_for_path(path: &Path) -> &'static str {
+    let Some(ext) = path.extension().and_then(|s| s.to_str()) else {
+        return DEFAULT_MIME_TYPE;
+    };
+    if JINJA_EXTENSIONS.contains(&ext) {
+        if let Some(stem) = path.file_stem().map(Path::new) {
+            if stem.extension().is_some() {
+                return mime_type_for_path(stem);
+            }
+        }
+    }
+    match ext.to_ascii_lowercase().as_str() {
+        "html" | "htm" | "askama" | "j2" | "jinja" | "jinja2" | "rinja" => "text/html",
+        "xhtml" => "application/xhtml+xml",
+        "xml" => "application/xml",
+        "svg" => "image/svg+xml",
+        "txt" => "text/plain",
+        "md" => "text/markdown",
+        "css" => "text/css",
+        "csv" => "text/csv",
+        "js" | "mjs" => "text/javascript",
+        "json" => "application/json",
+        "toml" => "application/toml",
+        "yaml" | "yml" => "application/yaml",
+        _ => DEFAULT_MIME_TYPE,
+    }
+}
+
+/// Determines the MIME type of a template by its `ext` argument.
+pub(crate) fn mime_type_for_ext(ext: &str) -> &'static str {
+    mime_type_for_path(Path::new(&format!("_.{ext}")))
+}
+
+const DEFAULT_MIME_TYPE: &str = "application/octet-stream";
+
 pub(crate) struct PartialTemplateArgs {
     pub(crate) template: Ident,
     pub(crate) source: Option<PartialTemplateArgsSource>,
@@ -775,9 +1018,18 @@ pub(crate) struct PartialTemplateArgs {
     pub(crate) print: Option<Print>,
     pub(crate) escape: Option<LitStr>,
     pub(crate) ext: Option<LitStr>,
+    pub(crate) mime_type: Option<LitStr>,
     pub(crate) syntax: Option<LitStr>,
     pub(crate) config: Option<LitStr>,
     pub(crate) whitespace: Option<Whitespace>,
+    pub(crate) values_only: Option<LitBool>,
+    pub(crate) strict: Option<LitBool>,
+    pub(crate) lenient: Option<LitBool>,
+    pub(crate) lint_safe_values: Option<LintLevel>,
+    pub(crate) is_async: Option<LitBool>,
+    pub(crate) bom: Option<LitBool>,
+    pub(crate) trailing_newline: Option<TrailingNewline>,
+    pub(crate) context: Option<ExprPath>,
     pub(crate) crate_name: Option<ExprPath>,
     #[cfg(feature = "blocks")]
     pub(crate) blocks: Option<Vec<LitStr>>,
@@ -792,6 +1044,29 @@ pub(crate) enum PartialTemplateArgsSource {
     InDoc(Span, Source),
 }
 
+impl PartialTemplateArgs {
+    /// The MIME type of the template, if it can be determined without resolving its path.
+    pub(crate) fn mime_type(&self) -> Option<String> {
+        if let Some(mime_type) = &self.mime_type {
+            return Some(mime_type.value());
+        }
+        let mime_type = match (&self.source, &self.ext) {
+            #[cfg(feature = "external-sources")]
+            (Some(PartialTemplateArgsSource::Path(path)), _) => {
+                mime_type_for_path(Path::new(&path.value()))
+            }
+            (_, Some(ext)) => mime_type_for_ext(&ext.value()),
+            _ => return None,
+        };
+        Some(mime_type.to_owned())
+    }
+
+    /// Whether `bom = true` was set.
+    pub(crate) fn bom(&self) -> bool {
+        self.bom.as_ref().is_some_and(|value| value.value())
+    }
+}
+
 impl PartialTemplateArgsSource {
     pub(crate) fn span(&self) -> Span {
         match self {
@@ -841,9 +1116,18 @@ const _: () = {
             print: None,
             escape: None,
             ext: None,
+            mime_type: None,
             syntax: None,
             config: None,
             whitespace: None,
+            values_only: None,
+            strict: None,
+            lenient: None,
+            lint_safe_values: None,
+            is_async: None,
+            bom: None,
+            trailing_newline: None,
+            context: None,
             crate_name: None,
             #[cfg(feature = "blocks")]
             blocks: None,
@@ -866,7 +1150,9 @@ const _: () = {
             }
 
             let args = attr
-                .parse_args_with(<Punctuated<Meta, Token![,]>>::parse_terminated)
+                .parse_args_with(|input: ParseStream<'_>| {
+                    Punctuated::<Meta, Token![,]>::parse_terminated_with(input, parse_template_arg)
+                })
                 .map_err(|e| {
                     CompileError::no_file_info(
                         format_args!("unable to parse template arguments: {e}"),
@@ -876,6 +1162,15 @@ const _: () = {
             for arg in args {
                 let pair = match arg {
                     Meta::NameValue(pair) => pair,
+                    // `async` is short for `async = true`
+                    Meta::Path(path) if path.is_ident("async") => MetaNameValue {
+                        eq_token: Token![=](path.span()),
+                        value: Expr::Lit(ExprLit {
+                            attrs: vec![],
+                            lit: Lit::Bool(LitBool::new(true, path.span())),
+                        }),
+                        path,
+                    },
                     v => {
                         return Err(CompileError::no_file_info(
                             "unsupported attribute argument",
@@ -899,6 +1194,17 @@ const _: () = {
                     ensure_only_once(ident, &mut this.crate_name)?;
                     this.crate_name = Some(get_exprpath(ident, pair.value)?);
                     continue;
+                } else if ident == "context" {
+                    if is_enum_variant {
+                        return Err(CompileError::no_file_info(
+                            "template attribute `context` can only be used on the `enum`, \
+                            not its variants",
+                            Some(ident.span()),
+                        ));
+                    }
+                    ensure_only_once(ident, &mut this.context)?;
+                    this.context = Some(get_exprpath(ident, pair.value)?);
+                    continue;
                 } else if ident == "blocks" {
                     if !cfg!(feature = "blocks") {
                         return Err(CompileError::no_file_info(
@@ -976,12 +1282,60 @@ const _: () = {
                     set_strlit_pair(ident, value, &mut this.escape)?;
                 } else if ident == "ext" {
                     set_strlit_pair(ident, value, &mut this.ext)?;
+                } else if ident == "mime_type" {
+                    set_strlit_pair(ident, value, &mut this.mime_type)?;
                 } else if ident == "syntax" {
                     set_strlit_pair(ident, value, &mut this.syntax)?;
                 } else if ident == "config" {
                     set_strlit_pair(ident, value, &mut this.config)?;
                 } else if ident == "whitespace" {
                     set_parseable_string(ident, value, &mut this.whitespace)?;
+                } else if ident == "values_only" {
+                    ensure_only_once(ident, &mut this.values_only)?;
+                    this.values_only = Some(get_boollit(ident, value)?);
+                } else if ident == "strict" {
+                    ensure_only_once(ident, &mut this.strict)?;
+                    this.strict = Some(get_boollit(ident, value)?);
+                } else if ident == "lenient" {
+                    if !cfg!(feature = "alloc") {
+                        return Err(CompileError::no_file_info(
+                            "template attribute `lenient` requires the `alloc` feature to be \
+                            enabled",
+                            Some(ident.span()),
+                        ));
+                    }
+                    ensure_only_once(ident, &mut this.lenient)?;
+                    this.lenient = Some(get_boollit(ident, value)?);
+                } else if ident == "lint_safe_values" {
+                    set_parseable_string(ident, value, &mut this.lint_safe_values)?;
+                } else if ident == "async" {
+                    if !cfg!(feature = "async") {
+                        return Err(CompileError::no_file_info(
+                            "enable feature `async` to use `async` argument",
+                            Some(ident.span()),
+                        ));
+                    }
+                    ensure_only_once(ident, &mut this.is_async)?;
+                    this.is_async = Some(get_boollit(ident, value)?);
+                } else if ident == "bom" {
+                    if is_enum_variant {
+                        return Err(CompileError::no_file_info(
+                            "template attribute `bom` can only be used on the `enum`, \
+                            not its variants",
+                            Some(ident.span()),
+                        ));
+                    }
+                    ensure_only_once(ident, &mut this.bom)?;
+                    this.bom = Some(get_boollit(ident, value)?);
+                } else if ident == "trailing_newline" {
+                    if is_enum_variant {
+                        return Err(CompileError::no_file_info(
+                            "template attribute `trailing_newline` can only be used on the \
+                            `enum`, not its variants",
+                            Some(ident.span()),
+                        ));
+                    }
+                    set_parseable_string(ident, value, &mut this.trailing_newline)?;
                 } else {
                     return Err(CompileError::no_file_info(
                         format_args!("unsupported template attribute `{ident}` found"),
@@ -1032,6 +1386,25 @@ const _: () = {
         Ok(())
     }
 
+    /// Parses an argument of the `template` attribute.
+    ///
+    /// `syn` does not accept keywords as the name of a [`Meta`], so `async` is parsed manually.
+    fn parse_template_arg(input: ParseStream<'_>) -> syn::Result<Meta> {
+        if !input.peek(Token![async]) {
+            return input.parse();
+        }
+        let token: Token![async] = input.parse()?;
+        let path = syn::Path::from(Ident::new("async", token.span));
+        if !input.peek(Token![=]) {
+            return Ok(Meta::Path(path));
+        }
+        Ok(Meta::NameValue(MetaNameValue {
+            path,
+            eq_token: input.parse()?,
+            value: input.parse()?,
+        }))
+    }
+
     fn ensure_only_once<T>(name: &Ident, dest: &mut Option<T>) -> Result<(), CompileError> {
         if dest.is_none() {
             Ok(())
@@ -1128,7 +1501,6 @@ const _: () = {
     }
 };
 
-#[cfg(feature = "code-in-doc")]
 const JINJA_EXTENSIONS: &[&str] = &["askama", "j2", "jinja", "jinja2", "rinja"];
 
 #[test]
@@ -1139,3 +1511,29 @@ fn get_source() {
         .unwrap();
     assert_eq!(get_template_source(&path, None).unwrap(), "bar".into());
 }
+
+#[test]
+fn mime_types() {
+    for (path, expected) in [
+        ("index.html", "text/html"),
+        ("index.HTM", "text/html"),
+        ("data.json", "application/json"),
+        ("readme.txt", "text/plain"),
+        ("index.html.j2", "text/html"),
+        ("data.json.jinja", "application/json"),
+        ("base.jinja", "text/html"),
+        ("doc.tex", "application/octet-stream"),
+        ("no-extension", "application/octet-stream"),
+    ] {
+        assert_eq!(mime_type_for_path(Path::new(path)), expected, "{path}");
+    }
+    for (ext, expected) in [
+        ("html", "text/html"),
+        ("JSON", "application/json"),
+        ("html.j2", "text/html"),
+        ("j2", "text/html"),
+        ("tex", "application/octet-stream"),
+    ] {
+        assert_eq!(mime_type_for_ext(ext), expected, "{ext}");
+    }
+}
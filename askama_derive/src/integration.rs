@@ -1,7 +1,7 @@
 use std::fmt::{Arguments, Display, Write};
 
-use proc_macro2::{TokenStream, TokenTree};
-use quote::{ToTokens, quote};
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{ToTokens, quote, quote_spanned};
 use syn::spanned::Spanned;
 use syn::{
     Data, DeriveInput, Fields, GenericParam, Generics, Ident, Lifetime, LifetimeParam, Token, Type,
@@ -31,6 +31,60 @@ pub(crate) fn write_header(ast: &DeriveInput, buf: &mut Buffer, target: impl Dis
     ));
 }
 
+/// Implement `render_with_context()` and `render_into_with_context()` as inherent methods of the
+/// given item, so that only a `context` of the declared type compiles.
+pub(crate) fn impl_render_with_context(ast: &DeriveInput, buf: &mut Buffer, context: &str) {
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let ident = &ast.ident;
+    buf.write(format_args!(
+        "#[allow(dead_code)]\
+        impl {} {} {{",
+        quote!(#impl_generics),
+        quote!(#ident #ty_generics #where_clause),
+    ));
+    #[cfg(feature = "alloc")]
+    buf.write(format_args!(
+        "\
+        #[doc = \"Renders the template into a new `String` with the typed global context.\"]\
+        #[inline]\
+        pub fn render_with_context(\
+            &self,\
+            context: &{context},\
+        ) -> askama::Result<askama::helpers::alloc::string::String> {{\
+            askama::Template::render_with_values(\
+                self,\
+                &(\
+                    askama::helpers::CONTEXT_KEY,\
+                    context as &dyn askama::helpers::core::any::Any,\
+                ),\
+            )\
+        }}",
+    ));
+    buf.write(format_args!(
+        "\
+        #[doc = \"Renders the template into the given `writer` with the typed global context.\"]\
+        #[inline]\
+        pub fn render_into_with_context<AskamaW>(\
+            &self,\
+            writer: &mut AskamaW,\
+            context: &{context},\
+        ) -> askama::Result<()>\
+        where \
+            AskamaW: askama::helpers::core::fmt::Write + ?askama::helpers::core::marker::Sized\
+        {{\
+            askama::Template::render_into_with_values(\
+                self,\
+                writer,\
+                &(\
+                    askama::helpers::CONTEXT_KEY,\
+                    context as &dyn askama::helpers::core::any::Any,\
+                ),\
+            )\
+        }}\
+        }}",
+    ));
+}
+
 /// Implement `Display` for the given item.
 fn impl_display(ast: &DeriveInput, buf: &mut Buffer) {
     let ident = &ast.ident;
@@ -84,6 +138,8 @@ pub(crate) struct Buffer {
     buf: String,
     discard: bool,
     last_was_write_str: bool,
+    /// Warnings about the template, and the span to report them at.
+    warnings: Vec<(String, Span)>,
 }
 
 impl Display for Buffer {
@@ -98,9 +154,37 @@ impl Buffer {
             buf: String::new(),
             discard: false,
             last_was_write_str: false,
+            warnings: Vec::new(),
         }
     }
 
+    pub(crate) fn add_warning(&mut self, msg: String, span: Span) {
+        self.warnings.push((msg, span));
+    }
+
+    /// Reports the collected warnings.
+    ///
+    /// There is no stable API to emit warnings from a proc-macro, so every warning is a
+    /// deprecated constant that is used right away, with the warning as deprecation note.
+    /// The span must point into the user's code, because rustc does not report deprecations
+    /// inside of the code that was generated by a derive macro.
+    pub(crate) fn warnings_to_tokens(&self) -> TokenStream {
+        self.warnings
+            .iter()
+            .map(|(msg, span)| {
+                quote_spanned! {
+                    *span =>
+                    const _: () = {
+                        #[deprecated(note = #msg)]
+                        #[allow(non_upper_case_globals)]
+                        const __askama_warning: () = ();
+                        __askama_warning
+                    };
+                }
+            })
+            .collect()
+    }
+
     pub(crate) fn as_str(&self) -> &str {
         &self.buf
     }
@@ -275,7 +359,14 @@ pub(crate) fn build_template_enum(
         .params
         .insert(0, GenericParam::Lifetime(LifetimeParam::new(lifetime)));
 
+    let bom = enum_args.as_ref().is_some_and(|args| args.bom());
+    let trailing_newline = enum_args.as_ref().and_then(|args| args.trailing_newline);
+    let context = enum_args
+        .as_ref()
+        .and_then(|args| args.context.as_ref())
+        .map(|path| path.to_token_stream().to_string());
     let mut biggest_size_hint = 0;
+    let mut mime_types = vec![];
     let mut render_into_arms = TokenStream::new();
     let mut size_hint_arms = TokenStream::new();
     for (var, var_args) in enum_data.variants.iter().zip(vars_args) {
@@ -291,10 +382,18 @@ pub(crate) fn build_template_enum(
             set_default(&mut var_args, enum_args, |v| &mut v.source);
             set_default(&mut var_args, enum_args, |v| &mut v.escape);
             set_default(&mut var_args, enum_args, |v| &mut v.ext);
+            set_default(&mut var_args, enum_args, |v| &mut v.mime_type);
             set_default(&mut var_args, enum_args, |v| &mut v.syntax);
             set_default(&mut var_args, enum_args, |v| &mut v.config);
             set_default(&mut var_args, enum_args, |v| &mut v.whitespace);
+            set_default(&mut var_args, enum_args, |v| &mut v.values_only);
+            set_default(&mut var_args, enum_args, |v| &mut v.strict);
+            set_default(&mut var_args, enum_args, |v| &mut v.lenient);
+            set_default(&mut var_args, enum_args, |v| &mut v.lint_safe_values);
+            set_default(&mut var_args, enum_args, |v| &mut v.is_async);
+            set_default(&mut var_args, enum_args, |v| &mut v.context);
         }
+        mime_types.push(var_args.mime_type());
         let size_hint = biggest_size_hint.max(build_template_item(
             buf,
             &var_ast,
@@ -313,6 +412,7 @@ pub(crate) fn build_template_enum(
         );
     }
     if has_default_impl {
+        mime_types.push(enum_args.as_ref().and_then(|args| args.mime_type()));
         let size_hint = build_template_item(
             buf,
             enum_ast,
@@ -368,15 +468,40 @@ pub(crate) fn build_template_enum(
             let mut buf = askama::helpers::alloc::string::String::new();\
             let _ = buf.try_reserve(size_hint);\
             self.render_into_with_values(&mut buf, __askama_values)?;\
+            askama::helpers::apply_trailing_newline(\
+                &mut buf,\
+                <Self as askama::Template>::TRAILING_NEWLINE,\
+            );\
             askama::Result::Ok(buf)\
         }}",
     ));
 
     buf.write(format_args!(
         "\
-        const SIZE_HINT: askama::helpers::core::primitive::usize = {biggest_size_hint}usize;\
-        }}",
+        const SIZE_HINT: askama::helpers::core::primitive::usize = {biggest_size_hint}usize;",
     ));
+    // The enum only gets a MIME type if all its variants agree on it.
+    if let Some((Some(mime_type), others)) = mime_types.split_first() {
+        if others.iter().all(|other| other.as_ref() == Some(mime_type)) {
+            buf.write(format_args!(
+                "const MIME_TYPE: &'static askama::helpers::core::primitive::str = {mime_type:?};",
+            ));
+        }
+    }
+    if bom {
+        buf.write("const BOM: askama::helpers::core::primitive::bool = true;");
+    }
+    if let Some(trailing_newline) = trailing_newline {
+        buf.write(format_args!(
+            "const TRAILING_NEWLINE: askama::TrailingNewline = {};",
+            trailing_newline.as_path(),
+        ));
+    }
+    buf.write('}');
+
+    if let Some(context) = context {
+        impl_render_with_context(enum_ast, buf, &context);
+    }
     Ok(biggest_size_hint)
 }
 
@@ -437,7 +562,7 @@ fn type_for_enum_variant(
         _ => Some(Token![;](span)),
     };
 
-    parse_quote! {
+    let mut var_ast: DeriveInput = parse_quote! {
         #[askama::helpers::core::prelude::rust_2021::derive(
             askama::helpers::core::prelude::rust_2021::Clone,
             askama::helpers::core::prelude::rust_2021::Copy,
@@ -445,7 +570,10 @@ fn type_for_enum_variant(
         )]
         #[allow(dead_code, non_camel_case_types, non_snake_case)]
         struct #id #enum_generics #fields #semicolon
-    }
+    };
+    // Quoting `Generics` does not include the `where` clause.
+    var_ast.generics.where_clause = enum_generics.where_clause.clone();
+    var_ast
 }
 
 /// Generates a `match` arm for an `enum` variant, that calls `<_ as EnumVariantTemplate>::render_into()`
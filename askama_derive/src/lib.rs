@@ -34,9 +34,9 @@ use quote::{quote, quote_spanned};
 use rustc_hash::FxBuildHasher;
 
 use crate::config::{Config, read_config_file};
-use crate::generator::{TmplKind, template_to_string};
+use crate::generator::{TmplKind, template_to_inline_string, template_to_string};
 use crate::heritage::{Context, Heritage};
-use crate::input::{AnyTemplateArgs, Print, TemplateArgs, TemplateInput};
+use crate::input::{AnyTemplateArgs, Print, RenderArgs, TemplateArgs, TemplateInput};
 use crate::integration::{Buffer, build_template_enum};
 
 /// [`true`] if and only if [`crate`] is compiled with feature `"external-sources"`.
@@ -128,6 +128,8 @@ macro_rules! make_derive_template {
         /// inheritance is also supported. This can be useful when you need to
         /// decompose your template for partial rendering, without needing to
         /// extract the partial into a separate template or macro.
+        /// Another struct can still render the whole file. Naming a block
+        /// that does not exist is a compile-time error.
         ///
         /// ```rust,ignore
         /// #[derive(Template)]
@@ -142,7 +144,7 @@ macro_rules! make_derive_template {
         /// Automatically generates (a number of) sub-templates that act as if they had a
         /// `block = "..."` attribute. You can access the sub-templates with the method
         /// <code>my_template.as_<em>block_name</em>()</code>, where *`block_name`* is the
-        /// name of the block:
+        /// name of the block. Naming a block that does not exist is a compile-time error:
         ///
         /// ```rust,ignore
         /// # use askama::Template;
@@ -177,6 +179,66 @@ macro_rules! make_derive_template {
         /// Override the template's extension used for the purpose of determining the escaper for
         /// this template. See the section on configuring custom escapers for more information.
         ///
+        /// ### mime_type
+        ///
+        /// E.g. `mime_type = "application/atom+xml"`
+        ///
+        /// Override the MIME type of the template, `Template::MIME_TYPE`, which is otherwise
+        /// determined by the template's extension, e.g. `"text/html"` for `.html` files.
+        ///
+        /// ### bom
+        ///
+        /// E.g. `bom = true`
+        ///
+        /// Write a UTF-8 byte order mark before the rendered content, `Template::BOM`. It is only
+        /// written by `write_into()` and `render_into_bytes()`, not when rendering into a `String`.
+        ///
+        /// ### trailing_newline
+        ///
+        /// E.g. `trailing_newline = "ensure"`
+        ///
+        /// Post-process the output of `render()`, `Template::TRAILING_NEWLINE`: `"ensure"` makes it
+        /// end with exactly one newline, `"trim"` removes all trailing newlines.
+        ///
+        /// ### context
+        ///
+        /// E.g. `context = crate::Globals`
+        ///
+        /// The type of the global context. The derive macro implements the inherent methods
+        /// `render_with_context(&self, ctx: &Type)` and `render_into_with_context()`, so passing
+        /// a context of another type does not compile. The template accesses it as `ctx`, and so
+        /// do its sub-templates. The struct must not have a field named `ctx`.
+        ///
+        /// ### strict
+        ///
+        /// E.g. `strict = true`
+        ///
+        /// Make it a compile error to use an identifier that is neither a variable of the template
+        /// nor a field of the struct. The error suggests a similarly named variable or field.
+        ///
+        /// ### lenient
+        ///
+        /// E.g. `lenient = true`
+        ///
+        /// When the template is rendered with `Template::render_lenient()`, errors in expressions
+        /// are passed to the error handler, and its output is written instead of the expression.
+        ///
+        /// ### lint_safe_values
+        ///
+        /// E.g. `lint_safe_values = "warn"` or `lint_safe_values = "deny"`
+        ///
+        /// Report `|safe` applied directly to a value of the runtime values, e.g.
+        /// `{{ askama::get_value::<String>("x")? | safe }}`, because such values are often untrusted.
+        /// `"warn"` emits a compiler warning, `"deny"` makes it a compile error.
+        ///
+        /// ### async
+        ///
+        /// E.g. `async` or `async = true`
+        ///
+        /// Generate the methods `render_async()`, `render_async_with_values()` and
+        /// `render_into_async_with_values()` instead of implementing `Template`. In the template,
+        /// `.await` awaits an expression, e.g. `{{ title().await }}`. Requires the feature `async`.
+        ///
         /// ### syntax
         ///
         /// E.g. `syntax = "foo"`
@@ -227,6 +289,45 @@ macro_rules! make_derive_template {
     };
 }
 
+#[macro_export]
+#[cfg(feature = "proc-macro")]
+macro_rules! make_render {
+    (
+        $(#[$meta:meta])*
+        $vis:vis fn $name:ident() {
+            $($import:stmt)+
+        }
+    ) => {
+        /// Renders an inline template without declaring a struct.
+        ///
+        /// The first argument is the template source, followed by the variables of the template
+        /// as `name = value` pairs. The macro expands to an expression of type
+        /// `askama::Result<String>`, just like `Template::render()` would return.
+        ///
+        /// ```rust,ignore
+        /// # use askama::render;
+        /// let rendered = render!("Hello, {{ name }}!", name = "world").unwrap();
+        /// assert_eq!(rendered, "Hello, world!");
+        /// ```
+        ///
+        /// The variables are moved into the template, so pass a reference if a value is needed
+        /// after the call, or if it is used more than once in a `{% for %}` loop.
+        /// The template is not escaped, because there is no file extension to select an escaper;
+        /// use the `escape` filter with an explicit escaper,
+        /// e.g. `{{ text|escape("html") }}`, if needed.
+        $(#[$meta])*
+        $vis fn $name(
+            input: $crate::__macro_support::TokenStream1,
+        ) -> $crate::__macro_support::TokenStream1 {
+            fn import_askama() -> $crate::__macro_support::TokenStream2 {
+                $crate::__macro_support::quote!($($import)*)
+            }
+
+            $crate::render(input.into(), import_askama).into()
+        }
+    };
+}
+
 pub fn derive_template(input: TokenStream, import_askama: fn() -> TokenStream) -> TokenStream {
     let ast = match syn::parse2(input) {
         Ok(ast) => ast,
@@ -242,6 +343,7 @@ pub fn derive_template(input: TokenStream, import_askama: fn() -> TokenStream) -
         }
     };
 
+    config::start_derive();
     let mut buf = Buffer::new();
     let mut args = AnyTemplateArgs::new(&ast);
     let crate_name = args
@@ -252,19 +354,9 @@ pub fn derive_template(input: TokenStream, import_askama: fn() -> TokenStream) -
     let ts = args
         .and_then(|args| build_template(&mut buf, &ast, args))
         .map(|_| {
-            let src = buf.as_str();
-            match src.parse() {
-                Ok(ts) => ts,
-                Err(err) => panic!(
-                    "Unparsable code was generated. Please report this bug to us: \
-                    <https://github.com/askama-rs/askama/issues>\n\n\
-                    Error: {err}\n\n\
-                    Generated source:\n\
-                    ------------------------------------------------\n\
-                    {src:?}\n\
-                    ------------------------------------------------\n\n"
-                ),
-            }
+            let mut ts = parse_generated_code(buf.as_str());
+            ts.extend(buf.warnings_to_tokens());
+            ts
         })
         .unwrap_or_else(|CompileError { msg, span }| {
             let mut ts = quote_spanned! {
@@ -290,6 +382,51 @@ pub fn derive_template(input: TokenStream, import_askama: fn() -> TokenStream) -
     }
 }
 
+pub fn render(input: TokenStream, import_askama: fn() -> TokenStream) -> TokenStream {
+    let args: RenderArgs = match syn::parse2(input) {
+        Ok(args) => args,
+        Err(err) => {
+            let err = err.into_compile_error();
+            return quote!({ #err });
+        }
+    };
+
+    config::start_derive();
+    let mut buf = Buffer::new();
+    let ts = match build_inline_template(&mut buf, &args) {
+        Ok(_) => parse_generated_code(buf.as_str()),
+        Err(CompileError { msg, span }) => quote_spanned! {
+            span.unwrap_or(args.source.span()) =>
+            askama::helpers::core::compile_error!(#msg)
+        },
+    };
+    let import_askama = import_askama();
+    let names = args.variables.iter().map(|(name, _)| name);
+    let values = args.variables.iter().map(|(_, value)| value);
+    quote! {
+        {
+            #import_askama
+            #(let #names = #values;)*
+            #ts
+        }
+    }
+}
+
+fn parse_generated_code(src: &str) -> TokenStream {
+    match src.parse() {
+        Ok(ts) => ts,
+        Err(err) => panic!(
+            "Unparsable code was generated. Please report this bug to us: \
+            <https://github.com/askama-rs/askama/issues>\n\n\
+            Error: {err}\n\n\
+            Generated source:\n\
+            ------------------------------------------------\n\
+            {src:?}\n\
+            ------------------------------------------------\n\n"
+        ),
+    }
+}
+
 fn build_skeleton(buf: &mut Buffer, ast: &syn::DeriveInput) -> Result<usize, CompileError> {
     let template_args = TemplateArgs::fallback();
     let config = Config::new("", None, None, None, None)?;
@@ -364,26 +501,42 @@ fn build_template_item(
     for (path, parsed) in &templates {
         contexts.insert(path, Context::new(input.config, path, parsed)?);
     }
+    for ctx in contexts.values() {
+        ctx.ensure_imported_macros_exist(&contexts)?;
+    }
 
     let ctx = &contexts[&input.path];
     let heritage = if !ctx.blocks.is_empty() || ctx.extends.is_some() {
-        Some(Heritage::new(ctx, &contexts))
+        Some(Heritage::new(
+            ctx,
+            &contexts,
+            input.config.max_include_depth,
+        )?)
     } else {
         None
     };
 
+    let has_block = |block_name: &str| match &heritage {
+        Some(heritage) => heritage.blocks.contains_key(block_name),
+        None => ctx.blocks.contains_key(block_name),
+    };
     if let Some((block_name, block_span)) = input.block {
-        let has_block = match &heritage {
-            Some(heritage) => heritage.blocks.contains_key(block_name),
-            None => ctx.blocks.contains_key(block_name),
-        };
-        if !has_block {
+        if !has_block(block_name) {
             return Err(CompileError::no_file_info(
                 format_args!("cannot find block `{block_name}`"),
                 Some(block_span),
             ));
         }
     }
+    #[cfg(feature = "blocks")]
+    for block in input.blocks {
+        if !has_block(&block.name) {
+            return Err(CompileError::no_file_info(
+                format_args!("cannot find block `{}`", block.name),
+                Some(block.span),
+            ));
+        }
+    }
 
     if input.print == Print::Ast || input.print == Print::All {
         eprintln!("{:?}", templates[&input.path].nodes());
@@ -394,9 +547,63 @@ fn build_template_item(
     if input.print == Print::Code || input.print == Print::All {
         eprintln!("{}", buf.marked_text(mark));
     }
+    let warning_span = input.source_span.unwrap_or_else(|| ast.ident.span());
+    for warning in input.warnings.take() {
+        buf.add_warning(warning, warning_span);
+    }
     Ok(size_hint)
 }
 
+/// Generates the expression of the `render!()` macro
+///
+/// The template source is compiled like the source of a `#[derive(Template)]` struct without any
+/// fields. Instead of fields, the template accesses the variables that were passed to the macro.
+fn build_inline_template(buf: &mut Buffer, args: &RenderArgs) -> Result<usize, CompileError> {
+    let ast: syn::DeriveInput = syn::parse_quote!(
+        struct __AskamaRender;
+    );
+    let template_args = TemplateArgs::inline(&args.source);
+    let config_path = template_args.config_path();
+    let (s, full_config_path) = read_config_file(config_path, template_args.config_span)?;
+    let config = Config::new(
+        &s,
+        config_path,
+        template_args.whitespace,
+        template_args.config_span,
+        full_config_path,
+    )?;
+    let input = TemplateInput::new(&ast, None, config, &template_args)?;
+
+    let mut templates = HashMap::default();
+    input.find_used_templates(&mut templates)?;
+
+    let mut contexts = HashMap::default();
+    for (path, parsed) in &templates {
+        contexts.insert(path, Context::new(input.config, path, parsed)?);
+    }
+    for ctx in contexts.values() {
+        ctx.ensure_imported_macros_exist(&contexts)?;
+    }
+
+    let ctx = &contexts[&input.path];
+    let heritage = if !ctx.blocks.is_empty() || ctx.extends.is_some() {
+        Some(Heritage::new(
+            ctx,
+            &contexts,
+            input.config.max_include_depth,
+        )?)
+    } else {
+        None
+    };
+
+    let variables = args
+        .variables
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .collect::<Vec<_>>();
+    template_to_inline_string(buf, &input, &contexts, heritage.as_ref(), &variables)
+}
+
 #[derive(Debug, Clone)]
 struct CompileError {
     msg: String,
@@ -563,7 +770,7 @@ impl<K: Hash + Eq, V> OnceMap<K, V> {
     ) -> Result<T, E>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
+        Q: Hash + Eq + ?Sized,
     {
         let shard_idx = (FxBuildHasher.hash_one(key) % self.0.len() as u64) as usize;
         let mut shard = self.0[shard_idx].lock().unwrap();
@@ -63,6 +63,7 @@ fn compare_ex(
                 askama::Result::Ok(())
             }
             const SIZE_HINT: askama::helpers::core::primitive::usize = #size_hint;
+            const MIME_TYPE: &'static askama::helpers::core::primitive::str = "text/plain";
         }
 
         /// Implement the [`format!()`][askama::helpers::std::format] trait for [`Foo`]
@@ -811,6 +812,22 @@ A
     );
 }
 
+#[cfg(not(feature = "chrono"))]
+#[test]
+fn test_date_without_chrono() {
+    // Without `chrono`, `date` and `datetime` are custom filters.
+    for jinja in ["{{ a|date }}", "{{ a|datetime(1) }}"] {
+        let generated = jinja_to_rust(jinja, &[("a", "u32")], "").unwrap();
+        let generated = unparse(&generated);
+        assert!(generated.contains("use filters::*;"), "{generated}");
+        assert!(!generated.contains("askama::filters::date"), "{generated}");
+        assert!(
+            generated.contains("requires the `chrono` feature"),
+            "{generated}"
+        );
+    }
+}
+
 #[cfg(feature = "code-in-doc")]
 #[test]
 fn test_code_in_comment() {
@@ -1190,6 +1207,32 @@ fn test_generated_with_error() {
     let _: syn::File = syn::parse2(ts).unwrap();
 }
 
+#[test]
+fn test_print_does_not_change_generated_code() {
+    // `print` only dumps the AST and/or the generated code to stderr.
+    let generate = |print: &str| {
+        let ast: syn::DeriveInput = syn::parse_str(&format!(
+            r#"#[template(source = "Hello, {{{{ name }}}}!", ext = "txt", print = "{print}")]
+            struct HelloWorld {{ name: String }}"#
+        ))
+        .unwrap();
+        build_template(&ast).unwrap()
+    };
+
+    let expected = generate("none");
+    assert!(expected.contains("fn render_into_with_values"));
+    for print in ["ast", "code", "all"] {
+        assert_eq!(generate(print), expected, "print = {print:?}");
+    }
+
+    let ast: syn::DeriveInput = syn::parse_str(
+        r#"#[template(source = "", ext = "txt", print = "everything")] struct HelloWorld;"#,
+    )
+    .unwrap();
+    let err = build_template(&ast).unwrap_err();
+    assert!(err.to_string().contains("invalid value for `print` option"));
+}
+
 #[test]
 fn test_filter_with_path() {
     compare(
@@ -1228,10 +1271,11 @@ fn fuzzed_0b85() -> Result<(), syn::Error> {
 
 #[test]
 fn fuzzed_comparator_chain() -> Result<(), syn::Error> {
+    // The second operator was `<`, but `<` comparisons can be chained now.
     let input = quote! {
         #[template(
             ext = "",
-            source = "\u{c}{{vu7218/63e3666663-666/3330e633/63e3666663666/3333<c\"}\u{1}2}\0\"<c7}}2\"\"\"\"\0\0\0\0"
+            source = "\u{c}{{vu7218/63e3666663-666/3330e633/63e3666663666/3333<c\"}\u{1}2}\0\"!=c7}}2\"\"\"\"\0\0\0\0"
         )]
         enum fff {}
     };
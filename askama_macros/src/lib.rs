@@ -8,3 +8,10 @@ askama_derive::make_derive_template! {
         extern crate askama;
     }
 }
+
+askama_derive::make_render! {
+    #[proc_macro]
+    pub fn render() {
+        extern crate askama;
+    }
+}
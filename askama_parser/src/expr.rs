@@ -97,10 +97,24 @@ fn check_expr<'a>(expr: &WithSpan<'a, Expr<'a>>, allowed: Allowed) -> Result<(),
             }
             Ok(())
         }
+        Expr::Map(entries) => {
+            for (key, value) in entries {
+                check_expr(key, Allowed::default())?;
+                check_expr(value, Allowed::default())?;
+            }
+            Ok(())
+        }
         Expr::Index(elem1, elem2) | Expr::BinOp(_, elem1, elem2) => {
             check_expr(elem1, Allowed::default())?;
             check_expr(elem2, Allowed::default())
         }
+        Expr::Compare(first, chain) => {
+            check_expr(first, Allowed::default())?;
+            for (_, elem) in chain {
+                check_expr(elem, Allowed::default())?;
+            }
+            Ok(())
+        }
         Expr::Range(_, elem1, elem2) => {
             if let Some(elem1) = elem1 {
                 check_expr(elem1, Allowed::default())?;
@@ -113,6 +127,13 @@ fn check_expr<'a>(expr: &WithSpan<'a, Expr<'a>>, allowed: Allowed) -> Result<(),
         Expr::As(elem, _) | Expr::Unary(_, elem) | Expr::Group(elem) => {
             check_expr(elem, Allowed::default())
         }
+        Expr::Is { expr, test, .. } => {
+            check_expr(expr, Allowed::default())?;
+            if let Test::DivisibleBy(divisor) = test {
+                check_expr(divisor, Allowed::default())?;
+            }
+            Ok(())
+        }
         Expr::Call { path, args, .. } => {
             check_expr(
                 path,
@@ -142,6 +163,8 @@ pub enum Expr<'a> {
     Var(&'a str),
     Path(Vec<&'a str>),
     Array(Vec<WithSpan<'a, Expr<'a>>>),
+    /// A map literal `{ key: value, ... }`.
+    Map(Vec<(WithSpan<'a, Expr<'a>>, WithSpan<'a, Expr<'a>>)>),
     Attr(Box<WithSpan<'a, Expr<'a>>>, Attr<'a>),
     Index(Box<WithSpan<'a, Expr<'a>>>, Box<WithSpan<'a, Expr<'a>>>),
     Filter(Filter<'a>),
@@ -171,7 +194,19 @@ pub enum Expr<'a> {
     FilterSource,
     IsDefined(&'a str),
     IsNotDefined(&'a str),
+    /// `expr is test` or `expr is not test`, for any test but `defined`.
+    Is {
+        expr: Box<WithSpan<'a, Expr<'a>>>,
+        negated: bool,
+        test: Test<'a>,
+    },
     Concat(Vec<WithSpan<'a, Expr<'a>>>),
+    /// A chain of at least two comparisons like `a < b <= c`, which means `a < b && b <= c`,
+    /// but evaluates `b` only once.
+    Compare(
+        Box<WithSpan<'a, Expr<'a>>>,
+        Vec<(&'a str, WithSpan<'a, Expr<'a>>)>,
+    ),
     /// If you have `&& let Some(y)`, this variant handles it.
     LetCond(Box<WithSpan<'a, CondTest<'a>>>),
     /// This variant should never be used directly.
@@ -179,6 +214,34 @@ pub enum Expr<'a> {
     ArgumentPlaceholder,
 }
 
+/// A test of the `is` operator, e.g. `empty` in `items is empty`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Test<'a> {
+    /// `is defined` on a value lookup: the lookup did not fail with `Error::ValueMissing`.
+    Defined,
+    /// `is none`: the value is `None`.
+    None,
+    /// `is empty`: calls the value's `is_empty()` method.
+    Empty,
+    /// `is even`: the number is divisible by two.
+    Even,
+    /// `is odd`: the number is not divisible by two.
+    Odd,
+    /// `is divisibleby(n)`: the number is divisible by `n`.
+    DivisibleBy(Box<WithSpan<'a, Expr<'a>>>),
+}
+
+/// The names of all tests that can follow the `is` operator.
+const TEST_NAMES: &[&str] = &[
+    "`defined`",
+    "`undefined`",
+    "`none`",
+    "`empty`",
+    "`even`",
+    "`odd`",
+    "`divisibleby(n)`",
+];
+
 impl<'a> Expr<'a> {
     pub(super) fn arguments(
         i: &mut &'a str,
@@ -313,19 +376,31 @@ impl<'a> Expr<'a> {
         let Some((op, rhs)) = opt(right).parse_next(i)? else {
             return Ok(expr);
         };
-        let expr = WithSpan::new(Self::BinOp(op, Box::new(expr), Box::new(rhs)), start);
 
-        if let Some((op2, _)) = opt(right).parse_next(i)? {
-            return Err(winnow::error::ErrMode::Cut(ErrorContext::new(
-                format!(
-                    "comparison operators cannot be chained; \
-                    consider using explicit parentheses, e.g.  `(_ {op} _) {op2} _`"
-                ),
-                op,
-            )));
+        // Like in Python, `a < b <= c` means `a < b && b <= c`. Chaining `==` or `!=` is
+        // rejected, because e.g. `a == b == c` could be meant as `(a == b) == c`, too.
+        let mut chain = vec![(op, rhs)];
+        while let Some((op2, rhs)) = opt(right).parse_next(i)? {
+            let (op, _) = chain[chain.len() - 1];
+            if !is_ordering_op(op) || !is_ordering_op(op2) {
+                return Err(winnow::error::ErrMode::Cut(ErrorContext::new(
+                    format!(
+                        "only the comparison operators `<`, `<=`, `>` and `>=` can be chained; \
+                        consider using explicit parentheses, e.g.  `(_ {op} _) {op2} _`"
+                    ),
+                    op,
+                )));
+            }
+            chain.push((op2, rhs));
         }
 
-        Ok(expr)
+        let expr = if chain.len() == 1 {
+            let (op, rhs) = chain.pop().unwrap();
+            Self::BinOp(op, Box::new(expr), Box::new(rhs))
+        } else {
+            Self::Compare(Box::new(expr), chain)
+        };
+        Ok(WithSpan::new(expr, start))
     }
 
     expr_prec_layer!(bor, bxor, "bitor".value("|"));
@@ -405,20 +480,88 @@ impl<'a> Expr<'a> {
             }
         }
 
-        let rhs = opt(terminated(opt(keyword("not")), ws(keyword("defined")))).parse_next(i)?;
-        let ctor = match rhs {
+        let negated = opt(ws(keyword("not"))).parse_next(i)?.is_some();
+        let test_start = *i;
+        let test = match opt(identifier).parse_next(i)? {
+            Some("defined") => None,
+            Some("undefined") => return Self::is_defined(lhs, !negated, start),
+            Some("none") => Some(Test::None),
+            Some("empty") => Some(Test::Empty),
+            Some("even") => Some(Test::Even),
+            Some("odd") => Some(Test::Odd),
+            Some("divisibleby") => {
+                let divisor = opt(preceded(
+                    ws('('),
+                    cut_err(terminated(
+                        ws(|i: &mut _| Self::parse(i, level, false)),
+                        ')',
+                    )),
+                ))
+                .parse_next(i)?;
+                let Some(divisor) = divisor else {
+                    return Err(winnow::error::ErrMode::Cut(ErrorContext::new(
+                        "expected a divisor in parentheses after `is divisibleby`, \
+                         e.g. `is divisibleby(3)`",
+                        test_start,
+                    )));
+                };
+                Some(Test::DivisibleBy(Box::new(divisor)))
+            }
+            Some(test) => {
+                return Err(winnow::error::ErrMode::Cut(ErrorContext::new(
+                    format!(
+                        "unknown test `{test}` after `is`, expected one of {}",
+                        TEST_NAMES.join(", "),
+                    ),
+                    test_start,
+                )));
+            }
             None => {
                 return Err(winnow::error::ErrMode::Cut(ErrorContext::new(
-                    "expected `defined` or `not defined` after `is`",
+                    format!(
+                        "expected a test after `is`, one of {}",
+                        TEST_NAMES.join(", ")
+                    ),
                     // We use `start` to show the whole `var is` thing instead of the current token.
                     start,
                 )));
             }
-            Some(None) => Self::IsDefined,
-            Some(Some(_)) => Self::IsNotDefined,
+        };
+        match test {
+            Some(test) => Ok(WithSpan::new(
+                Self::Is {
+                    expr: Box::new(lhs),
+                    negated,
+                    test,
+                },
+                start,
+            )),
+            None => Self::is_defined(lhs, negated, start),
+        }
+    }
+
+    fn is_defined(
+        lhs: WithSpan<'a, Self>,
+        negated: bool,
+        start: &'a str,
+    ) -> ParseResult<'a, WithSpan<'a, Self>> {
+        let ctor = match negated {
+            false => Self::IsDefined,
+            true => Self::IsNotDefined,
         };
         let var_name = match *lhs {
             Self::Var(var_name) => var_name,
+            // A value lookup, e.g. `askama::get_value::<u32>("page")`, is tested at runtime.
+            Self::Call { .. } => {
+                return Ok(WithSpan::new(
+                    Self::Is {
+                        expr: Box::new(lhs),
+                        negated,
+                        test: Test::Defined,
+                    },
+                    start,
+                ));
+            }
             Self::Attr(_, _) => {
                 return Err(winnow::error::ErrMode::Cut(ErrorContext::new(
                     "`is defined` operator can only be used on variables, not on their fields",
@@ -487,6 +630,7 @@ impl<'a> Expr<'a> {
             Self::char,
             Self::path_var_bool,
             move |i: &mut _| Self::array(i, level),
+            move |i: &mut _| Self::map(i, level),
             move |i: &mut _| Self::group(i, level),
         ))
         .parse_next(i)
@@ -538,6 +682,25 @@ impl<'a> Expr<'a> {
         Ok(WithSpan::new(Self::Array(array.unwrap_or_default()), start))
     }
 
+    fn map(i: &mut &'a str, level: Level<'_>) -> ParseResult<'a, WithSpan<'a, Self>> {
+        let start = *i;
+        let entry = move |i: &mut _| {
+            let key = ws(|i: &mut _| Self::parse(i, level, true)).parse_next(i)?;
+            let _ = ':'.parse_next(i)?;
+            let value = ws(|i: &mut _| Self::parse(i, level, true)).parse_next(i)?;
+            Ok((key, value))
+        };
+        let entries = preceded(
+            ws('{'),
+            cut_err(terminated(
+                opt(terminated(separated(1.., entry, ','), ws(opt(',')))),
+                '}',
+            )),
+        )
+        .parse_next(i)?;
+        Ok(WithSpan::new(Self::Map(entries.unwrap_or_default()), start))
+    }
+
     fn path_var_bool(i: &mut &'a str) -> ParseResult<'a, WithSpan<'a, Self>> {
         let start = *i;
         path_or_identifier
@@ -595,15 +758,22 @@ impl<'a> Expr<'a> {
             | Self::Index(_, _)
             | Self::Tuple(_)
             | Self::Array(_)
+            | Self::Map(_)
+            | Self::Is { .. }
             | Self::BinOp(_, _, _)
             | Self::Path(_)
             | Self::Concat(_)
+            | Self::Compare(_, _)
             | Self::LetCond(_)
             | Self::ArgumentPlaceholder => false,
         }
     }
 }
 
+fn is_ordering_op(op: &str) -> bool {
+    matches!(op, "<" | "<=" | ">" | ">=")
+}
+
 fn token_xor<'a>(i: &mut &'a str) -> ParseResult<'a> {
     let good = alt((keyword("xor").value(true), '^'.value(false))).parse_next(i)?;
     if good {
@@ -838,7 +1008,7 @@ pub struct TyGenerics<'a> {
 }
 
 impl<'i> TyGenerics<'i> {
-    fn parse(i: &mut &'i str, level: Level<'_>) -> ParseResult<'i, WithSpan<'i, Self>> {
+    pub(crate) fn parse(i: &mut &'i str, level: Level<'_>) -> ParseResult<'i, WithSpan<'i, Self>> {
         let start = *i;
         (
             repeat(0.., ws('&')),
@@ -26,7 +26,7 @@ use winnow::token::{any, one_of, take_till, take_while};
 use winnow::{ModalParser, Parser};
 
 use crate::ascii_str::{AsciiChar, AsciiStr};
-pub use crate::expr::{Attr, Expr, Filter, TyGenerics};
+pub use crate::expr::{Attr, Expr, Filter, Test, TyGenerics};
 pub use crate::node::Node;
 pub use crate::target::Target;
 
@@ -941,6 +941,42 @@ impl<'a> SyntaxBuilder<'a> {
             }
         }
 
+        let opening = [
+            (syntax.block_start, "block"),
+            (syntax.expr_start, "expression"),
+            (syntax.comment_start, "comment"),
+        ];
+        let closing = [
+            (syntax.block_end, "block"),
+            (syntax.expr_end, "expression"),
+            (syntax.comment_end, "comment"),
+        ];
+        for (s1, k1) in opening {
+            for (s2, k2) in closing {
+                if k1 != k2 && (s1.starts_with(s2) || s2.starts_with(s1)) {
+                    return Err(format!(
+                        "an opening delimiter may not be the prefix of a closing delimiter \
+                            of another kind, or vice versa. \
+                            The opening {k1} delimiter ({s1:?}) clashes with \
+                            the closing {k2} delimiter ({s2:?})",
+                    ));
+                }
+            }
+        }
+
+        // The content of a comment is skipped until the closing comment delimiter is found,
+        // so it must not be confused with the end of a block or expression inside the comment.
+        for (s, k) in [(syntax.block_end, "block"), (syntax.expr_end, "expression")] {
+            if s.starts_with(syntax.comment_end) || syntax.comment_end.starts_with(s) {
+                return Err(format!(
+                    "the closing comment delimiter may not be the prefix of another closing \
+                        delimiter, or vice versa. The closing comment delimiter ({:?}) clashes \
+                        with the closing {k} delimiter ({s:?})",
+                    syntax.comment_end,
+                ));
+            }
+        }
+
         Ok(syntax)
     }
 }
@@ -11,8 +11,9 @@ use winnow::{ModalParser, Parser};
 
 use crate::memchr_splitter::{Splitter1, Splitter2, Splitter3};
 use crate::{
-    ErrorContext, Expr, Filter, ParseResult, Span, State, Target, WithSpan, filter, identifier,
-    is_rust_keyword, keyword, path_or_identifier, skip_till, skip_ws0, str_lit_without_prefix, ws,
+    ErrorContext, Expr, Filter, ParseResult, Span, State, Target, TyGenerics, WithSpan, filter,
+    identifier, is_rust_keyword, keyword, path_or_identifier, skip_till, skip_ws0,
+    str_lit_without_prefix, ws,
 };
 
 #[derive(Debug, PartialEq)]
@@ -22,6 +23,7 @@ pub enum Node<'a> {
     Expr(Ws, WithSpan<'a, Expr<'a>>),
     Call(WithSpan<'a, Call<'a>>),
     Let(WithSpan<'a, Let<'a>>),
+    Do(WithSpan<'a, Do<'a>>),
     If(WithSpan<'a, If<'a>>),
     Match(WithSpan<'a, Match<'a>>),
     Loop(Box<WithSpan<'a, Loop<'a>>>),
@@ -29,11 +31,14 @@ pub enum Node<'a> {
     BlockDef(WithSpan<'a, BlockDef<'a>>),
     Include(WithSpan<'a, Include<'a>>),
     Import(WithSpan<'a, Import<'a>>),
+    FromImport(WithSpan<'a, FromImport<'a>>),
     Macro(WithSpan<'a, Macro<'a>>),
     Raw(WithSpan<'a, Raw<'a>>),
     Break(WithSpan<'a, Ws>),
     Continue(WithSpan<'a, Ws>),
     FilterBlock(WithSpan<'a, FilterBlock<'a>>),
+    With(WithSpan<'a, With<'a>>),
+    Cfg(WithSpan<'a, Cfg<'a>>),
 }
 
 impl<'a> Node<'a> {
@@ -93,18 +98,22 @@ impl<'a> Node<'a> {
         let func = match tag {
             "call" => |i: &mut _, s| Call::parse(i, s).map(Self::Call),
             "let" | "set" => |i: &mut _, s| Let::parse(i, s).map(Self::Let),
+            "do" => |i: &mut _, s| Do::parse(i, s).map(Self::Do),
             "if" => |i: &mut _, s| If::parse(i, s).map(Self::If),
             "for" => |i: &mut _, s| Loop::parse(i, s).map(|n| Self::Loop(Box::new(n))),
             "match" => |i: &mut _, s| Match::parse(i, s).map(Self::Match),
             "extends" => |i: &mut _, _s| Extends::parse(i).map(Self::Extends),
-            "include" => |i: &mut _, _s| Include::parse(i).map(Self::Include),
+            "include" => |i: &mut _, s| Include::parse(i, s).map(Self::Include),
             "import" => |i: &mut _, _s| Import::parse(i).map(Self::Import),
+            "from" => |i: &mut _, _s| FromImport::parse(i).map(Self::FromImport),
             "block" => |i: &mut _, s| BlockDef::parse(i, s).map(Self::BlockDef),
             "macro" => |i: &mut _, s| Macro::parse(i, s).map(Self::Macro),
             "raw" => |i: &mut _, s| Raw::parse(i, s).map(Self::Raw),
             "break" => |i: &mut _, s| Self::r#break(i, s),
             "continue" => |i: &mut _, s| Self::r#continue(i, s),
             "filter" => |i: &mut _, s| FilterBlock::parse(i, s).map(Self::FilterBlock),
+            "with" => |i: &mut _, s| With::parse(i, s).map(Self::With),
+            "cfg" => |i: &mut _, s| Cfg::parse(i, s).map(Self::Cfg),
             _ => return fail.parse_next(&mut start),
         };
 
@@ -200,6 +209,7 @@ impl<'a> Node<'a> {
             Self::Expr(_, span) => span.span,
             Self::Call(span) => span.span,
             Self::Let(span) => span.span,
+            Self::Do(span) => span.span,
             Self::If(span) => span.span,
             Self::Match(span) => span.span,
             Self::Loop(span) => span.span,
@@ -207,11 +217,14 @@ impl<'a> Node<'a> {
             Self::BlockDef(span) => span.span,
             Self::Include(span) => span.span,
             Self::Import(span) => span.span,
+            Self::FromImport(span) => span.span,
             Self::Macro(span) => span.span,
             Self::Raw(span) => span.span,
             Self::Break(span) => span.span,
             Self::Continue(span) => span.span,
             Self::FilterBlock(span) => span.span,
+            Self::With(span) => span.span,
+            Self::Cfg(span) => span.span,
         }
     }
 }
@@ -841,6 +854,161 @@ impl<'a> FilterBlock<'a> {
     }
 }
 
+/// `{% with a = expr, b = other_expr %}…{% endwith %}`
+///
+/// The variables are only available in the `nodes` of the block.
+#[derive(Debug, PartialEq)]
+pub struct With<'a> {
+    pub ws1: Ws,
+    pub bindings: Vec<(Target<'a>, WithSpan<'a, Expr<'a>>)>,
+    pub nodes: Vec<Node<'a>>,
+    pub ws2: Ws,
+}
+
+impl<'a> With<'a> {
+    fn parse(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, WithSpan<'a, Self>> {
+        let start_s = *i;
+        let binding = (
+            ws(|i: &mut _| Target::parse(i, s)),
+            ws('='),
+            ws(|i: &mut _| Expr::parse(i, s.level, false)),
+        )
+            .map(|(target, _, expr)| (target, expr));
+        let mut start = (
+            opt(Whitespace::parse),
+            ws(keyword("with")),
+            cut_node(
+                Some("with"),
+                (
+                    separated(1.., binding, ','),
+                    opt(Whitespace::parse),
+                    |i: &mut _| s.tag_block_end(i),
+                ),
+            ),
+        );
+        let (pws1, _, (bindings, nws1, _)) = start.parse_next(i)?;
+
+        let mut end = cut_node(
+            Some("with"),
+            (
+                |i: &mut _| Node::many(i, s),
+                cut_node(
+                    Some("with"),
+                    (
+                        |i: &mut _| check_block_start(i, start_s, s, "with", "endwith"),
+                        opt(Whitespace::parse),
+                        end_node("with", "endwith"),
+                        opt(Whitespace::parse),
+                    ),
+                ),
+            ),
+        );
+        let (nodes, (_, pws2, _, nws2)) = end.parse_next(i)?;
+
+        Ok(WithSpan::new(
+            Self {
+                ws1: Ws(pws1, nws1),
+                bindings,
+                nodes,
+                ws2: Ws(pws2, nws2),
+            },
+            start_s,
+        ))
+    }
+}
+
+/// `{% cfg(feature = "debug") %}…{% endcfg %}`
+///
+/// The `nodes` of the block are only compiled if the `predicate` holds. The predicate is
+/// passed verbatim to a `#[cfg(…)]` attribute.
+#[derive(Debug, PartialEq)]
+pub struct Cfg<'a> {
+    pub ws1: Ws,
+    pub predicate: &'a str,
+    pub nodes: Vec<Node<'a>>,
+    pub ws2: Ws,
+}
+
+impl<'a> Cfg<'a> {
+    fn parse(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, WithSpan<'a, Self>> {
+        let start_s = *i;
+        let mut start = (
+            opt(Whitespace::parse),
+            ws(keyword("cfg")),
+            cut_node(
+                Some("cfg"),
+                (ws(Self::predicate), opt(Whitespace::parse), |i: &mut _| {
+                    s.tag_block_end(i)
+                }),
+            ),
+        );
+        let (pws1, _, (predicate, nws1, _)) = start.parse_next(i)?;
+
+        let mut end = cut_node(
+            Some("cfg"),
+            (
+                |i: &mut _| Node::many(i, s),
+                cut_node(
+                    Some("cfg"),
+                    (
+                        |i: &mut _| check_block_start(i, start_s, s, "cfg", "endcfg"),
+                        opt(Whitespace::parse),
+                        end_node("cfg", "endcfg"),
+                        opt(Whitespace::parse),
+                    ),
+                ),
+            ),
+        );
+        let (nodes, (_, pws2, _, nws2)) = end.parse_next(i)?;
+
+        Ok(WithSpan::new(
+            Self {
+                ws1: Ws(pws1, nws1),
+                predicate,
+                nodes,
+                ws2: Ws(pws2, nws2),
+            },
+            start_s,
+        ))
+    }
+
+    /// Parses the parenthesized predicate, and returns its content without the parentheses.
+    fn predicate(i: &mut &'a str) -> ParseResult<'a> {
+        let start = *i;
+        '('.parse_next(i)?;
+        let content = *i;
+        let mut depth = 0_usize;
+        loop {
+            match i.chars().next() {
+                Some('"') => {
+                    str_lit_without_prefix.parse_next(i)?;
+                }
+                Some('(') => {
+                    depth += 1;
+                    any.parse_next(i)?;
+                }
+                Some(')') => {
+                    let Some(d) = depth.checked_sub(1) else {
+                        let predicate = &content[..content.len() - i.len()];
+                        any.parse_next(i)?;
+                        return Ok(predicate.trim());
+                    };
+                    depth = d;
+                    any.parse_next(i)?;
+                }
+                Some(_) => {
+                    any.parse_next(i)?;
+                }
+                None => {
+                    return Err(
+                        ErrorContext::new("unterminated predicate in `cfg` block", start).cut(),
+                    );
+                }
+            }
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Import<'a> {
     pub ws: Ws,
@@ -875,6 +1043,67 @@ impl<'a> Import<'a> {
     }
 }
 
+/// `{% from "path" import name, other_name as alias %}`
+#[derive(Debug, PartialEq)]
+pub struct FromImport<'a> {
+    pub ws: Ws,
+    pub path: &'a str,
+    /// The imported macros: their names, and the names they are bound to in this template.
+    pub names: Vec<ImportedName<'a>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ImportedName<'a> {
+    pub name: WithSpan<'a, &'a str>,
+    pub alias: Option<&'a str>,
+}
+
+impl<'a> ImportedName<'a> {
+    /// The name under which the macro is available in the importing template.
+    #[must_use]
+    pub fn bound_name(&self) -> &'a str {
+        self.alias.unwrap_or(*self.name)
+    }
+}
+
+impl<'a> FromImport<'a> {
+    fn parse(i: &mut &'a str) -> ParseResult<'a, WithSpan<'a, Self>> {
+        let start = *i;
+        let name = |i: &mut &'a str| {
+            let name = ws(identifier
+                .with_taken()
+                .map(|(name, span)| WithSpan::new(name, span)))
+            .parse_next(i)?;
+            let alias = opt(preceded(ws(keyword("as")), cut_err(ws(identifier)))).parse_next(i)?;
+            Ok(ImportedName { name, alias })
+        };
+        let mut p = (
+            opt(Whitespace::parse),
+            ws(keyword("from")),
+            cut_node(
+                Some("from"),
+                (
+                    ws(str_lit_without_prefix),
+                    ws(keyword("import")),
+                    cut_node(
+                        Some("from"),
+                        (separated(1.., name, ','), opt(Whitespace::parse)),
+                    ),
+                ),
+            ),
+        );
+        let (pws, _, (path, _, (names, nws))) = p.parse_next(i)?;
+        Ok(WithSpan::new(
+            Self {
+                ws: Ws(pws, nws),
+                path,
+                names,
+            },
+            start,
+        ))
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Call<'a> {
     pub ws1: Ws,
@@ -1035,6 +1264,13 @@ impl<'a> Match<'a> {
 pub struct BlockDef<'a> {
     pub ws1: Ws,
     pub name: &'a str,
+    /// `{% block name scoped %}`: accepted for compatibility with Jinja2. Blocks always see the
+    /// variables of the enclosing scope, e.g. the variables of a `{% for %}` loop.
+    pub scoped: bool,
+    /// `{% block name inherit_whitespace %}`: the block is rendered with the whitespace handling
+    /// of the template that defines the overridden block, instead of the handling of the template
+    /// that contains this definition.
+    pub inherit_whitespace: bool,
     pub nodes: Vec<Node<'a>>,
     pub ws2: Ws,
 }
@@ -1047,12 +1283,16 @@ impl<'a> BlockDef<'a> {
             ws(keyword("block")),
             cut_node(
                 Some("block"),
-                (ws(identifier), opt(Whitespace::parse), |i: &mut _| {
-                    s.tag_block_end(i)
-                }),
+                (
+                    ws(identifier),
+                    opt(ws(keyword("scoped"))),
+                    opt(ws(keyword("inherit_whitespace"))),
+                    opt(Whitespace::parse),
+                    |i: &mut _| s.tag_block_end(i),
+                ),
             ),
         );
-        let (pws1, _, (name, nws1, _)) = start.parse_next(i)?;
+        let (pws1, _, (name, scoped, inherit_whitespace, nws1, _)) = start.parse_next(i)?;
 
         let mut end = cut_node(
             Some("block"),
@@ -1085,6 +1325,8 @@ impl<'a> BlockDef<'a> {
             BlockDef {
                 ws1: Ws(pws1, nws1),
                 name,
+                scoped: scoped.is_some(),
+                inherit_whitespace: inherit_whitespace.is_some(),
                 nodes,
                 ws2: Ws(pws2, nws2),
             },
@@ -1203,6 +1445,8 @@ impl<'a> Raw<'a> {
 pub struct Let<'a> {
     pub ws: Ws,
     pub var: Target<'a>,
+    /// The optional type annotation, e.g. `u64` in `{% let x: u64 = 1 %}`
+    pub ty: Option<WithSpan<'a, TyGenerics<'a>>>,
     pub val: Option<WithSpan<'a, Expr<'a>>>,
 }
 
@@ -1216,6 +1460,10 @@ impl<'a> Let<'a> {
                 Some("let"),
                 (
                     ws(|i: &mut _| Target::parse(i, s)),
+                    opt(preceded(
+                        ws(':'),
+                        cut_err(ws(|i: &mut _| TyGenerics::parse(i, s.level))),
+                    )),
                     opt(preceded(
                         ws('='),
                         ws(|i: &mut _| Expr::parse(i, s.level, false)),
@@ -1224,7 +1472,7 @@ impl<'a> Let<'a> {
                 ),
             ),
         );
-        let (pws, _, (var, val, nws)) = p.parse_next(i)?;
+        let (pws, _, (var, ty, val, nws)) = p.parse_next(i)?;
         if val.is_none() {
             let kind = match &var {
                 Target::Name(_) => None,
@@ -1255,6 +1503,7 @@ impl<'a> Let<'a> {
             Let {
                 ws: Ws(pws, nws),
                 var,
+                ty,
                 val,
             },
             start,
@@ -1262,6 +1511,38 @@ impl<'a> Let<'a> {
     }
 }
 
+/// `{% do expr %}`: evaluates an expression for its side effects, without writing its result
+#[derive(Debug, PartialEq)]
+pub struct Do<'a> {
+    pub ws: Ws,
+    pub expr: WithSpan<'a, Expr<'a>>,
+}
+
+impl<'a> Do<'a> {
+    fn parse(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, WithSpan<'a, Self>> {
+        let start = *i;
+        let mut p = (
+            opt(Whitespace::parse),
+            ws(keyword("do")),
+            cut_node(
+                Some("do"),
+                (
+                    ws(|i: &mut _| Expr::parse(i, s.level, false)),
+                    opt(Whitespace::parse),
+                ),
+            ),
+        );
+        let (pws, _, (expr, nws)) = p.parse_next(i)?;
+        Ok(WithSpan::new(
+            Do {
+                ws: Ws(pws, nws),
+                expr,
+            },
+            start,
+        ))
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct If<'a> {
     pub ws: Ws,
@@ -1324,24 +1605,44 @@ impl<'a> If<'a> {
 pub struct Include<'a> {
     pub ws: Ws,
     pub path: &'a str,
+    /// Variables bound with `with a = x, b = y` in the scope of the included template.
+    pub bindings: Vec<(Target<'a>, WithSpan<'a, Expr<'a>>)>,
+    /// If `true`, the included template can only access the variables passed with `with`.
+    pub only: bool,
 }
 
 impl<'a> Include<'a> {
-    fn parse(i: &mut &'a str) -> ParseResult<'a, WithSpan<'a, Self>> {
+    fn parse(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, WithSpan<'a, Self>> {
         let start = *i;
+        let binding = (
+            ws(|i: &mut _| Target::parse(i, s)),
+            ws('='),
+            ws(|i: &mut _| Expr::parse(i, s.level, false)),
+        )
+            .map(|(target, _, expr)| (target, expr));
         let mut p = (
             opt(Whitespace::parse),
             ws(keyword("include")),
             cut_node(
                 Some("include"),
-                (ws(str_lit_without_prefix), opt(Whitespace::parse)),
+                (
+                    ws(str_lit_without_prefix),
+                    opt(preceded(
+                        ws(keyword("with")),
+                        cut_node(Some("include"), separated(1.., binding, ',')),
+                    )),
+                    opt(ws(keyword("only"))),
+                    opt(Whitespace::parse),
+                ),
             ),
         );
-        let (pws, _, (path, nws)) = p.parse_next(i)?;
+        let (pws, _, (path, bindings, only, nws)) = p.parse_next(i)?;
         Ok(WithSpan::new(
             Self {
                 ws: Ws(pws, nws),
                 path,
+                bindings: bindings.unwrap_or_default(),
+                only: only.is_some(),
             },
             start,
         ))
@@ -3,7 +3,7 @@ use winnow::Parser;
 use crate::node::{Lit, Whitespace, Ws};
 use crate::{
     Ast, Expr, Filter, InnerSyntax, Node, Num, PathOrIdentifier, Span, StrLit, Syntax,
-    SyntaxBuilder, WithSpan,
+    SyntaxBuilder, Target, Test, WithSpan,
 };
 
 impl<T> WithSpan<'static, T> {
@@ -1039,6 +1039,140 @@ fn test_parse_array() {
     );
 }
 
+#[test]
+fn test_parse_map() {
+    let syntax = Syntax::default();
+    assert_eq!(
+        Ast::from_str("{{ {} }}", None, &syntax).unwrap().nodes,
+        vec![Node::Expr(
+            Ws(None, None),
+            WithSpan::no_span(Expr::Map(vec![]))
+        )],
+    );
+
+    let n = || {
+        Node::Expr(
+            Ws(None, None),
+            WithSpan::no_span(Expr::Map(vec![
+                (
+                    WithSpan::no_span(Expr::StrLit(StrLit {
+                        content: "a",
+                        prefix: None,
+                    })),
+                    WithSpan::no_span(int_lit("1")),
+                ),
+                (
+                    WithSpan::no_span(Expr::Var("b")),
+                    WithSpan::no_span(Expr::Array(vec![])),
+                ),
+            ])),
+        )
+    };
+    assert_eq!(
+        Ast::from_str(
+            r#"{{ {"a": 1, b: []} }}{{ { "a" : 1 , b : [] , } }}{{ {"a":1,b:[]} }}"#,
+            None,
+            &syntax
+        )
+        .unwrap()
+        .nodes,
+        vec![n(), n(), n()],
+    );
+
+    assert!(Ast::from_str(r#"{{ {"a"} }}"#, None, &syntax).is_err());
+    assert!(Ast::from_str(r#"{{ {"a": 1 }}"#, None, &syntax).is_err());
+}
+
+#[test]
+fn test_parse_is_test() {
+    let syntax = Syntax::default();
+    let is = |negated, test| {
+        Node::Expr(
+            Ws(None, None),
+            WithSpan::no_span(Expr::Is {
+                expr: Box::new(WithSpan::no_span(Expr::Var("x"))),
+                negated,
+                test,
+            }),
+        )
+    };
+    assert_eq!(
+        Ast::from_str(
+            "{{ x is none }}{{ x is not empty }}{{ x is even }}{{ x is odd }}",
+            None,
+            &syntax
+        )
+        .unwrap()
+        .nodes,
+        vec![
+            is(false, Test::None),
+            is(true, Test::Empty),
+            is(false, Test::Even),
+            is(false, Test::Odd),
+        ],
+    );
+    assert_eq!(
+        Ast::from_str("{{ x is divisibleby( 3 ) }}", None, &syntax)
+            .unwrap()
+            .nodes,
+        vec![is(
+            false,
+            Test::DivisibleBy(Box::new(WithSpan::no_span(int_lit("3"))))
+        )],
+    );
+
+    assert_eq!(
+        Ast::from_str("{{ f() is defined }}", None, &syntax)
+            .unwrap()
+            .nodes,
+        vec![Node::Expr(
+            Ws(None, None),
+            WithSpan::no_span(Expr::Is {
+                expr: Box::new(WithSpan::no_span(Expr::Call {
+                    path: Box::new(WithSpan::no_span(Expr::Var("f"))),
+                    args: vec![],
+                    generics: vec![],
+                })),
+                negated: false,
+                test: Test::Defined,
+            }),
+        )],
+    );
+
+    assert!(Ast::from_str("{{ x is divisibleby }}", None, &syntax).is_err());
+    assert!(Ast::from_str("{{ x is divisibleby(3 }}", None, &syntax).is_err());
+    assert!(Ast::from_str("{{ x is blue }}", None, &syntax).is_err());
+    assert!(Ast::from_str("{{ x is not }}", None, &syntax).is_err());
+}
+
+#[test]
+fn test_parse_include_with_only() {
+    let syntax = Syntax::default();
+    let ast = Ast::from_str(
+        r#"{% include "a.html" with x = 1, y = z only %}"#,
+        None,
+        &syntax,
+    )
+    .unwrap();
+    let Node::Include(include) = &ast.nodes[0] else {
+        panic!("expected an include, got {:?}", ast.nodes);
+    };
+    assert_eq!(include.path, "a.html");
+    assert_eq!(include.bindings.len(), 2);
+    assert_eq!(*include.bindings[1].1, Expr::Var("z"));
+    assert!(include.only);
+
+    let ast = Ast::from_str(r#"{% include "a.html" only %}"#, None, &syntax).unwrap();
+    let Node::Include(include) = &ast.nodes[0] else {
+        panic!("expected an include, got {:?}", ast.nodes);
+    };
+    assert!(include.bindings.is_empty());
+    assert!(include.only);
+
+    assert!(Ast::from_str(r#"{% include "a.html" with %}"#, None, &syntax).is_err());
+    assert!(Ast::from_str(r#"{% include "a.html" with x %}"#, None, &syntax).is_err());
+}
+
 #[test]
 fn fuzzed_unicode_slice() {
     let d = "{eeuuu{b&{!!&{!!11{{
@@ -1090,6 +1224,34 @@ fn let_set() {
     );
 }
 
+#[test]
+fn let_with_type() {
+    let syntax = Syntax::default();
+    let ast = Ast::from_str("{% set x: u64 = 1 %}", None, &syntax).unwrap();
+    let Node::Let(l) = &ast.nodes()[0] else {
+        panic!("expected a `let` node");
+    };
+    assert_eq!(l.var, Target::Name("x"));
+    let ty = l.ty.as_deref().unwrap();
+    assert_eq!((ty.refs, &*ty.path, ty.args.len()), (0, &["u64"][..], 0));
+
+    let ast = Ast::from_str("{% let v: &Vec<u8> %}", None, &syntax).unwrap();
+    let Node::Let(l) = &ast.nodes()[0] else {
+        panic!("expected a `let` node");
+    };
+    let ty = l.ty.as_deref().unwrap();
+    assert_eq!((ty.refs, &*ty.path, ty.args.len()), (1, &["Vec"][..], 1));
+    assert!(l.val.is_none());
+
+    let ast = Ast::from_str("{% let x = 1 %}", None, &syntax).unwrap();
+    let Node::Let(l) = &ast.nodes()[0] else {
+        panic!("expected a `let` node");
+    };
+    assert!(l.ty.is_none());
+
+    assert!(Ast::from_str("{% let x: = 1 %}", None, &syntax).is_err());
+}
+
 #[test]
 fn fuzzed_filter_recursion() {
     const TEMPLATE: &str = include_str!("../tests/filter-recursion.txt");
@@ -1396,24 +1558,213 @@ fn there_is_no_digit_two_in_a_binary_integer() {
 }
 
 #[test]
-fn comparison_operators_cannot_be_chained() {
+fn comparison_operators_chaining() {
     const OPS: &[&str] = &["==", "!=", ">=", ">", "<=", "<"];
+    let is_ordering = |op: &str| !matches!(op, "==" | "!=");
 
     let syntax = Syntax::default();
     for op1 in OPS {
         assert!(Ast::from_str(&format!("{{{{ a {op1} b }}}}"), None, &syntax).is_ok());
         for op2 in OPS {
-            assert!(Ast::from_str(&format!("{{{{ a {op1} b {op2} c }}}}"), None, &syntax).is_err());
+            assert_eq!(
+                Ast::from_str(&format!("{{{{ a {op1} b {op2} c }}}}"), None, &syntax).is_ok(),
+                is_ordering(op1) && is_ordering(op2),
+            );
             for op3 in OPS {
-                assert!(
+                assert_eq!(
                     Ast::from_str(
                         &format!("{{{{ a {op1} b {op2} c {op3} d }}}}"),
                         None,
                         &syntax,
                     )
-                    .is_err()
+                    .is_ok(),
+                    is_ordering(op1) && is_ordering(op2) && is_ordering(op3),
                 );
             }
         }
     }
 }
+
+#[test]
+fn test_chained_comparison() {
+    let syntax = Syntax::default();
+    assert_eq!(
+        Ast::from_str("{{ 0 <= a + 1 < b }}", None, &syntax)
+            .unwrap()
+            .nodes,
+        vec![Node::Expr(
+            Ws(None, None),
+            WithSpan::no_span(Expr::Compare(
+                WithSpan::no_span(Expr::NumLit("0", Num::Int("0", None))).into(),
+                vec![
+                    (
+                        "<=",
+                        WithSpan::no_span(Expr::BinOp(
+                            "+",
+                            WithSpan::no_span(Expr::Var("a")).into(),
+                            WithSpan::no_span(Expr::NumLit("1", Num::Int("1", None))).into()
+                        ))
+                    ),
+                    ("<", WithSpan::no_span(Expr::Var("b"))),
+                ],
+            ))
+        )],
+    );
+    // A single comparison is still a `BinOp`, and `&&` binds weaker than the chain.
+    assert_eq!(
+        Ast::from_str("{{ a < b && b > c >= d }}", None, &syntax)
+            .unwrap()
+            .nodes,
+        vec![Node::Expr(
+            Ws(None, None),
+            WithSpan::no_span(Expr::BinOp(
+                "&&",
+                WithSpan::no_span(Expr::BinOp(
+                    "<",
+                    WithSpan::no_span(Expr::Var("a")).into(),
+                    WithSpan::no_span(Expr::Var("b")).into()
+                ))
+                .into(),
+                WithSpan::no_span(Expr::Compare(
+                    WithSpan::no_span(Expr::Var("b")).into(),
+                    vec![
+                        (">", WithSpan::no_span(Expr::Var("c"))),
+                        (">=", WithSpan::no_span(Expr::Var("d"))),
+                    ],
+                ))
+                .into()
+            ))
+        )],
+    );
+}
+
+#[test]
+fn test_from_import() {
+    use crate::node::{FromImport, ImportedName};
+
+    let syntax = Syntax::default();
+    assert_eq!(
+        Ast::from_str(
+            r#"{% from "macros.html" import heading, list as items %}"#,
+            None,
+            &syntax
+        )
+        .unwrap()
+        .nodes,
+        vec![Node::FromImport(WithSpan::no_span(FromImport {
+            ws: Ws(None, None),
+            path: "macros.html",
+            names: vec![
+                ImportedName {
+                    name: WithSpan::no_span("heading"),
+                    alias: None,
+                },
+                ImportedName {
+                    name: WithSpan::no_span("list"),
+                    alias: Some("items"),
+                },
+            ],
+        }))],
+    );
+
+    assert!(Ast::from_str(r#"{% from "macros.html" import %}"#, None, &syntax).is_err());
+    assert!(Ast::from_str(r#"{% from "macros.html" import a as %}"#, None, &syntax).is_err());
+    assert!(Ast::from_str(r#"{% from "macros.html" import a, %}"#, None, &syntax).is_err());
+}
+
+#[test]
+fn test_do() {
+    let syntax = Syntax::default();
+    let ast = Ast::from_str("{% do list.push(1) %}", None, &syntax).unwrap();
+    let [Node::Do(d)] = &*ast.nodes else {
+        panic!("expected a `do` node: {:?}", ast.nodes);
+    };
+    assert!(matches!(*d.expr, Expr::Call { .. }));
+
+    assert!(Ast::from_str("{% do %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% do a b %}", None, &syntax).is_err());
+}
+
+#[test]
+fn test_with() {
+    let syntax = Syntax::default();
+    let ast = Ast::from_str("{% with a = 1, b = c %}{{ a }}{% endwith %}", None, &syntax).unwrap();
+    let [Node::With(with)] = &*ast.nodes else {
+        panic!("expected a `with` node: {:?}", ast.nodes);
+    };
+    assert_eq!(with.bindings.len(), 2);
+    assert_eq!(with.nodes.len(), 1);
+
+    assert!(Ast::from_str("{% with %}{% endwith %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% with a %}{% endwith %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% with a = 1 %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% with a = 1 %}{% endfor %}", None, &syntax).is_err());
+}
+
+#[test]
+fn test_cfg() {
+    let syntax = Syntax::default();
+    let ast = Ast::from_str(
+        r#"{% cfg(all(feature = "a)", not(test))) %}{{ a }}{% endcfg %}"#,
+        None,
+        &syntax,
+    )
+    .unwrap();
+    let [Node::Cfg(cfg)] = &*ast.nodes else {
+        panic!("expected a `cfg` node: {:?}", ast.nodes);
+    };
+    assert_eq!(cfg.predicate, r#"all(feature = "a)", not(test))"#);
+    assert_eq!(cfg.nodes.len(), 1);
+
+    assert!(Ast::from_str("{% cfg %}{% endcfg %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% cfg(test %}{% endcfg %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% cfg(test) %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% cfg(test) %}{% endif %}", None, &syntax).is_err());
+}
+
+#[test]
+fn test_scoped_block() {
+    let syntax = Syntax::default();
+    let ast = Ast::from_str(
+        "{% block a scoped -%}{% endblock %}{% block b %}{% endblock b %}",
+        None,
+        &syntax,
+    )
+    .unwrap();
+    let [Node::BlockDef(a), Node::BlockDef(b)] = &*ast.nodes else {
+        panic!("expected two blocks: {:?}", ast.nodes);
+    };
+    assert_eq!((a.name, a.scoped), ("a", true));
+    assert_eq!(a.ws1, Ws(None, Some(Whitespace::Suppress)));
+    assert_eq!((b.name, b.scoped), ("b", false));
+
+    assert!(Ast::from_str("{% block scoped %}{% endblock %}", None, &syntax).is_ok());
+    assert!(Ast::from_str("{% block a scope %}{% endblock %}", None, &syntax).is_err());
+    assert!(Ast::from_str("{% block a scoped scoped %}{% endblock %}", None, &syntax).is_err());
+}
+
+#[test]
+fn test_block_inherit_whitespace() {
+    let syntax = Syntax::default();
+    let ast = Ast::from_str(
+        "{% block a inherit_whitespace -%}{% endblock %}\
+        {% block b scoped inherit_whitespace %}{% endblock %}\
+        {% block c %}{% endblock %}",
+        None,
+        &syntax,
+    )
+    .unwrap();
+    let [Node::BlockDef(a), Node::BlockDef(b), Node::BlockDef(c)] = &*ast.nodes else {
+        panic!("expected three blocks: {:?}", ast.nodes);
+    };
+    assert_eq!((a.name, a.scoped, a.inherit_whitespace), ("a", false, true));
+    assert_eq!(a.ws1, Ws(None, Some(Whitespace::Suppress)));
+    assert_eq!((b.name, b.scoped, b.inherit_whitespace), ("b", true, true));
+    assert_eq!(
+        (c.name, c.scoped, c.inherit_whitespace),
+        ("c", false, false)
+    );
+
+    let input = "{% block a inherit_whitespace scoped %}{% endblock %}";
+    assert!(Ast::from_str(input, None, &syntax).is_err());
+}
@@ -15,6 +15,8 @@ fn functions(c: &mut Criterion) {
     c.bench_function("Teams", teams);
     c.bench_function("Teams (fmt)", teams_fmt);
     c.bench_function("Teams (io)", teams_io);
+
+    c.bench_function("Dense expressions", dense_expressions);
 }
 
 fn big_table(b: &mut criterion::Bencher) {
@@ -108,3 +110,24 @@ struct Team {
     name: String,
     score: u8,
 }
+
+fn dense_expressions(b: &mut criterion::Bencher) {
+    let ctx = DenseExpressions::default();
+    b.iter(|| black_box(&ctx).render().unwrap());
+}
+
+#[derive(Template)]
+#[template(path = "dense-expressions.html")]
+struct DenseExpressions {
+    rows: Vec<(String, usize, String)>,
+}
+
+impl Default for DenseExpressions {
+    fn default() -> Self {
+        DenseExpressions {
+            rows: (0..1000)
+                .map(|i| (format!("row <{i}>"), i, format!("{i} & {}", i * 2)))
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,180 @@
+#![cfg(feature = "async")]
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::pin;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+use askama::Template;
+
+/// Polls `future` until it is ready. The futures in here never wait for anything but themselves.
+fn block_on<F: Future>(future: F) -> F::Output {
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(
+        |_| RawWaker::new(std::ptr::null(), &VTABLE),
+        |_| {},
+        |_| {},
+        |_| {},
+    );
+
+    // SAFETY: the vtable functions do not access the data pointer
+    let waker = unsafe { Waker::from_raw(RawWaker::new(std::ptr::null(), &VTABLE)) };
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+/// A future that is pending once, like a request to a database that is not answered instantly.
+async fn yield_now() {
+    let mut yielded = false;
+    std::future::poll_fn(|cx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await;
+}
+
+struct Comment {
+    author: &'static str,
+    text: &'static str,
+}
+
+#[derive(Template)]
+#[template(
+    source = "<h1>{{ title().await }}</h1>
+{%- let comments = comments().await %}
+{%- if comments.is_empty() %} No comments.
+{%- else %}
+{%- for comment in comments %} [{{ comment.author }}: {{ comment.text }}]{% endfor %}
+{%- endif %} ({{ id }})",
+    ext = "html",
+    async = true
+)]
+struct Post {
+    id: u32,
+    comments: Vec<Comment>,
+}
+
+impl Post {
+    async fn title(&self) -> String {
+        yield_now().await;
+        format!("Post <{}>", self.id)
+    }
+
+    async fn comments(&self) -> &[Comment] {
+        yield_now().await;
+        &self.comments
+    }
+}
+
+#[test]
+fn test_async_render() {
+    let post = Post {
+        id: 7,
+        comments: vec![
+            Comment {
+                author: "Alice",
+                text: "First!",
+            },
+            Comment {
+                author: "Bob",
+                text: "1 < 2",
+            },
+        ],
+    };
+    assert_eq!(
+        block_on(post.render_async()).unwrap(),
+        "<h1>Post &#60;7&#62;</h1> [Alice: First!] [Bob: 1 &#60; 2] (7)"
+    );
+
+    let post = Post {
+        id: 8,
+        comments: vec![],
+    };
+    assert_eq!(
+        block_on(post.render_async()).unwrap(),
+        "<h1>Post &#60;8&#62;</h1> No comments. (8)"
+    );
+}
+
+#[test]
+fn test_async_render_with_values() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ greeting().await }}, {{ askama::get_value::<String>("name")? }}!"#,
+        ext = "txt",
+        async = true,
+        trailing_newline = "ensure"
+    )]
+    struct Greeting;
+
+    impl Greeting {
+        async fn greeting(&self) -> &'static str {
+            yield_now().await;
+            "Hello"
+        }
+    }
+
+    let values: (&str, &(dyn Any + Send + Sync)) = ("name", &"world".to_owned());
+    assert_eq!(
+        block_on(Greeting.render_async_with_values(&values)).unwrap(),
+        "Hello, world!\n"
+    );
+
+    let mut buf = String::from("> ");
+    block_on(Greeting.render_into_async_with_values(&mut buf, &values)).unwrap();
+    assert_eq!(buf, "> Hello, world!");
+}
+
+#[test]
+fn test_async_await_evaluated_once() {
+    use std::cell::Cell;
+
+    #[derive(Template)]
+    #[template(source = "{{ next().await }} {{ next().await }}", ext = "txt", async)]
+    struct Counter {
+        count: Cell<u32>,
+    }
+
+    impl Counter {
+        async fn next(&self) -> u32 {
+            yield_now().await;
+            self.count.set(self.count.get() + 1);
+            self.count.get()
+        }
+    }
+
+    let counter = Counter {
+        count: Cell::new(0),
+    };
+    assert_eq!(block_on(counter.render_async()).unwrap(), "1 2");
+    assert_eq!(counter.count.get(), 2);
+}
+
+#[test]
+fn test_async_futures_are_send() {
+    fn assert_send<T: Send>(_: T) {}
+
+    let post = Post {
+        id: 1,
+        comments: vec![],
+    };
+    assert_send(post.render_async());
+    let values: (&str, &(dyn Any + Send + Sync)) = ("name", &"world");
+    assert_send(post.render_async_with_values(&values));
+    let mut buf = String::new();
+    assert_send(post.render_into_async_with_values(&mut buf, &values));
+
+    let mut values: HashMap<String, Box<dyn Any + Send + Sync>> = HashMap::new();
+    values.insert("name".to_owned(), Box::new("world"));
+    assert_send(post.render_async_with_values(&values));
+}
@@ -0,0 +1,168 @@
+use askama::Template;
+
+#[test]
+fn test_no_autoescape() {
+    #[derive(Template)]
+    #[template(
+        source = "<p>{{ text }}</p>{{ text|e }}",
+        ext = "html",
+        config = "no-autoescape.toml"
+    )]
+    struct Unescaped<'a> {
+        text: &'a str,
+    }
+
+    assert_eq!(
+        Unescaped { text: "<b>&</b>" }.render().unwrap(),
+        "<p><b>&</b></p><b>&</b>"
+    );
+}
+
+#[test]
+fn test_no_autoescape_opt_in() {
+    // Explicitly requested escaping still works.
+    #[derive(Template)]
+    #[template(
+        source = "<p>{{ text }}</p>",
+        ext = "html",
+        escape = "html",
+        config = "no-autoescape.toml"
+    )]
+    struct OptIn<'a> {
+        text: &'a str,
+    }
+
+    #[derive(Template)]
+    #[template(
+        source = r#"<p>{{ text|escape("html") }}</p>"#,
+        ext = "html",
+        config = "no-autoescape.toml"
+    )]
+    struct ExplicitFilter<'a> {
+        text: &'a str,
+    }
+
+    assert_eq!(
+        OptIn { text: "<b>" }.render().unwrap(),
+        "<p>&#60;b&#62;</p>"
+    );
+    assert_eq!(
+        ExplicitFilter { text: "<b>" }.render().unwrap(),
+        "<p>&#60;b&#62;</p>"
+    );
+}
+
+#[test]
+fn test_attribute_no_breakout() {
+    #[derive(Template)]
+    #[template(
+        source = r#"<a title="{{ text }}" data-x='{{ text }}'>{{ text }}</a>"#,
+        ext = "html"
+    )]
+    struct Attr<'a> {
+        text: &'a str,
+    }
+
+    let text = r#"" onmouseover='alert(1)' x=""#;
+    let rendered = Attr { text }.render().unwrap();
+    assert_eq!(
+        rendered,
+        "<a title=\"&#34; onmouseover=&#39;alert(1)&#39; x=&#34;\" \
+         data-x='&#34; onmouseover=&#39;alert(1)&#39; x=&#34;'>\
+         &#34; onmouseover=&#39;alert(1)&#39; x=&#34;</a>"
+    );
+    // Only the template's own quotes remain.
+    assert_eq!(rendered.matches('"').count(), 2);
+    assert_eq!(rendered.matches('\'').count(), 2);
+}
+
+#[test]
+fn test_svg_text_and_attribute() {
+    // SVG is XML, so only the five predefined entities and numeric character references may
+    // appear in the output. The HTML escaper only emits numeric character references.
+    #[derive(Template)]
+    #[template(
+        source = r#"<svg xmlns="http://www.w3.org/2000/svg"><text x="{{ x }}" class='{{ class }}'>{{ label }}</text></svg>"#,
+        ext = "svg"
+    )]
+    struct Chart<'a> {
+        x: &'a str,
+        class: &'a str,
+        label: &'a str,
+    }
+
+    let rendered = Chart {
+        x: r#"0" onload="alert(1)"#,
+        class: "a' onclick='alert(1)",
+        label: "</text><script>alert(1)</script> & <![CDATA[x]]>",
+    }
+    .render()
+    .unwrap();
+    assert_eq!(
+        rendered,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\">\
+         <text x=\"0&#34; onload=&#34;alert(1)\" class='a&#39; onclick=&#39;alert(1)'>\
+         &#60;/text&#62;&#60;script&#62;alert(1)&#60;/script&#62; &#38; \
+         &#60;![CDATA[x]]&#62;</text></svg>"
+    );
+    assert_eq!(rendered.matches('<').count(), 4);
+}
+
+#[test]
+fn test_extension_case_insensitive() {
+    #[derive(Template)]
+    #[template(source = "{{ text }}", ext = "HTML")]
+    struct UpperHtml<'a> {
+        text: &'a str,
+    }
+
+    #[derive(Template)]
+    #[template(source = "{{ text }}", ext = "Svg")]
+    struct MixedSvg<'a> {
+        text: &'a str,
+    }
+
+    #[derive(Template)]
+    #[template(source = r#"{{ text|escape("Html") }}"#, ext = "TXT")]
+    struct ExplicitFilter<'a> {
+        text: &'a str,
+    }
+
+    assert_eq!(UpperHtml { text: "<b>" }.render().unwrap(), "&#60;b&#62;");
+    assert_eq!(MixedSvg { text: "<b>" }.render().unwrap(), "&#60;b&#62;");
+    assert_eq!(
+        ExplicitFilter { text: "<b>" }.render().unwrap(),
+        "&#60;b&#62;"
+    );
+}
+
+#[test]
+fn test_dense_expressions() {
+    // The template of the "Dense expressions" benchmark: every expression is escaped on its own,
+    // even if it is adjacent to other expressions or to literal text.
+    #[derive(Template)]
+    #[template(path = "dense-expressions.html")]
+    struct DenseExpressions {
+        rows: Vec<(String, usize, String)>,
+    }
+
+    let rows = (0..3)
+        .map(|i| (format!("row <{i}>"), i, format!("{i} & {}", i * 2)))
+        .collect::<Vec<_>>();
+    let expected = rows
+        .iter()
+        .map(|(name, index, note)| {
+            let escape = |s: &str| {
+                s.replace('&', "&#38;")
+                    .replace('<', "&#60;")
+                    .replace('>', "&#62;")
+            };
+            let (name, escaped_note) = (escape(name), escape(note));
+            format!(
+                "<tr id=\"{index}\"><td>{name}{index}{escaped_note}</td>\
+                 <td>{name}-{escaped_note}</td><td>{note}{index}</td></tr>\n"
+            )
+        })
+        .collect::<String>();
+    assert_eq!(DenseExpressions { rows }.render().unwrap(), expected);
+}
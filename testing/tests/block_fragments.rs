@@ -14,6 +14,33 @@ fn test_fragment_simple() {
     assert_eq!(simple.render().unwrap(), "\n<p>Hello world!</p>\n");
 }
 
+/// Tests that the same file can be rendered as a full page and as a single block.
+#[test]
+fn test_fragment_and_full_page() {
+    #[derive(Template)]
+    #[template(path = "fragment-simple.html")]
+    struct Page<'a> {
+        name: &'a str,
+    }
+
+    #[derive(Template)]
+    #[template(path = "fragment-simple.html", block = "body")]
+    struct Content<'a> {
+        name: &'a str,
+    }
+
+    let page = Page { name: "world" };
+    assert_eq!(
+        page.render().unwrap(),
+        "<html>\n<head></head>\n<body>\n\n<p>Hello world!</p>\n\n\n\
+         <p>Don't render me.</p>\n\n</body>\n</html>"
+    );
+
+    let content = Content { name: "world" };
+    assert_eq!(content.render().unwrap(), "\n<p>Hello world!</p>\n");
+    assert!(page.render().unwrap().contains(&content.render().unwrap()));
+}
+
 /// Tests a case where a block fragment rendering calls the parent.
 /// Single inheritance only.
 #[test]
@@ -38,3 +38,24 @@ fn test_blocks() {
     assert_eq!(tmpl.as_third().render().unwrap(), "third=<bronze>");
     assert_eq!(tmpl.render().unwrap(), "better luck next time");
 }
+
+#[test]
+fn test_blocks_of_child_template() {
+    // Partial page updates: render single blocks of a child template without its layout.
+    #[derive(Template)]
+    #[template(path = "child.html", blocks = ["content", "foo"])]
+    struct Child<'a> {
+        title: &'a str,
+    }
+
+    let tmpl = Child { title: "Hello" };
+    assert_eq!(
+        tmpl.render().unwrap(),
+        "Hello\n(Hello) Content goes here\nFoo\nCopyright 2017"
+    );
+    assert_eq!(
+        tmpl.as_content().render().unwrap(),
+        "(Hello) Content goes here"
+    );
+    assert_eq!(tmpl.as_foo().render().unwrap(), "Foo");
+}
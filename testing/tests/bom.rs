@@ -0,0 +1,70 @@
+use askama::{DynTemplate, Template};
+
+#[derive(Template)]
+#[template(source = "a;b\n{{ value }};c", ext = "txt", bom = true)]
+struct Csv<'a> {
+    value: &'a str,
+}
+
+#[test]
+fn test_bom_write_into() {
+    let tmpl = Csv { value: "ä" };
+
+    let mut buf = Vec::new();
+    tmpl.write_into(&mut buf).unwrap();
+    assert_eq!(buf, b"\xEF\xBB\xBFa;b\n\xC3\xA4;c");
+
+    let mut buf = Vec::new();
+    (&tmpl as &dyn DynTemplate)
+        .dyn_write_into(&mut buf)
+        .unwrap();
+    assert_eq!(buf, b"\xEF\xBB\xBFa;b\n\xC3\xA4;c");
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn test_bom_render_into_bytes() {
+    let mut buf = bytes::BytesMut::new();
+    Csv { value: "x" }.render_into_bytes(&mut buf).unwrap();
+    assert_eq!(&buf[..], b"\xEF\xBB\xBFa;b\nx;c");
+}
+
+#[test]
+fn test_bom_not_in_string() {
+    let tmpl = Csv { value: "x" };
+    assert_eq!(tmpl.render().unwrap(), "a;b\nx;c");
+    assert_eq!(tmpl.to_string(), "a;b\nx;c");
+}
+
+#[test]
+fn test_no_bom_by_default() {
+    #[derive(Template)]
+    #[template(source = "a", ext = "txt", bom = false)]
+    struct NoBom;
+
+    #[derive(Template)]
+    #[template(source = "a", ext = "txt")]
+    struct Default;
+
+    let mut buf = Vec::new();
+    NoBom.write_into(&mut buf).unwrap();
+    Default.write_into(&mut buf).unwrap();
+    assert_eq!(buf, b"aa");
+}
+
+#[test]
+fn test_bom_enum() {
+    #[derive(Template)]
+    #[template(ext = "txt", bom = true)]
+    enum Export {
+        #[template(source = "csv")]
+        Csv,
+        #[template(source = "tsv")]
+        #[allow(dead_code)]
+        Tsv,
+    }
+
+    let mut buf = Vec::new();
+    Export::Csv.write_into(&mut buf).unwrap();
+    assert_eq!(buf, b"\xEF\xBB\xBFcsv");
+}
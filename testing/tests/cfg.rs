@@ -0,0 +1,44 @@
+use askama::Template;
+
+#[test]
+fn test_cfg() {
+    #[derive(Template)]
+    #[template(
+        source = r#"<main>{{ body }}</main>
+{%- cfg(feature = "markdown") %}
+<aside>markdown: {{ body|markdown }}</aside>
+{%- endcfg %}
+{%- cfg(not(feature = "markdown")) %}
+<aside>no markdown</aside>
+{%- endcfg %}"#,
+        ext = "html"
+    )]
+    struct Page<'a> {
+        body: &'a str,
+    }
+
+    let page = Page { body: "*a*" };
+    #[cfg(feature = "markdown")]
+    assert_eq!(
+        page.render().unwrap(),
+        "<main>*a*</main>\n<aside>markdown: <p><em>a</em></p>\n</aside>"
+    );
+    #[cfg(not(feature = "markdown"))]
+    assert_eq!(
+        page.render().unwrap(),
+        "<main>*a*</main>\n<aside>no markdown</aside>"
+    );
+}
+
+#[test]
+fn test_cfg_scope() {
+    #[derive(Template)]
+    #[template(
+        source = "{% let x = 1 %}{% cfg(all()) %}{% let x = 2 %}{{ x }}{% endcfg %}{{ x }}\
+                  {% cfg(any()) %}{{ missing_field }}{% endcfg %}",
+        ext = "txt"
+    )]
+    struct Scope;
+
+    assert_eq!(Scope.render().unwrap(), "21");
+}
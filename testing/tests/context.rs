@@ -0,0 +1,94 @@
+use askama::Template;
+
+struct Globals {
+    site_name: &'static str,
+    year: u32,
+}
+
+#[derive(Template)]
+#[template(source = "<li>{{ name }} @ {{ ctx.site_name }}</li>", ext = "html", context = Globals)]
+struct Item<'a> {
+    name: &'a str,
+}
+
+#[derive(Template)]
+#[template(
+    source = "<h1>{{ ctx.site_name }}</h1><ul>{% for item in items %}{{ item|safe }}{% endfor %}</ul>\
+              <p>&copy; {{ ctx.year }}</p>",
+    ext = "html",
+    context = Globals
+)]
+struct Page<'a> {
+    items: Vec<Item<'a>>,
+}
+
+#[test]
+fn test_context() {
+    let globals = Globals {
+        site_name: "Askama & co",
+        year: 2025,
+    };
+    let page = Page {
+        items: vec![Item { name: "a" }, Item { name: "b" }],
+    };
+    assert_eq!(
+        page.render_with_context(&globals).unwrap(),
+        "<h1>Askama &#38; co</h1><ul><li>a @ Askama &#38; co</li><li>b @ Askama &#38; co</li></ul>\
+         <p>&copy; 2025</p>"
+    );
+
+    let mut buf = String::new();
+    Item { name: "c" }
+        .render_into_with_context(&mut buf, &globals)
+        .unwrap();
+    assert_eq!(buf, "<li>c @ Askama &#38; co</li>");
+}
+
+#[test]
+fn test_context_missing() {
+    let item = Item { name: "a" };
+    assert!(matches!(item.render(), Err(askama::Error::ValueMissing)));
+}
+
+#[test]
+fn test_context_enum() {
+    #[derive(Template)]
+    #[template(ext = "txt", context = Globals)]
+    enum Footer {
+        #[template(source = "{{ ctx.site_name }}")]
+        Name,
+        #[template(source = "{{ ctx.year }}")]
+        Year,
+    }
+
+    let globals = Globals {
+        site_name: "Askama",
+        year: 2025,
+    };
+    assert_eq!(
+        Footer::Name.render_with_context(&globals).unwrap(),
+        "Askama"
+    );
+    let mut buf = String::new();
+    Footer::Year
+        .render_into_with_context(&mut buf, &globals)
+        .unwrap();
+    assert_eq!(buf, "2025");
+}
+
+#[test]
+fn test_context_shadowed() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ ctx.year }}{% for ctx in [1] %} {{ ctx }}{% endfor %}",
+        ext = "txt",
+        context = Globals
+    )]
+    struct Shadowed;
+
+    let globals = Globals {
+        site_name: "",
+        year: 2025,
+    };
+    assert_eq!(Shadowed.render_with_context(&globals).unwrap(), "2025 1");
+}
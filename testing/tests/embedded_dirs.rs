@@ -0,0 +1,41 @@
+// The templates of these tests are not in `templates`, but in a directory that `embedded-dirs.toml`
+// finds through an environment variable, like a directory that a build script wrote to `OUT_DIR`.
+
+use askama::Template;
+
+#[test]
+fn test_embedded_dir() {
+    #[derive(Template)]
+    #[template(path = "embedded.html", config = "embedded-dirs.toml")]
+    struct Embedded<'a> {
+        name: &'a str,
+    }
+
+    assert_eq!(
+        Embedded { name: "world" }.render().unwrap(),
+        "Hello, world!"
+    );
+}
+
+#[test]
+fn test_embedded_dir_extends() {
+    #[derive(Template)]
+    #[template(path = "embedded/child.html", config = "embedded-dirs.toml")]
+    struct Child<'a> {
+        name: &'a str,
+    }
+
+    assert_eq!(Child { name: "Title" }.render().unwrap(), "<h1>Title</h1>");
+}
+
+#[test]
+fn test_embedded_dir_fallback() {
+    // Templates in the normal `templates` directory are still found.
+    #[derive(Template)]
+    #[template(path = "hello.html", config = "embedded-dirs.toml")]
+    struct Hello<'a> {
+        name: &'a str,
+    }
+
+    assert_eq!(Hello { name: "world" }.render().unwrap(), "Hello, world!");
+}
@@ -235,6 +235,25 @@ fn associated_constants() {
     assert_eq!(tmpl.render().unwrap(), "<C> X");
 }
 
+#[test]
+fn test_enum_where_clause_and_const_generics() {
+    #[derive(Template, Debug)]
+    #[template(ext = "txt")]
+    enum Shape<'a, T: Display, const N: usize>
+    where
+        T: Copy + Debug,
+    {
+        #[template(source = "{{ self.0[0] }}/{{ N }}")]
+        Many(&'a [T; N]),
+        #[template(source = "one {{ value }}")]
+        One { value: T },
+    }
+
+    assert_eq!(Shape::Many(&[7u8, 8]).render().unwrap(), "7/2");
+    let tmpl: Shape<'_, u8, 3> = Shape::One { value: 5 };
+    assert_eq!(tmpl.render().unwrap(), "one 5");
+}
+
 #[derive(Debug)]
 struct X;
 
@@ -25,6 +25,37 @@ fn filter_escape() {
     );
 }
 
+#[test]
+fn filter_safe_if() {
+    #[derive(Template)]
+    #[template(
+        source = "<div>{{ content|safe_if(trusted) }}</div><div>{{ content|safe_if(!trusted) }}</div>",
+        ext = "html"
+    )]
+    struct SafeIfTemplate<'a> {
+        content: &'a str,
+        trusted: bool,
+    }
+
+    let t = SafeIfTemplate {
+        content: "<b>hi</b>",
+        trusted: true,
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "<div><b>hi</b></div><div>&#60;b&#62;hi&#60;/b&#62;</div>"
+    );
+
+    let t = SafeIfTemplate {
+        content: "<b>hi</b>",
+        trusted: false,
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "<div>&#60;b&#62;hi&#60;/b&#62;</div><div><b>hi</b></div>"
+    );
+}
+
 #[test]
 fn filter_opt_escaper_none() {
     #[derive(Template)]
@@ -110,6 +141,16 @@ mod filters {
     pub fn mytrim(s: &dyn std::fmt::Display, _: &dyn Values) -> ::askama::Result<String> {
         Ok(s.to_string().trim().to_owned())
     }
+
+    // for test_custom_filter_resembling_builtin
+    pub fn uper(s: &str, _: &dyn Values, n: usize) -> ::askama::Result<String> {
+        Ok(s.to_uppercase().repeat(n))
+    }
+
+    // for test_custom_filter_resembling_builtin
+    pub fn sup(s: &str, _: &dyn Values) -> ::askama::Result<String> {
+        Ok(format!("<sup>{s}</sup>"))
+    }
 }
 
 #[test]
@@ -124,6 +165,28 @@ fn test_my_filter() {
     assert_eq!(t.render().unwrap(), "faa");
 }
 
+#[test]
+fn test_custom_filter_resembling_builtin() {
+    // `uper` is close to the built-in `upper`, but the custom filter must still be used.
+    #[derive(Template)]
+    #[template(source = "{{ s|uper(2) }}", ext = "txt")]
+    struct MyFilterTemplate<'a> {
+        s: &'a str,
+    }
+
+    let t = MyFilterTemplate { s: "foo" };
+    assert_eq!(t.render().unwrap(), "FOOFOO");
+
+    // `sup` is close to the built-in `sum`.
+    #[derive(Template)]
+    #[template(source = "{{ s|sup }}", ext = "txt")]
+    struct SupTemplate<'a> {
+        s: &'a str,
+    }
+
+    assert_eq!(SupTemplate { s: "2" }.render().unwrap(), "<sup>2</sup>");
+}
+
 #[test]
 fn test_join() {
     #[derive(Template)]
@@ -152,6 +215,224 @@ fn test_vec_join() {
     assert_eq!(t.render().unwrap(), "foo, bar, bazz");
 }
 
+#[test]
+fn test_join_human() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ names|join_human(", ", " & ") }}|{{ names|join_human(", ", " and ", oxford = true) }}"#,
+        ext = "html"
+    )]
+    struct JoinHumanTemplate<'a> {
+        names: Vec<&'a str>,
+    }
+
+    let t = JoinHumanTemplate { names: vec!["Tom"] };
+    assert_eq!(t.render().unwrap(), "Tom|Tom");
+    let t = JoinHumanTemplate {
+        names: vec!["Tom", "Jerry"],
+    };
+    assert_eq!(t.render().unwrap(), "Tom &#38; Jerry|Tom and Jerry");
+    let t = JoinHumanTemplate {
+        names: vec!["Huey", "Dewey", "Louie"],
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "Huey, Dewey &#38; Louie|Huey, Dewey, and Louie"
+    );
+    let t = JoinHumanTemplate { names: vec![] };
+    assert_eq!(t.render().unwrap(), "|");
+}
+
+#[test]
+fn test_flatten() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ rows|flatten|join(\", \") }}|{% for x in rows|flatten %}[{{ x }}]{% endfor %}",
+        ext = "txt"
+    )]
+    struct FlattenTemplate {
+        rows: Vec<Vec<i32>>,
+    }
+
+    let t = FlattenTemplate {
+        rows: vec![vec![1, 2], vec![], vec![3], vec![]],
+    };
+    assert_eq!(t.render().unwrap(), "1, 2, 3|[1][2][3]");
+
+    let t = FlattenTemplate {
+        rows: vec![vec![], vec![]],
+    };
+    assert_eq!(t.render().unwrap(), "|");
+}
+
+#[test]
+fn test_flatten_depth() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ cube|flatten(2)|join(\", \") }}|{{ cube|flatten|flatten|join(\"\") }}",
+        ext = "txt"
+    )]
+    struct FlattenDepthTemplate<'a> {
+        cube: &'a [Vec<Vec<&'a str>>],
+    }
+
+    let t = FlattenDepthTemplate {
+        cube: &[vec![vec!["a"], vec![]], vec![vec!["b", "c"]]],
+    };
+    assert_eq!(t.render().unwrap(), "a, b, c|abc");
+}
+
+#[cfg(feature = "markdown")]
+#[test]
+fn test_markdown() {
+    #[derive(Template)]
+    #[template(
+        source = "<div>{{ text|markdown }}</div><div>{{ text|markdown(sanitize = !trusted) }}</div>",
+        ext = "html"
+    )]
+    struct MarkdownTemplate<'a> {
+        text: &'a str,
+        trusted: bool,
+    }
+
+    let t = MarkdownTemplate {
+        text: "**bold** <i>raw</i>",
+        trusted: false,
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "<div><p><strong>bold</strong> &lt;i&gt;raw&lt;/i&gt;</p>\n</div>\
+         <div><p><strong>bold</strong> &lt;i&gt;raw&lt;/i&gt;</p>\n</div>"
+    );
+
+    let t = MarkdownTemplate {
+        text: "**bold** <i>raw</i>",
+        trusted: true,
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "<div><p><strong>bold</strong> &lt;i&gt;raw&lt;/i&gt;</p>\n</div>\
+         <div><p><strong>bold</strong> <i>raw</i></p>\n</div>"
+    );
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_date_datetime() {
+    use chrono::{NaiveDate, NaiveDateTime};
+
+    #[derive(Template)]
+    #[template(
+        source = "{{ day|date }} {{ day|date(\"%d.%m.%Y\") }} \
+                  {{ created_at|datetime }} {{ created_at|datetime(format = fmt) }} \
+                  {{ created_at|date }}",
+        ext = "txt"
+    )]
+    struct DateTemplate<'a> {
+        day: NaiveDate,
+        created_at: NaiveDateTime,
+        fmt: &'a str,
+    }
+
+    let day = NaiveDate::from_ymd_opt(2025, 3, 8).unwrap();
+    let t = DateTemplate {
+        day,
+        created_at: day.and_hms_opt(14, 5, 0).unwrap(),
+        fmt: "%H:%M",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "2025-03-08 08.03.2025 2025-03-08 14:05:00 14:05 2025-03-08"
+    );
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_filter_replace_regex() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ s|replace_regex("\\s+", " ") }}|{{ s|replace_regex(pattern, "[$1]") }}"#,
+        ext = "html"
+    )]
+    struct ReplaceRegexTemplate<'a> {
+        s: &'a str,
+        pattern: &'a str,
+    }
+
+    let t = ReplaceRegexTemplate {
+        s: "a  <b>\n\t c",
+        pattern: r"([a-z])\b",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "a &#60;b&#62; c|[a]  &#60;[b]&#62;\n\t [c]"
+    );
+
+    let t = ReplaceRegexTemplate {
+        s: "a",
+        pattern: "(",
+    };
+    assert!(matches!(t.render(), Err(askama::Error::Custom(_))));
+}
+
+#[cfg(feature = "grapheme")]
+#[test]
+fn test_filter_graphemes() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ s|truncate_graphemes(n) }}|{{ s|length_graphemes }}|{{ s|reverse_graphemes }}",
+        ext = "txt"
+    )]
+    struct GraphemesTemplate<'a> {
+        s: &'a str,
+        n: usize,
+    }
+
+    // A family emoji is a single grapheme cluster of five code points joined by ZWJ.
+    let family = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}";
+    let s = format!("hi {family}!");
+    let t = GraphemesTemplate { s: &s, n: 4 };
+    assert_eq!(
+        t.render().unwrap(),
+        format!("hi {family}...|5|!{family} ih")
+    );
+
+    let t = GraphemesTemplate { s: &s, n: 3 };
+    assert_eq!(t.render().unwrap(), format!("hi ...|5|!{family} ih"));
+
+    let t = GraphemesTemplate { s: &s, n: 5 };
+    assert_eq!(t.render().unwrap(), format!("{s}|5|!{family} ih"));
+}
+
+#[cfg(feature = "unicode-normalization")]
+#[test]
+fn test_filter_unicode_normalization() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ s|nfc }}|{{ s|nfd }}|{{ s|nfkc }}|{{ s|nfkd }}",
+        ext = "txt"
+    )]
+    struct Normalize<'a> {
+        s: &'a str,
+    }
+
+    // "é" as "e" followed by a combining acute accent is composed into a single code point.
+    let t = Normalize { s: "e\u{301}" };
+    let rendered = t.render().unwrap();
+    let [nfc, nfd, nfkc, nfkd] = rendered.split('|').collect::<Vec<_>>()[..] else {
+        panic!("unexpected output: {rendered:?}");
+    };
+    assert_eq!(nfc, "\u{e9}");
+    assert_eq!(nfc.chars().count(), 1);
+    assert_eq!(nfd, "e\u{301}");
+    assert_eq!(nfkc, "\u{e9}");
+    assert_eq!(nfkd, "e\u{301}");
+
+    // Only the compatibility forms replace the ligature.
+    let t = Normalize { s: "\u{fb01}" };
+    assert_eq!(t.render().unwrap(), "\u{fb01}|\u{fb01}|fi|fi");
+}
+
 #[cfg(feature = "serde_json")]
 #[test]
 fn test_json() {
@@ -182,6 +463,33 @@ fn test_json() {
     );
 }
 
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_json_attr() {
+    #[derive(Template)]
+    #[template(
+        source = r#"<div data-config="{{ config|json_attr }}" data-alt='{{ config|json_attr }}'></div>"#,
+        ext = "html"
+    )]
+    struct JsonAttrTemplate<'a> {
+        config: &'a Value,
+    }
+
+    let val = json!({"title": r#"say "hi" & 'bye'"#, "n": 1});
+    let rendered = JsonAttrTemplate { config: &val }.render().unwrap();
+    assert_eq!(
+        rendered,
+        "<div data-config=\"{&#34;n&#34;:1,&#34;title&#34;:\
+         &#34;say \\&#34;hi\\&#34; \\u0026 \\u0027bye\\u0027&#34;}\" \
+         data-alt='{&#34;n&#34;:1,&#34;title&#34;:\
+         &#34;say \\&#34;hi\\&#34; \\u0026 \\u0027bye\\u0027&#34;}'></div>"
+    );
+
+    // No quote can end the attribute early: the only quotes are the attributes' delimiters.
+    assert_eq!(rendered.matches('"').count(), 2);
+    assert_eq!(rendered.matches('\'').count(), 2);
+}
+
 #[cfg(feature = "serde_json")]
 #[test]
 fn test_pretty_json() {
@@ -327,6 +635,1014 @@ fn test_filter_truncate() {
     );
 }
 
+#[test]
+fn test_filter_ellipsize_middle() {
+    #[derive(Template)]
+    #[template(source = "{{ path|ellipsize_middle(30) }}", ext = "html")]
+    struct EllipsizeMiddle<'a> {
+        path: &'a str,
+    }
+
+    let path = "/home/user/projects/askama/testing/templates/ellipsize-middle.html";
+    let rendered = EllipsizeMiddle { path }.render().unwrap();
+    assert_eq!(rendered, "/home/user/pro…ize-middle.html");
+    assert_eq!(rendered.chars().count(), 30);
+    let (start, end) = rendered.split_once('…').unwrap();
+    assert!(path.starts_with(start));
+    assert!(path.ends_with(end));
+    assert_eq!(end.chars().count(), start.chars().count() + 1);
+
+    assert_eq!(
+        EllipsizeMiddle {
+            path: "/etc/askama.toml"
+        }
+        .render()
+        .unwrap(),
+        "/etc/askama.toml"
+    );
+    // The filter is applied before the output is escaped.
+    assert_eq!(
+        EllipsizeMiddle {
+            path: "<a>&<b>&<c>&<d>&<e>&<f>&<g>&<h>"
+        }
+        .render()
+        .unwrap(),
+        "&#60;a&#62;&#38;&#60;b&#62;&#38;&#60;c&#62;&#38;&#60;d…&#60;e&#62;&#38;&#60;f&#62;&#38;&#60;g&#62;&#38;&#60;h&#62;"
+    );
+}
+
+#[test]
+fn test_filter_group_consecutive() {
+    struct Entry {
+        date: &'static str,
+        message: &'static str,
+    }
+
+    #[derive(Template)]
+    #[template(
+        source = "{% for (date, run) in entries|group_consecutive(\"date\") -%}\n\
+                  [{{ date }}]{% for entry in run %} {{ entry.message }}{% endfor %}\n\
+                  {% endfor %}",
+        ext = "txt"
+    )]
+    struct Log {
+        entries: Vec<Entry>,
+    }
+
+    #[derive(Template)]
+    #[template(
+        source = "{% for (level, run) in levels|group_consecutive %}{{ level }}x{{ run.len() }} {% endfor %}",
+        ext = "txt"
+    )]
+    struct Levels<'a> {
+        levels: &'a [&'a str],
+    }
+
+    let entry = |date, message| Entry { date, message };
+    let log = Log {
+        entries: vec![
+            entry("2024-05-01", "start"),
+            entry("2024-05-01", "load"),
+            entry("2024-05-02", "sync"),
+            entry("2024-05-01", "late"),
+        ],
+    };
+    // The returning date `2024-05-01` starts a new run.
+    assert_eq!(
+        log.render().unwrap(),
+        "[2024-05-01] start load\n[2024-05-02] sync\n[2024-05-01] late\n"
+    );
+    assert_eq!(Log { entries: vec![] }.render().unwrap(), "");
+
+    let levels = ["info", "info", "warn", "warn", "warn", "info"];
+    assert_eq!(
+        Levels { levels: &levels }.render().unwrap(),
+        "infox2 warnx3 infox1 "
+    );
+}
+
+#[test]
+fn test_filter_repeat() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ "="|repeat(5) }}|{{ s|repeat(count) }}|{{ "-"|repeat(0) }}"#,
+        ext = "html"
+    )]
+    struct Repeat<'a> {
+        s: &'a str,
+        count: i32,
+    }
+
+    assert_eq!(
+        Repeat { s: "<>", count: 2 }.render().unwrap(),
+        "=====|&#60;&#62;&#60;&#62;|"
+    );
+    assert_eq!(Repeat { s: "ab", count: 0 }.render().unwrap(), "=====||");
+    // A negative count that is only known at runtime yields an empty string.
+    assert_eq!(Repeat { s: "ab", count: -3 }.render().unwrap(), "=====||");
+}
+
+#[test]
+fn test_filter_escape_control() {
+    #[derive(Template)]
+    #[template(source = "<pre>{{ line|escape_control }}</pre>", ext = "html")]
+    struct EscapeControl<'a> {
+        line: &'a str,
+    }
+
+    let t = EscapeControl {
+        line: "\x1b[31merror\x1b[0m: <nul>\0\tend\n",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "<pre>␛[31merror␛[0m: &#60;nul&#62;␀\tend\n</pre>"
+    );
+}
+
+#[test]
+fn test_filter_redact() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ secret|redact(4) }} {{ secret|redact(2, first = 2, mask = '#') }}",
+        ext = "html"
+    )]
+    struct Redact<'a> {
+        secret: &'a str,
+    }
+
+    assert_eq!(
+        Redact {
+            secret: "4111111111111234"
+        }
+        .render()
+        .unwrap(),
+        "************1234 41############34"
+    );
+    // A value that is not longer than the revealed characters is masked completely.
+    assert_eq!(Redact { secret: "1234" }.render().unwrap(), "**** ####");
+    // The filter is applied before the output is escaped.
+    assert_eq!(
+        Redact {
+            secret: "p<ss>w&rd"
+        }
+        .render()
+        .unwrap(),
+        "*****w&#38;rd p&#60;#####rd"
+    );
+}
+
+#[test]
+fn test_filter_ordinal() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ n|ordinal }} {{ n|ordinal(true) }} {{ n|ordinal(words=spell) }}",
+        ext = "html"
+    )]
+    struct Ordinal {
+        n: i32,
+        spell: bool,
+    }
+
+    for (n, expected) in [
+        (1, "1st first 1st"),
+        (2, "2nd second 2nd"),
+        (3, "3rd third 3rd"),
+        (11, "11th eleventh 11th"),
+        (21, "21st 21st 21st"),
+        (113, "113th 113th 113th"),
+        (-2, "-2nd -2nd -2nd"),
+    ] {
+        assert_eq!(Ordinal { n, spell: false }.render().unwrap(), expected);
+    }
+    assert_eq!(
+        Ordinal { n: 12, spell: true }.render().unwrap(),
+        "12th twelfth twelfth"
+    );
+}
+
+#[test]
+fn test_filter_contains() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% if path|contains("/admin/") %}admin{% else %}user{% endif %}
+{{- path|contains(needle) }} {{ path|count_matches("/") }} {{ path|count_matches(needle) }}"#,
+        ext = "txt"
+    )]
+    struct ContainsTemplate<'a> {
+        path: &'a str,
+        needle: String,
+    }
+
+    let t = ContainsTemplate {
+        path: "/admin/users/1",
+        needle: "/users/".into(),
+    };
+    assert_eq!(t.render().unwrap(), "admintrue 3 1");
+
+    let t = ContainsTemplate {
+        path: "/Admin/Users",
+        needle: "/users/".into(),
+    };
+    assert_eq!(t.render().unwrap(), "userfalse 2 0");
+}
+
+#[test]
+fn test_filter_clamp() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ pct|clamp(0, 100) }} {{ ratio|clamp(-1.0, max = 1.0) }} {{ pct|clamp(lo, hi) }}",
+        ext = "txt"
+    )]
+    struct ClampTemplate {
+        pct: u8,
+        ratio: f64,
+        lo: u8,
+        hi: u8,
+    }
+
+    for (pct, ratio, expected) in [
+        (0, -2.5, "0 -1 10"),
+        (10, -1.0, "10 -1 10"),
+        (42, 0.5, "42 0.5 42"),
+        (100, 1.0, "100 1 50"),
+        (200, 7.0, "100 1 50"),
+        (50, f64::NAN, "50 NaN 50"),
+    ] {
+        let t = ClampTemplate {
+            pct,
+            ratio,
+            lo: 10,
+            hi: 50,
+        };
+        assert_eq!(t.render().unwrap(), expected);
+    }
+
+    // Loop variables are references.
+    #[derive(Template)]
+    #[template(
+        source = "{% for i in values %}{{ i|clamp(-2, 2) }},{% endfor %}",
+        ext = "txt"
+    )]
+    struct ClampLoopTemplate {
+        values: Vec<i64>,
+    }
+
+    let t = ClampLoopTemplate {
+        values: vec![-5, -2, 0, 2, 5],
+    };
+    assert_eq!(t.render().unwrap(), "-2,-2,0,2,2,");
+}
+
+#[test]
+fn test_filter_chunks() {
+    #[derive(Template)]
+    #[template(
+        source = "{% for row in cells|chunks(3) %}[{{ row|join(\",\") }}]{% endfor %} \
+                  {% for row in cells|chunks(size) %}[{{ row.len() }}]{% endfor %} \
+                  {% for row in (1..=5)|chunks(2) %}[{{ row|join(\",\") }}]{% endfor %}",
+        ext = "txt"
+    )]
+    struct ChunksTemplate<'a> {
+        cells: &'a [u32],
+        size: usize,
+    }
+
+    let t = ChunksTemplate {
+        cells: &[1, 2, 3, 4, 5, 6, 7],
+        size: 4,
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "[1,2,3][4,5,6][7] [4][3] [1,2][3,4][5]"
+    );
+
+    let t = ChunksTemplate {
+        cells: &[1, 2, 3],
+        size: 0,
+    };
+    assert_eq!(t.render().unwrap(), "[1,2,3]  [1,2][3,4][5]");
+
+    // Slices are chunked without copying, so the chunks are `&[String]`, not `Vec<&String>`.
+    #[derive(Template)]
+    #[template(
+        source = "{% for row in cells|chunks(2) %}{{ Self::chunk_len(row) }} {% endfor %}",
+        ext = "txt"
+    )]
+    struct ChunksSliceTemplate {
+        cells: Vec<String>,
+    }
+
+    impl ChunksSliceTemplate {
+        fn chunk_len(chunk: &[String]) -> usize {
+            chunk.len()
+        }
+    }
+
+    let t = ChunksSliceTemplate {
+        cells: vec!["a".into(), "b".into(), "c".into()],
+    };
+    assert_eq!(t.render().unwrap(), "2 1 ");
+}
+
+#[test]
+fn test_filter_columns() {
+    #[derive(Template)]
+    #[template(
+        source = "{% for column in items|columns(3) %}[{{ column|join(\",\") }}]{% endfor %} \
+                  {% for column in items|columns(count) %}[{{ column.len() }}]{% endfor %}",
+        ext = "txt"
+    )]
+    struct ColumnsTemplate {
+        items: Vec<u32>,
+        count: usize,
+    }
+
+    let t = ColumnsTemplate {
+        items: vec![1, 2, 3, 4, 5, 6, 7],
+        count: 2,
+    };
+    assert_eq!(t.render().unwrap(), "[1,2,3][4,5][6,7] [4][3]");
+
+    let t = ColumnsTemplate {
+        items: vec![1, 2],
+        count: 0,
+    };
+    assert_eq!(t.render().unwrap(), "[1][2][] ");
+}
+
+#[test]
+fn test_filter_shell_escape() {
+    #[derive(Template)]
+    #[template(source = "rm -- {{ path|shell_escape }}", ext = "txt")]
+    struct Remove<'a> {
+        path: &'a str,
+    }
+
+    assert_eq!(
+        Remove {
+            path: "it's; rm -rf /"
+        }
+        .render()
+        .unwrap(),
+        r"rm -- 'it'\''s; rm -rf /'"
+    );
+    assert_eq!(Remove { path: "" }.render().unwrap(), "rm -- ''");
+
+    #[derive(Template)]
+    #[template(
+        source = "echo {{ greeting }} {{ name|shell_escape }} {{ count }}",
+        ext = "sh"
+    )]
+    struct Script<'a> {
+        greeting: &'a str,
+        name: &'a str,
+        count: u32,
+    }
+
+    assert_eq!(
+        Script {
+            greeting: "Hello",
+            name: "O'Brien; rm -rf /",
+            count: 3,
+        }
+        .render()
+        .unwrap(),
+        r"echo 'Hello' 'O'\''Brien; rm -rf /' '3'"
+    );
+
+    // Only bare words are protected: inside of quotes the added single quotes do not quote the
+    // value, so the shell would execute the command substitution in both lines.
+    #[derive(Template)]
+    #[template(source = "echo \"{{ value }}\"\necho 'x{{ value }}'", ext = "sh")]
+    struct Quoted<'a> {
+        value: &'a str,
+    }
+
+    assert_eq!(
+        Quoted {
+            value: "$(touch pwned)"
+        }
+        .render()
+        .unwrap(),
+        "echo \"'$(touch pwned)'\"\necho 'x'$(touch pwned)''"
+    );
+}
+
+#[test]
+fn test_filter_zip() {
+    #[derive(Template)]
+    #[template(
+        source = "{% for (label, value) in labels|zip(values) %}[{{ label }}={{ value }}]{% endfor %} \
+                  {{ labels|zip(values)|length_pairs }} \
+                  {% for (a, b) in values|zip(other = labels) %}{{ a }}{{ b }}{% endfor %}",
+        ext = "txt"
+    )]
+    struct ZipTemplate<'a> {
+        labels: Vec<&'a str>,
+        values: [u32; 2],
+    }
+
+    mod filters {
+        pub fn length_pairs<T: IntoIterator>(
+            pairs: T,
+            _: &dyn askama::Values,
+        ) -> askama::Result<usize> {
+            Ok(pairs.into_iter().count())
+        }
+    }
+
+    let t = ZipTemplate {
+        labels: vec!["a", "b", "c"],
+        values: [1, 2],
+    };
+    assert_eq!(t.render().unwrap(), "[a=1][b=2] 2 1a2b");
+}
+
+#[test]
+fn test_filter_table() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ rows|table }}\n\n{{ rows|table(header = true) }}",
+        ext = "txt"
+    )]
+    struct TableTemplate<'a> {
+        rows: Vec<[&'a str; 3]>,
+    }
+
+    let t = TableTemplate {
+        rows: vec![["name", "kind", "size"], ["Cargo.toml", "file", "1.2k"]],
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "\
+name        kind  size
+Cargo.toml  file  1.2k
+
+name        kind  size
+----------  ----  ----
+Cargo.toml  file  1.2k"
+    );
+}
+
+#[test]
+fn test_filter_convert_case() {
+    #[derive(Template)]
+    #[template(
+        source = "{% for name in names %}\
+            {{ name|snake_case }} {{ name|camel_case }} {{ name|pascal_case }} {{ name|kebab_case }}\n\
+        {% endfor %}",
+        ext = "txt"
+    )]
+    struct ConvertCase<'a> {
+        names: &'a [&'a str],
+    }
+
+    let t = ConvertCase {
+        names: &["HTTPServer", "my-field name"],
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "\
+http_server httpServer HttpServer http-server
+my_field_name myFieldName MyFieldName my-field-name
+"
+    );
+}
+
+#[test]
+fn test_filter_box() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ text|box }}\n{{ text|box(title = \"Note\") }}\n{{ text|box(\"ascii\") }}",
+        ext = "txt"
+    )]
+    struct BoxTemplate<'a> {
+        text: &'a str,
+    }
+
+    let t = BoxTemplate {
+        text: "Hi\nthere, world",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "\
+┌──────────────┐
+│ Hi           │
+│ there, world │
+└──────────────┘
+┌─ Note ───────┐
+│ Hi           │
+│ there, world │
+└──────────────┘
++--------------+
+| Hi           |
+| there, world |
++--------------+"
+    );
+}
+
+#[test]
+fn test_filter_paginate() {
+    #[derive(Template)]
+    #[template(
+        source = "{% for item in items|paginate(page, 3) %}[{{ item }}]{% endfor %} \
+                  {{ page }}/{{ items|page_count(3) }}",
+        ext = "txt"
+    )]
+    struct PaginateTemplate {
+        items: Vec<u32>,
+        page: i32,
+    }
+
+    let t = |page| PaginateTemplate {
+        items: vec![1, 2, 3, 4, 5, 6, 7],
+        page,
+    };
+    assert_eq!(t(1).render().unwrap(), "[1][2][3] 1/3");
+    assert_eq!(t(3).render().unwrap(), "[7] 3/3");
+    assert_eq!(t(4).render().unwrap(), " 4/3");
+    assert_eq!(t(0).render().unwrap(), " 0/3");
+    assert_eq!(t(-1).render().unwrap(), " -1/3");
+
+    #[derive(Template)]
+    #[template(
+        source = "{{ items|paginate(2, per_page)|join(\",\") }} {{ items|page_count(per_page) }}",
+        ext = "txt"
+    )]
+    struct PerPageTemplate<'a> {
+        items: &'a [&'a str],
+        per_page: u8,
+    }
+
+    let t = PerPageTemplate {
+        items: &["a", "b", "c", "d"],
+        per_page: 2,
+    };
+    assert_eq!(t.render().unwrap(), "c,d 2");
+    let t = PerPageTemplate {
+        items: &[],
+        per_page: 2,
+    };
+    assert_eq!(t.render().unwrap(), " 0");
+}
+
+#[test]
+fn test_filter_escape_once() {
+    #[derive(Template)]
+    #[template(
+        source = "<p title='{{ s|escape_once }}'>{{ s|escape_once }}</p>",
+        ext = "html"
+    )]
+    struct EscapeOnceTemplate<'a> {
+        s: &'a str,
+    }
+
+    let t = EscapeOnceTemplate {
+        s: "Tom &amp; Jerry & 'Spike' &#60;3",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "<p title='Tom &amp; Jerry &#38; &#39;Spike&#39; &#60;3'>\
+         Tom &amp; Jerry &#38; &#39;Spike&#39; &#60;3</p>"
+    );
+}
+
+#[test]
+fn test_filter_highlight() {
+    #[derive(Template)]
+    #[template(source = "<p>{{ text|highlight(query) }}</p>", ext = "html")]
+    struct HighlightTemplate<'a> {
+        text: &'a str,
+        query: &'a str,
+    }
+
+    let t = HighlightTemplate {
+        text: "<b>Askama</b> & askama_derive",
+        query: "ASKAMA",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "<p>&#60;b&#62;<mark>Askama</mark>&#60;/b&#62; &#38; <mark>askama</mark>_derive</p>"
+    );
+
+    let t = HighlightTemplate {
+        text: "<b>",
+        query: "<b>",
+    };
+    assert_eq!(t.render().unwrap(), "<p><mark>&#60;b&#62;</mark></p>");
+
+    let t = HighlightTemplate {
+        text: "a & b",
+        query: "",
+    };
+    assert_eq!(t.render().unwrap(), "<p>a &#38; b</p>");
+}
+
+#[test]
+fn test_filter_format_each() {
+    #[derive(Template)]
+    #[template(
+        source = r##"<ul>{{ items|format_each("<li>{}</li>")|join("")|safe }}</ul>
+{% for item in items|format_each("{{{}}}") %}{{ item }} {% endfor %}
+{{ numbers|format_each(format = "#{}")|join(", ") }}"##,
+        ext = "html"
+    )]
+    struct FormatEachTemplate<'a> {
+        items: Vec<&'a str>,
+        numbers: [u32; 3],
+    }
+
+    let t = FormatEachTemplate {
+        items: vec!["one", "<two>"],
+        numbers: [1, 2, 3],
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "<ul><li>one</li><li>&#60;two&#62;</li></ul>\n{one} {&#60;two&#62;} \n#1, #2, #3"
+    );
+}
+
+#[test]
+fn test_filter_ljust_rjust() {
+    #[derive(Template)]
+    #[template(
+        source = "[{{ s|ljust(6) }}][{{ s|ljust(6, '*') }}][{{ s|rjust(6) }}]\
+                  [{{ n|rjust(width, fill = fill) }}][{{ s|rjust(2) }}]",
+        ext = "txt"
+    )]
+    struct JustifyTemplate<'a> {
+        s: &'a str,
+        n: u32,
+        width: usize,
+        fill: char,
+    }
+
+    let t = JustifyTemplate {
+        s: "abc",
+        n: 42,
+        width: 5,
+        fill: '0',
+    };
+    assert_eq!(t.render().unwrap(), "[abc   ][abc***][   abc][00042][abc]");
+}
+
+#[test]
+fn test_filter_justify_ansi_width() {
+    #[derive(Template)]
+    #[template(
+        source = "[{{ s|ljust(6, ansi_width = true) }}][{{ s|rjust(6, '.', true) }}]\
+                  [{{ s|center(7, ansi_width = true) }}][{{ s|ljust(6) }}]",
+        ext = "txt"
+    )]
+    struct JustifyTemplate<'a> {
+        s: &'a str,
+    }
+
+    let t = JustifyTemplate {
+        s: "\x1b[1;31mabc\x1b[0m",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "[\x1b[1;31mabc\x1b[0m   ][...\x1b[1;31mabc\x1b[0m]\
+         [  \x1b[1;31mabc\x1b[0m  ][\x1b[1;31mabc\x1b[0m]"
+    );
+
+    // The option can be chosen at runtime, too.
+    #[derive(Template)]
+    #[template(source = "[{{ s|center(5, ansi_width = ansi) }}]", ext = "txt")]
+    struct RuntimeAnsiWidth<'a> {
+        s: &'a str,
+        ansi: bool,
+    }
+
+    let s = "\x1b[1mab\x1b[0m";
+    assert_eq!(
+        RuntimeAnsiWidth { s, ansi: true }.render().unwrap(),
+        "[ \x1b[1mab\x1b[0m  ]"
+    );
+    assert_eq!(
+        RuntimeAnsiWidth { s, ansi: false }.render().unwrap(),
+        "[\x1b[1mab\x1b[0m]"
+    );
+}
+
+#[test]
+fn test_filter_visible_len() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ s|visible_len }}{% if s|visible_len > 4 %} wide{% endif %}",
+        ext = "txt"
+    )]
+    struct VisibleLen<'a> {
+        s: &'a str,
+    }
+
+    assert_eq!(VisibleLen { s: "abc" }.render().unwrap(), "3");
+    // The color codes are not counted, the CJK characters count twice.
+    assert_eq!(
+        VisibleLen {
+            s: "\x1b[1;31m東京\x1b[0m!"
+        }
+        .render()
+        .unwrap(),
+        "5 wide"
+    );
+}
+
+#[test]
+fn test_filter_trim_lines() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ code|trim_lines }}|{{ code|trim_lines(trailing_only = keep_indent) }}",
+        ext = "txt"
+    )]
+    struct TrimLinesTemplate<'a> {
+        code: &'a str,
+        keep_indent: bool,
+    }
+
+    let t = TrimLinesTemplate {
+        code: "  [server]  \n\n    port = 80\t\n",
+        keep_indent: true,
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "[server]\n\nport = 80\n|  [server]\n\n    port = 80\n"
+    );
+}
+
+#[test]
+fn test_filter_sign() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ count|sign }} {{ delta|sign }} {% if delta|sign == -1 %}down{% else %}not down{% endif %}"#,
+        ext = "txt"
+    )]
+    struct SignTemplate {
+        count: i64,
+        delta: f64,
+    }
+
+    let render = |count, delta| SignTemplate { count, delta }.render().unwrap();
+    assert_eq!(render(-3, -0.25), "-1 -1 down");
+    assert_eq!(render(0, 0.0), "0 0 not down");
+    assert_eq!(render(7, 1e-9), "1 1 not down");
+    assert_eq!(render(0, f64::NAN), "0 0 not down");
+}
+
+#[test]
+fn test_filter_default_if_none() {
+    #[derive(Template)]
+    #[template(
+        source = r#"[{{ name|default_if_none("anonymous") }}][{{ count|default_if_none(default = "?") }}]"#,
+        ext = "html"
+    )]
+    struct DefaultIfNoneTemplate<'a> {
+        name: Option<&'a str>,
+        count: Option<u32>,
+    }
+
+    let t = DefaultIfNoneTemplate {
+        name: Some(""),
+        count: Some(0),
+    };
+    assert_eq!(t.render().unwrap(), "[][0]");
+    let t = DefaultIfNoneTemplate {
+        name: None,
+        count: None,
+    };
+    assert_eq!(t.render().unwrap(), "[anonymous][?]");
+    let t = DefaultIfNoneTemplate {
+        name: Some("<b>"),
+        count: Some(3),
+    };
+    assert_eq!(t.render().unwrap(), "[&#60;b&#62;][3]");
+}
+
+#[test]
+fn test_filter_coalesce() {
+    #[derive(Template)]
+    #[template(
+        source = r#"[{{ nick|coalesce(username, email, "anonymous") }}][{{ count|coalesce(0u32) }}]"#,
+        ext = "html"
+    )]
+    struct CoalesceTemplate<'a> {
+        nick: Option<&'a str>,
+        username: String,
+        email: Option<String>,
+        count: Option<u32>,
+    }
+
+    let t = CoalesceTemplate {
+        nick: Some(""),
+        username: "<bob>".into(),
+        email: None,
+        count: Some(3),
+    };
+    assert_eq!(t.render().unwrap(), "[&#60;bob&#62;][3]");
+    let t = CoalesceTemplate {
+        nick: None,
+        username: String::new(),
+        email: Some("bob@example.com".into()),
+        count: None,
+    };
+    assert_eq!(t.render().unwrap(), "[bob@example.com][0]");
+    let t = CoalesceTemplate {
+        nick: None,
+        username: String::new(),
+        email: Some(String::new()),
+        count: None,
+    };
+    assert_eq!(t.render().unwrap(), "[anonymous][0]");
+    let t = CoalesceTemplate {
+        nick: Some("Bobby"),
+        username: "bob".into(),
+        email: None,
+        count: None,
+    };
+    assert_eq!(t.render().unwrap(), "[Bobby][0]");
+}
+
+#[test]
+fn test_filter_normalize_ws() {
+    #[derive(Template)]
+    #[template(source = "{{ text|normalize_ws }}", ext = "txt")]
+    struct NormalizeWsTemplate<'a> {
+        text: &'a str,
+    }
+
+    let t = NormalizeWsTemplate {
+        text: "\n\n   Dear  \t reader,\n\n\n\n\tthis   is\n  pasted    text.   \n \n\t\n\nBye!\n\n",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "Dear reader,\n\nthis is\npasted text.\n\nBye!"
+    );
+}
+
+#[test]
+fn test_filter_dedent() {
+    #[derive(Template)]
+    #[template(
+        source = "<pre>
+    {%- filter dedent %}
+        def greet(name):
+
+            print(f\"Hello, {name}!\")
+    {%- endfilter -%}
+</pre>
+[{{ text|dedent }}]",
+        ext = "txt"
+    )]
+    struct DedentTemplate<'a> {
+        text: &'a str,
+    }
+
+    let t = DedentTemplate {
+        text: "        a\n          b\n   \n        c",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "<pre>\ndef greet(name):\n\n    print(f\"Hello, {name}!\")</pre>\n[a\n  b\n\nc]"
+    );
+}
+
+#[test]
+fn test_filter_excerpt() {
+    #[derive(Template)]
+    #[template(source = "{{ body|excerpt(40) }}", ext = "html")]
+    struct ExcerptTemplate<'a> {
+        body: &'a str,
+    }
+
+    let words = (1..=50).map(|i| format!("w{i}")).collect::<Vec<_>>();
+    let body = format!(
+        "<h2>Q&amp;A</h2>\n\n<p class=\"intro\">  {}  </p>\n<ul><li><a href=\"/\">{}</a></li></ul>",
+        words[..20].join("\n   "),
+        words[20..].join(" <i>&nbsp;</i> "),
+    );
+    let expected = format!("Q&#38;A {}...", words[..39].join(" "));
+    assert_eq!(ExcerptTemplate { body: &body }.render().unwrap(), expected);
+}
+
+#[test]
+fn test_filter_indent_prefix() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ text|indent("// ", first = true) }}|{{ text|indent("// ") }}"#,
+        ext = "txt"
+    )]
+    struct CommentTemplate<'a> {
+        text: &'a str,
+    }
+
+    let t = CommentTemplate {
+        text: "one\ntwo\nthree",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "// one\n// two\n// three|one\n// two\n// three"
+    );
+}
+
+#[test]
+fn test_filter_sum_avg() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ counts|sum }} {{ counts|avg }} {{ scores|sum }} {{ scores|avg }}",
+        ext = "txt"
+    )]
+    struct SumTemplate {
+        counts: Vec<u32>,
+        scores: Vec<f64>,
+    }
+
+    let t = SumTemplate {
+        counts: vec![1, 2, 3, 4],
+        scores: vec![0.5, 1.0, 3.0],
+    };
+    assert_eq!(t.render().unwrap(), "10 2.5 4.5 1.5");
+
+    let t = SumTemplate {
+        counts: vec![],
+        scores: vec![],
+    };
+    assert_eq!(t.render().unwrap(), "0 NaN 0 NaN");
+
+    let t = SumTemplate {
+        counts: vec![u32::MAX, 1],
+        scores: vec![],
+    };
+    let err = t.render().unwrap_err();
+    assert_matches!(&err, askama::Error::Custom(_));
+    assert_eq!(err.to_string(), "integer overflow in the `sum` filter");
+    assert!(
+        err.into_box()
+            .downcast_ref::<askama::filters::SumOverflow>()
+            .is_some()
+    );
+}
+
+#[test]
+fn test_filter_sum_attribute() {
+    struct Price {
+        net: u64,
+    }
+
+    struct Order {
+        amount: u64,
+        price: Price,
+        rating: (f32, u8),
+    }
+
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ orders|sum("amount") }} {{ orders|sum("price.net") }} {{ orders|avg(attribute = "rating.0") }}"#,
+        ext = "txt"
+    )]
+    struct OrdersTemplate {
+        orders: Vec<Order>,
+    }
+
+    let order = |amount, net, rating| Order {
+        amount,
+        price: Price { net },
+        rating: (rating, 0),
+    };
+    let t = OrdersTemplate {
+        orders: vec![order(3, 100, 4.0), order(4, 250, 5.0)],
+    };
+    assert_eq!(t.render().unwrap(), "7 350 4.5");
+}
+
+#[test]
+fn test_filter_truncatewords() {
+    #[derive(Template)]
+    #[template(
+        source = "{{ text|truncatewords(3) }}|{{ text|truncatewords(count, suffix = \" [more]\") }}",
+        ext = "html"
+    )]
+    struct TruncateWordsFilter<'a> {
+        text: &'a str,
+        count: usize,
+    }
+
+    let t = TruncateWordsFilter {
+        text: " The quick\n brown  fox jumps over the lazy dog",
+        count: 5,
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "The quick brown...|The quick brown fox jumps [more]"
+    );
+
+    let t = TruncateWordsFilter {
+        text: " short  text ",
+        count: 2,
+    };
+    assert_eq!(t.render().unwrap(), " short  text | short  text ");
+}
+
 #[cfg(feature = "serde_json")]
 #[test]
 fn test_json_attribute() {
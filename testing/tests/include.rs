@@ -22,6 +22,17 @@ fn test_include_with_nested_paths() {
     assert!(LeafTemplate.render().is_ok());
 }
 
+#[test]
+fn test_include_explicitly_relative() {
+    // `./item.html` and `../sibling.html` are resolved relative to the including template,
+    // the bare `sibling.html` is looked up in the template directories.
+    #[derive(Template)]
+    #[template(path = "relative/nested/page.html")]
+    struct Page;
+
+    assert_eq!(Page.render().unwrap(), "item|relative sibling|root sibling");
+}
+
 #[test]
 fn test_include_extends() {
     #[derive(Template)]
@@ -102,3 +113,48 @@ fn block_in_include() {
         "block_in_partial: from partial!\n"
     );
 }
+
+#[test]
+fn test_include_with() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% for x in items -%}
+            {% include "include-row.html" with item = x, index = loop.index %};
+        {%- endfor %}"#,
+        ext = "txt"
+    )]
+    struct IncludeWith<'a> {
+        items: &'a [&'a str],
+    }
+
+    // Without `only`, the fields of the template struct are still accessible.
+    #[derive(Template)]
+    #[template(
+        source = r#"{% include "include-row.html" with item = "x" %}"#,
+        ext = "txt"
+    )]
+    struct IncludeWithFields {
+        index: u32,
+    }
+
+    assert_eq!(
+        IncludeWith { items: &["a", "b"] }.render().unwrap(),
+        "1: a;2: b;"
+    );
+    assert_eq!(IncludeWithFields { index: 7 }.render().unwrap(), "7: x");
+}
+
+#[test]
+fn test_include_only() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% include "include-row.html" with item = index * 2 only %}"#,
+        ext = "txt"
+    )]
+    struct IncludeOnly {
+        index: u32,
+    }
+
+    // `index` is a field of the template struct, but the included template cannot see it.
+    assert_eq!(IncludeOnly { index: 21 }.render().unwrap(), "42");
+}
@@ -354,3 +354,28 @@ fn test_named_end() {
     let n = NamedBlocks { title: "title" };
     assert_eq!(n.render().unwrap(), "title\n\ntadam\nCopyright 2017");
 }
+
+#[test]
+fn test_scoped_block() {
+    #[derive(Template)]
+    #[template(path = "scoped-block-base.html")]
+    struct ScopedBase<'a> {
+        items: &'a [&'a str],
+    }
+
+    #[derive(Template)]
+    #[template(path = "scoped-block-child.html")]
+    struct ScopedChild<'a> {
+        items: &'a [&'a str],
+    }
+
+    let items = &["a", "b"];
+    assert_eq!(
+        ScopedBase { items }.render().unwrap(),
+        "<ul>\n  <li>a</li>\n  <li>b</li>\n</ul>"
+    );
+    assert_eq!(
+        ScopedChild { items }.render().unwrap(),
+        "<ul>\n  <li>1. A</li>\n  <li>2. B</li>\n</ul>"
+    );
+}
@@ -0,0 +1,139 @@
+use askama::Template;
+
+#[test]
+fn is_empty() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% if items is empty %}no items{% endif %}
+{%- if items is not empty %}{{ items.len() }} items{% endif %}
+{%- if name is empty %}, no name{% endif %}"#,
+        ext = "txt"
+    )]
+    struct IsEmpty<'a> {
+        items: Vec<u32>,
+        name: &'a str,
+    }
+
+    let t = IsEmpty {
+        items: vec![],
+        name: "",
+    };
+    assert_eq!(t.render().unwrap(), "no items, no name");
+    let t = IsEmpty {
+        items: vec![1, 2],
+        name: "x",
+    };
+    assert_eq!(t.render().unwrap(), "2 items");
+}
+
+#[test]
+fn is_none() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% if user is none %}anonymous{% else %}{{ user.unwrap() }}{% endif %}"#,
+        ext = "txt"
+    )]
+    struct IsNone<'a> {
+        user: Option<&'a str>,
+    }
+
+    assert_eq!(IsNone { user: None }.render().unwrap(), "anonymous");
+    assert_eq!(IsNone { user: Some("kim") }.render().unwrap(), "kim");
+}
+
+#[test]
+fn is_defined_value() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% if askama::get_value::<u32>("page") is defined -%}
+page {{ askama::get_value::<u32>("page")? }}
+{%- endif %}
+{%- if askama::get_value::<u32>("page") is undefined %}no page{% endif %}"#,
+        ext = "txt"
+    )]
+    struct IsDefinedValue;
+
+    assert_eq!(IsDefinedValue.render().unwrap(), "no page");
+
+    let values: (&str, &dyn std::any::Any) = ("page", &2_u32);
+    assert_eq!(
+        IsDefinedValue.render_with_values(&values).unwrap(),
+        "page 2"
+    );
+
+    // A value of the wrong type is defined, so the error is not hidden.
+    let values: (&str, &dyn std::any::Any) = ("page", &"2");
+    assert!(matches!(
+        IsDefinedValue.render_with_values(&values),
+        Err(askama::Error::ValueType)
+    ));
+}
+
+#[test]
+fn is_defined_values_only() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% if title is defined %}title{% else %}no title{% endif %}
+{{- title is not defined }} {{ name is defined }}"#,
+        ext = "txt",
+        values_only = true
+    )]
+    struct IsDefinedValuesOnly {
+        name: u32,
+    }
+
+    let t = IsDefinedValuesOnly { name: 0 };
+    let _ = t.name;
+    assert_eq!(t.render().unwrap(), "no titletrue true");
+
+    let values: (&str, &dyn std::any::Any) = ("title", &"");
+    assert_eq!(t.render_with_values(&values).unwrap(), "titlefalse true");
+}
+
+#[test]
+fn is_even_odd() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% for i in values -%}
+{{ i }}:{% if i is odd %}odd{% endif %}{% if i is even %}even{% endif %}
+{%- if i is divisibleby(3) %},fizz{% endif %}{% if i is not divisibleby(divisor) %},not{% endif %}
+{% endfor %}"#,
+        ext = "txt"
+    )]
+    struct IsEvenOdd {
+        values: Vec<i32>,
+        divisor: i32,
+    }
+
+    let t = IsEvenOdd {
+        values: vec![-3, 0, 1, 2, 3, 4],
+        divisor: 2,
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "-3:odd,fizz,not\n0:even,fizz\n1:odd,not\n2:even\n3:odd,fizz,not\n4:even\n"
+    );
+
+    // Nothing is divisible by zero.
+    let t = IsEvenOdd {
+        values: vec![0],
+        divisor: 0,
+    };
+    assert_eq!(t.render().unwrap(), "0:even,fizz,not\n");
+}
+
+#[test]
+fn is_undefined() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ x is undefined }} {{ x is not undefined }} {{ y is undefined }}"#,
+        ext = "txt"
+    )]
+    struct IsUndefined {
+        y: u32,
+    }
+
+    let t = IsUndefined { y: 0 };
+    let _ = t.y;
+    assert_eq!(t.render().unwrap(), "true false false");
+}
@@ -0,0 +1,166 @@
+use std::any::Any;
+
+use askama::{Error, Template};
+
+#[derive(Template)]
+#[template(
+    source = r#"<h1>{{ title }}</h1>
+<p>Visitors: {{ askama::get_value::<u32>("visitors")? }}</p>
+<p>Sales: {{ askama::get_value::<u32>("sales")? }}</p>"#,
+    ext = "html",
+    lenient = true
+)]
+struct Dashboard<'a> {
+    title: &'a str,
+}
+
+#[test]
+fn test_lenient() {
+    let dashboard = Dashboard {
+        title: "Today & yesterday",
+    };
+    let values: [(&str, &dyn Any); 1] = [("visitors", &42_u32)];
+    assert_eq!(
+        dashboard
+            .render_lenient(&values, |err| match err {
+                Error::ValueMissing => "n/a".into(),
+                err => format!("error: {err}"),
+            })
+            .unwrap(),
+        "<h1>Today &#38; yesterday</h1>\n<p>Visitors: 42</p>\n<p>Sales: n/a</p>"
+    );
+
+    // Without the error handler, the error is not recovered.
+    assert!(matches!(
+        dashboard.render_with_values(&values),
+        Err(Error::ValueMissing),
+    ));
+}
+
+#[test]
+fn test_lenient_error_in_display() {
+    struct Broken;
+
+    impl std::fmt::Display for Broken {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("partial output")?;
+            Err(std::fmt::Error)
+        }
+    }
+
+    #[derive(Template)]
+    #[template(source = "[{{ broken }}] [{{ ok }}]", ext = "txt", lenient = true)]
+    struct Lenient {
+        broken: Broken,
+        ok: u32,
+    }
+
+    // The partial output of the failed expression is discarded.
+    let t = Lenient {
+        broken: Broken,
+        ok: 1,
+    };
+    assert_eq!(
+        t.render_lenient(askama::NO_VALUES, |err| match err {
+            Error::Fmt => "<fmt>".into(),
+            err => format!("<{err}>"),
+        })
+        .unwrap(),
+        "[<fmt>] [1]"
+    );
+}
+
+#[test]
+fn test_lenient_only_expressions() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% if askama::get_value::<bool>("flag")? %}yes{% endif %}"#,
+        ext = "txt",
+        lenient = true
+    )]
+    struct Condition;
+
+    assert!(matches!(
+        Condition.render_lenient(askama::NO_VALUES, |_| String::new()),
+        Err(Error::ValueMissing),
+    ));
+}
+
+#[test]
+fn test_lenient_not_enabled() {
+    #[derive(Template)]
+    #[template(source = r#"{{ askama::get_value::<u32>("a")? }}"#, ext = "txt")]
+    struct Strict;
+
+    assert!(matches!(
+        Strict.render_lenient(askama::NO_VALUES, |_| String::new()),
+        Err(Error::ValueMissing),
+    ));
+}
+
+#[test]
+fn test_lenient_sub_template() {
+    #[derive(Template)]
+    #[template(
+        source = r#"<section>{{ widget|safe }}</section><footer>{{ footer }}</footer>"#,
+        ext = "html",
+        lenient = true
+    )]
+    struct Page<'a> {
+        widget: Dashboard<'a>,
+        footer: &'a str,
+    }
+
+    let page = Page {
+        widget: Dashboard { title: "Widget" },
+        footer: "end",
+    };
+    assert_eq!(
+        page.render_lenient(askama::NO_VALUES, |_| "?".into())
+            .unwrap(),
+        "<section><h1>Widget</h1>\n<p>Visitors: ?</p>\n<p>Sales: ?</p></section>\
+         <footer>end</footer>"
+    );
+}
+
+#[test]
+fn test_lenient_buffers_only_with_handler() {
+    // Writes its output in two pieces.
+    struct TwoPieces;
+
+    impl std::fmt::Display for TwoPieces {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("a")?;
+            f.write_str("b")
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder(Vec<String>);
+
+    impl std::fmt::Write for Recorder {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            self.0.push(s.to_owned());
+            Ok(())
+        }
+    }
+
+    #[derive(Template)]
+    #[template(source = "{{ value }}", ext = "txt", lenient = true)]
+    struct Lenient {
+        value: TwoPieces,
+    }
+
+    let t = Lenient { value: TwoPieces };
+    assert_eq!(t.render().unwrap(), "ab");
+
+    // Without an error handler, the expression is written directly, not through a buffer.
+    let mut recorder = Recorder::default();
+    t.render_into(&mut recorder).unwrap();
+    assert_eq!(recorder.0, ["a", "b"]);
+
+    assert_eq!(
+        t.render_lenient(askama::NO_VALUES, |_| "?".into()).unwrap(),
+        "ab"
+    );
+}
@@ -51,3 +51,91 @@ fn underscore_ident2() {
 
     assert_eq!(X.render().unwrap(), "hey\nhoy\nmatched");
 }
+
+#[test]
+fn with_block() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ name }}
+{%- with name = "inner", count = items.len() %} {{ name }}: {{ count }}
+{%- endwith %} {{ name }}"#,
+        ext = "txt"
+    )]
+    struct X<'a> {
+        name: &'a str,
+        items: Vec<u32>,
+    }
+
+    let x = X {
+        name: "outer",
+        items: vec![1, 2, 3],
+    };
+    // The bindings of `with` are not available after `endwith`.
+    assert_eq!(x.render().unwrap(), "outer inner: 3 outer");
+}
+
+#[test]
+fn with_block_values_use_outer_scope() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% let a = 1 %}{% let b = 2 -%}
+{% with a = b, b = a, (c, d) = (a, "d") %}{{ a }}{{ b }}{{ c }}{{ d }}{% endwith %} {{ a }}{{ b }}"#,
+        ext = "txt"
+    )]
+    struct X;
+
+    assert_eq!(X.render().unwrap(), "211d 12");
+}
+
+#[test]
+fn do_tag() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% for x in items -%}
+{% if x % 2 == 0 %}{% do evens.borrow_mut().push(x.clone()) %}{% endif -%}
+{% endfor -%}
+{% do count.set(evens.borrow().len()) -%}
+{{ "{:?}"|format(evens.borrow()) }} ({{ count.get() }})"#,
+        ext = "txt"
+    )]
+    struct X {
+        items: Vec<u32>,
+        evens: std::cell::RefCell<Vec<u32>>,
+        count: std::cell::Cell<usize>,
+    }
+
+    let x = X {
+        items: vec![1, 2, 3, 4, 5, 6],
+        evens: Default::default(),
+        count: Default::default(),
+    };
+    // `do` discards the value of the expression and does not write anything.
+    assert_eq!(x.render().unwrap(), "[2, 4, 6] (3)");
+    assert_eq!(*x.evens.borrow(), [2, 4, 6]);
+}
+
+#[test]
+fn let_with_type() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% set x: u64 = 3 %}{{ x.pow(2) }} {{ self.kib(*x) }}
+{%- let name: String = title.to_uppercase() %} {{ self.shout(name.clone()) }}
+{%- let later: &str %}{% if x > 2 %}{% let later = "big" %}{% else %}{% let later = "small" %}{% endif %} {{ later }}"#,
+        ext = "txt"
+    )]
+    struct X<'a> {
+        title: &'a str,
+    }
+
+    impl X<'_> {
+        fn kib(&self, value: u64) -> u64 {
+            value * 1024
+        }
+
+        fn shout(&self, value: String) -> String {
+            value + "!"
+        }
+    }
+
+    assert_eq!(X { title: "hi" }.render().unwrap(), "9 3072 HI! big");
+}
@@ -189,3 +189,28 @@ fn test_t2() {
     };
     assert_eq!(t.render().unwrap(), "hello");
 }
+
+#[test]
+fn test_set_destruct_pairs() {
+    #[derive(Template)]
+    #[template(
+        source = "
+{%- for pair in pairs -%}
+    {%- set (key, (count, name)) = pair -%}
+    {{ key }}={{ count }}{{ name }};
+{%- endfor -%}
+{%- set NamedStruct { a, .. } = s -%}
+{{ a }}",
+        ext = "txt"
+    )]
+    struct SetDestruct<'a> {
+        pairs: Vec<(char, (u32, &'a str))>,
+        s: NamedStruct,
+    }
+
+    let t = SetDestruct {
+        pairs: vec![('a', (1, "x")), ('b', (2, "y"))],
+        s: NamedStruct { a: 7, b: 8, c: 9 },
+    };
+    assert_eq!(t.render().unwrap(), "a=1x;b=2y;7");
+}
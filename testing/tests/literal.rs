@@ -59,3 +59,24 @@ fn test_prefix_str_literal_in_target() {
     let t = TargetStr { data: *b"hi" };
     assert_eq!(t.render().unwrap(), "bc hoy");
 }
+
+#[test]
+fn test_map_literal() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% let prices = {"apple": 3, "pear": 5} -%}
+{{ prices["pear"] }} {{ prices.len() }}
+{%- for (fruit, price) in {fruit: price, "banana": 1} %} {{ fruit }}={{ price }}{% endfor %}"#,
+        ext = "txt"
+    )]
+    struct MapLiteral<'a> {
+        fruit: &'a str,
+        price: u32,
+    }
+
+    let t = MapLiteral {
+        fruit: "cherry",
+        price: 7,
+    };
+    assert_eq!(t.render().unwrap(), "5 2 banana=1 cherry=7");
+}
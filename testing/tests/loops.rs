@@ -73,6 +73,31 @@ fn test_for_range() {
     );
 }
 
+#[test]
+fn test_for_range_expressions() {
+    #[derive(Template)]
+    #[template(
+        source = "[{% for i in 0..3 %}{{ i }}{% endfor %}]\
+                  [{% for i in 1..=3 %}{{ i }}{% endfor %}]\
+                  [{% for _ in 0..rating %}*{% endfor %}{% for _ in rating..5 %}-{% endfor %}]\
+                  [{% for i in start..start + 2 %}{{ i }}{% endfor %}]\
+                  [{% for i in 3..3 %}{{ i }}{% else %}empty{% endfor %}]\
+                  [{% for i in rating..1 %}{{ i }}{% endfor %}]\
+                  [{% for i in rating..=1 %}{{ i }}{% endfor %}]",
+        ext = "txt"
+    )]
+    struct ForRangeExpressions {
+        rating: usize,
+        start: i32,
+    }
+
+    let t = ForRangeExpressions {
+        rating: 3,
+        start: -1,
+    };
+    assert_eq!(t.render().unwrap(), "[012][123][***--][-10][empty][][]");
+}
+
 #[test]
 fn test_for_array() {
     #[derive(Template)]
@@ -390,6 +415,40 @@ fn test_for_cycle() {
     assert_eq!(t.render().unwrap(), "r1,g2,b3,r4,g5,b6,r7,g8,b9,");
 }
 
+#[test]
+fn test_for_cycle_values() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{% for v in values %}{{ loop.cycle("a", "b") }}{% if !loop.last %} {% endif %}{% endfor %}"#,
+        ext = "txt"
+    )]
+    struct ForCycleValues<'a> {
+        values: &'a [u8],
+    }
+
+    let t = ForCycleValues {
+        values: &[1, 2, 3, 4],
+    };
+    assert_eq!(t.render().unwrap(), "a b a b");
+
+    #[derive(Template)]
+    #[template(
+        source = r#"{% for v in values %}<tr class="{{ loop.cycle("odd", "even") }}">{{ v }}</tr>{% endfor %}"#,
+        ext = "html"
+    )]
+    struct ForCycleRows<'a> {
+        values: &'a [&'a str],
+    }
+
+    let t = ForCycleRows {
+        values: &["x", "y", "z"],
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        r#"<tr class="odd">x</tr><tr class="even">y</tr><tr class="odd">z</tr>"#
+    );
+}
+
 mod test_for_cycle {
     use askama::Template;
 
@@ -440,6 +499,34 @@ fn test_for_in_if() {
     assert_eq!(t.render().unwrap(), ":(");
 }
 
+#[test]
+fn test_for_else_iterator() {
+    // The `else` block is only rendered if the loop body was never entered,
+    // even if the loop was left with `break` in its first iteration.
+    #[derive(Template)]
+    #[template(
+        source = "{% for name in names if name.starts_with('a') %}\
+            {{ name }}{% break %}\
+        {% else %}\
+            no results\
+        {% endfor %}",
+        ext = "txt"
+    )]
+    struct ForElseIterator<'a> {
+        names: &'a [&'a str],
+    }
+
+    let t = ForElseIterator {
+        names: &["bob", "alice", "anna"],
+    };
+    assert_eq!(t.render().unwrap(), "alice");
+
+    let t = ForElseIterator {
+        names: &["bob", "carol"],
+    };
+    assert_eq!(t.render().unwrap(), "no results");
+}
+
 // This is a regression test for <https://github.com/askama-rs/askama/issues/150>.
 // The loop didn't drop its locals context, creating a bug where a field could
 // not be retrieved although it existed.
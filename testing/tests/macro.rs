@@ -34,6 +34,55 @@ fn test_import() {
     assert_eq!(t.render().unwrap(), "foo foo foo");
 }
 
+#[test]
+fn test_from_import() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{%- from "from-import-macros.html" import heading, list as items -%}
+{%- call heading(title) %}{% endcall -%}
+{%- call items(entries) %}{% endcall -%}"#,
+        ext = "html"
+    )]
+    struct FromImportTemplate<'a> {
+        title: &'a str,
+        entries: &'a [&'a str],
+    }
+
+    let t = FromImportTemplate {
+        title: "Fruits",
+        entries: &["apple", "<banana>"],
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "<h1>Fruits</h1><ul><li>apple</li><li>&#60;banana&#62;</li></ul>"
+    );
+}
+
+#[test]
+fn test_map_literal_argument() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{%- macro attrs(map) -%}
+{%- for (name, value) in map %} {{ name }}="{{ value }}"{% endfor -%}
+{%- endmacro -%}
+<a{% call attrs({"id": id, "class": "button", "href": href,}) %}{% endcall %}>"#,
+        ext = "html"
+    )]
+    struct MapLiteralTemplate<'a> {
+        id: &'a str,
+        href: &'a str,
+    }
+
+    let t = MapLiteralTemplate {
+        id: "go",
+        href: "/next?a&b",
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        r#"<a class="button" href="/next?a&#38;b" id="go">"#
+    );
+}
+
 #[test]
 fn test_nested() {
     #[derive(Template)]
@@ -238,6 +287,31 @@ fn test_default_value3() {
     );
 }
 
+// This test ensures that a keyword can be used as argument name with a default value.
+#[test]
+fn test_default_value_keyword_name() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{%- macro input(name, type="text") -%}
+<input name="{{ name }}" type="{{ type }}">
+{% endmacro -%}
+
+{%- call input("user") -%}{%- endcall -%}
+{%- call input("pass", "password") -%}{%- endcall -%}
+{%- call input("mail", type="email") -%}{%- endcall -%}
+"#,
+        ext = "html"
+    )]
+    struct MacroDefaultValueKeyword;
+
+    assert_eq!(
+        MacroDefaultValueKeyword.render().unwrap(),
+        "<input name=\"user\" type=\"text\">\n\
+         <input name=\"pass\" type=\"password\">\n\
+         <input name=\"mail\" type=\"email\">\n"
+    );
+}
+
 // This test a caller expression with expressions in the arguments.
 #[test]
 fn test_caller_expr() {
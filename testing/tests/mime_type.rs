@@ -0,0 +1,93 @@
+use askama::{DynTemplate, Template};
+
+#[test]
+fn test_mime_type_from_extension() {
+    #[derive(Template)]
+    #[template(source = "<p>{{ 1 }}</p>", ext = "html")]
+    struct Html;
+
+    #[derive(Template)]
+    #[template(source = r#"{"a": {{ 1 }}}"#, ext = "json", escape = "none")]
+    struct Json;
+
+    #[derive(Template)]
+    #[template(source = "{{ 1 }}", ext = "txt")]
+    struct Text;
+
+    #[derive(Template)]
+    #[template(path = "foo.html.jinja")]
+    struct Jinja;
+
+    assert_eq!(Html::MIME_TYPE, "text/html");
+    assert_eq!(Json::MIME_TYPE, "application/json");
+    assert_eq!(Text::MIME_TYPE, "text/plain");
+    assert_eq!(Jinja::MIME_TYPE, "text/html");
+    assert_eq!((&Json as &dyn DynTemplate).mime_type(), "application/json");
+}
+
+#[test]
+fn test_mime_type_of_source_with_ext() {
+    // The `ext` argument of an inline template determines its MIME type,
+    // like the extension of a template file.
+    #[derive(Template)]
+    #[template(source = "# {{ 1 }}", ext = "md")]
+    struct Markdown;
+
+    #[derive(Template)]
+    #[template(source = "<p>{{ 1 }}</p>", ext = "html.j2")]
+    struct Jinja;
+
+    #[derive(Template)]
+    #[template(source = "{{ 1 }}", ext = "CSV", escape = "none")]
+    struct Csv;
+
+    #[derive(Template)]
+    #[template(source = "{{ 1 }}", ext = "tex", escape = "none")]
+    struct Tex;
+
+    assert_eq!(Markdown::MIME_TYPE, "text/markdown");
+    assert_eq!(Jinja::MIME_TYPE, "text/html");
+    assert_eq!(Jinja.render().unwrap(), "<p>1</p>");
+    assert_eq!(Csv::MIME_TYPE, "text/csv");
+    assert_eq!(Tex::MIME_TYPE, "application/octet-stream");
+}
+
+#[test]
+fn test_mime_type_override() {
+    #[derive(Template)]
+    #[template(
+        source = "<feed>{{ 1 }}</feed>",
+        ext = "xml",
+        mime_type = "application/atom+xml"
+    )]
+    struct Feed;
+
+    assert_eq!(Feed::MIME_TYPE, "application/atom+xml");
+    assert_eq!(Feed.render().unwrap(), "<feed>1</feed>");
+}
+
+#[test]
+fn test_mime_type_of_enum() {
+    #[derive(Template)]
+    #[template(ext = "html")]
+    enum Page {
+        #[template(source = "<p>a</p>")]
+        #[allow(dead_code)]
+        A,
+        #[template(source = "<p>b</p>")]
+        B,
+    }
+
+    #[derive(Template)]
+    #[allow(dead_code)]
+    enum Mixed {
+        #[template(source = "<p>a</p>", ext = "html")]
+        A,
+        #[template(source = "a", ext = "txt")]
+        B,
+    }
+
+    assert_eq!(Page::MIME_TYPE, "text/html");
+    assert_eq!(Page::B.render().unwrap(), "<p>b</p>");
+    assert_eq!(Mixed::MIME_TYPE, "application/octet-stream");
+}
@@ -61,3 +61,90 @@ fn test_short_circuit() {
     let t = ShortCircuitTemplate {};
     assert_eq!(t.render().unwrap(), "truetrue");
 }
+
+#[test]
+fn test_chained_comparison() {
+    #[derive(Template)]
+    #[template(
+        source = "{% if 0 < x < 10 %}inside{% else %}outside{% endif %}",
+        ext = "txt"
+    )]
+    struct Range {
+        x: i32,
+    }
+
+    assert_eq!(Range { x: 5 }.render().unwrap(), "inside");
+    assert_eq!(Range { x: 0 }.render().unwrap(), "outside");
+    assert_eq!(Range { x: 10 }.render().unwrap(), "outside");
+    assert_eq!(Range { x: -3 }.render().unwrap(), "outside");
+
+    #[derive(Template)]
+    #[template(
+        source = "{{ a <= b < c }} {{ a >= b > c }} {{ a < b + 1 <= c + 1 }}",
+        ext = "txt"
+    )]
+    struct Mixed {
+        a: u8,
+        b: u8,
+        c: u8,
+    }
+
+    assert_eq!(
+        Mixed { a: 1, b: 1, c: 2 }.render().unwrap(),
+        "true false true"
+    );
+    assert_eq!(
+        Mixed { a: 3, b: 2, c: 1 }.render().unwrap(),
+        "false true false"
+    );
+
+    #[derive(Template)]
+    #[template(source = r#"{{ "a" < s <= "m" }}"#, ext = "txt")]
+    struct Strings<'a> {
+        s: &'a str,
+    }
+
+    assert_eq!(Strings { s: "b" }.render().unwrap(), "true");
+    assert_eq!(Strings { s: "x" }.render().unwrap(), "false");
+}
+
+#[test]
+fn test_chained_comparison_evaluates_once() {
+    use std::cell::Cell;
+
+    #[derive(Template)]
+    #[template(
+        source = "{% if 0 < next() < 3 <= last() %}yes{% else %}no{% endif %}",
+        ext = "txt"
+    )]
+    struct Counter {
+        calls: Cell<i32>,
+        lasts: Cell<i32>,
+    }
+
+    impl Counter {
+        fn next(&self) -> i32 {
+            self.calls.set(self.calls.get() + 1);
+            self.calls.get()
+        }
+
+        fn last(&self) -> i32 {
+            self.lasts.set(self.lasts.get() + 1);
+            5
+        }
+    }
+
+    let t = Counter {
+        calls: Cell::new(0),
+        lasts: Cell::new(0),
+    };
+    assert_eq!(t.render().unwrap(), "yes");
+    assert_eq!(t.calls.get(), 1);
+    assert_eq!(t.lasts.get(), 1);
+
+    // The chain stops at the first comparison that fails, like `&&`.
+    t.calls.set(2);
+    assert_eq!(t.render().unwrap(), "no");
+    assert_eq!(t.calls.get(), 3);
+    assert_eq!(t.lasts.get(), 1);
+}
@@ -0,0 +1,59 @@
+use std::any::Any;
+
+use askama::{DynTemplate, NO_VALUES, Template};
+
+#[derive(Template)]
+#[template(source = "<h1>{{ title }}</h1>", ext = "html")]
+struct Header<'a> {
+    title: &'a str,
+}
+
+#[derive(Template)]
+#[template(
+    source = r#"<p>{{ text }}{% if let Ok(user) = "user"|value::<&str> %} ({{ user }}){% endif %}</p>"#,
+    ext = "html"
+)]
+struct Paragraph<'a> {
+    text: &'a str,
+}
+
+#[derive(Template)]
+#[template(source = "{{ text }}", ext = "txt")]
+struct Plain<'a> {
+    text: &'a str,
+}
+
+#[test]
+fn test_render_all_boxed() {
+    let components: Vec<Box<dyn DynTemplate>> = vec![
+        Box::new(Header { title: "<Hi>" }),
+        Box::new(Paragraph { text: "Body" }),
+        Box::new(Plain { text: "<raw>" }),
+    ];
+    assert_eq!(
+        askama::render_all(&components, NO_VALUES).unwrap(),
+        "<h1>&#60;Hi&#62;</h1><p>Body</p><raw>"
+    );
+
+    // All templates get the same values.
+    let values: (&str, &dyn Any) = ("user", &"alice");
+    assert_eq!(
+        askama::render_all(&components, &values).unwrap(),
+        "<h1>&#60;Hi&#62;</h1><p>Body (alice)</p><raw>"
+    );
+}
+
+#[test]
+fn test_render_all_refs() {
+    let header = Header { title: "A" };
+    let paragraph = Paragraph { text: "B" };
+    let components: [&dyn DynTemplate; 3] = [&paragraph, &header, &paragraph];
+    assert_eq!(
+        askama::render_all(&components, NO_VALUES).unwrap(),
+        "<p>B</p><h1>A</h1><p>B</p>"
+    );
+    assert_eq!(
+        askama::render_all::<&dyn DynTemplate>(&[], NO_VALUES).unwrap(),
+        ""
+    );
+}
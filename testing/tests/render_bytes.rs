@@ -0,0 +1,41 @@
+#![cfg(feature = "bytes")]
+
+use std::any::Any;
+
+use askama::Template;
+use bytes::BytesMut;
+
+#[derive(Template)]
+#[template(
+    source = "<p>Hello, {{ name }}!{% if let Ok(n) = \"n\"|value::<u32> %} #{{ n }}{% endif %}</p>",
+    ext = "html"
+)]
+struct Greeting<'a> {
+    name: &'a str,
+}
+
+#[test]
+fn test_render_into_bytes() {
+    let tmpl = Greeting { name: "<world>" };
+
+    let mut buf = BytesMut::new();
+    tmpl.render_into_bytes(&mut buf).unwrap();
+    assert_eq!(buf, tmpl.render().unwrap().as_bytes());
+
+    // The output is appended to the buffer.
+    let mut buf = BytesMut::from("HTTP body: ");
+    tmpl.render_into_bytes(&mut buf).unwrap();
+    assert_eq!(buf.freeze(), "HTTP body: <p>Hello, &#60;world&#62;!</p>");
+}
+
+#[test]
+fn test_render_into_bytes_with_values() {
+    let tmpl = Greeting { name: "world" };
+    let values: (&str, &dyn Any) = ("n", &7u32);
+
+    let mut buf = BytesMut::new();
+    tmpl.render_into_bytes_with_values(&mut buf, &values)
+        .unwrap();
+    assert_eq!(buf, tmpl.render_with_values(&values).unwrap().as_bytes());
+    assert_eq!(buf, "<p>Hello, world! #7</p>".as_bytes());
+}
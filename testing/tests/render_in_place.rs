@@ -38,3 +38,40 @@ fn test_render_in_place() {
         "Section 1: A=A\nB=B\nSection 2: C=C\nD=D\nSection 3 for:\n* A=1\nB=2\n* A=A\nB=B\n* A=a\nB=b\n"
     );
 }
+
+#[test]
+fn test_render_into_fmt() {
+    use std::collections::HashMap;
+    use std::fmt;
+
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ name }}{% if let Ok(suffix) = askama::get_value::<&str>("suffix") %}{{ suffix }}{% endif %}"#,
+        ext = "txt"
+    )]
+    struct Name<'a> {
+        name: &'a str,
+    }
+
+    struct Wrapper<'a>(Name<'a>);
+
+    impl fmt::Display for Wrapper<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "<")?;
+            self.0.render_into_fmt(f)?;
+            write!(f, ">")
+        }
+    }
+
+    impl fmt::Debug for Wrapper<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let values: HashMap<&str, Box<dyn std::any::Any>> =
+                HashMap::from([("suffix", Box::new("!") as Box<dyn std::any::Any>)]);
+            self.0.render_into_fmt_with_values(f, &values)
+        }
+    }
+
+    let w = Wrapper(Name { name: "world" });
+    assert_eq!(format!("hello {w}"), "hello <world>");
+    assert_eq!(format!("hello {w:?}"), "hello world!");
+}
@@ -0,0 +1,98 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = "<p>Hello, {{ name }}!</p>", ext = "html")]
+struct Greeting<'a> {
+    name: &'a str,
+}
+
+#[derive(Template)]
+#[template(source = "{% for i in 0..count %}{{ i }}{% endfor %}", ext = "txt")]
+struct Counter {
+    count: usize,
+}
+
+#[test]
+fn test_render_into_appends() {
+    let mut buf = String::from("> ");
+    Greeting { name: "<world>" }.render_into(&mut buf).unwrap();
+    Counter { count: 3 }.render_into(&mut buf).unwrap();
+    assert_eq!(buf, "> <p>Hello, &#60;world&#62;!</p>012");
+}
+
+#[test]
+fn test_render_into_reused_buffer() {
+    let mut buf = String::new();
+
+    Greeting { name: "Alice" }.render_into(&mut buf).unwrap();
+    assert_eq!(buf, "<p>Hello, Alice!</p>");
+    let capacity = buf.capacity();
+
+    buf.clear();
+    Greeting { name: "Bob" }.render_into(&mut buf).unwrap();
+    assert_eq!(buf, "<p>Hello, Bob!</p>");
+    assert_eq!(buf.capacity(), capacity);
+
+    buf.clear();
+    Counter { count: 5 }.render_into(&mut buf).unwrap();
+    assert_eq!(buf, "01234");
+}
+
+#[test]
+fn test_render_into_slice() {
+    let tmpl = Greeting { name: "world" };
+    let expected = tmpl.render().unwrap();
+
+    let mut buf = [0; 64];
+    let (len, truncated) = tmpl.render_into_slice(&mut buf).unwrap();
+    assert!(!truncated);
+    assert_eq!(&buf[..len], expected.as_bytes());
+
+    // A buffer of exactly the right size is not truncated.
+    let mut buf = vec![0; expected.len()];
+    assert_eq!(
+        tmpl.render_into_slice(&mut buf).unwrap(),
+        (expected.len(), false)
+    );
+    assert_eq!(buf, expected.as_bytes());
+
+    let mut buf = [0; 10];
+    let (len, truncated) = Counter { count: 20 }.render_into_slice(&mut buf).unwrap();
+    assert!(truncated);
+    assert_eq!(&buf[..len], b"0123456789");
+
+    let mut buf = [];
+    assert_eq!(tmpl.render_into_slice(&mut buf).unwrap(), (0, true));
+}
+
+#[test]
+fn test_render_into_slice_char_boundary() {
+    let tmpl = Greeting { name: "wörld" };
+
+    // The output is not cut in the middle of `ö`.
+    let mut buf = [0; 12];
+    let (len, truncated) = tmpl.render_into_slice(&mut buf).unwrap();
+    assert!(truncated);
+    assert_eq!(std::str::from_utf8(&buf[..len]).unwrap(), "<p>Hello, w");
+}
+
+#[test]
+fn test_render_into_slice_error() {
+    #[derive(Template)]
+    #[template(source = r#"{{ askama::get_value::<u32>("n")? }}"#, ext = "txt")]
+    struct Value;
+
+    let mut buf = [0; 16];
+    assert!(matches!(
+        Value.render_into_slice(&mut buf),
+        Err(askama::Error::ValueMissing)
+    ));
+    let values: (&str, &dyn std::any::Any) = ("n", &12345_u32);
+    assert_eq!(
+        Value
+            .render_into_slice_with_values(&mut buf, &values)
+            .unwrap(),
+        (5, false)
+    );
+    assert_eq!(&buf[..5], b"12345");
+}
@@ -0,0 +1,33 @@
+use askama::render;
+
+#[test]
+fn test_render_macro() {
+    let rendered: String = render!("Hello, {{ name }}!", name = "world").unwrap();
+    assert_eq!(rendered, "Hello, world!");
+}
+
+#[test]
+fn test_render_macro_variables() {
+    let items = vec!["a", "b", "c"];
+    assert_eq!(
+        render!(
+            "{% for item in items %}{{ item|upper }}{% if !loop.last %}{{ sep }}{% endif %}\
+             {% endfor %} ({{ items.len() }} of {{ max }})",
+            items = &items,
+            sep = ", ",
+            max = 3 + 2,
+        )
+        .unwrap(),
+        "A, B, C (3 of 5)"
+    );
+    assert_eq!(render!("{{ 1 + 2 }}").unwrap(), "3");
+}
+
+#[test]
+fn test_render_macro_include() {
+    // Included templates see the variables of the macro, too.
+    assert_eq!(
+        render!("{% include \"included.html\" %}", s = "foo").unwrap(),
+        "INCLUDED: foo"
+    );
+}
@@ -270,6 +270,48 @@ fn test_generics() {
     assert_eq!(t.render().unwrap(), "a42");
 }
 
+#[test]
+fn test_generics_lifetime_bounds_and_const() {
+    #[derive(Template)]
+    #[template(
+        source = "{% for item in items %}{{ item }}{% if !loop.last %}{{ sep }}{% endif %}{% endfor %} \
+                  ({{ N }}, {{ label }})",
+        ext = "html"
+    )]
+    struct Page<'a, 'b: 'a, T: std::fmt::Display, L, const N: usize>
+    where
+        L: AsRef<str> + std::fmt::Display + 'b,
+    {
+        items: &'a [T; N],
+        sep: &'b str,
+        label: L,
+    }
+
+    let t = Page {
+        items: &[1, 2, 3],
+        sep: ", ",
+        label: "<b>",
+    };
+    assert_eq!(t.render().unwrap(), "1, 2, 3 (3, &#60;b&#62;)");
+    assert_eq!(t.to_string(), "1, 2, 3 (3, &#60;b&#62;)");
+
+    #[derive(Template)]
+    #[template(source = "{{ values[0] }}{{ values[N - 1] }}", ext = "txt")]
+    struct Ends<T: Copy + std::fmt::Display + Default, const N: usize = 2> {
+        values: [T; N],
+    }
+
+    assert_eq!(Ends { values: ['a', 'b'] }.render().unwrap(), "ab");
+    assert_eq!(
+        Ends::<u8, 4> {
+            values: [1, 2, 3, 4]
+        }
+        .render()
+        .unwrap(),
+        "14"
+    );
+}
+
 #[test]
 fn test_composition() {
     #[derive(Template)]
@@ -471,6 +513,23 @@ fn test_raw_ws() {
     assert_eq!(template.render().unwrap(), "<{{hello}}>\n<{{bye}}>");
 }
 
+#[test]
+fn test_raw_only_ends_at_endraw_tag() {
+    #[derive(Template)]
+    #[template(
+        source = "{% raw %}{{ x }} {# endraw #} {{ endraw }} {% endrawx %} {%- if x %}{% endraw %}{{ x }}",
+        ext = "txt"
+    )]
+    struct RawTemplateEndraw {
+        x: u32,
+    }
+
+    assert_eq!(
+        RawTemplateEndraw { x: 1 }.render().unwrap(),
+        "{{ x }} {# endraw #} {{ endraw }} {% endrawx %} {%- if x %}1"
+    );
+}
+
 mod without_import_on_derive {
     #[derive(askama::Template)]
     #[template(source = "foo", ext = "txt")]
@@ -611,6 +670,54 @@ fn test_ref_custom_type() {
     assert_eq!(tmpl.render().unwrap(), TEXT);
 }
 
+#[test]
+fn test_template_name() {
+    #[derive(Template)]
+    #[template(path = "template-name.txt")]
+    struct FromPath;
+
+    #[derive(Template)]
+    #[template(source = "Generated from {{ askama::template_name() }}", ext = "txt")]
+    struct FromSource;
+
+    assert_eq!(
+        FromPath.render().unwrap(),
+        "Generated from templates/template-name.txt\n\
+        Included from templates/template-name-included.txt"
+    );
+    assert_eq!(FromSource.render().unwrap(), "Generated from <inline>");
+
+    // No absolute path of the build machine ends up in the output.
+    let rendered = FromPath.render().unwrap();
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    assert!(!rendered.contains(root.to_str().unwrap()));
+    assert!(!rendered.contains(" /"));
+}
+
+#[test]
+fn test_safe_wrapper() {
+    use askama::filters::Safe;
+
+    #[derive(Template)]
+    #[template(ext = "html", source = "{{ safe }} {{ text }} {{ nested.0 }}")]
+    struct SafeWrapper<'a> {
+        safe: Safe<&'a str>,
+        text: &'a str,
+        nested: (&'a Safe<String>,),
+    }
+
+    let nested = Safe("<br>".to_owned());
+    let t = SafeWrapper {
+        safe: Safe("<b>bold</b>"),
+        text: "<b>bold</b>",
+        nested: (&nested,),
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        "<b>bold</b> &#60;b&#62;bold&#60;/b&#62; <br>"
+    );
+}
+
 #[test]
 fn test_concat_outer() {
     #[derive(Template)]
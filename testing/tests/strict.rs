@@ -0,0 +1,45 @@
+use askama::Template;
+
+#[test]
+fn test_strict() {
+    #[derive(Template)]
+    #[template(
+        source = "{% macro greet(who) %}Hello, {{ who }}!{% endmacro %}\
+                  {% let sep = \", \" %}\
+                  {% for item in items %}{% if !loop.first %}{{ sep }}{% endif %}{{ item }}{% endfor %}\
+                  {% if let Some(name) = name %} {% call greet(name) %}{% endcall %}{% endif %} {{ self.len() }}",
+        ext = "txt",
+        strict = true
+    )]
+    struct Strict<'a> {
+        items: &'a [&'a str],
+        name: Option<&'a str>,
+    }
+
+    impl Strict<'_> {
+        fn len(&self) -> usize {
+            self.items.len()
+        }
+    }
+
+    let t = Strict {
+        items: &["a", "b"],
+        name: Some("world"),
+    };
+    assert_eq!(t.render().unwrap(), "a, b Hello, world! 2");
+}
+
+#[test]
+fn test_strict_enum() {
+    #[derive(Template)]
+    #[template(ext = "txt", strict = true)]
+    enum Strict {
+        #[template(source = "{{ value }}")]
+        Value { value: u32 },
+        #[template(source = "nothing")]
+        Nothing,
+    }
+
+    assert_eq!(Strict::Value { value: 42 }.render().unwrap(), "42");
+    assert_eq!(Strict::Nothing.render().unwrap(), "nothing");
+}
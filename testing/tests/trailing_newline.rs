@@ -0,0 +1,93 @@
+use askama::{DynTemplate, Template, TrailingNewline};
+
+#[derive(Template)]
+#[template(
+    source = "fn {{ name }}() {}",
+    ext = "txt",
+    trailing_newline = "ensure"
+)]
+struct Ensure<'a> {
+    name: &'a str,
+}
+
+#[derive(Template)]
+#[template(
+    source = "a = {{ value }}\n\n\r\n",
+    ext = "txt",
+    trailing_newline = "trim"
+)]
+struct Trim {
+    value: u32,
+}
+
+#[test]
+fn test_trailing_newline_ensure() {
+    let tmpl = Ensure { name: "main" };
+    assert_eq!(Ensure::TRAILING_NEWLINE, TrailingNewline::Ensure);
+    assert_eq!(tmpl.render().unwrap(), "fn main() {}\n");
+    assert_eq!(
+        (&tmpl as &dyn DynTemplate).dyn_render().unwrap(),
+        "fn main() {}\n"
+    );
+    // Only `render()` post-processes the output.
+    assert_eq!(tmpl.to_string(), "fn main() {}");
+}
+
+#[test]
+fn test_trailing_newline_ensure_collapses_newlines() {
+    #[derive(Template)]
+    #[template(source = "{{ text }}", ext = "txt", trailing_newline = "ensure")]
+    struct Text<'a> {
+        text: &'a str,
+    }
+
+    assert_eq!(Text { text: "a\n" }.render().unwrap(), "a\n");
+    assert_eq!(Text { text: "a\n\n\n" }.render().unwrap(), "a\n");
+    assert_eq!(Text { text: "a\r\n" }.render().unwrap(), "a\r\n");
+    assert_eq!(
+        Text {
+            text: "a\r\n\r\n\n"
+        }
+        .render()
+        .unwrap(),
+        "a\r\n"
+    );
+    assert_eq!(Text { text: "a\n\r\n" }.render().unwrap(), "a\n");
+    assert_eq!(Text { text: "" }.render().unwrap(), "\n");
+}
+
+#[test]
+fn test_trailing_newline_trim() {
+    let tmpl = Trim { value: 1 };
+    assert_eq!(Trim::TRAILING_NEWLINE, TrailingNewline::Trim);
+    assert_eq!(tmpl.render().unwrap(), "a = 1");
+
+    let mut buf = String::new();
+    tmpl.render_into(&mut buf).unwrap();
+    assert_eq!(buf, "a = 1\n\n\r\n");
+}
+
+#[test]
+fn test_trailing_newline_keep_by_default() {
+    #[derive(Template)]
+    #[template(source = "a\n\n", ext = "txt")]
+    struct Keep;
+
+    assert_eq!(Keep::TRAILING_NEWLINE, TrailingNewline::Keep);
+    assert_eq!(Keep.render().unwrap(), "a\n\n");
+}
+
+#[test]
+fn test_trailing_newline_enum() {
+    #[derive(Template)]
+    #[template(ext = "txt", trailing_newline = "ensure")]
+    enum Generated {
+        #[template(source = "one")]
+        One,
+        #[template(source = "two\n\n")]
+        Two,
+    }
+
+    assert_eq!(Generated::One.render().unwrap(), "one\n");
+    assert_eq!(Generated::Two.render().unwrap(), "two\n");
+}
@@ -0,0 +1,29 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = "{{ name().await }}", ext = "txt", async = true, lenient = true)]
+struct Lenient;
+
+#[derive(Template)]
+#[template(source = "{% block a %}{% endblock %}", ext = "txt", async, block = "a")]
+struct Block;
+
+#[derive(Template)]
+#[template(source = "", ext = "txt", async = true)]
+enum Enum {
+    A,
+}
+
+#[derive(Template)]
+#[template(
+    source = "{% filter upper %}{{ name().await }}{% endfilter %}",
+    ext = "txt",
+    async = true
+)]
+struct FilterBlock;
+
+#[derive(Template)]
+#[template(source = "", ext = "txt", async = true, async = false)]
+struct Twice;
+
+fn main() {}
@@ -0,0 +1,11 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(
+    ext = "txt",
+    source = "{% block title %}{% endblock %}{% block content %}{% endblock %}",
+    blocks = ["title", "contnet"]
+)]
+struct MissingBlock;
+
+fn main() {}
@@ -0,0 +1,13 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = r#"{{ "text"|box("double") }}"#, ext = "txt")]
+struct UnknownStyle;
+
+#[derive(Template)]
+#[template(source = r#"{{ "text"|box(style) }}"#, ext = "txt")]
+struct RuntimeStyle<'a> {
+    style: &'a str,
+}
+
+fn main() {}
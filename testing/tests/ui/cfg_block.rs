@@ -0,0 +1,15 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = "{% cfg(feature = ) %}a{% endcfg %}", ext = "txt")]
+struct InvalidPredicate;
+
+#[derive(Template)]
+#[template(source = r#"{% cfg(feature = "a" %}a{% endcfg %}"#, ext = "txt")]
+struct Unterminated;
+
+#[derive(Template)]
+#[template(source = "{% cfg(test) %}a", ext = "txt")]
+struct Unclosed;
+
+fn main() {}
@@ -0,0 +1,21 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(
+    source = "{% for row in cells|chunks(0) %}{{ row.len() }}{% endfor %}",
+    ext = "txt"
+)]
+struct ZeroSize {
+    cells: Vec<u32>,
+}
+
+#[derive(Template)]
+#[template(
+    source = "{% for row in cells|chunks %}{{ row.len() }}{% endfor %}",
+    ext = "txt"
+)]
+struct MissingSize {
+    cells: Vec<u32>,
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = "{{ pct|clamp(100, 0) }}", ext = "txt")]
+struct InvertedBounds {
+    pct: u8,
+}
+
+#[derive(Template)]
+#[template(source = "{{ ratio|clamp(1.5, -1.5) }}", ext = "txt")]
+struct InvertedFloatBounds {
+    ratio: f64,
+}
+
+#[derive(Template)]
+#[template(source = "{{ pct|clamp(0) }}", ext = "txt")]
+struct MissingMax {
+    pct: u8,
+}
+
+fn main() {}
@@ -1,4 +1,4 @@
-// Comparison operators cannot be chained, so our parser must reject chained comparisons.
+// `==` and `!=` cannot be chained, so our parser must reject chained comparisons that use them.
 
 use askama::Template;
 
@@ -11,13 +11,22 @@ struct EqNe {
 }
 
 #[derive(Template)]
-#[template(ext = "txt", source = "{{ a <= b < c }}")]
-struct Between {
+#[template(ext = "txt", source = "{{ a <= b == c }}")]
+struct OrderingEq {
     a: usize,
     b: usize,
     c: usize,
 }
 
+#[derive(Template)]
+#[template(ext = "txt", source = "{{ a < b < c != d }}")]
+struct LongChain {
+    a: usize,
+    b: usize,
+    c: usize,
+    d: usize,
+}
+
 #[derive(Template)]
 #[template(ext = "txt", source = "{{ ((a == b) == c) == d == e }}")]
 struct ThreeTimesOk {
@@ -39,10 +48,11 @@ struct ThreeTimesOk2 {
 }
 
 // Regression test for <https://github.com/askama-rs/askama/issues/454>
+// The second operator was `<`, but `<` comparisons can be chained now.
 #[derive(Template)]
 #[template(
     ext = "",
-    source = "\u{c}{{vu7218/63e3666663-666/3330e633/63e3666663666/3333<c\"}\u{1}2}\0\"<c7}}2\"\"\"\"\0\0\0\0"
+    source = "\u{c}{{vu7218/63e3666663-666/3330e633/63e3666663666/3333<c\"}\u{1}2}\0\"!=c7}}2\"\"\"\"\0\0\0\0"
 )]
 struct Regression {}
 
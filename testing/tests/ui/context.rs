@@ -0,0 +1,27 @@
+use askama::Template;
+
+struct Globals {
+    site_name: &'static str,
+}
+
+#[derive(Template)]
+#[template(source = "{{ ctx.site_name }}", ext = "txt", context = Globals)]
+struct Page;
+
+#[derive(Template)]
+#[template(source = "{{ ctx.site_name }}", ext = "txt", context = Globals)]
+struct FieldNamedCtx {
+    ctx: u32,
+}
+
+#[derive(Template)]
+#[template(ext = "txt")]
+enum ContextOnVariant {
+    #[template(source = "{{ ctx.site_name }}", context = Globals)]
+    Name,
+}
+
+fn main() {
+    let _ = Page.render_with_context(&42_u32);
+    let _ = Globals { site_name: "" }.site_name;
+}
@@ -0,0 +1,15 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = r#"{{ text | count_matches("") }}"#, ext = "html")]
+struct EmptyNeedle<'a> {
+    text: &'a str,
+}
+
+#[derive(Template)]
+#[template(source = r#"{{ text | contains }}"#, ext = "html")]
+struct NoArgument<'a> {
+    text: &'a str,
+}
+
+fn main() {}
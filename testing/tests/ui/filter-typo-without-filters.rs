@@ -0,0 +1,17 @@
+use askama::Template;
+
+// There is no `filters` module in scope.
+
+#[derive(Template)]
+#[template(source = r#"{{ text | upprer }}"#, ext = "html")]
+struct Upper<'a> {
+    text: &'a str,
+}
+
+#[derive(Template)]
+#[template(source = r#"{% filter lowre %}{{ text }}{% endfilter %}"#, ext = "html")]
+struct Lower<'a> {
+    text: &'a str,
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use askama::Template;
+
+mod filters {
+    pub fn uppr<T: std::fmt::Display>(s: T, _: &dyn askama::Values) -> askama::Result<String> {
+        Ok(s.to_string())
+    }
+}
+
+#[derive(Template)]
+#[template(source = r#"{{ text | upprer }}"#, ext = "html")]
+struct Upper<'a> {
+    text: &'a str,
+}
+
+#[derive(Template)]
+#[template(source = r#"{{ text | trunacte(5) }}"#, ext = "html")]
+struct Truncate<'a> {
+    text: &'a str,
+}
+
+fn main() {}
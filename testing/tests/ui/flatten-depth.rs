@@ -0,0 +1,15 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = "{{ rows|flatten(17)|join(\", \") }}", ext = "txt")]
+struct TooDeep {
+    rows: Vec<Vec<u32>>,
+}
+
+#[derive(Template)]
+#[template(source = "{{ rows|flatten(1000000000)|join(\", \") }}", ext = "txt")]
+struct WayTooDeep {
+    rows: Vec<Vec<u32>>,
+}
+
+fn main() {}
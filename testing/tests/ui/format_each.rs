@@ -0,0 +1,28 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = r#"{{ items|format_each("<li></li>")|join("") }}"#, ext = "txt")]
+struct NoPlaceholder<'a> {
+    items: &'a [&'a str],
+}
+
+#[derive(Template)]
+#[template(source = r#"{{ items|format_each("{}: {}")|join("") }}"#, ext = "txt")]
+struct TwoPlaceholders<'a> {
+    items: &'a [&'a str],
+}
+
+#[derive(Template)]
+#[template(source = r#"{{ items|format_each("{:>5}")|join("") }}"#, ext = "txt")]
+struct FormatSpec<'a> {
+    items: &'a [&'a str],
+}
+
+#[derive(Template)]
+#[template(source = r#"{{ items|format_each(fmt)|join("") }}"#, ext = "txt")]
+struct NotALiteral<'a> {
+    items: &'a [&'a str],
+    fmt: &'a str,
+}
+
+fn main() {}
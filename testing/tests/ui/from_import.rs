@@ -0,0 +1,32 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(
+    source = r#"{% from "from-import-macros.html" import heading, footer %}"#,
+    ext = "html"
+)]
+struct UnknownMacro;
+
+#[derive(Template)]
+#[template(
+    source = r#"{% from "from-import-macros.html" import heading, list as heading %}"#,
+    ext = "html"
+)]
+struct ImportedTwice;
+
+#[derive(Template)]
+#[template(
+    source = r#"{% from "from-import-macros.html" import heading %}
+{%- macro heading() %}{% endmacro %}"#,
+    ext = "html"
+)]
+struct ImportedAndDefined;
+
+#[derive(Template)]
+#[template(
+    source = r#"{% if true %}{% from "from-import-macros.html" import heading %}{% endif %}"#,
+    ext = "html"
+)]
+struct NotAtTopLevel;
+
+fn main() {}
@@ -0,0 +1,22 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(
+    source = r#"{% include "included.html" only %}"#,
+    ext = "txt"
+)]
+struct IncludeOnly<'a> {
+    s: &'a str,
+}
+
+#[derive(Template)]
+#[template(
+    source = r#"{% include "included.html" with t = s only %}"#,
+    ext = "txt"
+)]
+struct IncludeOnlyWrongName<'a> {
+    s: &'a str,
+}
+
+fn main() {
+}
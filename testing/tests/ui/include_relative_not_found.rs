@@ -0,0 +1,8 @@
+use askama::Template;
+
+// `included.html` exists in the template directory, but not next to the includer.
+#[derive(Template)]
+#[template(path = "relative/nested/missing-relative.html")]
+struct MissingRelative;
+
+fn main() {}
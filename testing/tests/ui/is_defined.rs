@@ -42,5 +42,12 @@ struct E;
 )]
 struct F;
 
+#[derive(Template)]
+#[template(
+    ext = "html",
+    source = r#"{% if x is divisibleby %}{% endif %}"#,
+)]
+struct G;
+
 fn main() {
 }
@@ -0,0 +1,22 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = "{% let Some(x) = y %}{{ x }}", ext = "txt")]
+struct OptionVariant {
+    y: Option<u32>,
+}
+
+#[derive(Template)]
+#[template(source = "{% set (x, 1) = y %}{{ x }}", ext = "txt")]
+struct NestedLiteral {
+    y: (u32, u32),
+}
+
+#[derive(Template)]
+#[template(source = "{% let (x, Err(e)) = y %}{{ x }}", ext = "txt")]
+struct NestedVariant {
+    y: (u32, Result<u32, u32>),
+}
+
+fn main() {
+}
@@ -0,0 +1,14 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(
+    source = r#"{% let x %}{% if true %}{% let x: u64 = 1 %}{% else %}{% let x = 2 %}{% endif %}{{ x }}"#,
+    ext = "txt"
+)]
+struct TypeOnAssignment;
+
+#[derive(Template)]
+#[template(source = r#"{% let x: = 1 %}{{ x }}"#, ext = "txt")]
+struct MissingType;
+
+fn main() {}
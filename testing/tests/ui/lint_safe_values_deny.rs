@@ -0,0 +1,23 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(
+    source = r#"{{ askama::get_value::<String>("x")? | safe }}"#,
+    ext = "html",
+    lint_safe_values = "deny"
+)]
+struct GetValue;
+
+#[derive(Template)]
+#[template(
+    source = r#"{{ ("x" | value::<String>)? | safe }}"#,
+    ext = "html",
+    lint_safe_values = "deny"
+)]
+struct ValueFilter;
+
+#[derive(Template)]
+#[template(source = "{{ 1 }}", ext = "html", lint_safe_values = "forbid")]
+struct InvalidLevel;
+
+fn main() {}
@@ -0,0 +1,24 @@
+#![deny(deprecated)]
+
+use askama::Template;
+
+#[derive(Template)]
+#[template(
+    source = r#"{{ askama::get_value::<String>("x")? | safe }}"#,
+    ext = "html",
+    lint_safe_values = "warn"
+)]
+struct GetValue;
+
+// Escaped runtime values and other safe values are not reported.
+#[derive(Template)]
+#[template(
+    source = r#"{{ askama::get_value::<String>("x")? }}{{ name | safe }}"#,
+    ext = "html",
+    lint_safe_values = "warn"
+)]
+struct Escaped {
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(
+    source = r#"{% include "include-depth-1.txt" %}"#,
+    ext = "txt",
+    config = "max-include-depth.toml"
+)]
+struct TooDeepInclude;
+
+#[derive(Template)]
+#[template(
+    source = r#"{% include "include-depth-3.txt" %}"#,
+    ext = "txt",
+    config = "max-include-depth.toml"
+)]
+struct DeepEnoughInclude;
+
+#[derive(Template)]
+#[template(
+    source = r#"{% extends "extends-depth-1.txt" %}"#,
+    ext = "txt",
+    config = "max-include-depth.toml"
+)]
+struct TooDeepExtends;
+
+fn main() {}
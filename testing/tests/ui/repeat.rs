@@ -0,0 +1,15 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = r#"{{ "="|repeat(-1) }}"#, ext = "txt")]
+struct NegativeCount;
+
+#[derive(Template)]
+#[template(source = r#"{{ "="|repeat(-(2)) }}"#, ext = "txt")]
+struct NegativeGroupedCount;
+
+#[derive(Template)]
+#[template(source = r#"{{ "="|repeat }}"#, ext = "txt")]
+struct MissingCount;
+
+fn main() {}
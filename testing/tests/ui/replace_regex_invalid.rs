@@ -0,0 +1,9 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = r#"{{ s|replace_regex("(\\d+", "") }}"#, ext = "txt")]
+struct InvalidPattern<'a> {
+    s: &'a str,
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = "Hello, {{ naem }}!", ext = "txt", strict = true)]
+struct Typo<'a> {
+    name: &'a str,
+}
+
+#[derive(Template)]
+#[template(
+    source = "{% for item in items %}{{ itme }}{% endfor %}",
+    ext = "txt",
+    strict = true
+)]
+struct LocalTypo<'a> {
+    items: &'a [&'a str],
+}
+
+#[derive(Template)]
+#[template(source = "{{ unrelated }}", ext = "txt", strict = true)]
+struct NoSuggestion<'a> {
+    name: &'a str,
+}
+
+#[derive(Template)]
+#[template(ext = "txt", strict = true)]
+enum Enum {
+    #[template(source = "{{ vale }}")]
+    Variant { value: u32 },
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = "{{ orders|sum(field) }}", ext = "txt")]
+struct NotALiteral {
+    orders: Vec<(u32,)>,
+    field: &'static str,
+}
+
+#[derive(Template)]
+#[template(source = r#"{{ orders|avg("price net") }}"#, ext = "txt")]
+struct NotAFieldName {
+    orders: Vec<(u32,)>,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = "", ext = "txt", trailing_newline = "keep")]
+struct InvalidValue;
+
+#[derive(Template)]
+#[template(ext = "txt")]
+enum OnVariant {
+    #[template(source = "", trailing_newline = "trim")]
+    A,
+}
+
+fn main() {}
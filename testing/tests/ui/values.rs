@@ -42,4 +42,11 @@ struct E;
 )]
 struct F;
 
+#[derive(Template)]
+#[template(
+    ext = "html",
+    source = r#"{{ askama::get_value_or::<u8>("a") }}"#,
+)]
+struct G;
+
 fn main() {}
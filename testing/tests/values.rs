@@ -20,6 +20,29 @@ fn test_values() {
     assert_eq!(V.render_with_values(&values).unwrap(), "");
 }
 
+#[test]
+fn test_values_unused() {
+    #[derive(Template)]
+    #[template(source = "{{ a }} + {{ b }} = {{ a + b }}", ext = "txt")]
+    struct NoValues {
+        a: u32,
+        b: u32,
+    }
+
+    let t = NoValues { a: 1, b: 2 };
+    let values: HashMap<String, Box<dyn Any>> = HashMap::default();
+    assert_eq!(t.render().unwrap(), "1 + 2 = 3");
+    assert_eq!(t.render_with_values(&values).unwrap(), "1 + 2 = 3");
+
+    // Both paths write into the given buffer, which does not need to grow.
+    let mut buf = String::with_capacity(64);
+    let capacity = buf.capacity();
+    t.render_into(&mut buf).unwrap();
+    t.render_into_with_values(&mut buf, &values).unwrap();
+    assert_eq!(buf, "1 + 2 = 31 + 2 = 3");
+    assert_eq!(buf.capacity(), capacity);
+}
+
 #[test]
 fn test_values2() {
     #[derive(Template)]
@@ -89,6 +112,32 @@ fn test_value_function_getter() {
     assert_eq!(V.render_with_values(&values).unwrap(), "");
 }
 
+#[test]
+fn test_value_inferred_type() {
+    // The type of the value is inferred from its use, so no turbofish is needed.
+    #[derive(Template)]
+    #[template(
+        source = r#"{% if let Ok(n) = "n"|value %}{{ self.double(n) }}{% endif %}
+            {{- askama::get_value_or("who", "guest") }}"#,
+        ext = "txt"
+    )]
+    struct V;
+
+    impl V {
+        fn double(&self, n: &u32) -> u32 {
+            n * 2
+        }
+    }
+
+    let mut values: HashMap<&str, Box<dyn Any>> = HashMap::new();
+    assert_eq!(V.render_with_values(&values).unwrap(), "guest");
+    values.insert("n", Box::new(21u32));
+    values.insert("who", Box::new("world"));
+    assert_eq!(V.render_with_values(&values).unwrap(), "42world");
+    values.insert("n", Box::new(21u8));
+    assert_eq!(V.render_with_values(&values).unwrap(), "world");
+}
+
 #[test]
 fn test_value_in_subtemplates() {
     // In this test we make sure that values are passed down to transcluded sub-templates,
@@ -112,6 +161,40 @@ fn test_value_in_subtemplates() {
     );
 }
 
+#[test]
+fn test_value_with_fallback() {
+    #[derive(Template)]
+    #[template(
+        source = r#"Hello, {{ askama::get_value_or::<String>("who", "guest".to_string()) }}!
+{%- if let Some(age) = askama::get_value_opt::<u32>("age") %} Age: {{ age }}{% endif %}"#,
+        ext = "txt"
+    )]
+    struct Greeting;
+
+    assert_eq!(Greeting.render().unwrap(), "Hello, guest!");
+
+    let mut values: HashMap<&str, Box<dyn Any>> = HashMap::new();
+    values.insert("who", Box::new("world".to_string()));
+    assert_eq!(
+        Greeting.render_with_values(&values).unwrap(),
+        "Hello, world!"
+    );
+
+    values.insert("age", Box::new(12u32));
+    assert_eq!(
+        Greeting.render_with_values(&values).unwrap(),
+        "Hello, world! Age: 12"
+    );
+
+    // A value of the wrong type is not an error, but treated like a missing value.
+    values.insert("who", Box::new(42u32));
+    values.insert("age", Box::new("twelve".to_string()));
+    assert_eq!(
+        Greeting.render_with_values(&values).unwrap(),
+        "Hello, guest!"
+    );
+}
+
 #[test]
 fn test_value_in_subtemplates_with_filters() {
     // In this test we make sure that values are passed down to transcluded sub-templates,
@@ -156,3 +239,258 @@ fn test_value_in_wordcount() {
     );
     assert_eq!(Parent.render_with_values(&values).unwrap(), "10");
 }
+
+#[test]
+fn test_localize_number() {
+    use askama::filters::Locale;
+
+    #[derive(Template)]
+    #[template(
+        source = "{{ n | localize_number }} {{ f | localize_number }}",
+        ext = "txt"
+    )]
+    struct V {
+        n: i64,
+        f: f64,
+    }
+
+    let v = V {
+        n: -9_876_543,
+        f: 12_345.678,
+    };
+    let mut values: HashMap<String, Box<dyn Any>> = HashMap::default();
+    assert_eq!(
+        v.render_with_values(&values).unwrap(),
+        "-9,876,543 12,345.678"
+    );
+    values.insert(
+        "locale".to_string(),
+        Box::new(Locale::from_tag("de").unwrap()),
+    );
+    assert_eq!(
+        v.render_with_values(&values).unwrap(),
+        "-9.876.543 12.345,678"
+    );
+    values.insert(
+        "locale".to_string(),
+        Box::new(Locale::from_tag("en-US").unwrap()),
+    );
+    assert_eq!(
+        v.render_with_values(&values).unwrap(),
+        "-9,876,543 12,345.678"
+    );
+}
+
+#[test]
+fn test_values_only() {
+    #[derive(Template)]
+    #[template(
+        source = r#"
+            {%- if title %}<h1>{{ title }}</h1>{% endif -%}
+            {%- if count %} {{ count }} items{% endif -%}
+            {%- if subtitle is none %} (no subtitle){% endif -%}
+            {%- let local = "!" %}{{ local }}{{ suffix -}}
+        "#,
+        ext = "html",
+        values_only = true
+    )]
+    struct Dashboard<'a> {
+        suffix: &'a str,
+    }
+
+    let t = Dashboard { suffix: "?" };
+    let mut values: HashMap<String, Box<dyn Any>> = HashMap::default();
+    values.insert("title".to_string(), Box::new("Tom & Jerry"));
+    values.insert("count".to_string(), Box::new(0_u32));
+    assert_eq!(
+        t.render_with_values(&values).unwrap(),
+        "<h1>Tom &#38; Jerry</h1> (no subtitle)!?"
+    );
+
+    values.insert("title".to_string(), Box::new(String::new()));
+    values.insert("count".to_string(), Box::new(3_i64));
+    values.insert("subtitle".to_string(), Box::new("sub"));
+    assert_eq!(t.render_with_values(&values).unwrap(), " 3 items!?");
+}
+
+#[test]
+fn test_values_only_errors() {
+    #[derive(Template)]
+    #[template(source = "{{ title }}", ext = "txt", values_only = true)]
+    struct Title;
+
+    assert!(matches!(Title.render(), Err(askama::Error::ValueMissing)));
+    let values: (&str, &dyn Any) = ("title", &vec![1, 2]);
+    assert!(matches!(
+        Title.render_with_values(&values),
+        Err(askama::Error::ValueType)
+    ));
+    let values: (&str, &dyn Any) = ("title", &'x');
+    assert_eq!(Title.render_with_values(&values).unwrap(), "x");
+}
+
+#[test]
+fn test_value_error_variants() {
+    #[derive(Template)]
+    #[template(source = r#"{{ ("count" | value::<u32>)? }} items"#, ext = "txt")]
+    struct Count;
+
+    let values: HashMap<String, Box<dyn Any>> = HashMap::default();
+    assert!(matches!(
+        Count.render_with_values(&values),
+        Err(askama::Error::ValueMissing)
+    ));
+    let values: (&str, &dyn Any) = ("count", &"three");
+    assert!(matches!(
+        Count.render_with_values(&values),
+        Err(askama::Error::ValueType)
+    ));
+    let values: (&str, &dyn Any) = ("count", &3_u32);
+    assert_eq!(Count.render_with_values(&values).unwrap(), "3 items");
+
+    // A failing writer is reported as `Error::Fmt`.
+    struct Failing;
+
+    impl std::fmt::Write for Failing {
+        fn write_str(&mut self, _: &str) -> std::fmt::Result {
+            Err(std::fmt::Error)
+        }
+    }
+
+    assert!(matches!(
+        Count.render_into_with_values(&mut Failing, &values),
+        Err(askama::Error::Fmt)
+    ));
+
+    // The variants are kept if the failing template is rendered by another template.
+    #[derive(Template)]
+    #[template(
+        source = "[{{ count }}] [{% filter upper %}{{ count }}{% endfilter %}]",
+        ext = "txt"
+    )]
+    struct Wrapper {
+        count: Count,
+    }
+
+    let values: HashMap<String, Box<dyn Any>> = HashMap::default();
+    assert!(matches!(
+        Wrapper { count: Count }.render_with_values(&values),
+        Err(askama::Error::ValueMissing)
+    ));
+    let values: (&str, &dyn Any) = ("count", &3_u32);
+    assert_eq!(
+        Wrapper { count: Count }
+            .render_with_values(&values)
+            .unwrap(),
+        "[3 items] [3 ITEMS]"
+    );
+    assert!(matches!(
+        Wrapper { count: Count }.render_into_with_values(&mut Failing, &values),
+        Err(askama::Error::Fmt)
+    ));
+}
+
+/// An escaper that refuses to write NUL characters.
+#[derive(Clone, Copy)]
+struct NoNul;
+
+impl askama::filters::Escaper for NoNul {
+    fn write_escaped_str<W: std::fmt::Write>(&self, mut dest: W, s: &str) -> std::fmt::Result {
+        if s.contains('\0') {
+            return Err(std::fmt::Error);
+        }
+        dest.write_str(s)
+    }
+}
+
+#[test]
+fn test_escape_error_variant() {
+    #[derive(Template)]
+    #[template(
+        source = "<{{ text }}> <{{ (\"count\" | value::<u32>)? }}>",
+        ext = "nonul",
+        config = "nonul-escaper.toml"
+    )]
+    struct Escaped<'a> {
+        text: &'a str,
+    }
+
+    struct Failing;
+
+    impl std::fmt::Write for Failing {
+        fn write_str(&mut self, _: &str) -> std::fmt::Result {
+            Err(std::fmt::Error)
+        }
+    }
+
+    let values: (&str, &dyn Any) = ("count", &3_u32);
+    assert_eq!(
+        Escaped { text: "a" }.render_with_values(&values).unwrap(),
+        "<a> <3>"
+    );
+
+    // The escaper fails, the writer does not.
+    assert!(matches!(
+        Escaped { text: "a\0" }.render_with_values(&values),
+        Err(askama::Error::Escape)
+    ));
+
+    // The writer fails, the escaper does not.
+    assert!(matches!(
+        Escaped { text: "a" }.render_into_with_values(&mut Failing, &values),
+        Err(askama::Error::Fmt)
+    ));
+
+    // A value lookup fails.
+    assert!(matches!(
+        Escaped { text: "a" }.render(),
+        Err(askama::Error::ValueMissing)
+    ));
+}
+
+#[test]
+fn test_csp_nonce() {
+    #[derive(Template)]
+    #[template(
+        source = r#"<script nonce="{{ askama::csp_nonce() }}">run();</script>"#,
+        ext = "html"
+    )]
+    struct Page;
+
+    let values: (&str, &dyn Any) = (askama::CSP_NONCE_KEY, &"bm9uY2U=");
+    assert_eq!(
+        Page.render_with_values(&values).unwrap(),
+        r#"<script nonce="bm9uY2U=">run();</script>"#
+    );
+
+    // The nonce is escaped, so it cannot break out of the attribute.
+    let mut values: HashMap<String, Box<dyn Any>> = HashMap::default();
+    values.insert("csp_nonce".to_owned(), Box::new(r#""><x"#.to_owned()));
+    assert_eq!(
+        Page.render_with_values(&values).unwrap(),
+        r#"<script nonce="&#34;&#62;&#60;x">run();</script>"#
+    );
+
+    assert!(matches!(Page.render(), Err(askama::Error::ValueMissing)));
+    let values: (&str, &dyn Any) = ("csp_nonce", &42_u32);
+    assert!(matches!(
+        Page.render_with_values(&values),
+        Err(askama::Error::ValueType)
+    ));
+}
+
+// The lint only warns, the template still works.
+#[allow(deprecated)]
+#[test]
+fn test_lint_safe_values_warn() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ askama::get_value::<&str>("html")? | safe }}"#,
+        ext = "html",
+        lint_safe_values = "warn"
+    )]
+    struct Html;
+
+    let values: (&str, &dyn Any) = ("html", &"<b>bold</b>");
+    assert_eq!(Html.render_with_values(&values).unwrap(), "<b>bold</b>");
+}
@@ -143,3 +143,136 @@ fn test_template_whitespace_config() {
     );
     test_template_ws_config!("test_minimize.toml", "suppress", "\n1{# #}\n\n\n2", "\n12");
 }
+
+#[test]
+fn test_whitespace_per_extension() {
+    // Markdown separates paragraphs with a blank line, which must survive a comment between
+    // them, even if the whitespace around tags is suppressed in the other templates.
+    #[derive(Template)]
+    #[template(
+        source = "First paragraph.\n{# a note for the authors #}\n\nSecond paragraph.\n",
+        ext = "md",
+        config = "md-whitespace.toml"
+    )]
+    struct Markdown;
+
+    assert_eq!(
+        Markdown.render().unwrap(),
+        "First paragraph.\n\n\nSecond paragraph.\n"
+    );
+
+    #[derive(Template)]
+    #[template(
+        source = "First paragraph.\n{# a note for the authors #}\n\nSecond paragraph.\n",
+        ext = "txt",
+        config = "md-whitespace.toml"
+    )]
+    struct Text;
+
+    assert_eq!(
+        Text.render().unwrap(),
+        "First paragraph.\n\nSecond paragraph.\n"
+    );
+
+    // The `escape` argument only selects the escaper, the handling still follows `ext`.
+    #[derive(Template)]
+    #[template(
+        source = "First paragraph.\n{# a note for the authors #}\n\nSecond paragraph.\n",
+        ext = "txt",
+        escape = "html",
+        config = "md-whitespace.toml"
+    )]
+    struct TextEscapedAsHtml;
+
+    assert_eq!(
+        TextEscapedAsHtml.render().unwrap(),
+        "First paragraph.\n\nSecond paragraph.\n"
+    );
+
+    // Extensions without an entry use the general setting.
+    #[derive(Template)]
+    #[template(
+        source = "First paragraph.\n{# a note for the authors #}\n\nSecond paragraph.\n",
+        ext = "html",
+        config = "md-whitespace.toml"
+    )]
+    struct Html;
+
+    assert_eq!(
+        Html.render().unwrap(),
+        "First paragraph.Second paragraph.\n"
+    );
+
+    // The `whitespace` argument of the template still has precedence.
+    #[derive(Template)]
+    #[template(
+        source = "First paragraph.\n{# a note for the authors #}\n\nSecond paragraph.\n",
+        ext = "md",
+        config = "md-whitespace.toml",
+        whitespace = "minimize"
+    )]
+    struct MarkdownMinimize;
+
+    assert_eq!(
+        MarkdownMinimize.render().unwrap(),
+        "First paragraph.\n\nSecond paragraph.\n"
+    );
+}
+
+#[test]
+fn test_path_extension_has_precedence_over_ext() {
+    // The template file is HTML, so it is escaped like HTML, and its whitespace is suppressed
+    // like in all templates of `md-whitespace.toml` but markdown and text templates.
+    #[derive(Template)]
+    #[template(path = "hello.html", ext = "md", config = "md-whitespace.toml")]
+    struct Hello<'a> {
+        name: &'a str,
+    }
+
+    assert_eq!(
+        Hello { name: "<world>" }.render().unwrap(),
+        "Hello,&#60;world&#62;!"
+    );
+}
+
+#[test]
+fn test_whitespace_inheritance() {
+    // The parent template suppresses the whitespace around its tags, the child template
+    // preserves it. Each template renders its own content with its own handling.
+    #[derive(Template)]
+    #[template(
+        source = "{% extends \"ws-inherit-base.html\" %}\n\
+            {% block items %}\n    <li>child</li>\n  {% endblock %}\n",
+        ext = "md",
+        config = "md-whitespace.toml"
+    )]
+    struct Child;
+
+    assert_eq!(Child.render().unwrap(), "<ul>\n    <li>child</li>\n  </ul>");
+
+    // `inherit_whitespace` renders the overriding block with the handling of the parent.
+    #[derive(Template)]
+    #[template(
+        source = "{% extends \"ws-inherit-base.html\" %}\n\
+            {% block items inherit_whitespace %}\n    <li>child</li>\n  {% endblock %}\n",
+        ext = "md",
+        config = "md-whitespace.toml"
+    )]
+    struct InheritWhitespace;
+
+    assert_eq!(
+        InheritWhitespace.render().unwrap(),
+        "<ul><li>child</li></ul>"
+    );
+
+    // Without an override, the parent's block uses the parent's handling, too.
+    #[derive(Template)]
+    #[template(
+        source = "{% extends \"ws-inherit-base.html\" %}\n",
+        ext = "md",
+        config = "md-whitespace.toml"
+    )]
+    struct NoOverride;
+
+    assert_eq!(NoOverride.render().unwrap(), "<ul><li>base</li></ul>");
+}